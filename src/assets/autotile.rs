@@ -0,0 +1,253 @@
+//! Auto-tile sets: groups of edge, corner and fill pieces chosen by rule.
+//!
+//! A cell's neighbors are packed into an 8-bit mask, clockwise from north.
+//! The rule table reduces the mask to the patterns the set distinguishes
+//! (47 for blob tiles, 16 for Wang tiles) and looks up a piece for it. Pieces
+//! only need to be assigned for one rotation of each pattern; the others reuse
+//! it turned by quarter-turns.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const NORTH: u8 = 1 << 0;
+pub const NORTH_EAST: u8 = 1 << 1;
+pub const EAST: u8 = 1 << 2;
+pub const SOUTH_EAST: u8 = 1 << 3;
+pub const SOUTH: u8 = 1 << 4;
+pub const SOUTH_WEST: u8 = 1 << 5;
+pub const WEST: u8 = 1 << 6;
+pub const NORTH_WEST: u8 = 1 << 7;
+
+const CARDINALS: u8 = NORTH | EAST | SOUTH | WEST;
+
+/// Mask bit and grid offset of each neighbor, clockwise from north
+pub const NEIGHBORS: [(u8, IVec2); 8] = [
+    (NORTH, IVec2::new(0, 1)),
+    (NORTH_EAST, IVec2::new(1, 1)),
+    (EAST, IVec2::new(1, 0)),
+    (SOUTH_EAST, IVec2::new(1, -1)),
+    (SOUTH, IVec2::new(0, -1)),
+    (SOUTH_WEST, IVec2::new(-1, -1)),
+    (WEST, IVec2::new(-1, 0)),
+    (NORTH_WEST, IVec2::new(-1, 1)),
+];
+
+/// Which neighbors of `cell` are filled
+pub fn neighbor_mask(cell: IVec2, filled: impl Fn(IVec2) -> bool) -> u8 {
+    NEIGHBORS
+        .iter()
+        .filter(|(_, offset)| filled(cell + *offset))
+        .fold(0, |mask, (bit, _)| mask | bit)
+}
+
+/// The mask seen by a piece turned `quarter_turns` counterclockwise
+pub fn rotate_mask(mask: u8, quarter_turns: u8) -> u8 {
+    mask.rotate_right(2 * u32::from(quarter_turns % 4))
+}
+
+/// How neighbors decide which piece a cell gets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AutoTileRules {
+    /// 47 patterns: edges plus inner corners (diagonals count when both
+    /// adjoining edges are filled)
+    #[default]
+    Blob47,
+    /// 16 patterns: the four edge neighbors only
+    Wang16,
+}
+
+impl AutoTileRules {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AutoTileRules::Blob47 => "47-tile blob",
+            AutoTileRules::Wang16 => "16-tile Wang",
+        }
+    }
+
+    pub fn all() -> &'static [AutoTileRules] {
+        &[AutoTileRules::Blob47, AutoTileRules::Wang16]
+    }
+
+    /// Drop the neighbor bits these rules ignore
+    pub fn reduce(&self, mask: u8) -> u8 {
+        let cardinals = mask & CARDINALS;
+        match self {
+            AutoTileRules::Wang16 => cardinals,
+            AutoTileRules::Blob47 => {
+                let corners = [
+                    (NORTH_EAST, NORTH | EAST),
+                    (SOUTH_EAST, SOUTH | EAST),
+                    (SOUTH_WEST, SOUTH | WEST),
+                    (NORTH_WEST, NORTH | WEST),
+                ];
+                corners
+                    .iter()
+                    .filter(|(corner, sides)| mask & corner != 0 && mask & sides == *sides)
+                    .fold(cardinals, |reduced, (corner, _)| reduced | corner)
+            }
+        }
+    }
+
+    /// The mask of a cell surrounded on all sides
+    pub fn full_mask(&self) -> u8 {
+        self.reduce(u8::MAX)
+    }
+
+    /// One pattern per rotation class, the ones pieces are assigned to
+    pub fn canonical_masks(&self) -> Vec<u8> {
+        let mut masks: Vec<u8> = (0..=u8::MAX)
+            .map(|mask| canonical(self.reduce(mask)))
+            .collect();
+        masks.sort_unstable();
+        masks.dedup();
+        masks
+    }
+}
+
+/// Smallest mask among a pattern's rotations
+fn canonical(mask: u8) -> u8 {
+    (0..4)
+        .map(|turns| rotate_mask(mask, turns))
+        .min()
+        .unwrap_or(mask)
+}
+
+/// A named group of pieces and the rules that pick between them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoTileSet {
+    pub name: String,
+    #[serde(default)]
+    pub rules: AutoTileRules,
+    /// Library-relative asset path of the piece for each neighbor pattern
+    #[serde(default)]
+    pub pieces: BTreeMap<u8, String>,
+}
+
+impl AutoTileSet {
+    pub fn new(name: impl Into<String>, rules: AutoTileRules) -> Self {
+        Self {
+            name: name.into(),
+            rules,
+            pieces: BTreeMap::new(),
+        }
+    }
+
+    /// Piece and counterclockwise quarter-turns for a cell with these neighbors.
+    ///
+    /// Patterns without a piece in any rotation fall back to the fill piece,
+    /// then to any piece, so every painted cell gets a tile.
+    pub fn resolve(&self, mask: u8) -> Option<(&str, u8)> {
+        let mask = self.rules.reduce(mask);
+        let rotated = (0..4).find_map(|turns| {
+            // The piece drawn for `piece_mask` covers `mask` once turned
+            let piece_mask = mask.rotate_left(2 * u32::from(turns));
+            self.pieces
+                .get(&piece_mask)
+                .map(|path| (path.as_str(), turns))
+        });
+        rotated
+            .or_else(|| {
+                self.pieces
+                    .get(&self.rules.full_mask())
+                    .map(|path| (path.as_str(), 0))
+            })
+            .or_else(|| self.pieces.values().next().map(|path| (path.as_str(), 0)))
+    }
+
+    /// Whether an asset is one of the set's pieces
+    pub fn contains_piece(&self, path: &str) -> bool {
+        self.pieces.values().any(|piece| piece == path)
+    }
+
+    /// Point pieces at an asset's new path. Returns true if any changed.
+    pub fn rekey_piece(&mut self, old_path: &str, new_path: &str) -> bool {
+        let mut changed = false;
+        for piece in self.pieces.values_mut().filter(|piece| *piece == old_path) {
+            *piece = new_path.to_string();
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_counts() {
+        let blob: std::collections::HashSet<u8> = (0..=u8::MAX)
+            .map(|m| AutoTileRules::Blob47.reduce(m))
+            .collect();
+        let wang: std::collections::HashSet<u8> = (0..=u8::MAX)
+            .map(|m| AutoTileRules::Wang16.reduce(m))
+            .collect();
+        assert_eq!(blob.len(), 47);
+        assert_eq!(wang.len(), 16);
+
+        assert_eq!(AutoTileRules::Blob47.canonical_masks().len(), 15);
+        assert_eq!(AutoTileRules::Wang16.canonical_masks().len(), 6);
+    }
+
+    #[test]
+    fn test_blob_ignores_corners_without_both_edges() {
+        let rules = AutoTileRules::Blob47;
+        assert_eq!(rules.reduce(NORTH_EAST), 0);
+        assert_eq!(rules.reduce(NORTH | NORTH_EAST), NORTH);
+        assert_eq!(
+            rules.reduce(NORTH | EAST | NORTH_EAST),
+            NORTH | EAST | NORTH_EAST
+        );
+    }
+
+    #[test]
+    fn test_neighbor_mask() {
+        let filled = [IVec2::new(0, 1), IVec2::new(1, 0), IVec2::new(-1, -1)];
+        let mask = neighbor_mask(IVec2::ZERO, |cell| filled.contains(&cell));
+        assert_eq!(mask, NORTH | EAST | SOUTH_WEST);
+    }
+
+    #[test]
+    fn test_rotate_mask_turns_counterclockwise() {
+        // A quarter-turn counterclockwise carries north to west
+        assert_eq!(rotate_mask(NORTH, 1), WEST);
+        assert_eq!(
+            rotate_mask(NORTH | EAST | NORTH_EAST, 1),
+            NORTH | WEST | NORTH_WEST
+        );
+        assert_eq!(rotate_mask(SOUTH, 4), SOUTH);
+    }
+
+    #[test]
+    fn test_resolve_rotates_pieces() {
+        let mut set = AutoTileSet::new("Cave", AutoTileRules::Wang16);
+        // A dead end drawn open to the north
+        set.pieces.insert(NORTH, "cave/end.png".to_string());
+        set.pieces.insert(
+            AutoTileRules::Wang16.full_mask(),
+            "cave/fill.png".to_string(),
+        );
+
+        assert_eq!(set.resolve(NORTH), Some(("cave/end.png", 0)));
+        // Open to the west: the north piece turned a quarter counterclockwise
+        assert_eq!(set.resolve(WEST), Some(("cave/end.png", 1)));
+        assert_eq!(set.resolve(EAST | NORTH_EAST), Some(("cave/end.png", 3)));
+        // No straight piece assigned, so it falls back to the fill
+        assert_eq!(set.resolve(NORTH | SOUTH), Some(("cave/fill.png", 0)));
+
+        assert_eq!(
+            AutoTileSet::new("Empty", AutoTileRules::Blob47).resolve(0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rekey_piece() {
+        let mut set = AutoTileSet::new("Water", AutoTileRules::Blob47);
+        set.pieces.insert(0, "water/pool.png".to_string());
+        assert!(set.rekey_piece("water/pool.png", "water/puddle.png"));
+        assert!(set.contains_piece("water/puddle.png"));
+        assert!(!set.rekey_piece("water/pool.png", "water/other.png"));
+    }
+}
@@ -4,8 +4,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use super::autotile::AutoTileSet;
 use super::RefreshAssetLibrary;
 use crate::config::AddRecentLibraryRequest;
+use crate::map::CreatureSize;
 
 /// Size of thumbnail previews in pixels
 pub const THUMBNAIL_SIZE: u32 = 24;
@@ -18,12 +20,20 @@ pub const LIBRARY_METADATA_FILE: &str = ".library.json";
 pub struct LibraryMetadata {
     /// User-defined name for the library
     pub name: String,
+    /// Creature size for token assets, keyed by library-relative path
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub creature_sizes: HashMap<String, CreatureSize>,
+    /// Auto-tile sets built from the library's assets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub autotile_sets: Vec<AutoTileSet>,
 }
 
 impl Default for LibraryMetadata {
     fn default() -> Self {
         Self {
             name: "Unnamed Library".to_string(),
+            creature_sizes: HashMap::new(),
+            autotile_sets: Vec::new(),
         }
     }
 }
@@ -65,6 +75,38 @@ impl AssetLibrary {
             .map(|a| (a.library_relative_path(), a.relative_path.as_str()))
             .collect()
     }
+
+    /// Creature size set for an asset in the library metadata
+    pub fn creature_size(&self, asset: &LibraryAsset) -> Option<CreatureSize> {
+        self.metadata
+            .creature_sizes
+            .get(&asset.library_relative_path())
+            .copied()
+    }
+
+    /// Auto-tile set with the given name
+    pub fn autotile_set(&self, name: &str) -> Option<&AutoTileSet> {
+        self.metadata
+            .autotile_sets
+            .iter()
+            .find(|set| set.name == name)
+    }
+
+    /// Carry an asset's metadata over to its new library-relative path after
+    /// a rename or move. Returns true if anything changed.
+    pub fn rekey_asset_metadata(&mut self, old_path: &str, new_path: &str) -> bool {
+        let mut changed = false;
+        if let Some(size) = self.metadata.creature_sizes.remove(old_path) {
+            self.metadata
+                .creature_sizes
+                .insert(new_path.to_string(), size);
+            changed = true;
+        }
+        for set in &mut self.metadata.autotile_sets {
+            changed |= set.rekey_piece(old_path, new_path);
+        }
+        changed
+    }
 }
 
 /// Result of validating/opening an asset library directory
@@ -139,7 +181,10 @@ pub fn load_library_metadata(library_path: &Path) -> LibraryMetadata {
         .unwrap_or("Unnamed Library")
         .to_string();
 
-    LibraryMetadata { name }
+    LibraryMetadata {
+        name,
+        ..Default::default()
+    }
 }
 
 /// Save library metadata to .library.json file
@@ -468,7 +513,10 @@ pub fn create_and_open_library(library: &mut AssetLibrary, path: PathBuf) -> Res
         .unwrap_or("Unnamed Library")
         .to_string();
 
-    library.metadata = LibraryMetadata { name };
+    library.metadata = LibraryMetadata {
+        name,
+        ..Default::default()
+    };
     save_library_metadata(&path, &library.metadata)?;
 
     library.library_path = path.clone();
@@ -1,6 +1,8 @@
+mod autotile;
 mod library;
 mod placeholder;
 mod validation;
+pub use autotile::{neighbor_mask, AutoTileRules, AutoTileSet, NEIGHBORS};
 pub use library::{
     create_and_open_library, get_image_dimensions, load_thumbnail, open_library_directory,
     save_library_metadata, AssetLibrary, LibraryAsset, ThumbnailCache, THUMBNAIL_SIZE,
@@ -9,7 +11,8 @@ pub use library::{
 use bevy::prelude::*;
 
 use crate::config::{AppConfig, ConfigLoaded};
-use crate::map::PlacedItem;
+use crate::editor::history::CommandHistory;
+use crate::map::{CreatureSize, PlacedItem};
 
 #[derive(Message)]
 pub struct RefreshAssetLibrary;
@@ -20,6 +23,20 @@ pub struct UpdateLibraryMetadataRequest {
     pub name: String,
 }
 
+/// Message to set (or clear) the creature size of a library asset
+#[derive(Message)]
+pub struct SetAssetCreatureSizeRequest {
+    /// Library-relative asset path (e.g. "tokens/ogre.png")
+    pub asset_path: String,
+    pub size: Option<CreatureSize>,
+}
+
+/// Message to replace the library's auto-tile sets
+#[derive(Message)]
+pub struct SetAutoTileSetsRequest {
+    pub sets: Vec<AutoTileSet>,
+}
+
 /// Message to update placed items after an asset is renamed
 #[derive(Message)]
 pub struct RenameAssetRequest {
@@ -36,6 +53,8 @@ impl Plugin for AssetLibraryPlugin {
             .init_resource::<ThumbnailCache>()
             .add_message::<RefreshAssetLibrary>()
             .add_message::<UpdateLibraryMetadataRequest>()
+            .add_message::<SetAssetCreatureSizeRequest>()
+            .add_message::<SetAutoTileSetsRequest>()
             .add_message::<RenameAssetRequest>()
             .add_systems(
                 Startup,
@@ -54,6 +73,9 @@ impl Plugin for AssetLibraryPlugin {
                     library::track_library_changes.run_if(resource_changed::<AssetLibrary>),
                     update_library_metadata_system
                         .run_if(on_message::<UpdateLibraryMetadataRequest>),
+                    set_asset_creature_size_system
+                        .run_if(on_message::<SetAssetCreatureSizeRequest>),
+                    set_autotile_sets_system.run_if(on_message::<SetAutoTileSetsRequest>),
                     rename_asset_system.run_if(on_message::<RenameAssetRequest>),
                     // Check for missing assets periodically (runs on entities without MissingAsset marker)
                     validation::detect_missing_assets,
@@ -77,12 +99,51 @@ fn update_library_metadata_system(
     }
 }
 
-/// System to update placed items when an asset is renamed
+/// System to store an asset's creature size in the library metadata
+fn set_asset_creature_size_system(
+    mut events: MessageReader<SetAssetCreatureSizeRequest>,
+    mut library: ResMut<AssetLibrary>,
+) {
+    for event in events.read() {
+        match event.size {
+            Some(size) => {
+                library
+                    .metadata
+                    .creature_sizes
+                    .insert(event.asset_path.clone(), size);
+            }
+            None => {
+                library.metadata.creature_sizes.remove(&event.asset_path);
+            }
+        }
+        if let Err(e) = save_library_metadata(&library.library_path, &library.metadata) {
+            warn!("Failed to save library metadata: {}", e);
+        }
+    }
+}
+
+/// System to store the library's auto-tile sets in its metadata
+fn set_autotile_sets_system(
+    mut events: MessageReader<SetAutoTileSetsRequest>,
+    mut library: ResMut<AssetLibrary>,
+) {
+    let Some(event) = events.read().last() else {
+        return;
+    };
+    library.metadata.autotile_sets = event.sets.clone();
+    if let Err(e) = save_library_metadata(&library.library_path, &library.metadata) {
+        warn!("Failed to save library metadata: {}", e);
+    }
+}
+
+/// System to update placed items, and the undo history, when an asset is renamed
 fn rename_asset_system(
     mut events: MessageReader<RenameAssetRequest>,
     mut placed_items: Query<&mut PlacedItem>,
+    mut history: ResMut<CommandHistory>,
 ) {
     for event in events.read() {
+        history.rename_asset(&event.old_path, &event.new_path);
         let mut count = 0;
         for mut item in placed_items.iter_mut() {
             if item.asset_path == event.old_path {
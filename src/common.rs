@@ -8,8 +8,8 @@ use bevy::window::{CursorIcon, SystemCursorIcon};
 /// Drag mode for selection and viewport interaction.
 ///
 /// Used by both the selection system (editor) and viewport indicator (session).
-/// Not all modes may be applicable in all contexts (e.g., Rotate is only used
-/// for selection, not viewport).
+/// Not all modes may be applicable in all contexts (e.g., Rotate and Vertex are
+/// only used for selection, not viewport).
 #[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum DragMode {
     #[default]
@@ -24,6 +24,8 @@ pub enum DragMode {
     ResizeNW,
     ResizeSE,
     ResizeSW,
+    /// Moving a single point of an annotation (path vertex, line endpoint)
+    Vertex,
 }
 
 impl DragMode {
@@ -33,6 +35,7 @@ impl DragMode {
             DragMode::None => None,
             DragMode::Move => Some(CursorIcon::System(SystemCursorIcon::Move)),
             DragMode::Rotate => Some(CursorIcon::System(SystemCursorIcon::Grab)),
+            DragMode::Vertex => Some(CursorIcon::System(SystemCursorIcon::Crosshair)),
             DragMode::ResizeN | DragMode::ResizeS => {
                 Some(CursorIcon::System(SystemCursorIcon::NsResize))
             }
@@ -89,6 +92,7 @@ mod tests {
         assert!(!DragMode::None.is_resize());
         assert!(!DragMode::Move.is_resize());
         assert!(!DragMode::Rotate.is_resize());
+        assert!(!DragMode::Vertex.is_resize());
         assert!(DragMode::ResizeN.is_resize());
         assert!(DragMode::ResizeSW.is_resize());
     }
@@ -1,8 +1,10 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use crate::constants::MAX_RECENT_LIBRARIES;
+use crate::dice::DiceMacro;
 
 /// System set for config loading (other plugins can run after this)
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
@@ -22,6 +24,18 @@ pub struct AppConfigData {
     /// Last opened map file path (not auto-loaded, just remembered for quick access)
     #[serde(default)]
     pub last_map_path: Option<PathBuf>,
+
+    /// Named rolls saved from the dice panel
+    #[serde(default)]
+    pub dice_macros: Vec<DiceMacro>,
+
+    /// Keyboard shortcuts changed from the defaults, by action id
+    #[serde(default)]
+    pub keybindings: BTreeMap<String, Vec<String>>,
+
+    /// Whether each map's undo history is saved next to the map file
+    #[serde(default)]
+    pub save_history: bool,
 }
 
 /// Runtime configuration resource
@@ -295,6 +309,15 @@ mod tests {
                 PathBuf::from("/path/two"),
             ],
             last_map_path: Some(PathBuf::from("/path/to/map.json")),
+            dice_macros: vec![DiceMacro {
+                name: "Longsword".to_string(),
+                expression: "1d8+3".to_string(),
+            }],
+            keybindings: BTreeMap::from([(
+                "toggle_help".to_string(),
+                vec!["F1".to_string()],
+            )]),
+            save_history: true,
         };
 
         let json = serde_json::to_string(&data).unwrap();
@@ -303,6 +326,9 @@ mod tests {
         assert_eq!(parsed.default_library_path, data.default_library_path);
         assert_eq!(parsed.recent_libraries, data.recent_libraries);
         assert_eq!(parsed.last_map_path, data.last_map_path);
+        assert_eq!(parsed.dice_macros, data.dice_macros);
+        assert_eq!(parsed.keybindings, data.keybindings);
+        assert!(parsed.save_history);
     }
 
     #[test]
@@ -0,0 +1,555 @@
+//! Dice notation parsing and rolling.
+//!
+//! Supports the notation used at the table:
+//! - `1d20+5`, `2d8+1d6+3`, `d%` (d100)
+//! - Keep/drop: `4d6kh3` (or `4d6k3`), `2d20kl1`, `4d6dl1`, `2d20dh1`
+//! - Advantage/disadvantage: `1d20+5 adv`, `1d20 dis`, or just `adv`
+//!
+//! Rolling takes the RNG as a parameter so tests can seed it.
+
+use serde::{Deserialize, Serialize};
+
+/// Most dice a single term may roll
+pub const MAX_DICE_PER_TERM: u32 = 100;
+
+/// Largest die allowed
+pub const MAX_DIE_SIDES: u32 = 1000;
+
+/// Most terms in one expression
+const MAX_TERMS: usize = 20;
+
+/// Largest flat modifier allowed
+const MAX_CONSTANT: i32 = 10_000;
+
+/// A saved roll, shown as a button in the dice panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiceMacro {
+    pub name: String,
+    pub expression: String,
+}
+
+/// Which dice of a term count toward the total
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiceTerm {
+    Dice {
+        count: u32,
+        sides: u32,
+        keep: Option<Keep>,
+    },
+    Constant(i32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTerm {
+    pub negative: bool,
+    pub term: DiceTerm,
+}
+
+/// A parsed dice expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceExpr {
+    pub terms: Vec<SignedTerm>,
+}
+
+/// One die as rolled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DieRoll {
+    pub value: u32,
+    /// False for dice dropped by a keep/drop modifier
+    pub kept: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RolledTerm {
+    Dice {
+        negative: bool,
+        sides: u32,
+        rolls: Vec<DieRoll>,
+    },
+    Constant(i32),
+}
+
+/// The outcome of rolling a [`DiceExpr`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollResult {
+    /// Canonical notation of what was rolled
+    pub expression: String,
+    pub terms: Vec<RolledTerm>,
+    pub total: i32,
+}
+
+impl Keep {
+    fn count(&self) -> u32 {
+        match self {
+            Keep::Highest(n) | Keep::Lowest(n) => *n,
+        }
+    }
+}
+
+impl std::fmt::Display for DiceExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, signed) in self.terms.iter().enumerate() {
+            if signed.negative {
+                write!(f, "-")?;
+            } else if i > 0 {
+                write!(f, "+")?;
+            }
+            match &signed.term {
+                DiceTerm::Dice { count, sides, keep } => {
+                    write!(f, "{}d{}", count, sides)?;
+                    match keep {
+                        Some(Keep::Highest(n)) => write!(f, "kh{}", n)?,
+                        Some(Keep::Lowest(n)) => write!(f, "kl{}", n)?,
+                        None => {}
+                    }
+                }
+                DiceTerm::Constant(value) => write!(f, "{}", value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse dice notation such as `4d6kh3`, `1d20+5 adv` or `2d8+1d6+3`
+pub fn parse(input: &str) -> Result<DiceExpr, String> {
+    let lowered = input.trim().to_ascii_lowercase();
+
+    // Advantage/disadvantage is a trailing keyword
+    let mut advantage = None;
+    let mut body = lowered.as_str();
+    for (keyword, keep) in [
+        // Longest first: "disadvantage" also ends in "advantage"
+        ("disadvantage", Keep::Lowest(1)),
+        ("advantage", Keep::Highest(1)),
+        ("dis", Keep::Lowest(1)),
+        ("adv", Keep::Highest(1)),
+    ] {
+        if let Some(rest) = body.strip_suffix(keyword) {
+            advantage = Some(keep);
+            body = rest;
+            break;
+        }
+    }
+
+    let compact: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+    let compact = if compact.is_empty() && advantage.is_some() {
+        "1d20".to_string()
+    } else {
+        compact
+    };
+    if compact.is_empty() {
+        return Err("Enter a roll, e.g. 1d20+5".to_string());
+    }
+
+    let mut expr = DiceExpr { terms: Vec::new() };
+    let mut rest = compact.as_str();
+    while !rest.is_empty() {
+        if expr.terms.len() == MAX_TERMS {
+            return Err(format!("Too many terms (max {})", MAX_TERMS));
+        }
+
+        let negative = match rest.as_bytes()[0] {
+            b'+' => {
+                rest = &rest[1..];
+                false
+            }
+            b'-' => {
+                rest = &rest[1..];
+                true
+            }
+            _ if expr.terms.is_empty() => false,
+            _ => return Err(format!("Expected + or - before \"{}\"", rest)),
+        };
+
+        let end = rest.find(['+', '-']).unwrap_or(rest.len());
+        let term = parse_term(&rest[..end])?;
+        expr.terms.push(SignedTerm { negative, term });
+        rest = &rest[end..];
+    }
+
+    if let Some(keep) = advantage {
+        let d20 = expr.terms.iter_mut().find_map(|t| match &mut t.term {
+            DiceTerm::Dice {
+                count: count @ 1,
+                sides: 20,
+                keep: keep_slot @ None,
+            } => Some((count, keep_slot)),
+            _ => None,
+        });
+        let Some((count, keep_slot)) = d20 else {
+            return Err("Advantage needs a single d20, e.g. 1d20+5 adv".to_string());
+        };
+        *count = 2;
+        *keep_slot = Some(keep);
+    }
+
+    Ok(expr)
+}
+
+fn parse_term(text: &str) -> Result<DiceTerm, String> {
+    if text.is_empty() {
+        return Err("Missing term after + or -".to_string());
+    }
+
+    let Some(d_index) = text.find('d') else {
+        let value: i32 = text
+            .parse()
+            .map_err(|_| format!("\"{}\" is not a number", text))?;
+        if value > MAX_CONSTANT {
+            return Err(format!("Modifier too large (max {})", MAX_CONSTANT));
+        }
+        return Ok(DiceTerm::Constant(value));
+    };
+
+    let count_text = &text[..d_index];
+    let count: u32 = if count_text.is_empty() {
+        1
+    } else {
+        count_text
+            .parse()
+            .map_err(|_| format!("\"{}\" is not a dice count", count_text))?
+    };
+    if count == 0 || count > MAX_DICE_PER_TERM {
+        return Err(format!("Dice count must be 1-{}", MAX_DICE_PER_TERM));
+    }
+
+    let after_d = &text[d_index + 1..];
+    let sides_end = after_d
+        .find(|c: char| !c.is_ascii_digit() && c != '%')
+        .unwrap_or(after_d.len());
+    let sides_text = &after_d[..sides_end];
+    let sides: u32 = if sides_text == "%" {
+        100
+    } else {
+        sides_text
+            .parse()
+            .map_err(|_| format!("\"{}\" needs a die size, e.g. d6", text))?
+    };
+    if !(2..=MAX_DIE_SIDES).contains(&sides) {
+        return Err(format!("Die size must be 2-{}", MAX_DIE_SIDES));
+    }
+
+    let keep_text = &after_d[sides_end..];
+    let keep = if keep_text.is_empty() {
+        None
+    } else {
+        let (kind, number) = if let Some(n) = keep_text.strip_prefix("kh") {
+            ("kh", n)
+        } else if let Some(n) = keep_text.strip_prefix("kl") {
+            ("kl", n)
+        } else if let Some(n) = keep_text.strip_prefix("dh") {
+            ("dh", n)
+        } else if let Some(n) = keep_text.strip_prefix("dl") {
+            ("dl", n)
+        } else if let Some(n) = keep_text.strip_prefix('k') {
+            ("kh", n)
+        } else {
+            return Err(format!("Unknown modifier \"{}\"", keep_text));
+        };
+        let n: u32 = number
+            .parse()
+            .map_err(|_| format!("\"{}\" needs a number", keep_text))?;
+        if n == 0 || n > count {
+            return Err(format!("Can only keep or drop 1-{} dice", count));
+        }
+        Some(match kind {
+            "kh" => Keep::Highest(n),
+            "kl" => Keep::Lowest(n),
+            // Dropping is keeping the rest
+            "dl" => Keep::Highest(count - n),
+            _ => Keep::Lowest(count - n),
+        })
+    };
+    if keep.is_some_and(|k| k.count() == 0) {
+        return Err("Can't drop every die".to_string());
+    }
+
+    Ok(DiceTerm::Dice { count, sides, keep })
+}
+
+/// Roll a parsed expression
+pub fn roll(expr: &DiceExpr, rng: &mut fastrand::Rng) -> RollResult {
+    let mut total = 0;
+    let mut terms = Vec::with_capacity(expr.terms.len());
+
+    for signed in &expr.terms {
+        let sign = if signed.negative { -1 } else { 1 };
+        match &signed.term {
+            DiceTerm::Dice { count, sides, keep } => {
+                let mut rolls: Vec<DieRoll> = (0..*count)
+                    .map(|_| DieRoll {
+                        value: rng.u32(1..=*sides),
+                        kept: true,
+                    })
+                    .collect();
+
+                if let Some(keep) = keep {
+                    // Rank dice by value; ties go to the earlier die
+                    let mut order: Vec<usize> = (0..rolls.len()).collect();
+                    match keep {
+                        Keep::Highest(_) => {
+                            order.sort_by_key(|&i| std::cmp::Reverse(rolls[i].value))
+                        }
+                        Keep::Lowest(_) => order.sort_by_key(|&i| rolls[i].value),
+                    }
+                    for &i in order.iter().skip(keep.count() as usize) {
+                        rolls[i].kept = false;
+                    }
+                }
+
+                let sum: i32 = rolls
+                    .iter()
+                    .filter(|r| r.kept)
+                    .map(|r| r.value as i32)
+                    .sum();
+                total += sign * sum;
+                terms.push(RolledTerm::Dice {
+                    negative: signed.negative,
+                    sides: *sides,
+                    rolls,
+                });
+            }
+            DiceTerm::Constant(value) => {
+                total += sign * value;
+                terms.push(RolledTerm::Constant(sign * value));
+            }
+        }
+    }
+
+    RollResult {
+        expression: expr.to_string(),
+        terms,
+        total,
+    }
+}
+
+/// Parse and roll in one step
+pub fn roll_notation(input: &str, rng: &mut fastrand::Rng) -> Result<RollResult, String> {
+    parse(input).map(|expr| roll(&expr, rng))
+}
+
+impl RollResult {
+    /// Individual dice and modifiers, e.g. `[6, 5, 3, (1)] + 2`.
+    /// Dropped dice are shown in parentheses.
+    pub fn breakdown(&self) -> String {
+        let mut text = String::new();
+        for (i, term) in self.terms.iter().enumerate() {
+            let (negative, body) = match term {
+                RolledTerm::Dice {
+                    negative, rolls, ..
+                } => {
+                    let dice: Vec<String> = rolls
+                        .iter()
+                        .map(|r| {
+                            if r.kept {
+                                r.value.to_string()
+                            } else {
+                                format!("({})", r.value)
+                            }
+                        })
+                        .collect();
+                    (*negative, format!("[{}]", dice.join(", ")))
+                }
+                RolledTerm::Constant(value) => (*value < 0, value.abs().to_string()),
+            };
+            match (i, negative) {
+                (0, true) => text.push('-'),
+                (0, false) => {}
+                (_, true) => text.push_str(" - "),
+                (_, false) => text.push_str(" + "),
+            }
+            text.push_str(&body);
+        }
+        text
+    }
+
+    /// The kept d20 when the roll is a single d20 check (with or without
+    /// advantage), for highlighting natural 1s and 20s
+    pub fn natural_d20(&self) -> Option<u32> {
+        let mut d20s = self.terms.iter().filter_map(|t| match t {
+            RolledTerm::Dice {
+                negative: false,
+                sides: 20,
+                rolls,
+            } => Some(rolls),
+            _ => None,
+        });
+        let rolls = d20s.next()?;
+        if d20s.next().is_some() {
+            return None;
+        }
+        let mut kept = rolls.iter().filter(|r| r.kept);
+        let die = kept.next()?;
+        kept.next().is_none().then_some(die.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dice(count: u32, sides: u32, keep: Option<Keep>) -> SignedTerm {
+        SignedTerm {
+            negative: false,
+            term: DiceTerm::Dice { count, sides, keep },
+        }
+    }
+
+    #[test]
+    fn test_parse_simple_check() {
+        let expr = parse("1d20+5").unwrap();
+        assert_eq!(expr.terms.len(), 2);
+        assert_eq!(expr.terms[0], dice(1, 20, None));
+        assert_eq!(expr.terms[1].term, DiceTerm::Constant(5));
+        assert_eq!(expr.to_string(), "1d20+5");
+    }
+
+    #[test]
+    fn test_parse_keep_and_drop() {
+        assert_eq!(
+            parse("4d6kh3").unwrap().terms[0],
+            dice(4, 6, Some(Keep::Highest(3)))
+        );
+        assert_eq!(parse("4d6k3").unwrap(), parse("4d6kh3").unwrap());
+        assert_eq!(parse("4d6dl1").unwrap(), parse("4d6kh3").unwrap());
+        assert_eq!(parse("2d20dh1").unwrap(), parse("2d20kl1").unwrap());
+    }
+
+    #[test]
+    fn test_parse_mixed_terms_and_whitespace() {
+        let expr = parse(" 2d8 + 1D6 - 3 ").unwrap();
+        assert_eq!(expr.to_string(), "2d8+1d6-3");
+        assert_eq!(parse("d%").unwrap().to_string(), "1d100");
+    }
+
+    #[test]
+    fn test_parse_advantage_and_disadvantage() {
+        assert_eq!(parse("1d20+5 adv").unwrap().to_string(), "2d20kh1+5");
+        assert_eq!(parse("d20 disadvantage").unwrap().to_string(), "2d20kl1");
+        assert_eq!(parse("adv").unwrap().to_string(), "2d20kh1");
+        assert!(parse("2d6 adv").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [
+            "", "1d", "d20+", "1d20++2", "0d6", "4d6kh5", "4d6x", "1d1", "abc",
+        ] {
+            assert!(parse(input).is_err(), "{input:?} should not parse");
+        }
+        assert!(parse("101d6").is_err());
+        assert!(parse("4d6dl4").is_err());
+    }
+
+    #[test]
+    fn test_seeded_rolls_are_repeatable() {
+        let expr = parse("4d6kh3+2").unwrap();
+        let a = roll(&expr, &mut fastrand::Rng::with_seed(7));
+        let b = roll(&expr, &mut fastrand::Rng::with_seed(7));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_keep_highest_drops_lowest_die() {
+        let mut rng = fastrand::Rng::with_seed(1);
+        for _ in 0..50 {
+            let result = roll_notation("4d6kh3", &mut rng).unwrap();
+            let RolledTerm::Dice { rolls, .. } = &result.terms[0] else {
+                panic!("expected dice");
+            };
+            assert_eq!(rolls.iter().filter(|r| r.kept).count(), 3);
+            let min_kept = rolls
+                .iter()
+                .filter(|r| r.kept)
+                .map(|r| r.value)
+                .min()
+                .unwrap();
+            let dropped = rolls.iter().find(|r| !r.kept).unwrap().value;
+            assert!(dropped <= min_kept);
+            let sum: u32 = rolls.iter().filter(|r| r.kept).map(|r| r.value).sum();
+            assert_eq!(result.total, sum as i32);
+        }
+    }
+
+    #[test]
+    fn test_totals_stay_in_range() {
+        let mut rng = fastrand::Rng::with_seed(99);
+        for _ in 0..200 {
+            let total = roll_notation("2d8+1d6+3", &mut rng).unwrap().total;
+            assert!((6..=25).contains(&total));
+            let total = roll_notation("1d4-5", &mut rng).unwrap().total;
+            assert!((-4..=-1).contains(&total));
+        }
+    }
+
+    #[test]
+    fn test_advantage_keeps_higher_d20() {
+        let mut rng = fastrand::Rng::with_seed(3);
+        for _ in 0..50 {
+            let result = roll_notation("1d20+1 adv", &mut rng).unwrap();
+            let RolledTerm::Dice { rolls, .. } = &result.terms[0] else {
+                panic!("expected dice");
+            };
+            let best = rolls.iter().map(|r| r.value).max().unwrap();
+            assert_eq!(result.natural_d20(), Some(best));
+            assert_eq!(result.total, best as i32 + 1);
+        }
+    }
+
+    #[test]
+    fn test_natural_d20_only_for_single_checks() {
+        let mut rng = fastrand::Rng::with_seed(5);
+        assert!(roll_notation("1d20+3", &mut rng)
+            .unwrap()
+            .natural_d20()
+            .is_some());
+        assert!(roll_notation("2d20", &mut rng)
+            .unwrap()
+            .natural_d20()
+            .is_none());
+        assert!(roll_notation("1d8+2", &mut rng)
+            .unwrap()
+            .natural_d20()
+            .is_none());
+    }
+
+    #[test]
+    fn test_breakdown_marks_dropped_dice() {
+        let result = RollResult {
+            expression: "4d6kh3-1".to_string(),
+            terms: vec![
+                RolledTerm::Dice {
+                    negative: false,
+                    sides: 6,
+                    rolls: vec![
+                        DieRoll {
+                            value: 6,
+                            kept: true,
+                        },
+                        DieRoll {
+                            value: 1,
+                            kept: false,
+                        },
+                        DieRoll {
+                            value: 4,
+                            kept: true,
+                        },
+                        DieRoll {
+                            value: 3,
+                            kept: true,
+                        },
+                    ],
+                },
+                RolledTerm::Constant(-1),
+            ],
+            total: 12,
+        };
+        assert_eq!(result.breakdown(), "[6, (1), 4, 3] - 1");
+    }
+}
@@ -18,17 +18,105 @@ pub struct DrawnLine {
     pub stroke_width: f32,
 }
 
-#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+/// Kind of a shape annotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ShapeKind {
+    #[default]
+    Rectangle,
+    Ellipse,
+    Polygon,
+    Arrow,
+}
+
+impl ShapeKind {
+    pub fn all() -> &'static [ShapeKind] {
+        &[
+            ShapeKind::Rectangle,
+            ShapeKind::Ellipse,
+            ShapeKind::Polygon,
+            ShapeKind::Arrow,
+        ]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ShapeKind::Rectangle => "Rectangle",
+            ShapeKind::Ellipse => "Ellipse",
+            ShapeKind::Polygon => "Polygon",
+            ShapeKind::Arrow => "Arrow",
+        }
+    }
+
+    /// Whether the shape encloses an area that can be filled
+    pub fn is_closed(&self) -> bool {
+        !matches!(self, ShapeKind::Arrow)
+    }
+}
+
+/// A shape annotation. Rectangles and ellipses store two opposite corners of
+/// their bounds, arrows their tail and head, and polygons their vertices.
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrawnShape {
+    pub kind: ShapeKind,
+    pub points: Vec<Vec2>,
+    pub color: Color,
+    pub stroke_width: f32,
+    /// Fill color for closed shapes, if any
+    #[serde(default)]
+    pub fill: Option<Color>,
+    #[serde(default)]
+    pub dashed: bool,
+}
+
+/// Advance of one glyph in the default (monospace) font, relative to font size
+const GLYPH_ADVANCE: f32 = 0.6;
+
+/// Default line height, relative to font size
+const LINE_HEIGHT: f32 = 1.2;
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextAnnotation {
     pub content: String,
     pub font_size: f32,
     pub color: Color,
+    /// Box drawn behind the text, if any
+    #[serde(default)]
+    pub background: Option<Color>,
+}
+
+impl TextAnnotation {
+    /// Size of the rendered text block in world units
+    pub fn size(&self) -> Vec2 {
+        let lines = self.content.split('\n');
+        let line_count = lines.clone().count() as f32;
+        let longest = lines.map(|line| line.chars().count()).max().unwrap_or(0) as f32;
+        Vec2::new(
+            longest * self.font_size * GLYPH_ADVANCE,
+            line_count * self.font_size * LINE_HEIGHT,
+        )
+    }
+
+    /// Padding around the text block, used for the background box and bounds
+    pub fn padding(&self) -> f32 {
+        self.font_size * 0.25
+    }
 }
 
 #[derive(Component)]
 pub struct AnnotationMarker;
 
-// Text tool disabled - see TODO in tools.rs
-#[allow(dead_code)]
+/// Marks an annotation that is also drawn in the player view
+#[derive(Component)]
+pub struct ShowToPlayers;
+
+/// Marks a text annotation whose content is open in the text editor popup
 #[derive(Component)]
 pub struct EditingText;
+
+/// Background box sprite, spawned as a child of a text annotation
+#[derive(Component)]
+pub struct TextAnnotationBackground;
+
+/// Fill mesh, spawned as a child of a shape annotation
+#[derive(Component)]
+pub struct ShapeFill;
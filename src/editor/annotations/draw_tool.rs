@@ -1,4 +1,7 @@
 //! Draw tool system for freehand path drawing.
+//!
+//! In sketch mode during a live session, finished strokes become fading
+//! [`SessionSketch`]es instead of saved paths.
 
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
@@ -11,7 +14,9 @@ use super::super::history::{EditorCommand, PathData, RecordEditorCommand};
 use super::super::tools::EditorTool;
 use super::components::{AnnotationMarker, DrawnPath};
 use super::layer_helpers::is_annotation_layer_locked;
-use super::state::{AnnotationResources, AnnotationSettings, DrawState};
+use super::sketch::SessionSketch;
+use super::smoothing::{catmull_rom, simplify_rdp, SIMPLIFY_TOLERANCE, SMOOTHING_SAMPLES};
+use super::state::{AnnotationResources, AnnotationSettings};
 
 pub fn handle_draw(
     mut commands: Commands,
@@ -24,15 +29,15 @@ pub fn handle_draw(
     if res.current_tool.tool != EditorTool::Draw {
         // If we were drawing but switched tools, finalize
         if res.draw_state.is_drawing && res.draw_state.current_points.len() >= 2 {
-            spawn_drawn_path(&mut commands, &res.draw_state, &res.settings, &mut history_writer);
+            finish_stroke(&mut commands, &mut res, &mut history_writer);
         }
         res.draw_state.is_drawing = false;
         res.draw_state.current_points.clear();
         return;
     }
 
-    // Don't allow drawing if annotation layer is locked
-    if is_annotation_layer_locked(&res.map_data) {
+    // Don't allow drawing if annotation layer is locked (sketches aren't on it)
+    if is_annotation_layer_locked(&res.map_data) && !res.is_sketching() {
         return;
     }
 
@@ -72,20 +77,43 @@ pub fn handle_draw(
     } else if res.mouse_button.just_released(MouseButton::Left) && res.draw_state.is_drawing {
         res.draw_state.is_drawing = false;
         if res.draw_state.current_points.len() >= 2 {
-            spawn_drawn_path(&mut commands, &res.draw_state, &res.settings, &mut history_writer);
+            finish_stroke(&mut commands, &mut res, &mut history_writer);
         }
         res.draw_state.current_points.clear();
     }
 }
 
+/// Turn the stroke just drawn into a path, or a sketch in sketch mode
+fn finish_stroke(
+    commands: &mut Commands,
+    res: &mut AnnotationResources,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    // Drop redundant mouse samples, then optionally round off what's left
+    let mut points = simplify_rdp(&res.draw_state.current_points, SIMPLIFY_TOLERANCE);
+    if res.settings.smooth_strokes {
+        points = catmull_rom(&points, SMOOTHING_SAMPLES);
+    }
+
+    if res.is_sketching() {
+        let color = res.settings.stroke_color;
+        res.sketches.sketches.push(SessionSketch {
+            points,
+            color,
+            age: 0.0,
+        });
+    } else {
+        spawn_drawn_path(commands, points, &res.settings, history_writer);
+    }
+}
+
 fn spawn_drawn_path(
     commands: &mut Commands,
-    draw_state: &DrawState,
+    points: Vec<Vec2>,
     settings: &AnnotationSettings,
     history_writer: &mut MessageWriter<RecordEditorCommand>,
 ) {
     let z = Layer::Annotation.z_base();
-    let points = draw_state.current_points.clone();
     let color = settings.stroke_color;
     let stroke_width = settings.stroke_width;
     let entity = commands
@@ -107,6 +135,7 @@ fn spawn_drawn_path(
                 points,
                 color,
                 stroke_width,
+                show_to_players: false,
             },
         },
     });
@@ -0,0 +1,296 @@
+//! Eraser tool system for partially erasing paths and lines.
+//!
+//! Dragging the eraser cuts away the parts of freehand paths and straight
+//! lines under its circle, splitting them into pieces where needed. The whole
+//! stroke is recorded as a single undo step.
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::map::MapData;
+
+use super::super::history::{
+    spawn_line, spawn_path, EditorCommand, LineData, PathData, RecordEditorCommand,
+};
+use super::super::params::{is_cursor_over_ui, CameraParams};
+use super::super::tools::{CurrentTool, EditorTool};
+use super::components::{AnnotationMarker, DrawnLine, DrawnPath, ShowToPlayers};
+use super::layer_helpers::{is_annotation_layer_locked, is_annotation_layer_visible};
+use super::state::{AnnotationSettings, ErasedAnnotation, EraserState};
+
+/// Erase the parts of a polyline inside a circle.
+///
+/// Returns None when the circle doesn't touch the polyline, otherwise the
+/// pieces left over (possibly none).
+pub fn erase_polyline(points: &[Vec2], center: Vec2, radius: f32) -> Option<Vec<Vec<Vec2>>> {
+    if points.len() < 2 {
+        return points
+            .iter()
+            .any(|point| point.distance(center) <= radius)
+            .then(Vec::new);
+    }
+
+    let mut touched = false;
+    let mut pieces = Vec::new();
+    let mut current: Vec<Vec2> = Vec::new();
+    for window in points.windows(2) {
+        let (a, b) = (window[0], window[1]);
+        let Some((enter, exit)) = segment_circle_overlap(a, b, center, radius) else {
+            if current.is_empty() {
+                current.push(a);
+            }
+            current.push(b);
+            continue;
+        };
+
+        touched = true;
+        if enter > 0.0 {
+            if current.is_empty() {
+                current.push(a);
+            }
+            current.push(a.lerp(b, enter));
+        }
+        finish_piece(&mut pieces, &mut current);
+        if exit < 1.0 {
+            current.push(a.lerp(b, exit));
+            current.push(b);
+        }
+    }
+    finish_piece(&mut pieces, &mut current);
+
+    touched.then_some(pieces)
+}
+
+/// Parameter range `(enter, exit)` within [0, 1] where segment `a`-`b` lies
+/// inside the circle, if it touches it at all
+fn segment_circle_overlap(a: Vec2, b: Vec2, center: Vec2, radius: f32) -> Option<(f32, f32)> {
+    let direction = b - a;
+    let offset = a - center;
+    let qa = direction.length_squared();
+    let qb = 2.0 * offset.dot(direction);
+    let qc = offset.length_squared() - radius * radius;
+
+    if qa == 0.0 {
+        return (qc <= 0.0).then_some((0.0, 1.0));
+    }
+    let discriminant = qb * qb - 4.0 * qa * qc;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let enter = ((-qb - root) / (2.0 * qa)).max(0.0);
+    let exit = ((-qb + root) / (2.0 * qa)).min(1.0);
+    (enter < exit).then_some((enter, exit))
+}
+
+fn finish_piece(pieces: &mut Vec<Vec<Vec2>>, current: &mut Vec<Vec2>) {
+    let piece = std::mem::take(current);
+    let length: f32 = piece.windows(2).map(|w| w[0].distance(w[1])).sum();
+    if length > 0.0 {
+        pieces.push(piece);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_eraser(
+    mut commands: Commands,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    current_tool: Res<CurrentTool>,
+    mut eraser_state: ResMut<EraserState>,
+    settings: Res<AnnotationSettings>,
+    map_data: Res<MapData>,
+    camera: CameraParams,
+    mut contexts: EguiContexts,
+    paths_query: Query<(Entity, &DrawnPath, Has<ShowToPlayers>), With<AnnotationMarker>>,
+    lines_query: Query<(Entity, &DrawnLine, Has<ShowToPlayers>), With<AnnotationMarker>>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    let erasing = current_tool.tool == EditorTool::Eraser
+        && !is_annotation_layer_locked(&map_data)
+        && is_annotation_layer_visible(&map_data);
+
+    if !erasing || !mouse_button.pressed(MouseButton::Left) {
+        finish_eraser_stroke(
+            &mut eraser_state,
+            &paths_query,
+            &lines_query,
+            &mut history_writer,
+        );
+        return;
+    }
+
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return;
+    };
+
+    if mouse_button.just_pressed(MouseButton::Left) {
+        if is_cursor_over_ui(&mut contexts) {
+            return;
+        }
+        eraser_state.is_erasing = true;
+    }
+    if !eraser_state.is_erasing {
+        return;
+    }
+
+    // Step along the cursor's movement since last frame so fast strokes
+    // don't skip over thin lines
+    let radius = settings.eraser_radius;
+    let from = eraser_state.last_position.unwrap_or(world_pos);
+    eraser_state.last_position = Some(world_pos);
+    let steps = ((from.distance(world_pos) / (radius * 0.5)).ceil() as usize).max(1);
+    let centers: Vec<Vec2> = (1..=steps)
+        .map(|step| from.lerp(world_pos, step as f32 / steps as f32))
+        .collect();
+
+    let erase = |points: &[Vec2]| -> Option<Vec<Vec<Vec2>>> {
+        let mut pieces = vec![points.to_vec()];
+        let mut touched = false;
+        for center in &centers {
+            pieces = pieces
+                .into_iter()
+                .flat_map(|piece| match erase_polyline(&piece, *center, radius) {
+                    Some(remaining) => {
+                        touched = true;
+                        remaining
+                    }
+                    None => vec![piece],
+                })
+                .collect();
+        }
+        touched.then_some(pieces)
+    };
+
+    for (entity, path, show_to_players) in &paths_query {
+        let Some(pieces) = erase(&path.points) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        let original = PathData {
+            show_to_players,
+            ..PathData::from(path)
+        };
+        eraser_state.replace(entity, ErasedAnnotation::Path(original));
+        for points in pieces {
+            let data = PathData {
+                points,
+                color: path.color,
+                stroke_width: path.stroke_width,
+                show_to_players,
+            };
+            eraser_state.pieces.push(spawn_path(&mut commands, &data));
+        }
+    }
+
+    for (entity, line, show_to_players) in &lines_query {
+        let Some(pieces) = erase(&[line.start, line.end]) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        let original = LineData {
+            show_to_players,
+            ..LineData::from(line)
+        };
+        eraser_state.replace(entity, ErasedAnnotation::Line(original));
+        for points in pieces {
+            let data = LineData {
+                start: points[0],
+                end: points[points.len() - 1],
+                color: line.color,
+                stroke_width: line.stroke_width,
+                show_to_players,
+            };
+            eraser_state.pieces.push(spawn_line(&mut commands, &data));
+        }
+    }
+}
+
+/// Record the stroke just finished as one undo step: the originals it
+/// touched are deleted and the surviving pieces created
+fn finish_eraser_stroke(
+    eraser_state: &mut EraserState,
+    paths_query: &Query<(Entity, &DrawnPath, Has<ShowToPlayers>), With<AnnotationMarker>>,
+    lines_query: &Query<(Entity, &DrawnLine, Has<ShowToPlayers>), With<AnnotationMarker>>,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    eraser_state.is_erasing = false;
+    eraser_state.last_position = None;
+    if eraser_state.erased.is_empty() {
+        return;
+    }
+
+    let mut batch: Vec<EditorCommand> = eraser_state
+        .erased
+        .drain(..)
+        .map(|erased| match erased {
+            ErasedAnnotation::Path(path) => EditorCommand::DeletePath { path },
+            ErasedAnnotation::Line(line) => EditorCommand::DeleteLine { line },
+        })
+        .collect();
+    for entity in eraser_state.pieces.drain(..) {
+        if let Ok((_, path, show_to_players)) = paths_query.get(entity) {
+            batch.push(EditorCommand::CreatePath {
+                entity,
+                path: PathData {
+                    show_to_players,
+                    ..PathData::from(path)
+                },
+            });
+        } else if let Ok((_, line, show_to_players)) = lines_query.get(entity) {
+            batch.push(EditorCommand::CreateLine {
+                entity,
+                line: LineData {
+                    show_to_players,
+                    ..LineData::from(line)
+                },
+            });
+        }
+    }
+
+    history_writer.write(RecordEditorCommand {
+        command: EditorCommand::Batch { commands: batch },
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erase_middle_splits_line() {
+        let points = [Vec2::ZERO, Vec2::new(100.0, 0.0)];
+        let pieces = erase_polyline(&points, Vec2::new(50.0, 0.0), 10.0).unwrap();
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[0][0], Vec2::ZERO);
+        assert!(pieces[0][1].abs_diff_eq(Vec2::new(40.0, 0.0), 1e-3));
+        assert!(pieces[1][0].abs_diff_eq(Vec2::new(60.0, 0.0), 1e-3));
+        assert_eq!(pieces[1][1], Vec2::new(100.0, 0.0));
+    }
+
+    #[test]
+    fn test_erase_misses() {
+        let points = [Vec2::ZERO, Vec2::new(100.0, 0.0)];
+        assert!(erase_polyline(&points, Vec2::new(50.0, 20.0), 10.0).is_none());
+    }
+
+    #[test]
+    fn test_erase_end_of_path() {
+        let points = [Vec2::ZERO, Vec2::new(50.0, 0.0), Vec2::new(50.0, 50.0)];
+        let pieces = erase_polyline(&points, Vec2::new(50.0, 50.0), 10.0).unwrap();
+        assert_eq!(
+            pieces,
+            vec![vec![
+                Vec2::ZERO,
+                Vec2::new(50.0, 0.0),
+                Vec2::new(50.0, 40.0)
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_erase_whole_line() {
+        let points = [Vec2::ZERO, Vec2::new(5.0, 0.0)];
+        let pieces = erase_polyline(&points, Vec2::new(2.5, 0.0), 10.0).unwrap();
+        assert!(pieces.is_empty());
+    }
+}
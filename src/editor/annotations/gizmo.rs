@@ -1,4 +1,8 @@
-//! Custom gizmo group for annotations (editor-only rendering).
+//! Custom gizmo groups for annotations.
+//!
+//! Annotations render in the editor only, except those marked
+//! [`super::ShowToPlayers`] and session sketches, which are also drawn with
+//! [`AnnotationPlayerGizmoGroup`] in the player view.
 
 use bevy::camera::visibility::RenderLayers;
 use bevy::gizmos::config::{GizmoConfigGroup, GizmoConfigStore};
@@ -8,9 +12,16 @@ use bevy::prelude::*;
 #[derive(Default, Reflect, GizmoConfigGroup)]
 pub struct AnnotationGizmoGroup;
 
-/// Configure the annotation gizmo group to only render to editor camera (layer 1)
+/// Gizmo group for annotations shown to players (player view only)
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct AnnotationPlayerGizmoGroup;
+
+/// Configure the annotation gizmo groups for their respective render layers
 pub fn configure_annotation_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
     let (config, _) = config_store.config_mut::<AnnotationGizmoGroup>();
     // Only render to layer 1 (editor-only, not visible in player view)
     config.render_layers = RenderLayers::layer(1);
+
+    let (player_config, _) = config_store.config_mut::<AnnotationPlayerGizmoGroup>();
+    player_config.render_layers = RenderLayers::layer(2);
 }
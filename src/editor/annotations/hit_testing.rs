@@ -2,7 +2,8 @@
 
 use bevy::prelude::*;
 
-use super::components::{DrawnLine, DrawnPath, TextAnnotation};
+use super::components::{DrawnLine, DrawnPath, DrawnShape, TextAnnotation};
+use super::shapes::{outline_points, point_in_polygon, stroke_segments};
 
 /// Check if a point is within a given distance of a line segment
 fn point_near_segment(point: Vec2, seg_start: Vec2, seg_end: Vec2, threshold: f32) -> bool {
@@ -39,16 +40,34 @@ pub fn point_near_line(point: Vec2, line: &DrawnLine) -> bool {
     point_near_segment(point, line.start, line.end, threshold)
 }
 
+/// Check if a point is on a shape's outline, or inside it when filled
+pub fn point_near_shape(point: Vec2, shape: &DrawnShape) -> bool {
+    let threshold = (shape.stroke_width * 2.0).max(8.0);
+    if shape.fill.is_some() && point_in_polygon(point, &outline_points(shape)) {
+        return true;
+    }
+    stroke_segments(&DrawnShape {
+        dashed: false,
+        ..shape.clone()
+    })
+    .iter()
+    .any(|&(start, end)| point_near_segment(point, start, end, threshold))
+}
+
 /// Check if a point is inside a text annotation's bounding box
 pub fn point_in_text(point: Vec2, transform: &Transform, text: &TextAnnotation) -> bool {
     let pos = transform.translation.truncate();
-    let width = (text.content.len() as f32 * text.font_size * 0.5).max(40.0);
-    let height = text.font_size.max(20.0);
-    let half_size = Vec2::new(width / 2.0, height / 2.0);
+    let half_size = text_half_size(text);
 
     (point.x - pos.x).abs() < half_size.x && (point.y - pos.y).abs() < half_size.y
 }
 
+/// Half-size of a text annotation's box, including padding
+pub fn text_half_size(text: &TextAnnotation) -> Vec2 {
+    // Keep empty or tiny text clickable
+    (text.size() / 2.0 + Vec2::splat(text.padding())).max(Vec2::new(20.0, 10.0))
+}
+
 /// Get the bounding box of a path (min, max corners)
 pub fn path_bounds(path: &DrawnPath) -> (Vec2, Vec2) {
     if path.points.is_empty() {
@@ -76,44 +95,21 @@ pub fn line_bounds(line: &DrawnLine) -> (Vec2, Vec2) {
     (min - Vec2::splat(padding), max + Vec2::splat(padding))
 }
 
+/// Get the bounding box of a shape (min, max corners)
+pub fn shape_bounds(shape: &DrawnShape) -> (Vec2, Vec2) {
+    let mut points = outline_points(shape).into_iter();
+    let Some(first) = points.next() else {
+        return (Vec2::ZERO, Vec2::ZERO);
+    };
+    let (min, max) = points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+    let padding = shape.stroke_width;
+    (min - Vec2::splat(padding), max + Vec2::splat(padding))
+}
+
 /// Get the bounding box of a text annotation (min, max corners)
 pub fn text_bounds(transform: &Transform, text: &TextAnnotation) -> (Vec2, Vec2) {
     let pos = transform.translation.truncate();
-    let width = (text.content.len() as f32 * text.font_size * 0.5).max(40.0);
-    let height = text.font_size.max(20.0);
-    let half_size = Vec2::new(width / 2.0, height / 2.0);
+    let half_size = text_half_size(text);
 
     (pos - half_size, pos + half_size)
 }
-
-/// Check if a path overlaps with a selection rectangle
-pub fn path_overlaps_rect(rect_min: Vec2, rect_max: Vec2, path: &DrawnPath) -> bool {
-    let (path_min, path_max) = path_bounds(path);
-    rect_min.x < path_max.x
-        && rect_max.x > path_min.x
-        && rect_min.y < path_max.y
-        && rect_max.y > path_min.y
-}
-
-/// Check if a line overlaps with a selection rectangle
-pub fn line_overlaps_rect(rect_min: Vec2, rect_max: Vec2, line: &DrawnLine) -> bool {
-    let (line_min, line_max) = line_bounds(line);
-    rect_min.x < line_max.x
-        && rect_max.x > line_min.x
-        && rect_min.y < line_max.y
-        && rect_max.y > line_min.y
-}
-
-/// Check if a text annotation overlaps with a selection rectangle
-pub fn text_overlaps_rect(
-    rect_min: Vec2,
-    rect_max: Vec2,
-    transform: &Transform,
-    text: &TextAnnotation,
-) -> bool {
-    let (text_min, text_max) = text_bounds(transform, text);
-    rect_min.x < text_max.x
-        && rect_max.x > text_min.x
-        && rect_min.y < text_max.y
-        && rect_max.y > text_min.y
-}
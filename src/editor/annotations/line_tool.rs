@@ -69,6 +69,7 @@ pub fn handle_line(
                         end: world_pos,
                         color,
                         stroke_width,
+                        show_to_players: false,
                     },
                 },
             });
@@ -1,71 +1,97 @@
 //! Annotation system for drawing on maps (editor-only layer).
 //!
-//! Annotations allow users to mark up maps with freehand paths, straight lines, and text.
+//! Annotations allow users to mark up maps with freehand paths, straight lines, shapes,
+//! and text.
 //! These are stored on the Annotation layer (z=350) and are only visible in the editor,
-//! not in the player view.
+//! unless marked [`ShowToPlayers`]. During a live session the Draw tool can also
+//! sketch temporary strokes that show in the player view and fade out.
 //!
 //! ## Module Structure
 //!
-//! - [`components`] - Entity components (DrawnPath, DrawnLine, TextAnnotation)
-//! - [`state`] - State resources (DrawState, LineDrawState, AnnotationSettings)
-//! - [`gizmo`] - Custom gizmo group for editor-only rendering
+//! - [`components`] - Entity components (DrawnPath, DrawnLine, DrawnShape, TextAnnotation)
+//! - [`state`] - State resources (DrawState, LineDrawState, ShapeDrawState, EraserState,
+//!   AnnotationSettings)
+//! - [`gizmo`] - Custom gizmo groups for editor and player rendering
 //! - [`hit_testing`] - Hit detection functions for selection
 //! - [`layer_helpers`] - Layer visibility/locking helpers
 //! - [`draw_tool`] - Freehand drawing system
+//! - [`smoothing`] - Freehand stroke simplification and smoothing
+//! - [`eraser_tool`] - Partial erasing of paths and lines
 //! - [`line_tool`] - Straight line drawing system
-//! - [`rendering`] - Gizmo rendering systems
-//! - [`text_tool`] - Text annotation system (disabled)
+//! - [`shape_tool`] - Rectangle, ellipse, polygon and arrow drawing system
+//! - [`shapes`] - Shape outline, dash and fill geometry
+//! - [`rendering`] - Gizmo, shape fill and text rendering systems
+//! - [`text_tool`] - Text creation and in-place editing
+//! - [`sketch`] - Fading session sketches shown to players
 //!
 //! ## Annotation Types
 //!
 //! - [`DrawnPath`]: Freehand drawing paths (a series of connected points)
 //! - [`DrawnLine`]: Straight lines between two points
-//! - [`TextAnnotation`]: Text labels at specific positions
+//! - [`DrawnShape`]: Rectangles, ellipses, polygons and arrows with optional fill and dashes
+//! - [`TextAnnotation`]: Multi-line text labels with optional background box
 //!
 //! ## Hit Testing
 //!
 //! Helper functions for detecting clicks on annotations:
 //! - [`point_near_path`]: Check if a point is near a drawn path
 //! - [`point_near_line`]: Check if a point is near a line
+//! - [`point_near_shape`]: Check if a point is on a shape's outline (or inside a filled one)
 //! - [`point_in_text`]: Check if a point is inside text bounds
 
 mod components;
 mod draw_tool;
+mod eraser_tool;
 mod gizmo;
 mod hit_testing;
 mod layer_helpers;
 mod line_tool;
 mod rendering;
+mod shape_tool;
+mod shapes;
+mod sketch;
+mod smoothing;
 mod state;
 mod text_tool;
 
 // Re-exports - Components
-pub use components::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
+pub use components::{
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, ShapeKind, ShowToPlayers, TextAnnotation,
+};
 
 // Re-exports - State
-pub use state::{AnnotationSettings, DrawState, LineDrawState, TextEditState};
+pub use state::{
+    AnnotationSettings, DrawState, EraserState, LineDrawState, ShapeDrawState, TextEditState,
+};
 
 // Re-exports - Gizmo
-pub use gizmo::{configure_annotation_gizmos, AnnotationGizmoGroup};
+pub use gizmo::{configure_annotation_gizmos, AnnotationGizmoGroup, AnnotationPlayerGizmoGroup};
 
 // Re-exports - Hit Testing
 pub use hit_testing::{
-    line_bounds, line_overlaps_rect, path_bounds, path_overlaps_rect, point_in_text,
-    point_near_line, point_near_path, text_bounds, text_overlaps_rect,
+    line_bounds, path_bounds, point_in_text, point_near_line, point_near_path, point_near_shape,
+    shape_bounds, text_bounds, text_half_size,
 };
 
+// Re-exports - Geometry (shared with the Fill tool)
+pub use shapes::point_in_polygon;
+
 // Re-exports - Layer Helpers
 pub use layer_helpers::{is_annotation_layer_locked, is_annotation_layer_visible};
 
 // Re-exports - Systems
 pub use draw_tool::handle_draw;
+pub use eraser_tool::handle_eraser;
 pub use line_tool::handle_line;
 pub use rendering::{
-    render_draw_preview, render_drawn_lines, render_drawn_paths, render_line_preview,
+    render_draw_preview, render_drawn_lines, render_drawn_paths, render_drawn_shapes,
+    render_eraser_preview, render_line_preview, render_shape_preview,
+    sync_annotation_render_layers, sync_shape_fills, sync_text_annotations,
+    update_annotation_visibility,
+};
+pub use shape_tool::handle_shape;
+pub use sketch::{draw_session_sketches, SessionSketches};
+pub use text_tool::{
+    commit_text_edit_on_tool_change, handle_text, handle_text_double_click,
+    text_annotation_input_ui, TEXT_FONT_SIZE_RANGE,
 };
-
-// Text tool disabled - see TODO in tools.rs
-#[allow(unused_imports)]
-pub use rendering::render_text_annotations;
-#[allow(unused_imports)]
-pub use text_tool::{handle_text, text_annotation_input_ui};
@@ -1,50 +1,91 @@
-//! Rendering systems for annotations (editor-only via gizmos).
+//! Rendering systems for annotations (via gizmos and Text2d).
+//!
+//! Everything renders in the editor; annotations marked [`ShowToPlayers`] are
+//! also drawn in the player view while a live session is running.
 
+use bevy::camera::visibility::RenderLayers;
+use bevy::asset::RenderAssetUsages;
 use bevy::gizmos::prelude::*;
+use bevy::mesh::{Indices, PrimitiveTopology};
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
-use bevy_egui::egui;
-use bevy_egui::EguiContexts;
 
 use super::super::camera::EditorCamera;
+use super::super::params::CameraParams;
 use super::super::tools::{CurrentTool, EditorTool};
-use super::components::{DrawnLine, DrawnPath, EditingText, TextAnnotation};
-use super::gizmo::AnnotationGizmoGroup;
+use super::components::{
+    DrawnLine, DrawnPath, DrawnShape, EditingText, ShapeFill, ShapeKind, ShowToPlayers,
+    TextAnnotation, TextAnnotationBackground,
+};
+use super::gizmo::{AnnotationGizmoGroup, AnnotationPlayerGizmoGroup};
 use super::layer_helpers::is_annotation_layer_visible;
-use super::state::{AnnotationSettings, DrawState, LineDrawState};
+use super::shapes::{fill_triangles, shape_from_drag, stroke_segments};
+use super::state::{AnnotationSettings, DrawState, LineDrawState, ShapeDrawState};
 use crate::map::MapData;
+use crate::session::LiveSessionState;
 
 pub fn render_drawn_paths(
     mut gizmos: Gizmos<AnnotationGizmoGroup>,
-    paths: Query<&DrawnPath>,
+    mut player_gizmos: Gizmos<AnnotationPlayerGizmoGroup>,
+    paths: Query<(&DrawnPath, Has<ShowToPlayers>)>,
     map_data: Res<MapData>,
+    session_state: Res<LiveSessionState>,
 ) {
     if !is_annotation_layer_visible(&map_data) {
         return;
     }
 
-    for path in paths.iter() {
+    for (path, show_to_players) in paths.iter() {
         if path.points.len() < 2 {
             continue;
         }
 
         for window in path.points.windows(2) {
             gizmos.line_2d(window[0], window[1], path.color);
+            if show_to_players && session_state.is_active {
+                player_gizmos.line_2d(window[0], window[1], path.color);
+            }
         }
     }
 }
 
 pub fn render_drawn_lines(
     mut gizmos: Gizmos<AnnotationGizmoGroup>,
-    lines: Query<&DrawnLine>,
+    mut player_gizmos: Gizmos<AnnotationPlayerGizmoGroup>,
+    lines: Query<(&DrawnLine, Has<ShowToPlayers>)>,
     map_data: Res<MapData>,
+    session_state: Res<LiveSessionState>,
 ) {
     if !is_annotation_layer_visible(&map_data) {
         return;
     }
 
-    for line in lines.iter() {
+    for (line, show_to_players) in lines.iter() {
         gizmos.line_2d(line.start, line.end, line.color);
+        if show_to_players && session_state.is_active {
+            player_gizmos.line_2d(line.start, line.end, line.color);
+        }
+    }
+}
+
+pub fn render_drawn_shapes(
+    mut gizmos: Gizmos<AnnotationGizmoGroup>,
+    mut player_gizmos: Gizmos<AnnotationPlayerGizmoGroup>,
+    shapes: Query<(&DrawnShape, Has<ShowToPlayers>)>,
+    map_data: Res<MapData>,
+    session_state: Res<LiveSessionState>,
+) {
+    if !is_annotation_layer_visible(&map_data) {
+        return;
+    }
+
+    for (shape, show_to_players) in shapes.iter() {
+        for (start, end) in stroke_segments(shape) {
+            gizmos.line_2d(start, end, shape.color);
+            if show_to_players && session_state.is_active {
+                player_gizmos.line_2d(start, end, shape.color);
+            }
+        }
     }
 }
 
@@ -87,9 +128,11 @@ pub fn render_line_preview(
 
 pub fn render_draw_preview(
     mut gizmos: Gizmos<AnnotationGizmoGroup>,
+    mut player_gizmos: Gizmos<AnnotationPlayerGizmoGroup>,
     current_tool: Res<CurrentTool>,
     draw_state: Res<DrawState>,
     settings: Res<AnnotationSettings>,
+    session_state: Res<LiveSessionState>,
 ) {
     if current_tool.tool != EditorTool::Draw || !draw_state.is_drawing {
         return;
@@ -99,66 +142,206 @@ pub fn render_draw_preview(
         return;
     }
 
+    // Players watch sketches being drawn
+    let sketching = settings.session_sketch && session_state.is_active;
     for window in draw_state.current_points.windows(2) {
         gizmos.line_2d(window[0], window[1], settings.stroke_color);
+        if sketching {
+            player_gizmos.line_2d(window[0], window[1], settings.stroke_color);
+        }
     }
 }
 
-// Text tool disabled - see TODO in tools.rs
-#[allow(dead_code)]
-/// Render text annotations using egui (editor-only, doesn't show in player view)
-pub fn render_text_annotations(
-    mut contexts: EguiContexts,
-    camera_query: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
-    texts: Query<(&Transform, &TextAnnotation), Without<EditingText>>,
-    map_data: Res<MapData>,
+/// Outline the eraser's reach around the cursor
+pub fn render_eraser_preview(
+    mut gizmos: Gizmos<AnnotationGizmoGroup>,
+    settings: Res<AnnotationSettings>,
+    camera: CameraParams,
 ) {
-    if !is_annotation_layer_visible(&map_data) {
-        return;
+    if let Some(cursor) = camera.cursor_world_pos() {
+        gizmos.circle_2d(cursor, settings.eraser_radius, Color::WHITE);
     }
+}
 
-    let Ok(ctx) = contexts.ctx_mut() else {
+/// Draw the shape being dragged out or the polygon being built
+pub fn render_shape_preview(
+    mut gizmos: Gizmos<AnnotationGizmoGroup>,
+    shape_state: Res<ShapeDrawState>,
+    settings: Res<AnnotationSettings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    camera: CameraParams,
+) {
+    let Some(cursor) = camera.cursor_world_pos() else {
         return;
     };
 
-    let Ok((camera, camera_transform)) = camera_query.single() else {
-        return;
+    let kind = settings.shape_kind;
+    let points = if kind == ShapeKind::Polygon {
+        if shape_state.polygon_points.is_empty() {
+            return;
+        }
+        let mut points = shape_state.polygon_points.clone();
+        points.push(cursor);
+        points
+    } else {
+        let Some(start) = shape_state.drag_start else {
+            return;
+        };
+        let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+        shape_from_drag(kind, start, cursor, shift)
     };
 
-    for (transform, text) in texts.iter() {
-        // Skip empty text
-        if text.content.is_empty() {
-            continue;
-        }
+    let preview = DrawnShape {
+        kind,
+        points,
+        color: settings.stroke_color.with_alpha(0.5),
+        stroke_width: settings.stroke_width,
+        fill: None,
+        dashed: settings.shape_dashed,
+    };
+    for (start, end) in stroke_segments(&preview) {
+        gizmos.line_2d(start, end, preview.color);
+    }
+}
+
+/// Keep the fill mesh of shape annotations in sync with their outline. Fills
+/// render only to the editor camera, below the gizmo outline.
+pub fn sync_shape_fills(
+    mut commands: Commands,
+    changed: Query<(Entity, &DrawnShape, Option<&Children>), Changed<DrawnShape>>,
+    fills: Query<(), With<ShapeFill>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, shape, children) in changed.iter() {
+        commands.entity(entity).insert_if_new(Visibility::default());
 
-        let world_pos = transform.translation.truncate();
+        for child in children.into_iter().flatten() {
+            if fills.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
 
-        // Convert world position to screen position
-        let Ok(screen_pos) = camera.world_to_viewport(camera_transform, world_pos.extend(0.0))
-        else {
+        let Some(fill) = shape.fill else {
             continue;
         };
+        let Some((vertices, indices)) = fill_triangles(shape) else {
+            continue;
+        };
+
+        let positions: Vec<[f32; 3]> = vertices.iter().map(|v| [v.x, v.y, 0.0]).collect();
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::RENDER_WORLD,
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices));
+
+        commands.entity(entity).with_child((
+            Mesh2d(meshes.add(mesh)),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(fill))),
+            Transform::from_xyz(0.0, 0.0, -0.1),
+            RenderLayers::layer(1),
+            ShapeFill,
+        ));
+    }
+}
+
+/// Keep the rendered text and background box of text annotations in sync with
+/// their content. Text renders only to the editor camera.
+pub fn sync_text_annotations(
+    mut commands: Commands,
+    changed: Query<(Entity, &TextAnnotation, Option<&Children>), Changed<TextAnnotation>>,
+    backgrounds: Query<(), With<TextAnnotationBackground>>,
+) {
+    for (entity, text, children) in changed.iter() {
+        commands.entity(entity).insert((
+            Text2d::new(text.content.clone()),
+            TextFont {
+                font_size: text.font_size,
+                ..default()
+            },
+            TextColor(text.color),
+            TextLayout::new_with_justify(Justify::Left),
+            RenderLayers::layer(1),
+        ));
+
+        for child in children.into_iter().flatten() {
+            if backgrounds.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
+
+        if let Some(background) = text.background {
+            let size = text.size() + Vec2::splat(text.padding() * 2.0);
+            commands.entity(entity).with_child((
+                Sprite::from_color(background, size),
+                Transform::from_xyz(0.0, 0.0, -0.1),
+                RenderLayers::layer(1),
+                TextAnnotationBackground,
+            ));
+        }
+    }
+}
+
+/// Render layers for an annotation's text and fill entities: editor only, or
+/// the player view as well
+fn annotation_render_layers(show_to_players: bool) -> RenderLayers {
+    if show_to_players {
+        RenderLayers::from_layers(&[1, 2])
+    } else {
+        RenderLayers::layer(1)
+    }
+}
 
-        // Convert Bevy color to egui color
-        let srgba = text.color.to_srgba();
-        let egui_color = egui::Color32::from_rgba_unmultiplied(
-            (srgba.red * 255.0) as u8,
-            (srgba.green * 255.0) as u8,
-            (srgba.blue * 255.0) as u8,
-            255,
-        );
-
-        // Render the text at the screen position using egui
-        egui::Area::new(egui::Id::new(format!("text_annotation_{}", world_pos)))
-            .fixed_pos(egui::pos2(screen_pos.x, screen_pos.y))
-            .pivot(egui::Align2::LEFT_CENTER)
-            .interactable(false)
-            .show(ctx, |ui| {
-                ui.label(
-                    egui::RichText::new(&text.content)
-                        .color(egui_color)
-                        .size(text.font_size),
-                );
-            });
+/// Put text and shape fills of annotations shown to players on the player
+/// view's render layer, and take them off again when the flag is cleared
+#[allow(clippy::type_complexity)]
+pub fn sync_annotation_render_layers(
+    annotations: Query<
+        (Entity, Has<ShowToPlayers>, Option<&Children>),
+        Or<(With<TextAnnotation>, With<DrawnShape>)>,
+    >,
+    mut render_layers: Query<&mut RenderLayers>,
+    parts: Query<(), Or<(With<ShapeFill>, With<TextAnnotationBackground>)>>,
+) {
+    for (entity, show_to_players, children) in annotations.iter() {
+        let layers = annotation_render_layers(show_to_players);
+        let own = render_layers.contains(entity).then_some(entity);
+        let parts = children
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|child| parts.contains(*child));
+        for target in own.into_iter().chain(parts) {
+            if let Ok(mut current) = render_layers.get_mut(target)
+                && *current != layers
+            {
+                *current = layers.clone();
+            }
+        }
+    }
+}
+
+/// Hide rendered text and shape fills while the annotation layer is hidden,
+/// and text while it is open in the editor popup
+#[allow(clippy::type_complexity)]
+pub fn update_annotation_visibility(
+    map_data: Res<MapData>,
+    mut annotations: Query<
+        (&mut Visibility, Has<EditingText>),
+        Or<(With<TextAnnotation>, With<DrawnShape>)>,
+    >,
+) {
+    let layer_visible = is_annotation_layer_visible(&map_data);
+    for (mut visibility, editing) in annotations.iter_mut() {
+        let new_visibility = if layer_visible && !editing {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != new_visibility {
+            *visibility = new_visibility;
+        }
     }
 }
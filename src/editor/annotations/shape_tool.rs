@@ -0,0 +1,176 @@
+//! Shape tool system for drawing rectangles, ellipses, polygons and arrows.
+//!
+//! Rectangles, ellipses and arrows are drawn by dragging (Shift constrains to
+//! a square or circle). Polygons are built one click per vertex and closed by
+//! clicking the first vertex or pressing Enter.
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::map::{Layer, MapData};
+
+use super::super::history::{EditorCommand, RecordEditorCommand, ShapeData};
+use super::super::params::{is_cursor_over_ui, CameraWithProjection};
+use super::super::tools::{CurrentTool, EditorTool};
+use super::components::{AnnotationMarker, DrawnShape, ShapeKind};
+use super::layer_helpers::is_annotation_layer_locked;
+use super::shapes::shape_from_drag;
+use super::state::{AnnotationSettings, ShapeDrawState};
+
+/// Drags shorter than this (world units) don't create a shape
+const MIN_SHAPE_SIZE: f32 = 4.0;
+
+/// Screen-space distance (pixels) within which a click closes a polygon
+const CLOSE_POLYGON_DISTANCE: f32 = 10.0;
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_shape(
+    mut commands: Commands,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    current_tool: Res<CurrentTool>,
+    mut shape_state: ResMut<ShapeDrawState>,
+    settings: Res<AnnotationSettings>,
+    map_data: Res<MapData>,
+    camera: CameraWithProjection,
+    mut contexts: EguiContexts,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    if current_tool.tool != EditorTool::Shape {
+        shape_state.clear();
+        return;
+    }
+
+    // Don't allow shape drawing if annotation layer is locked
+    if is_annotation_layer_locked(&map_data) {
+        shape_state.clear();
+        return;
+    }
+
+    // Switching kinds mid-shape abandons the shape in progress
+    if settings.shape_kind != ShapeKind::Polygon {
+        shape_state.polygon_points.clear();
+    }
+
+    if keyboard.just_pressed(KeyCode::Escape) || mouse_button.just_pressed(MouseButton::Right) {
+        shape_state.clear();
+        return;
+    }
+
+    if settings.shape_kind == ShapeKind::Polygon {
+        if keyboard.just_pressed(KeyCode::Backspace) {
+            shape_state.polygon_points.pop();
+        }
+        if keyboard.just_pressed(KeyCode::Enter) || keyboard.just_pressed(KeyCode::NumpadEnter) {
+            finish_polygon(
+                &mut commands,
+                &mut shape_state,
+                &settings,
+                &mut history_writer,
+            );
+            return;
+        }
+    }
+
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return;
+    };
+
+    // Releasing over the UI still finishes a drag started on the map
+    if shape_state.drag_start.is_none() && is_cursor_over_ui(&mut contexts) {
+        return;
+    }
+
+    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+
+    match settings.shape_kind {
+        ShapeKind::Polygon => {
+            if !mouse_button.just_pressed(MouseButton::Left) {
+                return;
+            }
+            let close_distance = CLOSE_POLYGON_DISTANCE * camera.zoom_scale();
+            if shape_state.polygon_points.len() >= 3
+                && shape_state.polygon_points[0].distance(world_pos) <= close_distance
+            {
+                finish_polygon(
+                    &mut commands,
+                    &mut shape_state,
+                    &settings,
+                    &mut history_writer,
+                );
+            } else {
+                shape_state.polygon_points.push(world_pos);
+            }
+        }
+        kind => {
+            if mouse_button.just_pressed(MouseButton::Left) {
+                shape_state.drag_start = Some(world_pos);
+            } else if mouse_button.just_released(MouseButton::Left)
+                && let Some(start) = shape_state.drag_start.take()
+            {
+                let points = shape_from_drag(kind, start, world_pos, shift);
+                let size = (points[1] - points[0]).abs();
+                let large_enough = if kind == ShapeKind::Arrow {
+                    size.length() >= MIN_SHAPE_SIZE
+                } else {
+                    size.min_element() >= MIN_SHAPE_SIZE
+                };
+                if large_enough {
+                    spawn_drawn_shape(&mut commands, kind, points, &settings, &mut history_writer);
+                }
+            }
+        }
+    }
+}
+
+/// Create the polygon in progress if it has enough vertices
+fn finish_polygon(
+    commands: &mut Commands,
+    shape_state: &mut ShapeDrawState,
+    settings: &AnnotationSettings,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    let points = std::mem::take(&mut shape_state.polygon_points);
+    if points.len() >= 3 {
+        spawn_drawn_shape(
+            commands,
+            ShapeKind::Polygon,
+            points,
+            settings,
+            history_writer,
+        );
+    }
+}
+
+fn spawn_drawn_shape(
+    commands: &mut Commands,
+    kind: ShapeKind,
+    points: Vec<Vec2>,
+    settings: &AnnotationSettings,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    let shape = DrawnShape {
+        kind,
+        points,
+        color: settings.stroke_color,
+        stroke_width: settings.stroke_width,
+        fill: settings.shape_fill.filter(|_| kind.is_closed()),
+        dashed: settings.shape_dashed,
+    };
+    let z = Layer::Annotation.z_base();
+    let data = ShapeData::from(&shape);
+    let entity = commands
+        .spawn((
+            Transform::from_translation(Vec3::new(0.0, 0.0, z)),
+            shape,
+            AnnotationMarker,
+        ))
+        .id();
+
+    history_writer.write(RecordEditorCommand {
+        command: EditorCommand::CreateShape {
+            entity,
+            shape: data,
+        },
+    });
+}
@@ -0,0 +1,315 @@
+//! Geometry for shape annotations: outlines, dashes, arrowheads and fills.
+
+use bevy::prelude::*;
+
+use super::components::{DrawnShape, ShapeKind};
+
+/// Number of segments used to approximate an ellipse
+const ELLIPSE_SEGMENTS: usize = 64;
+
+/// Build a shape from two drag points, optionally constrained (square/circle)
+pub fn shape_from_drag(kind: ShapeKind, start: Vec2, end: Vec2, constrain: bool) -> Vec<Vec2> {
+    let end = if constrain && kind != ShapeKind::Arrow {
+        let delta = end - start;
+        let side = delta.abs().max_element();
+        start + Vec2::new(side.copysign(delta.x), side.copysign(delta.y))
+    } else {
+        end
+    };
+    vec![start, end]
+}
+
+/// Points of the shape's outline as a closed ring (rectangle, ellipse,
+/// polygon) or the arrow shaft
+pub fn outline_points(shape: &DrawnShape) -> Vec<Vec2> {
+    match shape.kind {
+        ShapeKind::Rectangle => {
+            let Some((min, max)) = corners(shape) else {
+                return Vec::new();
+            };
+            vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+        }
+        ShapeKind::Ellipse => {
+            let Some((min, max)) = corners(shape) else {
+                return Vec::new();
+            };
+            let center = (min + max) / 2.0;
+            let radii = (max - min) / 2.0;
+            (0..ELLIPSE_SEGMENTS)
+                .map(|i| {
+                    let angle = i as f32 / ELLIPSE_SEGMENTS as f32 * std::f32::consts::TAU;
+                    center + Vec2::new(angle.cos(), angle.sin()) * radii
+                })
+                .collect()
+        }
+        ShapeKind::Polygon | ShapeKind::Arrow => shape.points.clone(),
+    }
+}
+
+/// Stroke segments of the shape, split into dashes when the shape is dashed
+pub fn stroke_segments(shape: &DrawnShape) -> Vec<(Vec2, Vec2)> {
+    let outline = outline_points(shape);
+    if outline.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut segments: Vec<(Vec2, Vec2)> = outline.windows(2).map(|w| (w[0], w[1])).collect();
+    if shape.kind.is_closed() {
+        segments.push((outline[outline.len() - 1], outline[0]));
+    }
+    if shape.kind == ShapeKind::Arrow {
+        let (tail, head) = (outline[outline.len() - 2], outline[outline.len() - 1]);
+        let [left, right] = arrow_head(tail, head, shape.stroke_width);
+        segments.push((head, left));
+        segments.push((head, right));
+    }
+
+    if !shape.dashed {
+        return segments;
+    }
+    let dash = (shape.stroke_width * 4.0).max(8.0);
+    segments
+        .into_iter()
+        .flat_map(|(a, b)| dash_segment(a, b, dash, dash * 0.75))
+        .collect()
+}
+
+/// The two barb ends of an arrowhead at `head`
+pub fn arrow_head(tail: Vec2, head: Vec2, stroke_width: f32) -> [Vec2; 2] {
+    let direction = (head - tail).normalize_or_zero();
+    let length = (stroke_width * 5.0)
+        .max(14.0)
+        .min((head - tail).length() * 0.5);
+    let back = head - direction * length;
+    let side = direction.perp() * length * 0.5;
+    [back + side, back - side]
+}
+
+/// Split a segment into dashes of `dash` length separated by `gap`
+pub fn dash_segment(a: Vec2, b: Vec2, dash: f32, gap: f32) -> Vec<(Vec2, Vec2)> {
+    let length = a.distance(b);
+    if length <= dash || dash <= 0.0 {
+        return vec![(a, b)];
+    }
+
+    let direction = (b - a) / length;
+    let mut dashes = Vec::new();
+    let mut distance = 0.0;
+    while distance < length {
+        let end = (distance + dash).min(length);
+        dashes.push((a + direction * distance, a + direction * end));
+        distance = end + gap;
+    }
+    dashes
+}
+
+/// Triangles covering the inside of a closed shape, as (vertices, indices).
+/// Returns None for open shapes and degenerate outlines.
+pub fn fill_triangles(shape: &DrawnShape) -> Option<(Vec<Vec2>, Vec<u32>)> {
+    if !shape.kind.is_closed() {
+        return None;
+    }
+    let outline = outline_points(shape);
+    if outline.len() < 3 {
+        return None;
+    }
+    let indices = triangulate_polygon(&outline);
+    if indices.is_empty() {
+        return None;
+    }
+    Some((outline, indices))
+}
+
+/// Ear-clipping triangulation of a simple polygon (either winding order)
+pub fn triangulate_polygon(points: &[Vec2]) -> Vec<u32> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    // Work counter-clockwise so convex corners have a positive cross product
+    if signed_area(points) < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut indices = Vec::with_capacity((points.len() - 2) * 3);
+    let mut attempts = 0;
+    let mut i = 0;
+    while remaining.len() > 3 && attempts < remaining.len() {
+        let count = remaining.len();
+        let (prev, current, next) = (
+            remaining[(i + count - 1) % count],
+            remaining[i % count],
+            remaining[(i + 1) % count],
+        );
+        let (a, b, c) = (points[prev], points[current], points[next]);
+
+        let is_ear = (b - a).perp_dot(c - b) > 0.0
+            && !remaining.iter().any(|&other| {
+                other != prev
+                    && other != current
+                    && other != next
+                    && point_in_triangle(points[other], a, b, c)
+            });
+
+        if is_ear {
+            indices.extend([prev as u32, current as u32, next as u32]);
+            remaining.remove(i % count);
+            attempts = 0;
+        } else {
+            i += 1;
+            attempts += 1;
+        }
+    }
+
+    // Self-intersecting outlines can leave no ears; fill what was found
+    if remaining.len() == 3 {
+        indices.extend(remaining.iter().map(|&index| index as u32));
+    }
+    indices
+}
+
+/// Check if a point is inside a polygon (even-odd rule)
+pub fn point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn corners(shape: &DrawnShape) -> Option<(Vec2, Vec2)> {
+    let (&first, &second) = (shape.points.first()?, shape.points.get(1)?);
+    Some((first.min(second), first.max(second)))
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+    for i in 0..points.len() {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        area += a.perp_dot(b);
+    }
+    area / 2.0
+}
+
+fn point_in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let d1 = (b - a).perp_dot(p - a);
+    let d2 = (c - b).perp_dot(p - b);
+    let d3 = (a - c).perp_dot(p - c);
+    d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(kind: ShapeKind, points: Vec<Vec2>) -> DrawnShape {
+        DrawnShape {
+            kind,
+            points,
+            color: Color::WHITE,
+            stroke_width: 2.0,
+            fill: None,
+            dashed: false,
+        }
+    }
+
+    #[test]
+    fn test_rectangle_outline_is_closed() {
+        let rect = shape(
+            ShapeKind::Rectangle,
+            vec![Vec2::new(10.0, 10.0), Vec2::new(0.0, 0.0)],
+        );
+        assert_eq!(outline_points(&rect)[0], Vec2::ZERO);
+        assert_eq!(stroke_segments(&rect).len(), 4);
+    }
+
+    #[test]
+    fn test_constrained_drag_is_square() {
+        let points = shape_from_drag(
+            ShapeKind::Rectangle,
+            Vec2::ZERO,
+            Vec2::new(30.0, -10.0),
+            true,
+        );
+        assert_eq!(points[1], Vec2::new(30.0, -30.0));
+    }
+
+    #[test]
+    fn test_arrow_has_head() {
+        let arrow = shape(ShapeKind::Arrow, vec![Vec2::ZERO, Vec2::new(100.0, 0.0)]);
+        let segments = stroke_segments(&arrow);
+        // Shaft plus two barbs
+        assert_eq!(segments.len(), 3);
+        let [left, right] = arrow_head(Vec2::ZERO, Vec2::new(100.0, 0.0), 2.0);
+        assert!(left.x < 100.0 && right.x < 100.0);
+        assert!(left.y > 0.0 && right.y < 0.0);
+    }
+
+    #[test]
+    fn test_dashes_cover_segment() {
+        let dashes = dash_segment(Vec2::ZERO, Vec2::new(100.0, 0.0), 10.0, 10.0);
+        assert_eq!(dashes.len(), 5);
+        assert_eq!(dashes[0], (Vec2::ZERO, Vec2::new(10.0, 0.0)));
+        assert_eq!(dashes[4].1, Vec2::new(90.0, 0.0));
+    }
+
+    #[test]
+    fn test_triangulate_concave_polygon() {
+        // L-shape, clockwise
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 20.0),
+            Vec2::new(10.0, 20.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(20.0, 0.0),
+        ];
+        let indices = triangulate_polygon(&points);
+        assert_eq!(indices.len(), (points.len() - 2) * 3);
+
+        // Total triangle area matches the polygon area (300)
+        let area: f32 = indices
+            .chunks(3)
+            .map(|t| {
+                let (a, b, c) = (
+                    points[t[0] as usize],
+                    points[t[1] as usize],
+                    points[t[2] as usize],
+                );
+                (b - a).perp_dot(c - a).abs() / 2.0
+            })
+            .sum();
+        assert!((area - 300.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_open_shapes_have_no_fill() {
+        let arrow = shape(ShapeKind::Arrow, vec![Vec2::ZERO, Vec2::new(10.0, 0.0)]);
+        assert!(fill_triangles(&arrow).is_none());
+
+        let ellipse = shape(ShapeKind::Ellipse, vec![Vec2::ZERO, Vec2::new(40.0, 20.0)]);
+        let (vertices, indices) = fill_triangles(&ellipse).unwrap();
+        assert_eq!(vertices.len(), ELLIPSE_SEGMENTS);
+        assert_eq!(indices.len(), (ELLIPSE_SEGMENTS - 2) * 3);
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let square = [
+            Vec2::ZERO,
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        assert!(point_in_polygon(Vec2::new(5.0, 5.0), &square));
+        assert!(!point_in_polygon(Vec2::new(15.0, 5.0), &square));
+    }
+}
@@ -0,0 +1,80 @@
+//! Session sketches: quick freehand strokes shown to players.
+//!
+//! While a live session is running, the Draw tool's sketch mode turns strokes
+//! into [`SessionSketch`]es instead of annotations. They are drawn in both
+//! views, fade out after [`AnnotationSettings::sketch_duration`] seconds and
+//! are never saved with the map.
+
+use bevy::prelude::*;
+
+use crate::session::LiveSessionState;
+
+use super::gizmo::{AnnotationGizmoGroup, AnnotationPlayerGizmoGroup};
+use super::state::AnnotationSettings;
+
+/// A temporary stroke drawn for the players
+pub struct SessionSketch {
+    pub points: Vec<Vec2>,
+    pub color: Color,
+    /// Seconds since the stroke was finished
+    pub age: f32,
+}
+
+/// Sketches still fading out
+#[derive(Resource, Default)]
+pub struct SessionSketches {
+    pub sketches: Vec<SessionSketch>,
+}
+
+/// Opacity of a sketch of the given age, fading linearly to zero
+pub fn sketch_alpha(age: f32, duration: f32) -> f32 {
+    (1.0 - age / duration.max(0.1)).clamp(0.0, 1.0)
+}
+
+/// Age, fade and draw session sketches in both views
+pub fn draw_session_sketches(
+    mut editor_gizmos: Gizmos<AnnotationGizmoGroup>,
+    mut player_gizmos: Gizmos<AnnotationPlayerGizmoGroup>,
+    mut sketches: ResMut<SessionSketches>,
+    settings: Res<AnnotationSettings>,
+    time: Res<Time>,
+    session_state: Res<LiveSessionState>,
+) {
+    if sketches.sketches.is_empty() {
+        return;
+    }
+
+    // Sketches belong to the session they were drawn in
+    if !session_state.is_active {
+        sketches.sketches.clear();
+        return;
+    }
+
+    let delta = time.delta_secs();
+    let duration = settings.sketch_duration;
+    sketches.sketches.retain_mut(|sketch| {
+        sketch.age += delta;
+        sketch_alpha(sketch.age, duration) > 0.0
+    });
+
+    for sketch in &sketches.sketches {
+        let alpha = sketch_alpha(sketch.age, duration);
+        let color = sketch.color.with_alpha(sketch.color.alpha() * alpha);
+        for window in sketch.points.windows(2) {
+            editor_gizmos.line_2d(window[0], window[1], color);
+            player_gizmos.line_2d(window[0], window[1], color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sketch_fades_out() {
+        assert_eq!(sketch_alpha(0.0, 10.0), 1.0);
+        assert_eq!(sketch_alpha(5.0, 10.0), 0.5);
+        assert_eq!(sketch_alpha(12.0, 10.0), 0.0);
+    }
+}
@@ -0,0 +1,125 @@
+//! Simplification and smoothing of freehand strokes.
+//!
+//! Raw strokes carry a point for every couple of pixels of mouse movement.
+//! Ramer–Douglas–Peucker drops points that don't change the shape of the
+//! stroke, and Catmull-Rom interpolation can round off the corners that are
+//! left.
+
+use bevy::prelude::*;
+
+/// Maximum distance (world units) a dropped point may be from the simplified stroke
+pub const SIMPLIFY_TOLERANCE: f32 = 1.5;
+
+/// Points inserted between each pair of vertices when smoothing
+pub const SMOOTHING_SAMPLES: usize = 4;
+
+/// Ramer–Douglas–Peucker simplification, keeping the first and last points
+pub fn simplify_rdp(points: &[Vec2], epsilon: f32) -> Vec<Vec2> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    // Iterative to avoid deep recursion on long strokes
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let (mut farthest, mut max_distance) = (start, 0.0);
+        for i in start + 1..end {
+            let distance = distance_to_segment(points[i], points[start], points[end]);
+            if distance > max_distance {
+                farthest = i;
+                max_distance = distance;
+            }
+        }
+        if max_distance > epsilon {
+            keep[farthest] = true;
+            stack.push((start, farthest));
+            stack.push((farthest, end));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(point, keep)| keep.then_some(*point))
+        .collect()
+}
+
+/// Uniform Catmull-Rom spline through all points, inserting `samples`
+/// interpolated points between each pair
+pub fn catmull_rom(points: &[Vec2], samples: usize) -> Vec<Vec2> {
+    if points.len() < 3 || samples == 0 {
+        return points.to_vec();
+    }
+
+    let last = points.len() - 1;
+    let mut smoothed = Vec::with_capacity(last * (samples + 1) + 1);
+    for i in 0..last {
+        // Endpoints are duplicated so the curve passes through them
+        let p0 = points[i.saturating_sub(1)];
+        let (p1, p2) = (points[i], points[i + 1]);
+        let p3 = points[(i + 2).min(last)];
+
+        smoothed.push(p1);
+        for step in 1..=samples {
+            let t = step as f32 / (samples + 1) as f32;
+            let (t2, t3) = (t * t, t * t * t);
+            smoothed.push(
+                0.5 * (2.0 * p1
+                    + (p2 - p0) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3),
+            );
+        }
+    }
+    smoothed.push(points[last]);
+    smoothed
+}
+
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_squared();
+    if length_sq == 0.0 {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify_straight_line() {
+        let points: Vec<Vec2> = (0..100).map(|i| Vec2::new(i as f32, 0.0)).collect();
+        let simplified = simplify_rdp(&points, SIMPLIFY_TOLERANCE);
+        assert_eq!(simplified, vec![Vec2::ZERO, Vec2::new(99.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_simplify_keeps_corners() {
+        let mut points: Vec<Vec2> = (0..=50).map(|i| Vec2::new(i as f32, 0.0)).collect();
+        points.extend((1..=50).map(|i| Vec2::new(50.0, i as f32)));
+        let simplified = simplify_rdp(&points, SIMPLIFY_TOLERANCE);
+        assert_eq!(
+            simplified,
+            vec![Vec2::ZERO, Vec2::new(50.0, 0.0), Vec2::new(50.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn test_catmull_rom_passes_through_points() {
+        let points = [Vec2::ZERO, Vec2::new(10.0, 10.0), Vec2::new(20.0, 0.0)];
+        let smoothed = catmull_rom(&points, 3);
+        assert_eq!(smoothed.len(), 2 * 4 + 1);
+        assert_eq!(smoothed[0], points[0]);
+        assert_eq!(smoothed[4], points[1]);
+        assert_eq!(smoothed[8], points[2]);
+        // Interpolated points stay between the neighbouring vertices
+        assert!(smoothed[2].x > 0.0 && smoothed[2].x < 10.0);
+    }
+}
@@ -4,8 +4,12 @@ use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
 use crate::map::MapData;
+use crate::session::LiveSessionState;
 
+use super::super::history::{LineData, PathData};
 use super::super::tools::CurrentTool;
+use super::components::{ShapeKind, TextAnnotation};
+use super::sketch::SessionSketches;
 
 #[derive(Resource, Default)]
 pub struct DrawState {
@@ -18,13 +22,70 @@ pub struct LineDrawState {
     pub start_point: Option<Vec2>,
 }
 
-// Text tool disabled - see TODO in tools.rs
-#[allow(dead_code)]
+#[derive(Resource, Default)]
+pub struct ShapeDrawState {
+    /// Press position of a rectangle, ellipse or arrow drag in progress
+    pub drag_start: Option<Vec2>,
+    /// Vertices placed so far for a polygon in progress
+    pub polygon_points: Vec<Vec2>,
+}
+
+impl ShapeDrawState {
+    pub fn clear(&mut self) {
+        self.drag_start = None;
+        self.polygon_points.clear();
+    }
+}
+
+/// An annotation removed by the eraser, as it was before the stroke
+#[derive(Clone, Debug)]
+pub enum ErasedAnnotation {
+    Path(PathData),
+    Line(LineData),
+}
+
+#[derive(Resource, Default)]
+pub struct EraserState {
+    pub is_erasing: bool,
+    /// Cursor position on the previous frame of the stroke
+    pub last_position: Option<Vec2>,
+    /// Annotations that existed before the stroke and were cut by it
+    pub erased: Vec<ErasedAnnotation>,
+    /// Pieces spawned by the stroke that still exist
+    pub pieces: Vec<Entity>,
+}
+
+impl EraserState {
+    /// Note that `entity` was cut. Pieces spawned earlier in the same stroke
+    /// are simply dropped; anything else is remembered for undo.
+    pub fn replace(&mut self, entity: Entity, original: ErasedAnnotation) {
+        if let Some(index) = self.pieces.iter().position(|piece| *piece == entity) {
+            self.pieces.swap_remove(index);
+        } else {
+            self.erased.push(original);
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct TextEditState {
+    /// Text annotation open in the editor popup
     pub editing_entity: Option<Entity>,
-    pub text_buffer: String,
-    pub cursor_position: Vec2,
+    /// Working copy edited in the popup, applied when the edit is committed.
+    /// Set while editing; `editing_entity` is None for text not yet created.
+    pub draft: Option<TextAnnotation>,
+    /// World position of the top-left corner of the text being edited
+    pub anchor: Vec2,
+    /// Whether the popup should grab keyboard focus on its next frame
+    pub focus_pending: bool,
+    /// Last text annotation clicked and when (seconds), for double-click detection
+    pub last_click: Option<(Entity, f64)>,
+}
+
+impl TextEditState {
+    pub fn is_editing(&self) -> bool {
+        self.draft.is_some()
+    }
 }
 
 #[derive(Resource)]
@@ -32,6 +93,21 @@ pub struct AnnotationSettings {
     pub stroke_color: Color,
     pub stroke_width: f32,
     pub font_size: f32,
+    /// Background box for new text, if any
+    pub text_background: Option<Color>,
+    pub shape_kind: ShapeKind,
+    /// Fill color for new closed shapes, if any
+    pub shape_fill: Option<Color>,
+    pub shape_dashed: bool,
+    /// Round off freehand strokes with a Catmull-Rom spline
+    pub smooth_strokes: bool,
+    /// Radius of the eraser in world units
+    pub eraser_radius: f32,
+    /// During a live session, Draw makes fading sketches shown to players
+    /// instead of annotations
+    pub session_sketch: bool,
+    /// Seconds a session sketch stays visible
+    pub sketch_duration: f32,
 }
 
 impl Default for AnnotationSettings {
@@ -40,6 +116,14 @@ impl Default for AnnotationSettings {
             stroke_color: Color::srgb(1.0, 0.0, 0.0),
             stroke_width: 3.0,
             font_size: 24.0,
+            text_background: None,
+            shape_kind: ShapeKind::default(),
+            shape_fill: None,
+            shape_dashed: false,
+            smooth_strokes: false,
+            eraser_radius: 12.0,
+            session_sketch: false,
+            sketch_duration: 10.0,
         }
     }
 }
@@ -51,4 +135,13 @@ pub(crate) struct AnnotationResources<'w> {
     pub draw_state: ResMut<'w, DrawState>,
     pub settings: Res<'w, AnnotationSettings>,
     pub map_data: Res<'w, MapData>,
+    pub session_state: Res<'w, LiveSessionState>,
+    pub sketches: ResMut<'w, SessionSketches>,
+}
+
+impl AnnotationResources<'_> {
+    /// Whether Draw strokes currently become session sketches
+    pub fn is_sketching(&self) -> bool {
+        self.settings.session_sketch && self.session_state.is_active
+    }
 }
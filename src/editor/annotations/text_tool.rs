@@ -1,45 +1,140 @@
 //! Text tool system for creating and editing text annotations.
 //!
-//! Note: Text tool is currently disabled - see TODO in tools.rs
+//! Text is edited in an egui popup placed over the annotation. The popup works
+//! on a draft copy; committing applies it to the annotation and records a
+//! `CreateText`, `EditText` or `DeleteText` command so every edit is undoable.
 
 use bevy::prelude::*;
 use bevy_egui::egui;
 use bevy_egui::EguiContexts;
 
+use crate::editor::history::{EditorCommand, RecordEditorCommand, TextData};
 use crate::map::{Layer, MapData};
+use crate::theme;
 
-use super::super::camera::EditorCamera;
-use super::super::params::{is_cursor_over_ui, CameraParams};
+use super::super::params::{is_cursor_over_ui, CameraParams, CameraWithProjection};
 use super::super::tools::{CurrentTool, EditorTool};
 use super::components::{AnnotationMarker, EditingText, TextAnnotation};
-use super::layer_helpers::is_annotation_layer_locked;
+use super::hit_testing::{point_in_text, text_half_size};
+use super::layer_helpers::{is_annotation_layer_locked, is_annotation_layer_visible};
 use super::state::{AnnotationSettings, TextEditState};
 
-// Text tool disabled - see TODO in tools.rs
-#[allow(dead_code)]
+/// Maximum time between two clicks for a double-click (seconds)
+const DOUBLE_CLICK_TIME: f64 = 0.4;
+
+/// Font size range offered by the text editor
+pub const TEXT_FONT_SIZE_RANGE: std::ops::RangeInclusive<f32> = 8.0..=144.0;
+
+/// Offset from a text annotation's center to the top-left of its box
+fn top_left_offset(text: &TextAnnotation) -> Vec2 {
+    let half = text_half_size(text);
+    Vec2::new(-half.x, half.y)
+}
+
+/// Open an existing text annotation in the editor popup
+fn begin_text_edit(
+    commands: &mut Commands,
+    text_state: &mut TextEditState,
+    entity: Entity,
+    transform: &Transform,
+    text: &TextAnnotation,
+) {
+    text_state.editing_entity = Some(entity);
+    text_state.draft = Some(text.clone());
+    text_state.anchor = transform.translation.truncate() + top_left_offset(text);
+    text_state.focus_pending = true;
+    commands.entity(entity).insert(EditingText);
+}
+
+/// Apply the draft and record the change. Empty text is deleted.
+fn commit_text_edit(
+    commands: &mut Commands,
+    text_state: &mut TextEditState,
+    texts: &Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    let editing_entity = text_state.editing_entity.take();
+    let Some(draft) = text_state.draft.take() else {
+        return;
+    };
+    // The anchor stays put; the box grows right and down from it
+    let position = text_state.anchor - top_left_offset(&draft);
+    let is_empty = draft.content.trim().is_empty();
+
+    let Some(entity) = editing_entity else {
+        // New text
+        if is_empty {
+            return;
+        }
+        let text = TextData::from_annotation(position, &draft);
+        let entity = commands
+            .spawn((
+                Transform::from_translation(position.extend(Layer::Annotation.z_base())),
+                draft,
+                AnnotationMarker,
+            ))
+            .id();
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::CreateText { entity, text },
+        });
+        return;
+    };
+
+    let Ok((_, transform, original)) = texts.get(entity) else {
+        // Deleted while the editor was open
+        return;
+    };
+    commands.entity(entity).remove::<EditingText>();
+
+    let before = TextData::from_annotation(transform.translation.truncate(), original);
+    if is_empty {
+        commands.entity(entity).despawn();
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::DeleteText { text: before },
+        });
+        return;
+    }
+
+    let after = TextData::from_annotation(position, &draft);
+    if after == before {
+        return;
+    }
+    commands.entity(entity).insert((
+        draft,
+        Transform::from_translation(position.extend(transform.translation.z)),
+    ));
+    history_writer.write(RecordEditorCommand {
+        command: EditorCommand::EditText {
+            texts: vec![(entity, before, after)],
+        },
+    });
+}
+
+/// Close the editor popup, discarding the draft
+fn cancel_text_edit(commands: &mut Commands, text_state: &mut TextEditState) {
+    if let Some(entity) = text_state.editing_entity.take()
+        && let Ok(mut entity_commands) = commands.get_entity(entity)
+    {
+        entity_commands.remove::<EditingText>();
+    }
+    text_state.draft = None;
+}
+
+/// Text tool: click empty space to start new text, click text to edit it.
+/// Clicking away from the popup commits the current edit.
 #[allow(clippy::too_many_arguments)]
 pub fn handle_text(
     mut commands: Commands,
     mouse_button: Res<ButtonInput<MouseButton>>,
-    current_tool: Res<CurrentTool>,
     mut text_state: ResMut<TextEditState>,
     settings: Res<AnnotationSettings>,
     map_data: Res<MapData>,
     camera: CameraParams,
     mut contexts: EguiContexts,
-    text_query: Query<(Entity, &Transform, &TextAnnotation), Without<EditingText>>,
-    mut editing_query: Query<(Entity, &mut TextAnnotation), With<EditingText>>,
+    texts: Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
 ) {
-    if current_tool.tool != EditorTool::Text {
-        // Finalize any editing text when switching away
-        if text_state.editing_entity.is_some() {
-            for (entity, mut text) in editing_query.iter_mut() {
-                text.content = text_state.text_buffer.clone();
-                commands.entity(entity).remove::<EditingText>();
-            }
-            text_state.editing_entity = None;
-            text_state.text_buffer.clear();
-        }
+    if !mouse_button.just_pressed(MouseButton::Left) {
         return;
     }
 
@@ -56,154 +151,233 @@ pub fn handle_text(
         return;
     };
 
-    if mouse_button.just_pressed(MouseButton::Left) {
-        // Check if clicking on existing text
-        let mut clicked_text = None;
-        for (entity, transform, _text) in text_query.iter() {
-            let pos = transform.translation.truncate();
-            let half_size = Vec2::new(100.0, 20.0); // Approximate text bounds
-            if (world_pos.x - pos.x).abs() < half_size.x
-                && (world_pos.y - pos.y).abs() < half_size.y
-            {
-                clicked_text = Some(entity);
-                break;
-            }
-        }
+    if text_state.is_editing() {
+        commit_text_edit(&mut commands, &mut text_state, &texts, &mut history_writer);
+        return;
+    }
 
-        if let Some(entity) = clicked_text {
-            // Edit existing text
-            if let Ok((_, _, text)) = text_query.get(entity) {
-                text_state.editing_entity = Some(entity);
-                text_state.text_buffer = text.content.clone();
-                commands.entity(entity).insert(EditingText);
-            }
-        } else {
-            // Finalize any current editing
-            if text_state.editing_entity.is_some() {
-                for (entity, mut text) in editing_query.iter_mut() {
-                    text.content = text_state.text_buffer.clone();
-                    commands.entity(entity).remove::<EditingText>();
-                }
-            }
-
-            // Create new text at position
-            let z = Layer::Annotation.z_base();
-            let entity = commands
-                .spawn((
-                    Transform::from_translation(world_pos.extend(z)),
-                    TextAnnotation {
-                        content: String::new(),
-                        font_size: settings.font_size,
-                        color: settings.stroke_color,
-                    },
-                    AnnotationMarker,
-                    EditingText,
-                ))
-                .id();
-            text_state.editing_entity = Some(entity);
-            text_state.text_buffer.clear();
-            text_state.cursor_position = world_pos;
-        }
+    let clicked = is_annotation_layer_visible(&map_data)
+        .then(|| {
+            texts
+                .iter()
+                .find(|(_, transform, text)| point_in_text(world_pos, transform, text))
+        })
+        .flatten();
+
+    if let Some((entity, transform, text)) = clicked {
+        begin_text_edit(&mut commands, &mut text_state, entity, transform, text);
+    } else {
+        // New text starts at the click position
+        text_state.editing_entity = None;
+        text_state.draft = Some(TextAnnotation {
+            content: String::new(),
+            font_size: settings.font_size,
+            color: settings.stroke_color,
+            background: settings.text_background,
+        });
+        text_state.anchor = world_pos;
+        text_state.focus_pending = true;
     }
 }
 
-// Text tool disabled - see TODO in tools.rs
-#[allow(dead_code)]
-/// UI system for editing text annotations - shows an egui text input
-pub fn text_annotation_input_ui(
+/// Select tool: double-click a text annotation to edit it in place
+#[allow(clippy::too_many_arguments)]
+pub fn handle_text_double_click(
     mut commands: Commands,
-    mut contexts: EguiContexts,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
     mut text_state: ResMut<TextEditState>,
-    mut editing_query: Query<(Entity, &Transform, &mut TextAnnotation), With<EditingText>>,
-    camera_query: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    map_data: Res<MapData>,
+    camera: CameraParams,
+    mut contexts: EguiContexts,
+    texts: Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
 ) {
-    // Only show if we're editing a text annotation
-    let Some(editing_entity) = text_state.editing_entity else {
+    if !mouse_button.just_pressed(MouseButton::Left) || is_cursor_over_ui(&mut contexts) {
         return;
-    };
+    }
 
-    // Get the editing text annotation
-    let Ok((entity, transform, mut text_annotation)) = editing_query.get_mut(editing_entity) else {
-        // Entity no longer exists, clear editing state
-        text_state.editing_entity = None;
-        text_state.text_buffer.clear();
+    let Some(world_pos) = camera.cursor_world_pos() else {
         return;
     };
 
-    let Ok(ctx) = contexts.ctx_mut() else {
+    if text_state.is_editing() {
+        commit_text_edit(&mut commands, &mut text_state, &texts, &mut history_writer);
+    }
+
+    if !is_annotation_layer_visible(&map_data) || is_annotation_layer_locked(&map_data) {
         return;
-    };
+    }
 
-    // Convert world position to screen position for the egui window
-    let world_pos = transform.translation.truncate();
+    let now = time.elapsed_secs_f64();
+    let clicked = texts
+        .iter()
+        .find(|(_, transform, text)| point_in_text(world_pos, transform, text));
 
-    // Get camera for coordinate conversion
-    let Ok((camera, camera_transform)) = camera_query.single() else {
+    let Some((entity, transform, text)) = clicked else {
+        text_state.last_click = None;
         return;
     };
 
-    // Convert world position to screen position
-    let Ok(screen_pos) = camera.world_to_viewport(camera_transform, world_pos.extend(0.0)) else {
-        return;
-    };
+    let is_double_click = text_state
+        .last_click
+        .is_some_and(|(last, at)| last == entity && now - at < DOUBLE_CLICK_TIME);
+    if is_double_click {
+        text_state.last_click = None;
+        begin_text_edit(&mut commands, &mut text_state, entity, transform, text);
+    } else {
+        text_state.last_click = Some((entity, now));
+    }
+}
 
-    // Check for escape to cancel
-    if keyboard.just_pressed(KeyCode::Escape) {
-        // Cancel editing - remove if empty, otherwise keep original content
-        if text_annotation.content.is_empty() && text_state.text_buffer.is_empty() {
-            commands.entity(entity).despawn();
-        } else {
-            commands.entity(entity).remove::<EditingText>();
-        }
-        text_state.editing_entity = None;
-        text_state.text_buffer.clear();
-        return;
+/// Commit any open text edit when the tool changes
+pub fn commit_text_edit_on_tool_change(
+    mut commands: Commands,
+    current_tool: Res<CurrentTool>,
+    mut last_tool: Local<Option<EditorTool>>,
+    mut text_state: ResMut<TextEditState>,
+    texts: Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    let changed = last_tool.is_some_and(|tool| tool != current_tool.tool);
+    *last_tool = Some(current_tool.tool);
+
+    if changed && text_state.is_editing() {
+        commit_text_edit(&mut commands, &mut text_state, &texts, &mut history_writer);
+    }
+}
+
+/// UI system for editing text annotations - a multi-line editor over the text
+#[allow(clippy::too_many_arguments)]
+pub fn text_annotation_input_ui(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut text_state: ResMut<TextEditState>,
+    texts: Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    camera: CameraWithProjection,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) -> Result {
+    if !text_state.is_editing() {
+        return Ok(());
+    }
+
+    // The annotation was removed while its editor was open (undo, map switch)
+    if let Some(entity) = text_state.editing_entity
+        && !texts.contains(entity)
+    {
+        cancel_text_edit(&mut commands, &mut text_state);
+        return Ok(());
     }
 
-    let mut should_finalize = false;
+    let Ok((camera_component, camera_transform, _)) = camera.camera.single() else {
+        return Ok(());
+    };
+    let Ok(screen_pos) =
+        camera_component.world_to_viewport(camera_transform, text_state.anchor.extend(0.0))
+    else {
+        return Ok(());
+    };
+    let zoom = camera.zoom_scale().max(0.01);
+
+    let ctx = contexts.ctx_mut()?;
+    let focus_pending = std::mem::take(&mut text_state.focus_pending);
+
+    let commit = ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Enter));
+    let cancel = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+    let mut done_clicked = false;
+
+    let Some(draft) = text_state.draft.as_mut() else {
+        return Ok(());
+    };
 
-    // Create an egui Area at the text position
     egui::Area::new(egui::Id::new("text_annotation_input"))
         .fixed_pos(egui::pos2(screen_pos.x, screen_pos.y))
-        .pivot(egui::Align2::LEFT_CENTER)
+        .pivot(egui::Align2::LEFT_TOP)
+        .order(egui::Order::Foreground)
         .show(ctx, |ui| {
-            ui.set_min_width(150.0);
+            let font_size = (draft.font_size / zoom).clamp(6.0, 200.0);
+            let padding = (draft.padding() / zoom).max(2.0);
+            let fill = draft
+                .background
+                .map(theme::bevy_to_egui)
+                .unwrap_or(egui::Color32::from_black_alpha(140));
+
+            egui::Frame::new()
+                .fill(fill)
+                .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(51, 153, 255)))
+                .inner_margin(padding)
+                .show(ui, |ui| {
+                    let rows = draft.content.split('\n').count().max(1);
+                    let width = (draft.size().x / zoom + font_size).max(120.0);
+                    let text_color = theme::bevy_to_egui_opaque(draft.color);
+                    let response = ui.add(
+                        egui::TextEdit::multiline(&mut draft.content)
+                            .font(egui::FontId::monospace(font_size))
+                            .text_color(text_color)
+                            .frame(false)
+                            .desired_rows(rows)
+                            .desired_width(width)
+                            .hint_text("Enter text..."),
+                    );
+
+                    // Focus the editor once when it opens
+                    if focus_pending {
+                        response.request_focus();
+                    }
+                });
+
             egui::Frame::popup(ui.style()).show(ui, |ui| {
-                let response = ui.add(
-                    egui::TextEdit::singleline(&mut text_state.text_buffer)
-                        .hint_text("Enter text...")
-                        .desired_width(200.0)
-                        .font(egui::TextStyle::Body),
-                );
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut draft.font_size)
+                            .range(TEXT_FONT_SIZE_RANGE)
+                            .speed(1.0)
+                            .suffix(" pt"),
+                    )
+                    .on_hover_text("Font size");
 
-                // Request focus only when not already focused
-                if !response.has_focus() {
-                    response.request_focus();
-                }
+                    let mut color = theme::bevy_to_egui(draft.color);
+                    if ui
+                        .color_edit_button_srgba(&mut color)
+                        .on_hover_text("Text color")
+                        .changed()
+                    {
+                        draft.color = theme::egui_to_bevy(color);
+                    }
 
-                // Finalize on Enter
-                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                    should_finalize = true;
-                }
+                    let mut has_background = draft.background.is_some();
+                    if ui.checkbox(&mut has_background, "Box").changed() {
+                        draft.background =
+                            has_background.then_some(Color::srgba(0.0, 0.0, 0.0, 0.6));
+                    }
+                    if let Some(background) = draft.background.as_mut() {
+                        let mut color = theme::bevy_to_egui(*background);
+                        if ui
+                            .color_edit_button_srgba(&mut color)
+                            .on_hover_text("Box color")
+                            .changed()
+                        {
+                            *background = theme::egui_to_bevy(color);
+                        }
+                    }
 
-                // Show hint
-                ui.label("Press Enter to confirm, Esc to cancel");
+                    if ui.button("Done").clicked() {
+                        done_clicked = true;
+                    }
+                });
+                ui.label(
+                    egui::RichText::new("Ctrl+Enter to finish, Esc to cancel")
+                        .color(egui::Color32::GRAY)
+                        .size(11.0),
+                );
             });
         });
 
-    if should_finalize {
-        // Save the text
-        text_annotation.content = text_state.text_buffer.clone();
-
-        // If empty, delete the annotation
-        if text_annotation.content.trim().is_empty() {
-            commands.entity(entity).despawn();
-        } else {
-            commands.entity(entity).remove::<EditingText>();
-        }
-
-        text_state.editing_entity = None;
-        text_state.text_buffer.clear();
+    if cancel {
+        cancel_text_edit(&mut commands, &mut text_state);
+    } else if commit || done_clicked {
+        commit_text_edit(&mut commands, &mut text_state, &texts, &mut history_writer);
     }
+
+    Ok(())
 }
@@ -0,0 +1,177 @@
+//! Token light and aura rings.
+//!
+//! Light and aura radii are stored on [`TokenData`] in feet and converted to
+//! world units with the map's grid size, measured from the edge of the token.
+//! Rings are redrawn from the token's transform every frame, so they follow
+//! the token while it's dragged.
+//!
+//! Light also feeds fog of war: moving a token that sheds light reveals the
+//! cells within its bright + dim radius.
+//!
+//! ## Rendering
+//!
+//! Uses two gizmo groups:
+//! - [`AuraEditorGizmoGroup`]: Auras and light radii in the editor view (RenderLayers::layer(1))
+//! - [`AuraPlayerGizmoGroup`]: Player-visible auras in the player view (RenderLayers::layer(2))
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::gizmos::config::{GizmoConfigGroup, GizmoConfigStore};
+use bevy::prelude::*;
+
+use super::fog::{is_fog_layer_locked, is_fog_layer_visible};
+use super::history::{EditorCommand, RecordEditorCommand};
+use super::selection::get_sprite_half_size;
+use crate::map::{
+    cells_in_radius, feet_to_world, world_to_cell, FogOfWarData, MapData, MapDirtyState, OpenMaps,
+    PlacedItem, TokenData,
+};
+use crate::session::LiveSessionState;
+use crate::theme;
+
+/// Segments used for aura circles, which can get large on screen
+const RING_RESOLUTION: u32 = 64;
+
+/// Gizmo group for auras and light radii in editor view
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct AuraEditorGizmoGroup;
+
+/// Gizmo group for player-visible auras in player view
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct AuraPlayerGizmoGroup;
+
+/// Configure the aura gizmo groups for their respective render layers
+pub fn configure_aura_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (editor_config, _) = config_store.config_mut::<AuraEditorGizmoGroup>();
+    editor_config.render_layers = RenderLayers::layer(1);
+    editor_config.line.width = 2.0;
+
+    let (player_config, _) = config_store.config_mut::<AuraPlayerGizmoGroup>();
+    player_config.render_layers = RenderLayers::layer(2);
+    player_config.line.width = 4.0;
+}
+
+/// Distance from the token's center to the edge of its space
+fn token_edge_radius(transform: &Transform, sprite: &Sprite, images: &Assets<Image>) -> f32 {
+    (get_sprite_half_size(sprite, images) * transform.scale.truncate().abs()).max_element()
+}
+
+/// Draw aura rings in both views and light radii in the editor view
+#[allow(clippy::too_many_arguments)]
+pub fn draw_token_auras(
+    mut editor_gizmos: Gizmos<AuraEditorGizmoGroup>,
+    mut player_gizmos: Gizmos<AuraPlayerGizmoGroup>,
+    tokens: Query<(&PlacedItem, &TokenData, &Transform, &Sprite, &Visibility)>,
+    images: Res<Assets<Image>>,
+    map_data: Res<MapData>,
+    fog_data: Res<FogOfWarData>,
+    session_state: Res<LiveSessionState>,
+) {
+    let grid_size = map_data.grid_size;
+    let fog_visible = is_fog_layer_visible(&map_data);
+
+    for (item, token, transform, sprite, visibility) in tokens.iter() {
+        if *visibility == Visibility::Hidden || (token.auras.is_empty() && !token.emits_light()) {
+            continue;
+        }
+
+        let center = transform.translation.truncate();
+        let edge = token_edge_radius(transform, sprite, &images);
+
+        if token.bright_light_ft > 0.0 {
+            editor_gizmos
+                .circle_2d(
+                    Isometry2d::from_translation(center),
+                    edge + feet_to_world(token.bright_light_ft, grid_size),
+                    theme::LIGHT_BRIGHT,
+                )
+                .resolution(RING_RESOLUTION);
+        }
+        if token.dim_light_ft > 0.0 {
+            editor_gizmos
+                .circle_2d(
+                    Isometry2d::from_translation(center),
+                    edge + feet_to_world(token.light_reach_ft(), grid_size),
+                    theme::LIGHT_DIM,
+                )
+                .resolution(RING_RESOLUTION);
+        }
+
+        // Players only see auras on visible tokens outside the fog
+        let show_to_players = session_state.is_active
+            && item.layer.is_player_visible()
+            && (!fog_visible || fog_data.is_cell_revealed(world_to_cell(center, grid_size)));
+
+        for aura in &token.auras {
+            if aura.radius_ft <= 0.0 {
+                continue;
+            }
+            let [r, g, b, a] = aura.color;
+            let color = Color::srgba(r, g, b, a);
+            let radius = edge + feet_to_world(aura.radius_ft, grid_size);
+
+            editor_gizmos
+                .circle_2d(Isometry2d::from_translation(center), radius, color)
+                .resolution(RING_RESOLUTION);
+            if show_to_players && aura.player_visible {
+                player_gizmos
+                    .circle_2d(Isometry2d::from_translation(center), radius, color)
+                    .resolution(RING_RESOLUTION);
+            }
+        }
+    }
+}
+
+/// Reveal fog around tokens that shed light whenever they move or their
+/// light changes
+#[allow(clippy::type_complexity)]
+pub fn reveal_fog_from_token_light(
+    tokens: Query<
+        (&PlacedItem, &TokenData, &Transform, &Sprite, &Visibility),
+        Or<(Changed<Transform>, Changed<TokenData>)>,
+    >,
+    images: Res<Assets<Image>>,
+    map_data: Res<MapData>,
+    mut fog_data: ResMut<FogOfWarData>,
+    mut dirty_state: ResMut<MapDirtyState>,
+    mut open_maps: ResMut<OpenMaps>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    if tokens.is_empty() || !is_fog_layer_visible(&map_data) || is_fog_layer_locked(&map_data) {
+        return;
+    }
+
+    let grid_size = map_data.grid_size;
+    let mut revealed = Vec::new();
+
+    for (item, token, transform, sprite, visibility) in tokens.iter() {
+        // Light carried by hidden tokens (or on GM-only layers) stays secret
+        if !token.emits_light()
+            || !item.layer.is_player_visible()
+            || *visibility == Visibility::Hidden
+        {
+            continue;
+        }
+
+        let center = transform.translation.truncate();
+        let radius = token_edge_radius(transform, sprite, &images)
+            + feet_to_world(token.light_reach_ft(), grid_size);
+        for cell in cells_in_radius(center, radius, grid_size) {
+            if !fog_data.is_cell_revealed(cell) {
+                fog_data.reveal_cell(cell);
+                revealed.push(cell);
+            }
+        }
+    }
+
+    // Tokens respawned by a load/switch reveal what they already had revealed;
+    // anything new then is stale fog, not a user edit
+    if !revealed.is_empty() && dirty_state.suppress_detection == 0 {
+        dirty_state.is_dirty = true;
+        if let Some(active_map) = open_maps.active_map_mut() {
+            active_map.is_dirty = true;
+        }
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::LightRevealFog { cells: revealed },
+        });
+    }
+}
@@ -0,0 +1,353 @@
+//! Auto-tile painting for the Brush tool.
+//!
+//! In auto-tile mode the brush paints whole grid cells with one of the
+//! library's auto-tile sets. A cell belongs to the set when an item on the
+//! brush layer uses one of the set's pieces, so painted areas are plain placed
+//! items and need nothing extra in the map file. Whenever a cell is painted or
+//! erased, it and its eight neighbors are re-resolved and any cell whose piece
+//! or rotation changed gets its item swapped.
+//!
+//! Drag to paint, right-drag to erase. Each stroke is recorded as one undo
+//! step.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::assets::{neighbor_mask, AssetLibrary, LibraryAsset};
+use crate::map::{MapData, PlacedItem, TokenData};
+
+use super::brush::BrushMode;
+use super::fill::{cell_center, cell_fit_scale, cell_of};
+use super::history::{
+    spawn_placed_item, EditorCommand, PlacedItemData, RecordEditorCommand, TransformData,
+};
+use super::params::{is_cursor_over_ui, CameraParams};
+use super::selection::SelectionGizmoGroup;
+use super::tools::SelectedLayer;
+
+/// Outline color of the cell under the cursor
+const CURSOR_COLOR: Color = Color::srgba(0.2, 0.8, 0.4, 0.8);
+
+/// Auto-tile brush settings and the stroke in progress
+#[derive(Resource, Default)]
+pub struct AutoTileBrush {
+    /// Name of the auto-tile set to paint with
+    pub set_name: Option<String>,
+    /// Whether a stroke started on the map is in progress
+    stroking: bool,
+    /// Cell the cursor was over last frame, for filling in fast drags
+    last_cell: Option<IVec2>,
+    /// Items spawned by this stroke that are still on the map
+    placed: Vec<PlacedItemData>,
+    /// Items that were on the map before this stroke and have been removed
+    removed: Vec<PlacedItemData>,
+}
+
+impl AutoTileBrush {
+    /// Note an item leaving the map. Items placed earlier in the same stroke
+    /// just drop out of it.
+    fn record_removal(&mut self, item: PlacedItemData) {
+        match self.placed.iter().position(|p| p.entity == item.entity) {
+            Some(index) => {
+                self.placed.swap_remove(index);
+            }
+            None => self.removed.push(item),
+        }
+    }
+
+    /// The stroke's changes as a single command, resetting the stroke
+    fn take_stroke(&mut self) -> Option<EditorCommand> {
+        self.stroking = false;
+        self.last_cell = None;
+
+        let mut commands = Vec::new();
+        if !self.removed.is_empty() {
+            commands.push(EditorCommand::DeleteItems {
+                items: std::mem::take(&mut self.removed),
+            });
+        }
+        if !self.placed.is_empty() {
+            commands.push(EditorCommand::PlaceItems {
+                items: std::mem::take(&mut self.placed),
+            });
+        }
+        match commands.len() {
+            0 => None,
+            1 => commands.pop(),
+            _ => Some(EditorCommand::Batch { commands }),
+        }
+    }
+
+    fn end_stroke(&mut self, history_writer: &mut MessageWriter<RecordEditorCommand>) {
+        if let Some(command) = self.take_stroke() {
+            history_writer.write(RecordEditorCommand { command });
+        }
+    }
+}
+
+/// Cells on the line from `from` to `to`, excluding `from`, so a fast drag
+/// leaves no gaps
+pub fn cells_between(from: IVec2, to: IVec2) -> Vec<IVec2> {
+    let delta = to - from;
+    let steps = delta.x.abs().max(delta.y.abs());
+    (1..=steps)
+        .map(|step| {
+            let t = step as f32 / steps as f32;
+            from + (delta.as_vec2() * t).round().as_ivec2()
+        })
+        .collect()
+}
+
+/// Handle Brush tool input in auto-tile mode
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn handle_autotile_brush(
+    mut commands: Commands,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    brush_mode: Res<BrushMode>,
+    mut brush: ResMut<AutoTileBrush>,
+    selected_layer: Res<SelectedLayer>,
+    library: Res<AssetLibrary>,
+    map_data: Res<MapData>,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+    items: Query<(Entity, &Transform, &PlacedItem, Option<&TokenData>)>,
+    camera: CameraParams,
+    mut contexts: EguiContexts,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    let painting = mouse_button.pressed(MouseButton::Left);
+    let erasing = !painting && mouse_button.pressed(MouseButton::Right);
+    if *brush_mode != BrushMode::AutoTile || !(painting || erasing) {
+        brush.end_stroke(&mut history_writer);
+        return;
+    }
+
+    if mouse_button.just_pressed(MouseButton::Left) || mouse_button.just_pressed(MouseButton::Right)
+    {
+        brush.end_stroke(&mut history_writer);
+        if is_cursor_over_ui(&mut contexts) {
+            return;
+        }
+        brush.stroking = true;
+    }
+    if !brush.stroking {
+        return;
+    }
+
+    let Some(set) = brush
+        .set_name
+        .clone()
+        .and_then(|name| library.autotile_set(&name))
+    else {
+        return;
+    };
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return;
+    };
+    let grid_size = map_data.grid_size;
+    let cell = cell_of(world_pos, grid_size);
+    let path = match brush.last_cell {
+        Some(last) if last == cell => return,
+        Some(last) => cells_between(last, cell),
+        None => vec![cell],
+    };
+    brush.last_cell = Some(cell);
+
+    // The set's pieces that exist in the library, by library-relative path
+    let pieces: HashMap<String, &LibraryAsset> = library
+        .assets
+        .iter()
+        .map(|asset| (asset.library_relative_path(), asset))
+        .filter(|(path, _)| set.contains_piece(path))
+        .collect();
+    if pieces.is_empty() {
+        return;
+    }
+    let piece_paths: HashSet<&str> = pieces
+        .values()
+        .map(|asset| asset.relative_path.as_str())
+        .collect();
+
+    // Cells the set covers on this layer; `None` marks a cell painted this
+    // frame that has no item yet
+    let layer = selected_layer.layer;
+    let mut tiles: HashMap<IVec2, Option<PlacedItemData>> = items
+        .iter()
+        .filter(|(_, _, item, _)| {
+            item.layer == layer && piece_paths.contains(item.asset_path.as_str())
+        })
+        .map(|(entity, transform, item, token)| {
+            let data = PlacedItemData {
+                entity,
+                asset_path: item.asset_path.clone(),
+                layer: item.layer,
+                z_index: item.z_index,
+                transform: TransformData::from(transform),
+                token: token.cloned(),
+            };
+            (
+                cell_of(transform.translation.truncate(), grid_size),
+                Some(data),
+            )
+        })
+        .collect();
+
+    let mut dirty = HashSet::new();
+    for cell in path {
+        if painting {
+            if tiles.contains_key(&cell) {
+                continue;
+            }
+            tiles.insert(cell, None);
+        } else {
+            let Some(tile) = tiles.remove(&cell) else {
+                continue;
+            };
+            if let Some(tile) = tile {
+                commands.entity(tile.entity).despawn();
+                brush.record_removal(tile);
+            }
+        }
+        dirty.insert(cell);
+        dirty.extend(neighbors(cell));
+    }
+
+    for cell in dirty {
+        if !tiles.contains_key(&cell) {
+            continue;
+        }
+        let mask = neighbor_mask(cell, |neighbor| tiles.contains_key(&neighbor));
+        let Some((piece, quarter_turns)) = set.resolve(mask) else {
+            continue;
+        };
+        let Some(asset) = pieces.get(piece) else {
+            continue;
+        };
+        let rotation =
+            Quat::from_rotation_z(f32::from(quarter_turns) * std::f32::consts::FRAC_PI_2);
+
+        let Some(slot) = tiles.get_mut(&cell) else {
+            continue;
+        };
+        if let Some(tile) = slot.take() {
+            if tile.asset_path == asset.relative_path
+                && tile.transform.rotation.angle_between(rotation) < 0.01
+            {
+                *slot = Some(tile);
+                continue;
+            }
+            commands.entity(tile.entity).despawn();
+            brush.record_removal(tile);
+        }
+
+        let texture: Handle<Image> = asset_server.load(&asset.relative_path);
+        let transform = Transform {
+            translation: cell_center(cell, grid_size).extend(layer.z_base()),
+            rotation,
+            scale: cell_fit_scale(asset, &texture, grid_size, &images),
+        };
+        let mut data = PlacedItemData {
+            entity: Entity::PLACEHOLDER,
+            asset_path: asset.relative_path.clone(),
+            layer,
+            z_index: 0,
+            transform: TransformData::from(&transform),
+            token: None,
+        };
+        data.entity = spawn_placed_item(&mut commands, &asset_server, &data);
+        brush.placed.push(data.clone());
+        *slot = Some(data);
+    }
+}
+
+/// The eight cells around `cell`
+fn neighbors(cell: IVec2) -> impl Iterator<Item = IVec2> {
+    (-1..=1)
+        .flat_map(|y| (-1..=1).map(move |x| IVec2::new(x, y)))
+        .filter(|offset| *offset != IVec2::ZERO)
+        .map(move |offset| cell + offset)
+}
+
+/// Outline the cell the auto-tile brush would paint
+pub fn draw_autotile_cursor(
+    mut gizmos: Gizmos<SelectionGizmoGroup>,
+    brush_mode: Res<BrushMode>,
+    map_data: Res<MapData>,
+    camera: CameraParams,
+) {
+    if *brush_mode != BrushMode::AutoTile {
+        return;
+    }
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return;
+    };
+    let grid_size = map_data.grid_size;
+    gizmos.rect_2d(
+        Isometry2d::from_translation(cell_center(cell_of(world_pos, grid_size), grid_size)),
+        Vec2::splat(grid_size),
+        CURSOR_COLOR,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Layer;
+
+    fn item(index: u32) -> PlacedItemData {
+        PlacedItemData {
+            entity: Entity::from_raw_u32(index).unwrap(),
+            asset_path: "tiles/floor.png".to_string(),
+            layer: Layer::Terrain,
+            z_index: 0,
+            transform: TransformData::from(&Transform::IDENTITY),
+            token: None,
+        }
+    }
+
+    #[test]
+    fn test_cells_between_leaves_no_gaps() {
+        let cells = cells_between(IVec2::ZERO, IVec2::new(3, 1));
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells.last(), Some(&IVec2::new(3, 1)));
+        for pair in cells.windows(2) {
+            let step = (pair[1] - pair[0]).abs();
+            assert!(step.x <= 1 && step.y <= 1);
+        }
+        assert!(cells_between(IVec2::ONE, IVec2::ONE).is_empty());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let cells: Vec<IVec2> = neighbors(IVec2::new(2, 2)).collect();
+        assert_eq!(cells.len(), 8);
+        assert!(!cells.contains(&IVec2::new(2, 2)));
+        assert!(cells.contains(&IVec2::new(1, 3)));
+    }
+
+    #[test]
+    fn test_stroke_records_one_command() {
+        let mut brush = AutoTileBrush::default();
+        assert!(brush.take_stroke().is_none());
+
+        // Placing then replacing an item in the same stroke leaves only the
+        // replacement
+        brush.placed.push(item(1));
+        brush.record_removal(item(1));
+        brush.placed.push(item(2));
+        assert!(matches!(
+            brush.take_stroke(),
+            Some(EditorCommand::PlaceItems { items }) if items.len() == 1
+        ));
+
+        // Replacing an existing item deletes it and places the new piece
+        brush.record_removal(item(3));
+        brush.placed.push(item(4));
+        assert!(matches!(
+            brush.take_stroke(),
+            Some(EditorCommand::Batch { commands }) if commands.len() == 2
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+//! Recalling camera bookmarks.
+//!
+//! Bookmarks are recalled from the toolbar's Bookmarks menu or with the number
+//! keys. A recall moves the editor camera and, during a live session, can
+//! also move the player viewport. With smooth panning on, both glide to the
+//! bookmark instead of jumping.
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::keybindings::{Action, ActionInput};
+use crate::map::{CameraBookmark, CameraBookmarks};
+use crate::session::LiveSessionState;
+
+use super::camera::{CameraZoom, EditorCamera};
+
+/// Seconds a smooth pan takes
+const PAN_DURATION: f32 = 0.6;
+
+/// How bookmarks are recalled
+#[derive(Resource)]
+pub struct BookmarkSettings {
+    /// Glide to bookmarks instead of jumping
+    pub smooth_pan: bool,
+    /// Number keys also move the player viewport during a live session
+    pub move_players: bool,
+}
+
+impl Default for BookmarkSettings {
+    fn default() -> Self {
+        Self {
+            smooth_pan: true,
+            move_players: false,
+        }
+    }
+}
+
+/// Message to move the editor camera and/or the player viewport to a bookmark
+#[derive(Message)]
+pub struct RecallBookmarkRequest {
+    pub index: usize,
+    pub camera: bool,
+    /// Ignored outside a live session
+    pub players: bool,
+}
+
+/// A pan from one view to another. Each end is a position and a size, the
+/// camera zoom or the viewport width.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Pan {
+    from: (Vec2, f32),
+    to: (Vec2, f32),
+    elapsed: f32,
+}
+
+impl Pan {
+    fn new(from: (Vec2, f32), to: (Vec2, f32)) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: 0.0,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= PAN_DURATION
+    }
+
+    /// Position and size at the current time, eased in and out
+    fn sample(&self) -> (Vec2, f32) {
+        let t = (self.elapsed / PAN_DURATION).clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+        (
+            self.from.0.lerp(self.to.0, t),
+            self.from.1 + (self.to.1 - self.from.1) * t,
+        )
+    }
+}
+
+/// Smooth pans in progress
+#[derive(Resource, Default)]
+pub struct BookmarkPans {
+    camera: Option<Pan>,
+    viewport: Option<Pan>,
+}
+
+/// Where the player viewport goes for a bookmark: the saved viewport, or the
+/// bookmark's camera position at the current width when none was saved
+fn viewport_target(bookmark: &CameraBookmark, current_width: f32) -> (Vec2, f32, Option<i32>) {
+    match bookmark.viewport {
+        Some(viewport) => (
+            viewport.center,
+            viewport.width,
+            Some(viewport.rotation_degrees),
+        ),
+        None => (bookmark.position, current_width, None),
+    }
+}
+
+/// Send recall requests for the number-key bookmark shortcuts
+pub fn handle_bookmark_shortcuts(
+    keys: ActionInput,
+    mut contexts: EguiContexts,
+    settings: Res<BookmarkSettings>,
+    mut recall_writer: MessageWriter<RecallBookmarkRequest>,
+) {
+    // Don't trigger if typing in UI
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_keyboard_input()
+    {
+        return;
+    }
+
+    let pressed = (0..)
+        .map_while(Action::recall_bookmark)
+        .position(|a| keys.just_pressed(a));
+    if let Some(index) = pressed {
+        recall_writer.write(RecallBookmarkRequest {
+            index,
+            camera: true,
+            players: settings.move_players,
+        });
+    }
+}
+
+/// Move the camera and viewport to requested bookmarks, or start pans there
+pub fn handle_recall_bookmark(
+    mut events: MessageReader<RecallBookmarkRequest>,
+    bookmarks: Res<CameraBookmarks>,
+    settings: Res<BookmarkSettings>,
+    mut pans: ResMut<BookmarkPans>,
+    mut camera_query: Query<(&mut Transform, &mut CameraZoom), With<EditorCamera>>,
+    mut session_state: ResMut<LiveSessionState>,
+) {
+    for event in events.read() {
+        let Some(bookmark) = bookmarks.get(event.index) else {
+            continue;
+        };
+
+        if event.camera
+            && let Ok((mut transform, mut zoom)) = camera_query.single_mut()
+        {
+            let to = (bookmark.position, bookmark.zoom.clamp(0.1, 10.0));
+            if settings.smooth_pan {
+                let from = (transform.translation.truncate(), zoom.scale);
+                pans.camera = Some(Pan::new(from, to));
+            } else {
+                pans.camera = None;
+                transform.translation.x = to.0.x;
+                transform.translation.y = to.0.y;
+                zoom.scale = to.1;
+            }
+        }
+
+        if event.players && session_state.is_active {
+            let (center, width, rotation) =
+                viewport_target(bookmark, session_state.viewport_size.x);
+            if let Some(rotation) = rotation {
+                session_state.rotation_degrees = rotation;
+            }
+            if settings.smooth_pan {
+                let from = (session_state.viewport_center, session_state.viewport_size.x);
+                pans.viewport = Some(Pan::new(from, (center, width)));
+            } else {
+                pans.viewport = None;
+                let aspect = session_state.monitor_aspect_ratio();
+                session_state.viewport_center = center;
+                session_state.viewport_size = Vec2::new(width, width / aspect);
+            }
+        }
+    }
+}
+
+/// Advance smooth pans. Panning the camera by hand cancels its pan.
+pub fn animate_bookmark_pans(
+    time: Res<Time>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut pans: ResMut<BookmarkPans>,
+    mut camera_query: Query<(&mut Transform, &mut CameraZoom), With<EditorCamera>>,
+    mut session_state: ResMut<LiveSessionState>,
+) {
+    let delta = time.delta_secs();
+
+    if mouse_button.pressed(MouseButton::Middle) {
+        pans.camera = None;
+    }
+    if let Some(pan) = &mut pans.camera {
+        pan.elapsed += delta;
+        let (center, scale) = pan.sample();
+        if let Ok((mut transform, mut zoom)) = camera_query.single_mut() {
+            transform.translation.x = center.x;
+            transform.translation.y = center.y;
+            zoom.scale = scale;
+        }
+        if pan.is_finished() {
+            pans.camera = None;
+        }
+    }
+
+    if !session_state.is_active {
+        pans.viewport = None;
+    }
+    if let Some(pan) = &mut pans.viewport {
+        pan.elapsed += delta;
+        let (center, width) = pan.sample();
+        let aspect = session_state.monitor_aspect_ratio();
+        session_state.viewport_center = center;
+        session_state.viewport_size = Vec2::new(width, width / aspect);
+        if pan.is_finished() {
+            pans.viewport = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::BookmarkViewport;
+
+    #[test]
+    fn test_pan_eases_between_views() {
+        let mut pan = Pan::new((Vec2::ZERO, 1.0), (Vec2::new(100.0, -50.0), 3.0));
+        assert_eq!(pan.sample(), (Vec2::ZERO, 1.0));
+
+        pan.elapsed = PAN_DURATION / 2.0;
+        assert_eq!(pan.sample(), (Vec2::new(50.0, -25.0), 2.0));
+        assert!(!pan.is_finished());
+
+        // Overshooting the duration lands exactly on the target
+        pan.elapsed = PAN_DURATION * 2.0;
+        assert_eq!(pan.sample(), (Vec2::new(100.0, -50.0), 3.0));
+        assert!(pan.is_finished());
+    }
+
+    #[test]
+    fn test_viewport_target() {
+        let mut bookmark = CameraBookmark {
+            name: "Tavern".to_string(),
+            position: Vec2::new(10.0, 20.0),
+            zoom: 1.0,
+            viewport: None,
+        };
+        assert_eq!(
+            viewport_target(&bookmark, 700.0),
+            (Vec2::new(10.0, 20.0), 700.0, None)
+        );
+
+        bookmark.viewport = Some(BookmarkViewport {
+            center: Vec2::new(30.0, 40.0),
+            width: 500.0,
+            rotation_degrees: 180,
+        });
+        assert_eq!(
+            viewport_target(&bookmark, 700.0),
+            (Vec2::new(30.0, 40.0), 500.0, Some(180))
+        );
+    }
+}
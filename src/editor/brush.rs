@@ -8,19 +8,33 @@
 //! - Default (click+drag): Places assets continuously, new placement when cursor
 //!   leaves the bounds of the last placed item
 //! - Shift+click: Grid-fitted placement - resizes asset to fit grid cell and centers it
+//! - Assets with a creature size are always fitted to their footprint
+//!
+//! ## Scatter Mode
+//!
+//! In scatter mode the brush fills a circle around the cursor with
+//! randomly placed, rotated, scaled and mirrored assets picked from a weighted
+//! set. Each stroke draws its random numbers from [`ScatterSettings::seed`], so
+//! repeating a stroke with the same seed repeats its choices.
 
 use bevy::camera::visibility::RenderLayers;
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
-use crate::assets::SelectedAsset;
-use crate::map::{MapData, PlacedItem};
+use crate::assets::{AssetLibrary, LibraryAsset, SelectedAsset};
+use crate::map::{Layer, MapData, PlacedItem, TokenData};
 
-use super::grid::snap_to_grid;
+use super::grid::{snap_point, snap_to_footprint};
 use super::history::{EditorCommand, PlacedItemData, RecordEditorCommand, TransformData};
-use super::params::{is_cursor_over_ui, CameraParams};
+use super::params::{is_cursor_over_ui, CameraParams, CameraWithProjection};
+use super::placement::{creature_fit_scale, token_size};
+use super::selection::SelectionGizmoGroup;
+use super::snapping::SnapParams;
 use super::tools::SelectedLayer;
 
+/// Candidate points tried per frame when scattering
+const SCATTER_ATTEMPTS_PER_FRAME: usize = 12;
+
 /// Bounding box for a placed item
 #[derive(Debug, Clone, Copy)]
 pub struct PlacedBounds {
@@ -47,6 +61,178 @@ pub struct BrushState {
     /// Items placed during the current brush stroke, accumulated so the whole
     /// stroke is recorded as a single undo step on release.
     pub stroke_items: Vec<PlacedItemData>,
+    /// Random numbers for the current scatter stroke
+    rng: fastrand::Rng,
+}
+
+/// An asset in the scatter set and how often it's picked relative to the others
+#[derive(Debug, Clone)]
+pub struct ScatterAsset {
+    pub asset: LibraryAsset,
+    pub weight: f32,
+}
+
+/// What the Brush tool paints with
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrushMode {
+    /// The selected asset, one per grid cell
+    #[default]
+    Stamp,
+    /// Randomized assets around the cursor
+    Scatter,
+    /// Pieces of an auto-tile set, joined to their neighbors
+    AutoTile,
+}
+
+impl BrushMode {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            BrushMode::Stamp => "Stamp",
+            BrushMode::Scatter => "Scatter",
+            BrushMode::AutoTile => "Auto-tile",
+        }
+    }
+
+    pub fn all() -> &'static [BrushMode] {
+        &[BrushMode::Stamp, BrushMode::Scatter, BrushMode::AutoTile]
+    }
+}
+
+/// Settings for the Brush tool's scatter mode
+#[derive(Resource, Debug, Clone)]
+pub struct ScatterSettings {
+    /// Radius of the scatter circle, in grid cells
+    pub radius: f32,
+    /// Items per grid cell of brush area
+    pub density: f32,
+    /// Minimum distance between items in a stroke, in grid cells
+    pub spacing: f32,
+    /// Maximum random rotation either way, in degrees
+    pub rotation_range: f32,
+    /// Random scale multiplier range
+    pub scale_min: f32,
+    pub scale_max: f32,
+    /// Randomly mirror items horizontally
+    pub random_flip: bool,
+    /// Weighted assets to pick from (the selected asset when empty)
+    pub assets: Vec<ScatterAsset>,
+    /// Seed for the next stroke's random numbers
+    pub seed: u32,
+    /// Reuse the seed for every stroke instead of advancing it
+    pub lock_seed: bool,
+}
+
+impl Default for ScatterSettings {
+    fn default() -> Self {
+        Self {
+            radius: 2.0,
+            density: 0.5,
+            spacing: 0.5,
+            rotation_range: 180.0,
+            scale_min: 0.8,
+            scale_max: 1.2,
+            random_flip: true,
+            assets: Vec::new(),
+            seed: fastrand::u32(..),
+            lock_seed: false,
+        }
+    }
+}
+
+impl ScatterSettings {
+    /// Number of items a stroke keeps inside the brush circle
+    pub fn target_count(&self) -> usize {
+        let area = std::f32::consts::PI * self.radius * self.radius;
+        (self.density * area).round().max(1.0) as usize
+    }
+
+    /// Add an asset to the set, or bump its weight if it's already there
+    pub fn add_asset(&mut self, asset: &LibraryAsset) {
+        match self
+            .assets
+            .iter_mut()
+            .find(|entry| entry.asset.relative_path == asset.relative_path)
+        {
+            Some(entry) => entry.weight += 1.0,
+            None => self.assets.push(ScatterAsset {
+                asset: asset.clone(),
+                weight: 1.0,
+            }),
+        }
+    }
+
+    /// Random scale, rotation and mirroring for one scattered item
+    fn random_variation(&self, rng: &mut fastrand::Rng) -> (Quat, f32, bool) {
+        let angle = (rng.f32() * 2.0 - 1.0) * self.rotation_range;
+        let (min, max) = (
+            self.scale_min.min(self.scale_max),
+            self.scale_min.max(self.scale_max),
+        );
+        let scale = min + rng.f32() * (max - min);
+        let flip = self.random_flip && rng.bool();
+        (Quat::from_rotation_z(angle.to_radians()), scale, flip)
+    }
+}
+
+/// Pick an asset from the set in proportion to its weight
+pub fn pick_weighted<'a>(
+    assets: &'a [ScatterAsset],
+    rng: &mut fastrand::Rng,
+) -> Option<&'a LibraryAsset> {
+    let total: f32 = assets.iter().map(|entry| entry.weight.max(0.0)).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut roll = rng.f32() * total;
+    for entry in assets.iter().filter(|entry| entry.weight > 0.0) {
+        if roll < entry.weight {
+            return Some(&entry.asset);
+        }
+        roll -= entry.weight;
+    }
+    // Rounding can leave the roll just past the last entry
+    assets
+        .iter()
+        .rev()
+        .find(|entry| entry.weight > 0.0)
+        .map(|entry| &entry.asset)
+}
+
+/// New scatter points around `center`, keeping at most `target` of the
+/// stroke's points inside the circle and every point `spacing` apart
+pub fn scatter_points(
+    existing: &[Vec2],
+    center: Vec2,
+    radius: f32,
+    spacing: f32,
+    target: usize,
+    rng: &mut fastrand::Rng,
+) -> Vec<Vec2> {
+    let mut placed: Vec<Vec2> = Vec::new();
+    let mut inside = existing
+        .iter()
+        .filter(|point| point.distance(center) <= radius)
+        .count();
+
+    for _ in 0..SCATTER_ATTEMPTS_PER_FRAME {
+        if inside >= target {
+            break;
+        }
+        // sqrt keeps points evenly spread rather than bunched at the center
+        let angle = rng.f32() * std::f32::consts::TAU;
+        let distance = radius * rng.f32().sqrt();
+        let point = center + Vec2::from_angle(angle) * distance;
+
+        let crowded = existing
+            .iter()
+            .chain(placed.iter())
+            .any(|other| other.distance(point) < spacing);
+        if !crowded {
+            placed.push(point);
+            inside += 1;
+        }
+    }
+    placed
 }
 
 impl BrushState {
@@ -60,6 +246,53 @@ impl BrushState {
             });
         }
     }
+
+    /// Finish a scatter stroke, advancing the seed unless it's locked
+    fn end_scatter_stroke(&mut self, settings: &mut ScatterSettings) {
+        if !settings.lock_seed {
+            settings.seed = self.rng.u32(..);
+        }
+    }
+}
+
+/// Spawn a placed item for the brush and return its undo data
+fn spawn_brush_item(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    asset: &LibraryAsset,
+    layer: Layer,
+    transform: Transform,
+    token: Option<TokenData>,
+) -> PlacedItemData {
+    // Items on non-player-visible layers go to render layer 1 (editor-only)
+    let render_layer = if layer.is_player_visible() {
+        RenderLayers::layer(0)
+    } else {
+        RenderLayers::layer(1)
+    };
+
+    let mut entity_commands = commands.spawn((
+        Sprite::from_image(asset_server.load(&asset.relative_path)),
+        transform,
+        PlacedItem {
+            asset_path: asset.relative_path.clone(),
+            layer,
+            z_index: 0,
+        },
+        render_layer,
+    ));
+    if let Some(token) = &token {
+        entity_commands.insert(token.clone());
+    }
+
+    PlacedItemData {
+        entity: entity_commands.id(),
+        asset_path: asset.relative_path.clone(),
+        layer,
+        z_index: 0,
+        transform: TransformData::from(&transform),
+        token,
+    }
 }
 
 /// Handle brush tool input for continuous asset placement
@@ -70,11 +303,14 @@ pub fn handle_brush(
     keyboard: Res<ButtonInput<KeyCode>>,
     selected_layer: Res<SelectedLayer>,
     selected_asset: Res<SelectedAsset>,
+    asset_library: Res<AssetLibrary>,
     map_data: Res<MapData>,
     asset_server: Res<AssetServer>,
-    images: Res<Assets<Image>>,
-    camera: CameraParams,
+    camera: CameraWithProjection,
+    mut snap: SnapParams,
     mut brush_state: ResMut<BrushState>,
+    brush_mode: Res<BrushMode>,
+    mut scatter: ResMut<ScatterSettings>,
     mut contexts: EguiContexts,
     mut history_writer: MessageWriter<RecordEditorCommand>,
 ) {
@@ -83,6 +319,11 @@ pub fn handle_brush(
         return;
     }
 
+    // Auto-tile painting is handled by its own system
+    if *brush_mode == BrushMode::AutoTile {
+        return;
+    }
+
     // Handle mouse press - start brushing
     if mouse_button.just_pressed(MouseButton::Left) {
         // Flush any leftover stroke (e.g. a previous release that happened over
@@ -90,10 +331,14 @@ pub fn handle_brush(
         brush_state.flush_stroke(&mut history_writer);
         brush_state.is_brushing = true;
         brush_state.last_placed_bounds = None;
+        brush_state.rng = fastrand::Rng::with_seed(scatter.seed.into());
     }
 
     // Handle mouse release - stop brushing and record the stroke
     if mouse_button.just_released(MouseButton::Left) {
+        if brush_state.is_brushing && *brush_mode == BrushMode::Scatter {
+            brush_state.end_scatter_stroke(&mut scatter);
+        }
         brush_state.is_brushing = false;
         brush_state.last_placed_bounds = None;
         brush_state.flush_stroke(&mut history_writer);
@@ -104,11 +349,27 @@ pub fn handle_brush(
         return;
     }
 
-    let Some(ref asset) = selected_asset.asset else {
+    let Some(world_pos) = camera.cursor_world_pos() else {
         return;
     };
 
-    let Some(world_pos) = camera.cursor_world_pos() else {
+    if *brush_mode == BrushMode::Scatter {
+        scatter_at(
+            &mut commands,
+            world_pos,
+            &scatter,
+            &mut brush_state,
+            selected_layer.layer,
+            selected_asset.asset.as_ref(),
+            &asset_library,
+            &asset_server,
+            &snap.images,
+            map_data.grid_size,
+        );
+        return;
+    }
+
+    let Some(ref asset) = selected_asset.asset else {
         return;
     };
 
@@ -119,15 +380,15 @@ pub fn handle_brush(
     let texture: Handle<Image> = asset_server.load(&asset.relative_path);
 
     // Get image dimensions (needed for both bounds checking and scale calculation)
-    let (img_width, img_height) = if let Some(image) = images.get(&texture) {
-        (image.width() as f32, image.height() as f32)
-    } else {
-        // Image not loaded yet - use grid size as fallback for bounds
-        (grid_size, grid_size)
-    };
+    let loaded_size = snap.images.get(&texture).map(|image| image.size().as_vec2());
+    // Image not loaded yet - use grid size as fallback for bounds
+    let (img_width, img_height) = loaded_size.map_or((grid_size, grid_size), |s| (s.x, s.y));
 
     // Calculate the scale that will be applied
-    let scale = if shift_held {
+    let creature_size = token_size(&asset_library, asset, selected_layer.layer);
+    let scale = if let Some(size) = creature_size {
+        creature_fit_scale(size, asset, loaded_size, grid_size)
+    } else if shift_held {
         if img_width > 0.0 && img_height > 0.0 {
             let scale_x = grid_size / img_width;
             let scale_y = grid_size / img_height;
@@ -141,7 +402,10 @@ pub fn handle_brush(
     };
 
     // Calculate the actual size of the placed item after scaling
-    let placed_size = Vec2::new(img_width * scale.x, img_height * scale.y);
+    let placed_size = match creature_size {
+        Some(size) => Vec2::splat(size.footprint_cells() * grid_size),
+        None => Vec2::new(img_width * scale.x, img_height * scale.y),
+    };
 
     // Check if cursor is still inside the last placed item's bounds
     if let Some(bounds) = brush_state.last_placed_bounds
@@ -150,8 +414,15 @@ pub fn handle_brush(
         return;
     }
 
-    // Calculate placement position (center of grid cell)
-    let final_pos = snap_to_grid(world_pos, grid_size, true);
+    // Calculate placement position (center of grid cell, or footprint-aligned)
+    let final_pos = match creature_size {
+        Some(size) => snap_to_footprint(world_pos, grid_size, size.footprint_cells(), true),
+        None => {
+            let pos = snap_point(world_pos, grid_size, snap.settings.snap_mode);
+            let half = placed_size / 2.0;
+            pos + snap.snap_to_guides((pos - half, pos + half), camera.zoom_scale())
+        }
+    };
 
     // Update the bounds for the newly placed item
     brush_state.last_placed_bounds = Some(PlacedBounds {
@@ -160,41 +431,113 @@ pub fn handle_brush(
     });
 
     let layer = selected_layer.layer;
-    let z = layer.z_base();
-
-    // Items on non-player-visible layers go to render layer 1 (editor-only)
-    let render_layer = if layer.is_player_visible() {
-        RenderLayers::layer(0)
-    } else {
-        RenderLayers::layer(1)
-    };
-
     let transform = Transform {
-        translation: final_pos.extend(z),
+        translation: final_pos.extend(layer.z_base()),
         scale,
         ..default()
     };
-    let entity = commands
-        .spawn((
-            Sprite::from_image(texture),
-            transform,
-            PlacedItem {
-                asset_path: asset.relative_path.clone(),
-                layer,
-                z_index: 0,
-            },
-            render_layer,
-        ))
-        .id();
+    let token = creature_size.map(|size| TokenData {
+        size: Some(size),
+        ..default()
+    });
+    let item = spawn_brush_item(&mut commands, &asset_server, asset, layer, transform, token);
 
     // Accumulate for the current stroke; recorded as one undo step on release.
-    brush_state.stroke_items.push(PlacedItemData {
-        entity,
-        asset_path: asset.relative_path.clone(),
-        layer,
-        z_index: 0,
-        transform: TransformData::from(&transform),
-    });
+    brush_state.stroke_items.push(item);
+}
+
+/// Fill the scatter circle around the cursor with randomized items
+#[allow(clippy::too_many_arguments)]
+fn scatter_at(
+    commands: &mut Commands,
+    world_pos: Vec2,
+    scatter: &ScatterSettings,
+    brush_state: &mut BrushState,
+    layer: Layer,
+    selected_asset: Option<&LibraryAsset>,
+    asset_library: &AssetLibrary,
+    asset_server: &AssetServer,
+    images: &Assets<Image>,
+    grid_size: f32,
+) {
+    // Without a set, scatter the selected asset
+    if scatter.assets.is_empty() && selected_asset.is_none() {
+        return;
+    }
+
+    let existing: Vec<Vec2> = brush_state
+        .stroke_items
+        .iter()
+        .map(|item| item.transform.translation.truncate())
+        .collect();
+    let points = scatter_points(
+        &existing,
+        world_pos,
+        scatter.radius * grid_size,
+        scatter.spacing * grid_size,
+        scatter.target_count(),
+        &mut brush_state.rng,
+    );
+
+    for point in points {
+        let asset = if scatter.assets.is_empty() {
+            selected_asset
+        } else {
+            pick_weighted(&scatter.assets, &mut brush_state.rng)
+        };
+        let Some(asset) = asset else {
+            return;
+        };
+        let (rotation, factor, flip) = scatter.random_variation(&mut brush_state.rng);
+
+        let creature_size = token_size(asset_library, asset, layer);
+        let base_scale = match creature_size {
+            Some(size) => {
+                let texture: Handle<Image> = asset_server.load(&asset.relative_path);
+                let loaded_size = images.get(&texture).map(|image| image.size().as_vec2());
+                creature_fit_scale(size, asset, loaded_size, grid_size)
+            }
+            None => Vec3::ONE,
+        };
+        let mut scale = base_scale * Vec3::new(factor, factor, 1.0);
+        // Mirrored items keep a negative X scale so the flip is saved with them
+        if flip {
+            scale.x = -scale.x;
+        }
+
+        let transform = Transform {
+            translation: point.extend(layer.z_base()),
+            rotation,
+            scale,
+        };
+        let token = creature_size.map(|size| TokenData {
+            size: Some(size),
+            ..default()
+        });
+        let item = spawn_brush_item(commands, asset_server, asset, layer, transform, token);
+        brush_state.stroke_items.push(item);
+    }
+}
+
+/// Outline the scatter circle around the cursor
+pub fn draw_scatter_brush(
+    mut gizmos: Gizmos<SelectionGizmoGroup>,
+    brush_mode: Res<BrushMode>,
+    scatter: Res<ScatterSettings>,
+    map_data: Res<MapData>,
+    camera: CameraParams,
+) {
+    if *brush_mode != BrushMode::Scatter {
+        return;
+    }
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return;
+    };
+    gizmos.circle_2d(
+        Isometry2d::from_translation(world_pos),
+        scatter.radius * map_data.grid_size,
+        Color::srgba(0.2, 0.6, 1.0, 0.6),
+    );
 }
 
 #[cfg(test)]
@@ -230,4 +573,82 @@ mod tests {
         assert!(!bounds.contains(Vec2::new(100.0, 0.0)));
         assert!(!bounds.contains(Vec2::new(100.0, 200.0)));
     }
+
+    fn scatter_asset(name: &str, weight: f32) -> ScatterAsset {
+        ScatterAsset {
+            asset: LibraryAsset {
+                name: name.to_string(),
+                relative_path: format!("library/{}.png", name),
+                folder_path: String::new(),
+                extension: "png".to_string(),
+                full_path: format!("{}.png", name).into(),
+            },
+            weight,
+        }
+    }
+
+    #[test]
+    fn test_pick_weighted_follows_weights() {
+        let assets = [
+            scatter_asset("tree", 3.0),
+            scatter_asset("bush", 1.0),
+            scatter_asset("rock", 0.0),
+        ];
+        let mut rng = fastrand::Rng::with_seed(5);
+        let mut trees = 0;
+        for _ in 0..1000 {
+            let picked = pick_weighted(&assets, &mut rng).unwrap();
+            assert_ne!(picked.name, "rock");
+            if picked.name == "tree" {
+                trees += 1;
+            }
+        }
+        assert!((650..850).contains(&trees), "picked {} trees", trees);
+
+        let unweighted = [scatter_asset("rock", 0.0)];
+        assert!(pick_weighted(&unweighted, &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_scatter_points_respect_spacing_and_radius() {
+        let mut rng = fastrand::Rng::with_seed(11);
+        let mut points: Vec<Vec2> = Vec::new();
+        for _ in 0..20 {
+            let new = scatter_points(&points, Vec2::ZERO, 140.0, 35.0, 10, &mut rng);
+            points.extend(new);
+        }
+
+        assert_eq!(points.len(), 10);
+        assert!(points.iter().all(|p| p.length() <= 140.0));
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                assert!(a.distance(*b) >= 35.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scatter_is_reproducible_from_seed() {
+        let run = |seed| {
+            let settings = ScatterSettings::default();
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let points = scatter_points(&[], Vec2::ZERO, 140.0, 20.0, 6, &mut rng);
+            let variation = settings.random_variation(&mut rng);
+            (points, variation)
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42).0, run(43).0);
+    }
+
+    #[test]
+    fn test_scatter_settings_add_asset_bumps_weight() {
+        let mut settings = ScatterSettings::default();
+        let tree = scatter_asset("tree", 1.0).asset;
+        settings.add_asset(&tree);
+        settings.add_asset(&tree);
+        assert_eq!(settings.assets.len(), 1);
+        assert_eq!(settings.assets[0].weight, 2.0);
+        // 0.5 per cell over a 2-cell radius
+        assert_eq!(settings.target_count(), 6);
+    }
 }
@@ -3,28 +3,29 @@
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
-use crate::map::{PlacedItem, SavedLine, SavedPath, SavedPlacedItem, SavedTextBox, Selected};
+use crate::keybindings::{Action, ActionInput};
+use crate::map::{
+    PlacedItem, SavedLine, SavedPath, SavedPlacedItem, SavedShape, SavedTextBox, Selected,
+    TokenData,
+};
 
-use super::super::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
-use super::helpers::{calculate_selection_centroid, color_to_array, path_center};
-use super::types::{Clipboard, ClipboardLine, ClipboardPath, ClipboardPlacedItem, ClipboardText};
+use super::super::params::SelectedAnnotationQueries;
+use super::helpers::{calculate_selection_centroid, color_to_array, path_center, shape_center};
+use super::types::{
+    Clipboard, ClipboardLine, ClipboardPath, ClipboardPlacedItem, ClipboardShape, ClipboardText,
+};
 
 /// Copy selected items to clipboard (Ctrl+C)
-#[allow(clippy::type_complexity)]
 pub fn handle_copy(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     mut clipboard: ResMut<Clipboard>,
     mut contexts: EguiContexts,
     // PlacedItem queries
-    selected_items: Query<(&PlacedItem, &Transform), With<Selected>>,
-    // Annotation queries
-    selected_paths: Query<&DrawnPath, (With<Selected>, With<AnnotationMarker>)>,
-    selected_lines: Query<&DrawnLine, (With<Selected>, With<AnnotationMarker>)>,
-    selected_texts: Query<(&Transform, &TextAnnotation), (With<Selected>, With<AnnotationMarker>)>,
+    selected_items: Query<(&PlacedItem, &Transform, Option<&TokenData>), With<Selected>>,
+    annotations: SelectedAnnotationQueries,
 ) {
     // Check for Ctrl+C
-    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
-    if !ctrl || !keyboard.just_pressed(KeyCode::KeyC) {
+    if !keys.just_pressed(Action::Copy) {
         return;
     }
 
@@ -37,27 +38,34 @@ pub fn handle_copy(
 
     // Nothing selected? Don't clear clipboard
     if selected_items.is_empty()
-        && selected_paths.is_empty()
-        && selected_lines.is_empty()
-        && selected_texts.is_empty()
+        && annotations.paths.is_empty()
+        && annotations.lines.is_empty()
+        && annotations.shapes.is_empty()
+        && annotations.texts.is_empty()
     {
         return;
     }
 
+    copy_selection(&selected_items, &annotations, &mut clipboard);
+}
+
+/// Replace the contents of `clipboard` with the selection
+#[allow(clippy::type_complexity)]
+pub(super) fn copy_selection(
+    selected_items: &Query<(&PlacedItem, &Transform, Option<&TokenData>), With<Selected>>,
+    annotations: &SelectedAnnotationQueries,
+    clipboard: &mut Clipboard,
+) {
     // Calculate centroid of all selected items
-    let centroid = calculate_selection_centroid(
-        &selected_items,
-        &selected_paths,
-        &selected_lines,
-        &selected_texts,
-    );
+    let centroid = calculate_selection_centroid(selected_items, annotations);
 
     // Clear clipboard
     clipboard.clear();
+    clipboard.source_centroid = centroid;
 
     // Copy placed items
-    for (item, transform) in selected_items.iter() {
-        let saved = SavedPlacedItem::from_entity(item, transform);
+    for (item, transform, token) in selected_items.iter() {
+        let saved = SavedPlacedItem::from_entity(item, transform, token);
         let offset = saved.position - centroid;
         clipboard
             .placed_items
@@ -65,19 +73,20 @@ pub fn handle_copy(
     }
 
     // Copy paths
-    for path in selected_paths.iter() {
+    for (entity, path) in annotations.paths.iter() {
         let center = path_center(path);
         let offset = center - centroid;
         let saved = SavedPath {
             points: path.points.clone(),
             color: color_to_array(path.color),
             stroke_width: path.stroke_width,
+            show_to_players: annotations.player_visible.contains(entity),
         };
         clipboard.paths.push(ClipboardPath { saved, offset });
     }
 
     // Copy lines
-    for line in selected_lines.iter() {
+    for (entity, line) in annotations.lines.iter() {
         let line_center = (line.start + line.end) / 2.0;
         let offset = line_center - centroid;
         let saved = SavedLine {
@@ -85,12 +94,28 @@ pub fn handle_copy(
             end: line.end,
             color: color_to_array(line.color),
             stroke_width: line.stroke_width,
+            show_to_players: annotations.player_visible.contains(entity),
         };
         clipboard.lines.push(ClipboardLine { saved, offset });
     }
 
+    // Copy shapes
+    for (entity, shape) in annotations.shapes.iter() {
+        let offset = shape_center(shape) - centroid;
+        let saved = SavedShape {
+            kind: shape.kind,
+            points: shape.points.clone(),
+            color: color_to_array(shape.color),
+            stroke_width: shape.stroke_width,
+            fill: shape.fill.map(color_to_array),
+            dashed: shape.dashed,
+            show_to_players: annotations.player_visible.contains(entity),
+        };
+        clipboard.shapes.push(ClipboardShape { saved, offset });
+    }
+
     // Copy text annotations
-    for (transform, text) in selected_texts.iter() {
+    for (entity, transform, text) in annotations.texts.iter() {
         let pos = transform.translation.truncate();
         let offset = pos - centroid;
         let saved = SavedTextBox {
@@ -98,6 +123,8 @@ pub fn handle_copy(
             content: text.content.clone(),
             font_size: text.font_size,
             color: color_to_array(text.color),
+            background: text.background.map(color_to_array),
+            show_to_players: annotations.player_visible.contains(entity),
         };
         clipboard.texts.push(ClipboardText { saved, offset });
     }
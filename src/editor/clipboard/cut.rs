@@ -3,24 +3,32 @@
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
-use crate::map::{PlacedItem, SavedLine, SavedPath, SavedPlacedItem, SavedTextBox, Selected};
+use crate::keybindings::{Action, ActionInput};
+use crate::map::{
+    PlacedItem, SavedLine, SavedPath, SavedPlacedItem, SavedShape, SavedTextBox, Selected,
+    TokenData,
+};
 
 use super::super::params::SelectedAnnotationQueries;
-use super::helpers::{color_to_array, path_center};
-use super::types::{Clipboard, ClipboardLine, ClipboardPath, ClipboardPlacedItem, ClipboardText};
+use super::helpers::{color_to_array, path_center, shape_center};
+use super::types::{
+    Clipboard, ClipboardLine, ClipboardPath, ClipboardPlacedItem, ClipboardShape, ClipboardText,
+};
 
 /// Cut selected items to clipboard (Ctrl+X) - copy then delete
 pub fn handle_cut(
     mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     mut clipboard: ResMut<Clipboard>,
     mut contexts: EguiContexts,
-    selected_items: Query<(Entity, &PlacedItem, &Transform), With<Selected>>,
+    selected_items: Query<
+        (Entity, &PlacedItem, &Transform, Option<&TokenData>),
+        With<Selected>,
+    >,
     annotations: SelectedAnnotationQueries,
 ) {
     // Check for Ctrl+X
-    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
-    if !ctrl || !keyboard.just_pressed(KeyCode::KeyX) {
+    if !keys.just_pressed(Action::Cut) {
         return;
     }
 
@@ -35,6 +43,7 @@ pub fn handle_cut(
     if selected_items.is_empty()
         && annotations.paths.is_empty()
         && annotations.lines.is_empty()
+        && annotations.shapes.is_empty()
         && annotations.texts.is_empty()
     {
         return;
@@ -42,7 +51,7 @@ pub fn handle_cut(
 
     // Calculate centroid - need separate queries for the calculation
     let mut positions: Vec<Vec2> = Vec::new();
-    for (_, _, transform) in selected_items.iter() {
+    for (_, _, transform, _) in selected_items.iter() {
         positions.push(transform.translation.truncate());
     }
     for (_, path) in annotations.paths.iter() {
@@ -51,6 +60,9 @@ pub fn handle_cut(
     for (_, line) in annotations.lines.iter() {
         positions.push((line.start + line.end) / 2.0);
     }
+    for (_, shape) in annotations.shapes.iter() {
+        positions.push(shape_center(shape));
+    }
     for (_, transform, _) in annotations.texts.iter() {
         positions.push(transform.translation.truncate());
     }
@@ -64,10 +76,11 @@ pub fn handle_cut(
 
     // Clear clipboard
     clipboard.clear();
+    clipboard.source_centroid = centroid;
 
     // Copy and delete placed items
-    for (entity, item, transform) in selected_items.iter() {
-        let saved = SavedPlacedItem::from_entity(item, transform);
+    for (entity, item, transform, token) in selected_items.iter() {
+        let saved = SavedPlacedItem::from_entity(item, transform, token);
         let offset = saved.position - centroid;
         clipboard
             .placed_items
@@ -83,6 +96,7 @@ pub fn handle_cut(
             points: path.points.clone(),
             color: color_to_array(path.color),
             stroke_width: path.stroke_width,
+            show_to_players: annotations.player_visible.contains(entity),
         };
         clipboard.paths.push(ClipboardPath { saved, offset });
         commands.entity(entity).despawn();
@@ -97,11 +111,28 @@ pub fn handle_cut(
             end: line.end,
             color: color_to_array(line.color),
             stroke_width: line.stroke_width,
+            show_to_players: annotations.player_visible.contains(entity),
         };
         clipboard.lines.push(ClipboardLine { saved, offset });
         commands.entity(entity).despawn();
     }
 
+    // Copy and delete shapes
+    for (entity, shape) in annotations.shapes.iter() {
+        let offset = shape_center(shape) - centroid;
+        let saved = SavedShape {
+            kind: shape.kind,
+            points: shape.points.clone(),
+            color: color_to_array(shape.color),
+            stroke_width: shape.stroke_width,
+            fill: shape.fill.map(color_to_array),
+            dashed: shape.dashed,
+            show_to_players: annotations.player_visible.contains(entity),
+        };
+        clipboard.shapes.push(ClipboardShape { saved, offset });
+        commands.entity(entity).despawn();
+    }
+
     // Copy and delete text annotations
     for (entity, transform, text) in annotations.texts.iter() {
         let pos = transform.translation.truncate();
@@ -111,6 +142,8 @@ pub fn handle_cut(
             content: text.content.clone(),
             font_size: text.font_size,
             color: color_to_array(text.color),
+            background: text.background.map(color_to_array),
+            show_to_players: annotations.player_visible.contains(entity),
         };
         clipboard.texts.push(ClipboardText { saved, offset });
         commands.entity(entity).despawn();
@@ -0,0 +1,251 @@
+//! Duplicate (Ctrl+D) and array copies of the selection.
+//!
+//! Both copy the selection without touching the clipboard, then spawn the
+//! copies through the same helper as paste. Duplicate repeats the offset of
+//! the last paste, so copy, paste once where the next item goes, then Ctrl+D
+//! continues the row. Every copy made by one request is a single undo step.
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::keybindings::{Action, ActionInput};
+use crate::map::{MapData, PlacedItem, Selected, TokenData};
+
+use super::super::history::{EditorCommand, RecordEditorCommand};
+use super::super::params::SelectedAnnotationQueries;
+use super::copy::copy_selection;
+use super::paste::spawn_clipboard;
+use super::types::Clipboard;
+
+/// How an array lays out its copies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrayLayout {
+    /// Columns run right and rows run down from the selection, `spacing`
+    /// grid cells apart. The selection is the top-left copy.
+    Grid {
+        columns: u32,
+        rows: u32,
+        spacing: Vec2,
+    },
+    /// Copies spread around `pivot` (grid cells from the selection's center)
+    /// over `sweep_degrees`, counter-clockwise. `count` includes the
+    /// selection itself.
+    Radial {
+        count: u32,
+        pivot: Vec2,
+        sweep_degrees: f32,
+        rotate_copies: bool,
+    },
+}
+
+/// Message to duplicate the selection by the last paste offset
+#[derive(Message)]
+pub struct DuplicateSelectionRequest;
+
+/// Message to make an array of copies of the selection
+#[derive(Message)]
+pub struct ArrayDuplicateRequest {
+    pub layout: ArrayLayout,
+}
+
+/// Where each copy of an array goes, as the move of the selection's center
+/// and the turn (radians) applied to it. The selection itself is left out.
+pub fn array_placements(layout: ArrayLayout, grid_size: f32) -> Vec<(Vec2, f32)> {
+    match layout {
+        ArrayLayout::Grid {
+            columns,
+            rows,
+            spacing,
+        } => (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .skip(1)
+            .map(|(column, row)| {
+                let offset = Vec2::new(column as f32, -(row as f32)) * spacing * grid_size;
+                (offset, 0.0)
+            })
+            .collect(),
+        ArrayLayout::Radial {
+            count,
+            pivot,
+            sweep_degrees,
+            rotate_copies,
+        } => {
+            if count < 2 {
+                return Vec::new();
+            }
+            // A full circle would put the last copy on the first, so split it
+            // into `count` steps; a partial sweep ends on its last copy
+            let full_circle = (sweep_degrees.abs() - 360.0).abs() < 0.01;
+            let steps = if full_circle { count } else { count - 1 };
+            let step = sweep_degrees.to_radians() / steps as f32;
+
+            let pivot = pivot * grid_size;
+            (1..count)
+                .map(|index| {
+                    let angle = step * index as f32;
+                    let offset = pivot - Vec2::from_angle(angle).rotate(pivot);
+                    (offset, if rotate_copies { angle } else { 0.0 })
+                })
+                .collect()
+        }
+    }
+}
+
+/// Send a duplicate request for Ctrl+D
+pub fn handle_duplicate_shortcut(
+    keys: ActionInput,
+    mut contexts: EguiContexts,
+    mut duplicate_writer: MessageWriter<DuplicateSelectionRequest>,
+) {
+    if !keys.just_pressed(Action::Duplicate) {
+        return;
+    }
+
+    // Don't duplicate if UI has keyboard focus
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_keyboard_input()
+    {
+        return;
+    }
+
+    duplicate_writer.write(DuplicateSelectionRequest);
+}
+
+/// Copy the selection by the last paste offset (one grid cell right and down
+/// if nothing has been pasted), selecting the copies
+#[allow(clippy::too_many_arguments)]
+pub fn handle_duplicate(
+    mut commands: Commands,
+    mut events: MessageReader<DuplicateSelectionRequest>,
+    clipboard: Res<Clipboard>,
+    asset_server: Res<AssetServer>,
+    map_data: Res<MapData>,
+    selected_query: Query<Entity, With<Selected>>,
+    selected_items: Query<(&PlacedItem, &Transform, Option<&TokenData>), With<Selected>>,
+    annotations: SelectedAnnotationQueries,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    // Several requests in one frame would all copy the same selection
+    if events.read().count() == 0 {
+        return;
+    }
+
+    let mut copied = Clipboard::default();
+    copy_selection(&selected_items, &annotations, &mut copied);
+    if copied.is_empty() {
+        return;
+    }
+
+    let offset = clipboard
+        .last_paste_offset
+        .unwrap_or(Vec2::new(map_data.grid_size, -map_data.grid_size));
+
+    for entity in selected_query.iter() {
+        commands.entity(entity).remove::<Selected>();
+    }
+    let spawned = spawn_clipboard(
+        &mut commands,
+        &asset_server,
+        &map_data,
+        &copied,
+        copied.source_centroid + offset,
+        0.0,
+    );
+    record_as_one(&mut history_writer, spawned);
+}
+
+/// Make the requested array of copies, adding them to the selection
+#[allow(clippy::too_many_arguments)]
+pub fn handle_array_duplicate(
+    mut commands: Commands,
+    mut events: MessageReader<ArrayDuplicateRequest>,
+    asset_server: Res<AssetServer>,
+    map_data: Res<MapData>,
+    selected_items: Query<(&PlacedItem, &Transform, Option<&TokenData>), With<Selected>>,
+    annotations: SelectedAnnotationQueries,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    for event in events.read() {
+        let mut copied = Clipboard::default();
+        copy_selection(&selected_items, &annotations, &mut copied);
+        if copied.is_empty() {
+            continue;
+        }
+
+        let spawned = array_placements(event.layout, map_data.grid_size)
+            .into_iter()
+            .flat_map(|(offset, angle)| {
+                spawn_clipboard(
+                    &mut commands,
+                    &asset_server,
+                    &map_data,
+                    &copied,
+                    copied.source_centroid + offset,
+                    angle,
+                )
+            })
+            .collect();
+        record_as_one(&mut history_writer, spawned);
+    }
+}
+
+/// Record spawned copies as a single undo step
+fn record_as_one(
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+    mut spawned: Vec<EditorCommand>,
+) {
+    let command = match spawned.len() {
+        0 => return,
+        1 => spawned.remove(0),
+        _ => EditorCommand::Batch { commands: spawned },
+    };
+    history_writer.write(RecordEditorCommand { command });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_array_skips_the_original() {
+        let layout = ArrayLayout::Grid {
+            columns: 3,
+            rows: 2,
+            spacing: Vec2::new(2.0, 1.0),
+        };
+        let placements = array_placements(layout, 10.0);
+        assert_eq!(placements.len(), 5);
+        assert_eq!(placements[0], (Vec2::new(20.0, 0.0), 0.0));
+        assert_eq!(placements[1], (Vec2::new(40.0, 0.0), 0.0));
+        // Rows run down the screen
+        assert_eq!(placements[2], (Vec2::new(0.0, -10.0), 0.0));
+        assert_eq!(placements[4], (Vec2::new(40.0, -10.0), 0.0));
+    }
+
+    #[test]
+    fn test_radial_array_around_pivot() {
+        let layout = ArrayLayout::Radial {
+            count: 4,
+            pivot: Vec2::new(0.0, -1.0),
+            sweep_degrees: 360.0,
+            rotate_copies: true,
+        };
+        let placements = array_placements(layout, 10.0);
+        assert_eq!(placements.len(), 3);
+        // Half way round a pivot 10 below, the copy lands 20 below
+        let (offset, angle) = placements[1];
+        assert!((offset - Vec2::new(0.0, -20.0)).length() < 0.001);
+        assert!((angle - std::f32::consts::PI).abs() < 0.001);
+
+        // A partial sweep ends on its last copy
+        let half = ArrayLayout::Radial {
+            count: 3,
+            pivot: Vec2::new(0.0, -1.0),
+            sweep_degrees: 180.0,
+            rotate_copies: false,
+        };
+        let placements = array_placements(half, 10.0);
+        assert!((placements[1].0 - Vec2::new(0.0, -20.0)).length() < 0.001);
+        assert_eq!(placements[1].1, 0.0);
+    }
+}
@@ -2,10 +2,11 @@
 
 use bevy::prelude::*;
 
-use crate::map::{SavedPath, Selected};
+use crate::map::{SavedPath, SavedShape, Selected};
 
-use super::super::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
-use crate::map::PlacedItem;
+use super::super::annotations::{DrawnPath, DrawnShape};
+use super::super::params::SelectedAnnotationQueries;
+use crate::map::{PlacedItem, TokenData};
 
 /// Convert Color to [f32; 4] array for saved formats
 pub fn color_to_array(color: Color) -> [f32; 4] {
@@ -36,33 +37,54 @@ pub fn saved_path_center(saved: &SavedPath) -> Vec2 {
     sum / saved.points.len() as f32
 }
 
+/// Calculate the center of a DrawnShape's points
+pub fn shape_center(shape: &DrawnShape) -> Vec2 {
+    if shape.points.is_empty() {
+        return Vec2::ZERO;
+    }
+    let sum: Vec2 = shape.points.iter().copied().sum();
+    sum / shape.points.len() as f32
+}
+
+/// Calculate the center from saved shape points
+pub fn saved_shape_center(saved: &SavedShape) -> Vec2 {
+    if saved.points.is_empty() {
+        return Vec2::ZERO;
+    }
+    let sum: Vec2 = saved.points.iter().copied().sum();
+    sum / saved.points.len() as f32
+}
+
 /// Calculate the centroid of all selected items
 #[allow(clippy::type_complexity)]
 pub fn calculate_selection_centroid(
-    placed_items: &Query<(&PlacedItem, &Transform), With<Selected>>,
-    paths: &Query<&DrawnPath, (With<Selected>, With<AnnotationMarker>)>,
-    lines: &Query<&DrawnLine, (With<Selected>, With<AnnotationMarker>)>,
-    texts: &Query<(&Transform, &TextAnnotation), (With<Selected>, With<AnnotationMarker>)>,
+    placed_items: &Query<(&PlacedItem, &Transform, Option<&TokenData>), With<Selected>>,
+    annotations: &SelectedAnnotationQueries,
 ) -> Vec2 {
     let mut positions: Vec<Vec2> = Vec::new();
 
     // Collect placed item positions
-    for (_, transform) in placed_items.iter() {
+    for (_, transform, _) in placed_items.iter() {
         positions.push(transform.translation.truncate());
     }
 
     // Collect path centers
-    for path in paths.iter() {
+    for (_, path) in annotations.paths.iter() {
         positions.push(path_center(path));
     }
 
     // Collect line centers
-    for line in lines.iter() {
+    for (_, line) in annotations.lines.iter() {
         positions.push((line.start + line.end) / 2.0);
     }
 
+    // Collect shape centers
+    for (_, shape) in annotations.shapes.iter() {
+        positions.push(shape_center(shape));
+    }
+
     // Collect text positions
-    for (transform, _) in texts.iter() {
+    for (_, transform, _) in annotations.texts.iter() {
         positions.push(transform.translation.truncate());
     }
 
@@ -10,7 +10,8 @@
 //! - [`helpers`] - Color conversion and centroid calculation utilities
 //! - [`copy`] - Copy system (Ctrl+C)
 //! - [`cut`] - Cut system (Ctrl+X)
-//! - [`paste`] - Paste system (Ctrl+V)
+//! - [`paste`] - Paste system (Ctrl+V) and the spawn helper shared with duplicate
+//! - [`duplicate`] - Duplicate (Ctrl+D) and array copies
 //!
 //! ## Key Types
 //!
@@ -18,29 +19,38 @@
 //! - [`ClipboardPlacedItem`]: Placed item data for clipboard
 //! - [`ClipboardPath`]: Path annotation data for clipboard
 //! - [`ClipboardLine`]: Line annotation data for clipboard
+//! - [`ClipboardShape`]: Shape annotation data for clipboard
 //! - [`ClipboardText`]: Text annotation data for clipboard
+//! - [`ArrayLayout`]: Grid or radial layout for array copies
 //!
 //! ## Systems
 //!
 //! - [`handle_copy`]: Copy selected items to clipboard (Ctrl+C)
 //! - [`handle_cut`]: Cut selected items to clipboard (Ctrl+X)
 //! - [`handle_paste`]: Paste clipboard items at cursor position (Ctrl+V)
+//! - [`handle_duplicate`]: Copy the selection by the last paste offset (Ctrl+D)
+//! - [`handle_array_duplicate`]: Make an array of copies of the selection
 
 mod copy;
 mod cut;
+mod duplicate;
 mod helpers;
 mod paste;
 mod tests;
 mod types;
 
 // Re-exports - Types
+pub use duplicate::{ArrayDuplicateRequest, ArrayLayout, DuplicateSelectionRequest};
 pub use types::Clipboard;
 
 // These types are used in tests and for completeness
 #[allow(unused_imports)]
-pub use types::{ClipboardLine, ClipboardPath, ClipboardPlacedItem, ClipboardText};
+pub use types::{
+    ClipboardLine, ClipboardPath, ClipboardPlacedItem, ClipboardShape, ClipboardText,
+};
 
 // Re-exports - Systems
 pub use copy::handle_copy;
 pub use cut::handle_cut;
+pub use duplicate::{handle_array_duplicate, handle_duplicate, handle_duplicate_shortcut};
 pub use paste::handle_paste;
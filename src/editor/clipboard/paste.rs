@@ -4,22 +4,26 @@ use bevy::camera::visibility::RenderLayers;
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
+use crate::keybindings::{Action, ActionInput};
 use crate::map::{Layer, MapData, PlacedItem, Selected};
 
-use super::super::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
+use super::super::annotations::{
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, ShowToPlayers, TextAnnotation,
+};
 use super::super::history::{
-    EditorCommand, LineData, PathData, PlacedItemData, RecordEditorCommand, TextData, TransformData,
+    EditorCommand, LineData, PathData, PlacedItemData, RecordEditorCommand, ShapeData, TextData,
+    TransformData,
 };
 use super::super::params::CameraParams;
-use super::helpers::{array_to_color, saved_path_center};
+use super::helpers::{array_to_color, saved_path_center, saved_shape_center};
 use super::types::Clipboard;
 
 /// Paste clipboard items at cursor position (Ctrl+V)
 #[allow(clippy::too_many_arguments)]
 pub fn handle_paste(
     mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    clipboard: Res<Clipboard>,
+    keys: ActionInput,
+    mut clipboard: ResMut<Clipboard>,
     mut contexts: EguiContexts,
     asset_server: Res<AssetServer>,
     camera: CameraParams,
@@ -28,8 +32,7 @@ pub fn handle_paste(
     mut history_writer: MessageWriter<RecordEditorCommand>,
 ) {
     // Check for Ctrl+V
-    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
-    if !ctrl || !keyboard.just_pressed(KeyCode::KeyV) {
+    if !keys.just_pressed(Action::Paste) {
         return;
     }
 
@@ -49,12 +52,44 @@ pub fn handle_paste(
     let Some(paste_pos) = camera.cursor_world_pos() else {
         return;
     };
+    clipboard.last_paste_offset = Some(paste_pos - clipboard.source_centroid);
 
     // Clear current selection
     for entity in selected_query.iter() {
         commands.entity(entity).remove::<Selected>();
     }
 
+    let pasted = spawn_clipboard(
+        &mut commands,
+        &asset_server,
+        &map_data,
+        &clipboard,
+        paste_pos,
+        0.0,
+    );
+    for command in pasted {
+        history_writer.write(RecordEditorCommand { command });
+    }
+}
+
+/// Spawn a selected copy of the clipboard contents with their centroid at
+/// `center`, turned by `angle` radians around it. Placed items turn with the
+/// copy; annotations keep their orientation and only follow it around.
+///
+/// Items on locked layers are skipped. Returns the commands that undo the
+/// spawn: one for all placed items, then one per annotation.
+pub(super) fn spawn_clipboard(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    map_data: &MapData,
+    clipboard: &Clipboard,
+    center: Vec2,
+    angle: f32,
+) -> Vec<EditorCommand> {
+    let turn = Vec2::from_angle(angle);
+    let place = |offset: Vec2| center + turn.rotate(offset);
+    let mut spawned = Vec::new();
+
     // Accumulate pasted placed items so the batch is one undo step.
     let mut pasted_items: Vec<PlacedItemData> = Vec::new();
 
@@ -72,7 +107,7 @@ pub fn handle_paste(
             continue;
         }
 
-        let new_pos = paste_pos + clip_item.offset;
+        let new_pos = place(clip_item.offset);
         let z = clip_item.saved.layer.z_base() + clip_item.saved.z_index as f32;
 
         let texture: Handle<Image> = asset_server.load(&clip_item.saved.asset_path);
@@ -86,22 +121,24 @@ pub fn handle_paste(
 
         let transform = Transform {
             translation: new_pos.extend(z),
-            rotation: Quat::from_rotation_z(clip_item.saved.rotation),
+            rotation: Quat::from_rotation_z(clip_item.saved.rotation + angle),
             scale: clip_item.saved.scale.extend(1.0),
         };
-        let entity = commands
-            .spawn((
-                Sprite::from_image(texture),
-                transform,
-                PlacedItem {
-                    asset_path: clip_item.saved.asset_path.clone(),
-                    layer: clip_item.saved.layer,
-                    z_index: clip_item.saved.z_index,
-                },
-                render_layer,
-                Selected, // Auto-select pasted item
-            ))
-            .id();
+        let mut entity_commands = commands.spawn((
+            Sprite::from_image(texture),
+            transform,
+            PlacedItem {
+                asset_path: clip_item.saved.asset_path.clone(),
+                layer: clip_item.saved.layer,
+                z_index: clip_item.saved.z_index,
+            },
+            render_layer,
+            Selected, // Auto-select pasted item
+        ));
+        if let Some(token) = &clip_item.saved.token {
+            entity_commands.insert(token.clone());
+        }
+        let entity = entity_commands.id();
 
         pasted_items.push(PlacedItemData {
             entity,
@@ -109,14 +146,13 @@ pub fn handle_paste(
             layer: clip_item.saved.layer,
             z_index: clip_item.saved.z_index,
             transform: TransformData::from(&transform),
+            token: clip_item.saved.token.clone(),
         });
     }
 
     if !pasted_items.is_empty() {
-        history_writer.write(RecordEditorCommand {
-            command: EditorCommand::PlaceItems {
-                items: pasted_items,
-            },
+        spawned.push(EditorCommand::PlaceItems {
+            items: pasted_items,
         });
     }
 
@@ -129,7 +165,7 @@ pub fn handle_paste(
         .unwrap_or(false);
 
     if annotation_locked {
-        return;
+        return spawned;
     }
 
     let annotation_z = Layer::Annotation.z_base();
@@ -137,8 +173,7 @@ pub fn handle_paste(
     // Paste paths
     for clip_path in &clipboard.paths {
         // Translate all points to new position
-        let center = saved_path_center(&clip_path.saved);
-        let translation = paste_pos + clip_path.offset - center;
+        let translation = place(clip_path.offset) - saved_path_center(&clip_path.saved);
 
         let new_points: Vec<Vec2> = clip_path
             .saved
@@ -149,6 +184,7 @@ pub fn handle_paste(
 
         let color = array_to_color(clip_path.saved.color);
         let stroke_width = clip_path.saved.stroke_width;
+        let show_to_players = clip_path.saved.show_to_players;
         let entity = commands
             .spawn((
                 Transform::from_translation(Vec3::new(0.0, 0.0, annotation_z)),
@@ -160,16 +196,16 @@ pub fn handle_paste(
                 AnnotationMarker,
                 Selected,
             ))
+            .insert_if(ShowToPlayers, || show_to_players)
             .id();
 
-        history_writer.write(RecordEditorCommand {
-            command: EditorCommand::CreatePath {
-                entity,
-                path: PathData {
-                    points: new_points,
-                    color,
-                    stroke_width,
-                },
+        spawned.push(EditorCommand::CreatePath {
+            entity,
+            path: PathData {
+                points: new_points,
+                color,
+                stroke_width,
+                show_to_players,
             },
         });
     }
@@ -177,12 +213,13 @@ pub fn handle_paste(
     // Paste lines
     for clip_line in &clipboard.lines {
         let line_center = (clip_line.saved.start + clip_line.saved.end) / 2.0;
-        let translation = paste_pos + clip_line.offset - line_center;
+        let translation = place(clip_line.offset) - line_center;
 
         let start = clip_line.saved.start + translation;
         let end = clip_line.saved.end + translation;
         let color = array_to_color(clip_line.saved.color);
         let stroke_width = clip_line.saved.stroke_width;
+        let show_to_players = clip_line.saved.show_to_players;
         let entity = commands
             .spawn((
                 Transform::from_translation(Vec3::new(0.0, 0.0, annotation_z)),
@@ -195,51 +232,89 @@ pub fn handle_paste(
                 AnnotationMarker,
                 Selected,
             ))
+            .insert_if(ShowToPlayers, || show_to_players)
             .id();
 
-        history_writer.write(RecordEditorCommand {
-            command: EditorCommand::CreateLine {
-                entity,
-                line: LineData {
-                    start,
-                    end,
-                    color,
-                    stroke_width,
-                },
+        spawned.push(EditorCommand::CreateLine {
+            entity,
+            line: LineData {
+                start,
+                end,
+                color,
+                stroke_width,
+                show_to_players,
             },
         });
     }
 
+    // Paste shapes
+    for clip_shape in &clipboard.shapes {
+        let translation = place(clip_shape.offset) - saved_shape_center(&clip_shape.saved);
+
+        let shape = DrawnShape {
+            kind: clip_shape.saved.kind,
+            points: clip_shape
+                .saved
+                .points
+                .iter()
+                .map(|p| *p + translation)
+                .collect(),
+            color: array_to_color(clip_shape.saved.color),
+            stroke_width: clip_shape.saved.stroke_width,
+            fill: clip_shape.saved.fill.map(array_to_color),
+            dashed: clip_shape.saved.dashed,
+        };
+        let show_to_players = clip_shape.saved.show_to_players;
+        let shape_data = ShapeData {
+            show_to_players,
+            ..ShapeData::from(&shape)
+        };
+        let entity = commands
+            .spawn((
+                Transform::from_translation(Vec3::new(0.0, 0.0, annotation_z)),
+                shape,
+                AnnotationMarker,
+                Selected,
+            ))
+            .insert_if(ShowToPlayers, || show_to_players)
+            .id();
+
+        spawned.push(EditorCommand::CreateShape {
+            entity,
+            shape: shape_data,
+        });
+    }
+
     // Paste text annotations
     for clip_text in &clipboard.texts {
-        let new_pos = paste_pos + clip_text.offset;
+        let new_pos = place(clip_text.offset);
 
-        let content = clip_text.saved.content.clone();
-        let font_size = clip_text.saved.font_size;
-        let color = array_to_color(clip_text.saved.color);
+        let text = TextAnnotation {
+            content: clip_text.saved.content.clone(),
+            font_size: clip_text.saved.font_size,
+            color: array_to_color(clip_text.saved.color),
+            background: clip_text.saved.background.map(array_to_color),
+        };
+        let show_to_players = clip_text.saved.show_to_players;
+        let text_data = TextData {
+            show_to_players,
+            ..TextData::from_annotation(new_pos, &text)
+        };
         let entity = commands
             .spawn((
                 Transform::from_translation(new_pos.extend(annotation_z)),
-                TextAnnotation {
-                    content: content.clone(),
-                    font_size,
-                    color,
-                },
+                text,
                 AnnotationMarker,
                 Selected,
             ))
+            .insert_if(ShowToPlayers, || show_to_players)
             .id();
 
-        history_writer.write(RecordEditorCommand {
-            command: EditorCommand::CreateText {
-                entity,
-                text: TextData {
-                    text: content,
-                    position: new_pos,
-                    color,
-                    font_size,
-                },
-            },
+        spawned.push(EditorCommand::CreateText {
+            entity,
+            text: text_data,
         });
     }
+
+    spawned
 }
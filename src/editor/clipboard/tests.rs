@@ -4,14 +4,14 @@
 
 use bevy::prelude::*;
 
-use crate::editor::annotations::DrawnPath;
+use crate::editor::annotations::{DrawnPath, ShapeKind};
 use crate::editor::clipboard::helpers::{
-    array_to_color, color_to_array, path_center, saved_path_center,
+    array_to_color, color_to_array, path_center, saved_path_center, saved_shape_center,
 };
 use crate::editor::clipboard::types::{
-    Clipboard, ClipboardLine, ClipboardPath, ClipboardPlacedItem, ClipboardText,
+    Clipboard, ClipboardLine, ClipboardPath, ClipboardPlacedItem, ClipboardShape, ClipboardText,
 };
-use crate::map::{Layer, SavedLine, SavedPath, SavedPlacedItem, SavedTextBox};
+use crate::map::{Layer, SavedLine, SavedPath, SavedPlacedItem, SavedShape, SavedTextBox};
 
 // Clipboard resource tests
 #[test]
@@ -31,6 +31,7 @@ fn test_clipboard_is_empty_with_placed_item() {
             scale: Vec2::ONE,
             layer: Layer::Token,
             z_index: 0,
+            token: None,
         },
         offset: Vec2::ZERO,
     });
@@ -45,6 +46,7 @@ fn test_clipboard_is_empty_with_path() {
             points: vec![Vec2::ZERO, Vec2::ONE],
             color: [1.0, 0.0, 0.0, 1.0],
             stroke_width: 2.0,
+            show_to_players: false,
         },
         offset: Vec2::ZERO,
     });
@@ -60,12 +62,48 @@ fn test_clipboard_is_empty_with_line() {
             end: Vec2::new(100.0, 100.0),
             color: [0.0, 1.0, 0.0, 1.0],
             stroke_width: 3.0,
+            show_to_players: false,
         },
         offset: Vec2::ZERO,
     });
     assert!(!clipboard.is_empty());
 }
 
+#[test]
+fn test_clipboard_is_empty_with_shape() {
+    let mut clipboard = Clipboard::default();
+    clipboard.shapes.push(ClipboardShape {
+        saved: SavedShape {
+            kind: ShapeKind::Rectangle,
+            points: vec![Vec2::ZERO, Vec2::new(40.0, 20.0)],
+            color: [1.0, 0.0, 0.0, 1.0],
+            stroke_width: 2.0,
+            fill: None,
+            dashed: false,
+            show_to_players: false,
+        },
+        offset: Vec2::ZERO,
+    });
+    assert!(!clipboard.is_empty());
+
+    clipboard.clear();
+    assert!(clipboard.shapes.is_empty());
+}
+
+#[test]
+fn test_saved_shape_center() {
+    let saved = SavedShape {
+        kind: ShapeKind::Polygon,
+        points: vec![Vec2::ZERO, Vec2::new(30.0, 0.0), Vec2::new(0.0, 30.0)],
+        color: [1.0, 1.0, 1.0, 1.0],
+        stroke_width: 2.0,
+        fill: Some([1.0, 1.0, 1.0, 0.5]),
+        dashed: false,
+        show_to_players: false,
+    };
+    assert_eq!(saved_shape_center(&saved), Vec2::new(10.0, 10.0));
+}
+
 #[test]
 fn test_clipboard_is_empty_with_text() {
     let mut clipboard = Clipboard::default();
@@ -75,6 +113,8 @@ fn test_clipboard_is_empty_with_text() {
             content: "Hello".to_string(),
             font_size: 16.0,
             color: [1.0, 1.0, 1.0, 1.0],
+            background: None,
+            show_to_players: false,
         },
         offset: Vec2::ZERO,
     });
@@ -94,6 +134,7 @@ fn test_clipboard_clear() {
             scale: Vec2::ONE,
             layer: Layer::Token,
             z_index: 0,
+            token: None,
         },
         offset: Vec2::ZERO,
     });
@@ -102,6 +143,7 @@ fn test_clipboard_clear() {
             points: vec![Vec2::ZERO],
             color: [1.0, 0.0, 0.0, 1.0],
             stroke_width: 2.0,
+            show_to_players: false,
         },
         offset: Vec2::ZERO,
     });
@@ -111,6 +153,7 @@ fn test_clipboard_clear() {
             end: Vec2::ONE,
             color: [0.0, 1.0, 0.0, 1.0],
             stroke_width: 3.0,
+            show_to_players: false,
         },
         offset: Vec2::ZERO,
     });
@@ -120,6 +163,8 @@ fn test_clipboard_clear() {
             content: "Test".to_string(),
             font_size: 12.0,
             color: [1.0, 1.0, 1.0, 1.0],
+            background: None,
+            show_to_players: false,
         },
         offset: Vec2::ZERO,
     });
@@ -217,6 +262,7 @@ fn test_saved_path_center_empty() {
         points: vec![],
         color: [1.0, 1.0, 1.0, 1.0],
         stroke_width: 1.0,
+        show_to_players: false,
     };
     assert_eq!(saved_path_center(&saved), Vec2::ZERO);
 }
@@ -227,6 +273,7 @@ fn test_saved_path_center_multiple_points() {
         points: vec![Vec2::new(-50.0, -50.0), Vec2::new(50.0, 50.0)],
         color: [1.0, 1.0, 1.0, 1.0],
         stroke_width: 1.0,
+        show_to_players: false,
     };
     assert_eq!(saved_path_center(&saved), Vec2::ZERO);
 }
@@ -297,6 +344,7 @@ fn test_clipboard_placed_item_clone() {
             scale: Vec2::new(2.0, 2.0),
             layer: Layer::Token,
             z_index: 5,
+            token: None,
         },
         offset: Vec2::new(10.0, 20.0),
     };
@@ -321,6 +369,7 @@ fn test_clipboard_path_clone() {
             ],
             color: [1.0, 0.0, 0.0, 1.0],
             stroke_width: 3.0,
+            show_to_players: false,
         },
         offset: Vec2::new(-25.0, 15.0),
     };
@@ -342,6 +391,7 @@ fn test_clipboard_line_clone() {
             end: Vec2::new(110.0, 120.0),
             color: [0.0, 1.0, 0.0, 0.5],
             stroke_width: 5.0,
+            show_to_players: false,
         },
         offset: Vec2::new(30.0, 40.0),
     };
@@ -363,6 +413,8 @@ fn test_clipboard_text_clone() {
             content: "Test annotation".to_string(),
             font_size: 24.0,
             color: [0.0, 0.0, 1.0, 1.0],
+            background: None,
+            show_to_players: false,
         },
         offset: Vec2::new(-50.0, -60.0),
     };
@@ -389,6 +441,7 @@ fn test_clipboard_with_mixed_content() {
             scale: Vec2::ONE,
             layer: Layer::Doodad,
             z_index: 0,
+            token: None,
         },
         offset: Vec2::new(10.0, 0.0),
     });
@@ -398,6 +451,7 @@ fn test_clipboard_with_mixed_content() {
             points: vec![Vec2::new(50.0, 50.0)],
             color: [1.0, 0.0, 0.0, 1.0],
             stroke_width: 2.0,
+            show_to_players: false,
         },
         offset: Vec2::new(-10.0, 0.0),
     });
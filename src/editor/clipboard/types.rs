@@ -2,7 +2,7 @@
 
 use bevy::prelude::*;
 
-use crate::map::{SavedLine, SavedPath, SavedPlacedItem, SavedTextBox};
+use crate::map::{SavedLine, SavedPath, SavedPlacedItem, SavedShape, SavedTextBox};
 
 /// Clipboard data for a placed item with offset from selection centroid
 #[derive(Clone)]
@@ -25,6 +25,13 @@ pub struct ClipboardLine {
     pub offset: Vec2,
 }
 
+/// Clipboard data for a shape annotation with offset from selection centroid
+#[derive(Clone)]
+pub struct ClipboardShape {
+    pub saved: SavedShape,
+    pub offset: Vec2,
+}
+
 /// Clipboard data for a text annotation with offset from selection centroid
 #[derive(Clone)]
 pub struct ClipboardText {
@@ -38,7 +45,13 @@ pub struct Clipboard {
     pub placed_items: Vec<ClipboardPlacedItem>,
     pub paths: Vec<ClipboardPath>,
     pub lines: Vec<ClipboardLine>,
+    pub shapes: Vec<ClipboardShape>,
     pub texts: Vec<ClipboardText>,
+    /// Centroid of the selection when it was copied
+    pub source_centroid: Vec2,
+    /// How far the last paste landed from the copied selection; Duplicate
+    /// repeats it
+    pub last_paste_offset: Option<Vec2>,
 }
 
 impl Clipboard {
@@ -46,6 +59,7 @@ impl Clipboard {
         self.placed_items.is_empty()
             && self.paths.is_empty()
             && self.lines.is_empty()
+            && self.shapes.is_empty()
             && self.texts.is_empty()
     }
 
@@ -53,6 +67,8 @@ impl Clipboard {
         self.placed_items.clear();
         self.paths.clear();
         self.lines.clear();
+        self.shapes.clear();
         self.texts.clear();
+        self.last_paste_offset = None;
     }
 }
@@ -0,0 +1,429 @@
+//! Tile fill tool for covering rooms with a floor asset.
+//!
+//! Drag a rectangle, or click polygon vertices (closed by clicking the first
+//! vertex or pressing Enter), and every grid cell the area covers gets one
+//! copy of the selected asset, fitted to the cell like fit-to-grid. Cells
+//! already covered by an item on the same layer can be skipped, and tiles can
+//! be given a random quarter-turn so repeating floors look less uniform.
+//!
+//! The whole fill is recorded as one `PlaceItems` command.
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::assets::{get_image_dimensions, LibraryAsset, SelectedAsset};
+use crate::map::{MapData, PlacedItem};
+
+use super::annotations::point_in_polygon;
+use super::history::{EditorCommand, PlacedItemData, RecordEditorCommand, TransformData};
+use super::params::{is_cursor_over_ui, CameraWithProjection};
+use super::selection::{point_in_item, SelectionGizmoGroup};
+use super::tools::SelectedLayer;
+
+/// Screen-space distance (pixels) within which a click closes a polygon
+const CLOSE_POLYGON_DISTANCE: f32 = 10.0;
+
+/// Largest number of tiles a single fill may place
+pub const MAX_FILL_TILES: usize = 10_000;
+
+/// Outline color of the fill preview
+const PREVIEW_COLOR: Color = Color::srgba(0.2, 0.8, 0.4, 0.8);
+
+/// How the fill area is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillShape {
+    #[default]
+    Rectangle,
+    Polygon,
+}
+
+impl FillShape {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FillShape::Rectangle => "Rectangle",
+            FillShape::Polygon => "Polygon",
+        }
+    }
+
+    pub fn all() -> &'static [FillShape] {
+        &[FillShape::Rectangle, FillShape::Polygon]
+    }
+}
+
+/// Fill tool settings and the area being drawn
+#[derive(Resource)]
+pub struct FillState {
+    pub shape: FillShape,
+    /// Turn each tile by a random multiple of 90 degrees
+    pub random_rotation: bool,
+    /// Skip cells already covered by an item on the target layer
+    pub skip_covered: bool,
+    /// Corner where the rectangle drag started
+    drag_start: Option<Vec2>,
+    /// Polygon vertices placed so far
+    polygon_points: Vec<Vec2>,
+    rng: fastrand::Rng,
+}
+
+impl Default for FillState {
+    fn default() -> Self {
+        Self {
+            shape: FillShape::Rectangle,
+            random_rotation: false,
+            skip_covered: true,
+            drag_start: None,
+            polygon_points: Vec::new(),
+            rng: fastrand::Rng::new(),
+        }
+    }
+}
+
+impl FillState {
+    fn clear(&mut self) {
+        self.drag_start = None;
+        self.polygon_points.clear();
+    }
+}
+
+/// Index of the grid cell containing `point`
+pub fn cell_of(point: Vec2, grid_size: f32) -> IVec2 {
+    (point / grid_size).floor().as_ivec2()
+}
+
+/// Center of a grid cell
+pub fn cell_center(cell: IVec2, grid_size: f32) -> Vec2 {
+    (cell.as_vec2() + 0.5) * grid_size
+}
+
+/// Number of cells touched by the rectangle between two corners
+pub fn rect_cell_count(a: Vec2, b: Vec2, grid_size: f32) -> usize {
+    let span = cell_of(a.max(b), grid_size) - cell_of(a.min(b), grid_size) + IVec2::ONE;
+    span.x as usize * span.y as usize
+}
+
+/// Every cell touched by the rectangle between two corners
+pub fn rect_cells(a: Vec2, b: Vec2, grid_size: f32) -> Vec<IVec2> {
+    let min = cell_of(a.min(b), grid_size);
+    let max = cell_of(a.max(b), grid_size);
+    let mut cells = Vec::new();
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            cells.push(IVec2::new(x, y));
+        }
+    }
+    cells
+}
+
+/// Every cell whose center lies inside the polygon
+pub fn polygon_cells(polygon: &[Vec2], grid_size: f32) -> Vec<IVec2> {
+    if polygon.len() < 3 {
+        return Vec::new();
+    }
+    let (min, max) = polygon_bounds(polygon);
+    rect_cells(min, max, grid_size)
+        .into_iter()
+        .filter(|cell| point_in_polygon(cell_center(*cell, grid_size), polygon))
+        .collect()
+}
+
+fn polygon_bounds(polygon: &[Vec2]) -> (Vec2, Vec2) {
+    polygon.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), p| (min.min(*p), max.max(*p)),
+    )
+}
+
+/// Scale that fits an asset into one grid cell, keeping its aspect ratio
+pub fn cell_fit_scale(
+    asset: &LibraryAsset,
+    texture: &Handle<Image>,
+    grid_size: f32,
+    images: &Assets<Image>,
+) -> Vec3 {
+    let image_size = images
+        .get(texture)
+        .map(|image| image.size().as_vec2())
+        .or_else(|| {
+            get_image_dimensions(&asset.full_path).map(|(w, h)| Vec2::new(w as f32, h as f32))
+        });
+    let fit = image_size
+        .filter(|size| size.x > 0.0 && size.y > 0.0)
+        .map_or(1.0, |size| (grid_size / size).min_element());
+    Vec3::new(fit, fit, 1.0)
+}
+
+/// Whether an area spans more cells than a fill may place
+fn exceeds_fill_limit(points: &[Vec2], grid_size: f32) -> bool {
+    let (min, max) = polygon_bounds(points);
+    rect_cell_count(min, max, grid_size) > MAX_FILL_TILES
+}
+
+/// Handle Fill tool input
+#[allow(clippy::too_many_arguments)]
+pub fn handle_fill(
+    mut commands: Commands,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut fill_state: ResMut<FillState>,
+    selected_layer: Res<SelectedLayer>,
+    selected_asset: Res<SelectedAsset>,
+    map_data: Res<MapData>,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+    existing_items: Query<(&Transform, &Sprite, &PlacedItem)>,
+    camera: CameraWithProjection,
+    mut contexts: EguiContexts,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    // Switching shapes abandons the area in progress
+    if fill_state.shape != FillShape::Polygon {
+        fill_state.polygon_points.clear();
+    }
+
+    if keyboard.just_pressed(KeyCode::Escape) || mouse_button.just_pressed(MouseButton::Right) {
+        fill_state.clear();
+        return;
+    }
+
+    let grid_size = map_data.grid_size;
+    // Corners of a rectangle, or polygon vertices
+    let mut area: Option<Vec<Vec2>> = None;
+
+    if fill_state.shape == FillShape::Polygon {
+        if keyboard.just_pressed(KeyCode::Backspace) {
+            fill_state.polygon_points.pop();
+        }
+        if keyboard.just_pressed(KeyCode::Enter) || keyboard.just_pressed(KeyCode::NumpadEnter) {
+            let points = std::mem::take(&mut fill_state.polygon_points);
+            area = Some(points);
+        }
+    }
+
+    if area.is_none() {
+        let Some(world_pos) = camera.cursor_world_pos() else {
+            return;
+        };
+        // Releasing over the UI still finishes a drag started on the map
+        if fill_state.drag_start.is_none() && is_cursor_over_ui(&mut contexts) {
+            return;
+        }
+
+        match fill_state.shape {
+            FillShape::Rectangle => {
+                if mouse_button.just_pressed(MouseButton::Left) {
+                    fill_state.drag_start = Some(world_pos);
+                } else if mouse_button.just_released(MouseButton::Left)
+                    && let Some(start) = fill_state.drag_start.take()
+                {
+                    area = Some(vec![start, world_pos]);
+                }
+            }
+            FillShape::Polygon => {
+                if !mouse_button.just_pressed(MouseButton::Left) {
+                    return;
+                }
+                let close_distance = CLOSE_POLYGON_DISTANCE * camera.zoom_scale();
+                if fill_state.polygon_points.len() >= 3
+                    && fill_state.polygon_points[0].distance(world_pos) <= close_distance
+                {
+                    let points = std::mem::take(&mut fill_state.polygon_points);
+                    area = Some(points);
+                } else {
+                    fill_state.polygon_points.push(world_pos);
+                }
+            }
+        }
+    }
+
+    let Some(area) = area else {
+        return;
+    };
+    let Some(ref asset) = selected_asset.asset else {
+        return;
+    };
+    if exceeds_fill_limit(&area, grid_size) {
+        warn!(
+            "Fill area is larger than the limit of {} cells",
+            MAX_FILL_TILES
+        );
+        return;
+    }
+    let mut cells = match fill_state.shape {
+        FillShape::Rectangle => rect_cells(area[0], area[1], grid_size),
+        FillShape::Polygon => polygon_cells(&area, grid_size),
+    };
+
+    let layer = selected_layer.layer;
+    if fill_state.skip_covered {
+        let layer_items: Vec<_> = existing_items
+            .iter()
+            .filter(|(_, _, item)| item.layer == layer)
+            .collect();
+        cells.retain(|cell| {
+            let center = cell_center(*cell, grid_size);
+            !layer_items
+                .iter()
+                .any(|(transform, sprite, _)| point_in_item(center, transform, sprite, &images))
+        });
+    }
+    if cells.is_empty() {
+        return;
+    }
+
+    let texture: Handle<Image> = asset_server.load(&asset.relative_path);
+    let scale = cell_fit_scale(asset, &texture, grid_size, &images);
+
+    // Items on non-player-visible layers go to render layer 1 (editor-only)
+    let render_layer = if layer.is_player_visible() {
+        RenderLayers::layer(0)
+    } else {
+        RenderLayers::layer(1)
+    };
+
+    let mut items = Vec::with_capacity(cells.len());
+    for cell in cells {
+        let rotation = if fill_state.random_rotation {
+            let quarter_turns = fill_state.rng.u8(0..4);
+            Quat::from_rotation_z(f32::from(quarter_turns) * std::f32::consts::FRAC_PI_2)
+        } else {
+            Quat::IDENTITY
+        };
+        let transform = Transform {
+            translation: cell_center(cell, grid_size).extend(layer.z_base()),
+            rotation,
+            scale,
+        };
+        let entity = commands
+            .spawn((
+                Sprite::from_image(texture.clone()),
+                transform,
+                PlacedItem {
+                    asset_path: asset.relative_path.clone(),
+                    layer,
+                    z_index: 0,
+                },
+                render_layer.clone(),
+            ))
+            .id();
+        items.push(PlacedItemData {
+            entity,
+            asset_path: asset.relative_path.clone(),
+            layer,
+            z_index: 0,
+            transform: TransformData::from(&transform),
+            token: None,
+        });
+    }
+
+    history_writer.write(RecordEditorCommand {
+        command: EditorCommand::PlaceItems { items },
+    });
+}
+
+/// Preview the area being filled, outlining the cells it covers
+pub fn draw_fill_preview(
+    mut gizmos: Gizmos<SelectionGizmoGroup>,
+    fill_state: Res<FillState>,
+    map_data: Res<MapData>,
+    camera: CameraWithProjection,
+) {
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return;
+    };
+    let grid_size = map_data.grid_size;
+
+    let cells = match fill_state.shape {
+        FillShape::Rectangle => {
+            let Some(start) = fill_state.drag_start else {
+                return;
+            };
+            gizmos.rect_2d(
+                Isometry2d::from_translation((start + world_pos) / 2.0),
+                (world_pos - start).abs(),
+                PREVIEW_COLOR,
+            );
+            if exceeds_fill_limit(&[start, world_pos], grid_size) {
+                return;
+            }
+            rect_cells(start, world_pos, grid_size)
+        }
+        FillShape::Polygon => {
+            let points = &fill_state.polygon_points;
+            if points.is_empty() {
+                return;
+            }
+            for pair in points.windows(2) {
+                gizmos.line_2d(pair[0], pair[1], PREVIEW_COLOR);
+            }
+            gizmos.line_2d(points[points.len() - 1], world_pos, PREVIEW_COLOR);
+
+            let mut polygon = points.clone();
+            polygon.push(world_pos);
+            if exceeds_fill_limit(&polygon, grid_size) {
+                return;
+            }
+            polygon_cells(&polygon, grid_size)
+        }
+    };
+
+    for cell in cells {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(cell_center(cell, grid_size)),
+            Vec2::splat(grid_size * 0.9),
+            PREVIEW_COLOR,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_cells_cover_touched_cells() {
+        // Dragging from inside cell (0, 0) to inside cell (2, 1), in any direction
+        let cells = rect_cells(Vec2::new(150.0, 80.0), Vec2::new(10.0, 10.0), 70.0);
+        assert_eq!(cells.len(), 6);
+        assert!(cells.contains(&IVec2::new(0, 0)));
+        assert!(cells.contains(&IVec2::new(2, 1)));
+
+        let negative = rect_cells(Vec2::new(-10.0, -10.0), Vec2::new(10.0, 10.0), 70.0);
+        assert_eq!(negative.len(), 4);
+        assert!(negative.contains(&IVec2::new(-1, -1)));
+    }
+
+    #[test]
+    fn test_polygon_cells_use_cell_centers() {
+        // Right triangle whose hypotenuse cuts through the cells
+        let triangle = [Vec2::ZERO, Vec2::new(300.0, 0.0), Vec2::new(0.0, 300.0)];
+        let cells = polygon_cells(&triangle, 70.0);
+        assert!(cells.contains(&IVec2::new(0, 0)));
+        assert!(cells.contains(&IVec2::new(3, 0)));
+        assert!(cells.contains(&IVec2::new(1, 2)));
+        // Cut by the hypotenuse, but the center is outside
+        assert!(!cells.contains(&IVec2::new(2, 2)));
+        assert_eq!(cells.len(), 10);
+
+        assert!(polygon_cells(&triangle[..2], 70.0).is_empty());
+    }
+
+    #[test]
+    fn test_fill_limit() {
+        assert_eq!(rect_cell_count(Vec2::ZERO, Vec2::new(150.0, 80.0), 70.0), 6);
+        assert!(!exceeds_fill_limit(&[Vec2::ZERO, Vec2::splat(700.0)], 70.0));
+        assert!(exceeds_fill_limit(
+            &[Vec2::ZERO, Vec2::splat(70_000.0)],
+            70.0
+        ));
+    }
+
+    #[test]
+    fn test_cell_center() {
+        assert_eq!(cell_center(IVec2::new(0, 0), 70.0), Vec2::new(35.0, 35.0));
+        assert_eq!(
+            cell_center(IVec2::new(-1, 2), 70.0),
+            Vec2::new(-35.0, 175.0)
+        );
+    }
+}
@@ -21,6 +21,7 @@ use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
 use super::camera::EditorCamera;
+use super::history::{EditorCommand, RecordEditorCommand};
 use super::params::{is_cursor_over_ui, CameraParams};
 use crate::map::{cell_to_world, cells_in_radius, FogOfWarData, Layer, MapData, MapDirtyState};
 use crate::session::LiveSessionState;
@@ -86,6 +87,8 @@ pub struct FogState {
     pub brush_size: f32,
     /// Whether currently erasing fog
     pub is_erasing: bool,
+    /// Cells revealed by the current stroke, recorded as one undo step on release
+    pub stroke_cells: Vec<(i32, i32)>,
     /// Editor fog opacity (0.0 = invisible, 1.0 = fully opaque)
     pub editor_opacity: f32,
 }
@@ -95,6 +98,7 @@ impl Default for FogState {
         Self {
             brush_size: 2.0,
             is_erasing: false,
+            stroke_cells: Vec::new(),
             editor_opacity: 0.6,
         }
     }
@@ -115,7 +119,20 @@ pub fn handle_fog(
     mut dirty_state: ResMut<MapDirtyState>,
     camera_params: CameraParams,
     mut contexts: EguiContexts,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
 ) {
+    // Finish the stroke first, so releasing over the UI still records it
+    if mouse_button.just_released(MouseButton::Left) {
+        fog_state.is_erasing = false;
+        if !fog_state.stroke_cells.is_empty() {
+            history_writer.write(RecordEditorCommand {
+                command: EditorCommand::RevealFog {
+                    cells: std::mem::take(&mut fog_state.stroke_cells),
+                },
+            });
+        }
+    }
+
     // Don't process if layer is locked
     if is_fog_layer_locked(&map_data) {
         return;
@@ -138,10 +155,6 @@ pub fn handle_fog(
         fog_state.is_erasing = true;
     }
 
-    if mouse_button.just_released(MouseButton::Left) {
-        fog_state.is_erasing = false;
-    }
-
     // Reveal cells while mouse is held (erase fog)
     if mouse_button.pressed(MouseButton::Left) && fog_state.is_erasing {
         let mut changed = false;
@@ -151,6 +164,7 @@ pub fn handle_fog(
             let cell = crate::map::world_to_cell(world_pos, grid_size);
             if !fog_data.is_cell_revealed(cell) {
                 fog_data.reveal_cell(cell);
+                fog_state.stroke_cells.push(cell);
                 changed = true;
             }
         } else {
@@ -160,6 +174,7 @@ pub fn handle_fog(
             for cell in cells {
                 if !fog_data.is_cell_revealed(cell) {
                     fog_data.reveal_cell(cell);
+                    fog_state.stroke_cells.push(cell);
                     changed = true;
                 }
             }
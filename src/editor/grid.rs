@@ -7,14 +7,89 @@ use crate::theme;
 use super::camera::CameraZoom;
 use super::EditorCamera;
 
+/// Grid points that placed and moved items snap to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapMode {
+    #[default]
+    CellCenter,
+    CellCorner,
+    EdgeMidpoint,
+    /// Centers, corners and edge midpoints
+    HalfCell,
+}
+
+impl SnapMode {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SnapMode::CellCenter => "Cell centers",
+            SnapMode::CellCorner => "Cell corners",
+            SnapMode::EdgeMidpoint => "Edge midpoints",
+            SnapMode::HalfCell => "Half cells",
+        }
+    }
+
+    pub fn all() -> &'static [SnapMode] {
+        &[
+            SnapMode::CellCenter,
+            SnapMode::CellCorner,
+            SnapMode::EdgeMidpoint,
+            SnapMode::HalfCell,
+        ]
+    }
+}
+
+/// Angle step for rotate-drags with Shift held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RotationSnap {
+    #[default]
+    Degrees15,
+    Degrees45,
+    Degrees90,
+}
+
+impl RotationSnap {
+    pub fn degrees(&self) -> f32 {
+        match self {
+            RotationSnap::Degrees15 => 15.0,
+            RotationSnap::Degrees45 => 45.0,
+            RotationSnap::Degrees90 => 90.0,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            RotationSnap::Degrees15 => "15°",
+            RotationSnap::Degrees45 => "45°",
+            RotationSnap::Degrees90 => "90°",
+        }
+    }
+
+    pub fn all() -> &'static [RotationSnap] {
+        &[
+            RotationSnap::Degrees15,
+            RotationSnap::Degrees45,
+            RotationSnap::Degrees90,
+        ]
+    }
+}
+
 #[derive(Resource)]
 pub struct GridSettings {
     pub snap_enabled: bool,
+    pub snap_mode: SnapMode,
+    pub rotation_snap: RotationSnap,
+    /// Snap to the edges and centers of nearby items, showing guide lines
+    pub smart_guides: bool,
 }
 
 impl Default for GridSettings {
     fn default() -> Self {
-        Self { snap_enabled: true }
+        Self {
+            snap_enabled: true,
+            snap_mode: SnapMode::default(),
+            rotation_snap: RotationSnap::default(),
+            smart_guides: true,
+        }
     }
 }
 
@@ -31,6 +106,59 @@ pub fn snap_to_grid(position: Vec2, grid_size: f32, snap_enabled: bool) -> Vec2
     )
 }
 
+/// Snap position to the nearest grid point of the given kind
+pub fn snap_point(position: Vec2, grid_size: f32, mode: SnapMode) -> Vec2 {
+    let round_to = |value: f32, step: f32| (value / step).round() * step;
+    match mode {
+        SnapMode::CellCenter => snap_to_grid(position, grid_size, true),
+        SnapMode::CellCorner => Vec2::new(
+            round_to(position.x, grid_size),
+            round_to(position.y, grid_size),
+        ),
+        SnapMode::EdgeMidpoint => {
+            let center = snap_to_grid(position, grid_size, true);
+            // Midpoints of vertical edges sit level with a cell center, those
+            // of horizontal edges directly above or below one
+            let on_vertical = Vec2::new(round_to(position.x, grid_size), center.y);
+            let on_horizontal = Vec2::new(center.x, round_to(position.y, grid_size));
+            if position.distance_squared(on_vertical) <= position.distance_squared(on_horizontal)
+            {
+                on_vertical
+            } else {
+                on_horizontal
+            }
+        }
+        SnapMode::HalfCell => Vec2::new(
+            round_to(position.x, grid_size / 2.0),
+            round_to(position.y, grid_size / 2.0),
+        ),
+    }
+}
+
+/// Snap a token so its footprint lines up with the grid. Footprints an even
+/// number of cells wide (Large, Gargantuan) center on a cell corner; odd and
+/// fractional ones center on a cell.
+pub fn snap_to_footprint(
+    position: Vec2,
+    grid_size: f32,
+    footprint_cells: f32,
+    snap_enabled: bool,
+) -> Vec2 {
+    if !snap_enabled {
+        return position;
+    }
+
+    let is_even = footprint_cells >= 2.0 && footprint_cells % 2.0 == 0.0;
+    if is_even {
+        Vec2::new(
+            (position.x / grid_size).round() * grid_size,
+            (position.y / grid_size).round() * grid_size,
+        )
+    } else {
+        snap_to_grid(position, grid_size, true)
+    }
+}
+
 pub fn draw_grid(
     mut gizmos: Gizmos,
     map_data: Res<MapData>,
@@ -183,4 +311,45 @@ mod tests {
             assert_eq!(result, center, "Position {:?} should snap to {:?}", pos, center);
         }
     }
+
+    // snap_to_footprint tests
+    #[test]
+    fn test_footprint_large_snaps_to_corner() {
+        let result = snap_to_footprint(Vec2::new(60.0, 80.0), 70.0, 2.0, true);
+        assert_eq!(result, Vec2::new(70.0, 70.0));
+    }
+
+    #[test]
+    fn test_footprint_huge_snaps_to_center() {
+        // A 3x3 space is centered on its middle cell
+        let result = snap_to_footprint(Vec2::new(60.0, 80.0), 70.0, 3.0, true);
+        assert_eq!(result, Vec2::new(35.0, 105.0));
+    }
+
+    #[test]
+    fn test_footprint_tiny_snaps_to_center() {
+        let result = snap_to_footprint(Vec2::new(10.0, 10.0), 70.0, 0.5, true);
+        assert_eq!(result, Vec2::new(35.0, 35.0));
+    }
+
+    #[test]
+    fn test_footprint_snap_disabled() {
+        let pos = Vec2::new(12.0, 34.0);
+        assert_eq!(snap_to_footprint(pos, 70.0, 2.0, false), pos);
+    }
+
+    // snap_point tests
+    #[test]
+    fn test_snap_point_modes() {
+        let pos = Vec2::new(60.0, 20.0);
+        assert_eq!(snap_point(pos, 70.0, SnapMode::CellCenter), Vec2::new(35.0, 35.0));
+        assert_eq!(snap_point(pos, 70.0, SnapMode::CellCorner), Vec2::new(70.0, 0.0));
+        // Closer to the right edge's midpoint than the bottom edge's
+        assert_eq!(snap_point(pos, 70.0, SnapMode::EdgeMidpoint), Vec2::new(70.0, 35.0));
+        assert_eq!(snap_point(pos, 70.0, SnapMode::HalfCell), Vec2::new(70.0, 35.0));
+        assert_eq!(
+            snap_point(Vec2::new(30.0, 5.0), 70.0, SnapMode::EdgeMidpoint),
+            Vec2::new(35.0, 0.0)
+        );
+    }
 }
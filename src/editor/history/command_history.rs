@@ -1,41 +1,137 @@
 //! Command history resource for tracking undo/redo state.
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::commands::EditorCommand;
-use super::MAX_HISTORY_SIZE;
+use super::{COALESCE_WINDOW_SECS, MAX_HISTORY_BRANCHES, MAX_HISTORY_SIZE};
 
 /// Resource tracking command history for undo/redo
+///
+/// The undo and redo stacks form the current line of history. Making a new
+/// edit after undoing doesn't throw the redo stack away: it's set aside as a
+/// [`HistoryBranch`] that can be returned to from the history panel.
 #[derive(Resource, Default)]
 pub struct CommandHistory {
     /// Stack of commands that can be undone (most recent last)
     undo_stack: Vec<EditorCommand>,
     /// Stack of commands that can be redone (most recent last)
     redo_stack: Vec<EditorCommand>,
+    /// Undone work set aside by later edits, oldest first
+    branches: Vec<HistoryBranch>,
+    /// When the top of the undo stack was last recorded or extended, while it
+    /// may still absorb a continuation of the same edit
+    last_recorded: Option<f64>,
+}
+
+/// Undone commands that were set aside when a different edit was made
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryBranch {
+    /// Number of steps of the current history shared with this branch before
+    /// it splits off
+    pub fork: usize,
+    /// The branch's commands in redo stack order (the first step last)
+    pub commands: Vec<EditorCommand>,
+}
+
+/// A point in the history that can be jumped to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryPoint {
+    /// After this many steps of the current history (0 = before any edit)
+    Main(usize),
+    /// After this many steps into a branch
+    Branch { branch: usize, steps: usize },
+}
+
+/// A single move through the history, applied one at a time so each step
+/// sees the entities spawned by the one before
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryStep {
+    Undo,
+    Redo,
+    /// Make a branch the redo stack; the current redo stack becomes a branch
+    EnterBranch(usize),
+}
+
+/// Persistence format for a map's history. Entities are stored by their
+/// position in the map's saved entity list (see [`CommandHistory::to_saved`]).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SavedHistory {
+    pub undo_stack: Vec<EditorCommand>,
+    pub redo_stack: Vec<EditorCommand>,
+    #[serde(default)]
+    pub branches: Vec<HistoryBranch>,
+}
+
+impl SavedHistory {
+    /// Replace each stored asset path for which `map` returns a new one
+    pub fn map_asset_paths(&mut self, mut map: impl FnMut(&str) -> Option<String>) {
+        let branches = self.branches.iter_mut().flat_map(|b| &mut b.commands);
+        for command in self
+            .undo_stack
+            .iter_mut()
+            .chain(&mut self.redo_stack)
+            .chain(branches)
+        {
+            command.map_asset_paths(&mut map);
+        }
+    }
 }
 
 #[allow(dead_code)]
 impl CommandHistory {
     /// Push a new command to the history
     pub fn push(&mut self, command: EditorCommand) {
-        // Clear redo stack when a new action is performed
-        self.redo_stack.clear();
+        // Set the redo stack aside when a new action is performed
+        self.shelve_redo();
 
         self.undo_stack.push(command);
 
         // Trim history if it exceeds max size
         while self.undo_stack.len() > MAX_HISTORY_SIZE {
             self.undo_stack.remove(0);
+            // Branches off the dropped step can't be reached any more
+            self.branches.retain(|branch| branch.fork > 0);
+            for branch in &mut self.branches {
+                branch.fork -= 1;
+            }
+        }
+    }
+
+    /// Record a command made at `now` (in seconds). An in-place edit that
+    /// continues the previous one within [`COALESCE_WINDOW_SECS`] is merged
+    /// into it, so dragging a value records one step rather than one per frame.
+    pub fn record(&mut self, command: EditorCommand, now: f64) {
+        let continues = self
+            .last_recorded
+            .is_some_and(|last| now - last <= COALESCE_WINDOW_SECS);
+        let merged = continues
+            && self
+                .undo_stack
+                .last_mut()
+                .is_some_and(|previous| previous.absorb(&command));
+
+        if merged {
+            self.shelve_redo();
+            // An edit dragged back to where it started isn't worth a step
+            if self.undo_stack.last().is_some_and(EditorCommand::is_noop) {
+                self.undo_stack.pop();
+            }
+        } else if !command.is_noop() {
+            self.push(command);
         }
+        self.last_recorded = Some(now);
     }
 
     /// Pop the last command for undo
     pub fn pop_undo(&mut self) -> Option<EditorCommand> {
+        self.last_recorded = None;
         self.undo_stack.pop()
     }
 
     /// Pop the last command for redo
     pub fn pop_redo(&mut self) -> Option<EditorCommand> {
+        self.last_recorded = None;
         self.redo_stack.pop()
     }
 
@@ -69,9 +165,162 @@ impl CommandHistory {
         self.redo_stack.len()
     }
 
+    /// Commands that can be undone, oldest first
+    pub fn undo_commands(&self) -> impl Iterator<Item = &EditorCommand> {
+        self.undo_stack.iter()
+    }
+
+    /// Commands that can be redone, next first
+    pub fn redo_commands(&self) -> impl Iterator<Item = &EditorCommand> {
+        self.redo_stack.iter().rev()
+    }
+
+    /// Undone work set aside by later edits, oldest first
+    pub fn branches(&self) -> &[HistoryBranch] {
+        &self.branches
+    }
+
     /// Clear all history
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
+        self.branches.clear();
+        self.last_recorded = None;
+    }
+
+    /// Follow an asset rename in every stored command
+    pub fn rename_asset(&mut self, old_path: &str, new_path: &str) {
+        for command in self.commands_mut() {
+            command.rename_asset(old_path, new_path);
+        }
+    }
+
+    /// The steps that take the history from where it is to `target`
+    pub fn steps_to(&self, target: HistoryPoint) -> Vec<HistoryStep> {
+        let depth = self.undo_stack.len();
+        let main_steps = |to: usize| {
+            if to < depth {
+                vec![HistoryStep::Undo; depth - to]
+            } else {
+                vec![HistoryStep::Redo; (to - depth).min(self.redo_stack.len())]
+            }
+        };
+        match target {
+            HistoryPoint::Main(to) => main_steps(to),
+            HistoryPoint::Branch { branch, steps } => {
+                let Some(fork) = self.branches.get(branch).map(|b| b.fork) else {
+                    return Vec::new();
+                };
+                let mut plan = main_steps(fork);
+                plan.push(HistoryStep::EnterBranch(branch));
+                plan.extend(vec![HistoryStep::Redo; steps]);
+                plan
+            }
+        }
+    }
+
+    /// Make a branch the redo stack, setting the current redo stack aside.
+    /// Only possible once undone or redone to the branch's fork.
+    pub fn enter_branch(&mut self, index: usize) -> bool {
+        if self.branches.get(index).map(|b| b.fork) != Some(self.undo_stack.len()) {
+            return false;
+        }
+        let branch = self.branches.remove(index);
+        self.shelve_redo();
+        self.redo_stack = branch.commands;
+        self.last_recorded = None;
+        true
+    }
+
+    /// Convert to the persistence format.
+    ///
+    /// `entity_index` gives the position of an entity in the saved map's
+    /// entity list; entities that aren't on the map are stored as
+    /// [`Entity::PLACEHOLDER`].
+    pub fn to_saved(&self, entity_index: impl Fn(Entity) -> Option<usize>) -> SavedHistory {
+        let mut to_index = |entity: Entity| {
+            entity_index(entity)
+                .and_then(|i| Entity::from_raw_u32(i as u32))
+                .unwrap_or(Entity::PLACEHOLDER)
+        };
+        let mut convert = |commands: &[EditorCommand]| -> Vec<EditorCommand> {
+            commands
+                .iter()
+                .cloned()
+                .map(|mut command| {
+                    command.map_entities(&mut to_index);
+                    command
+                })
+                .collect()
+        };
+        SavedHistory {
+            undo_stack: convert(&self.undo_stack),
+            redo_stack: convert(&self.redo_stack),
+            branches: self
+                .branches
+                .iter()
+                .map(|branch| HistoryBranch {
+                    fork: branch.fork,
+                    commands: convert(&branch.commands),
+                })
+                .collect(),
+        }
+    }
+
+    /// Restore from the persistence format.
+    ///
+    /// `entities` holds the spawned entity for each entry of the saved map's
+    /// entity list, in the same order.
+    pub fn from_saved(saved: &SavedHistory, entities: &[Entity]) -> Self {
+        let mut history = Self {
+            undo_stack: saved.undo_stack.clone(),
+            redo_stack: saved.redo_stack.clone(),
+            branches: saved.branches.clone(),
+            last_recorded: None,
+        };
+        for command in history.commands_mut() {
+            command.map_entities(&mut |entity| {
+                if entity == Entity::PLACEHOLDER {
+                    return entity;
+                }
+                entities
+                    .get(entity.index_u32() as usize)
+                    .copied()
+                    .unwrap_or(Entity::PLACEHOLDER)
+            });
+        }
+        history
+    }
+
+    /// Move the redo stack into a branch at the current step. Branches that
+    /// split off further along it are re-rooted here, since the steps they
+    /// shared with the current history are leaving it.
+    fn shelve_redo(&mut self) {
+        if self.redo_stack.is_empty() {
+            return;
+        }
+        let depth = self.undo_stack.len();
+        let redo = std::mem::take(&mut self.redo_stack);
+        for branch in &mut self.branches {
+            if branch.fork > depth {
+                let shared = branch.fork - depth;
+                branch.commands.extend_from_slice(&redo[redo.len() - shared..]);
+                branch.fork = depth;
+            }
+        }
+        self.branches.push(HistoryBranch {
+            fork: depth,
+            commands: redo,
+        });
+        if self.branches.len() > MAX_HISTORY_BRANCHES {
+            self.branches.remove(0);
+        }
+    }
+
+    fn commands_mut(&mut self) -> impl Iterator<Item = &mut EditorCommand> {
+        self.undo_stack
+            .iter_mut()
+            .chain(&mut self.redo_stack)
+            .chain(self.branches.iter_mut().flat_map(|b| &mut b.commands))
     }
 }
@@ -1,11 +1,19 @@
 //! Editor command enum for undo/redo operations.
 
+use std::cmp::Ordering;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::map::{CameraBookmark, InitiativeTracker, MapData, MapNote, MapNotes};
 
-use super::data_types::{LineData, PathData, PlacedItemData, TextData, TransformData};
+use super::data_types::{
+    AnnotationData, ItemAssetData, ItemStateData, LineData, PathData, PlacedItemData, ShapeData,
+    TextData, TransformData,
+};
 
 /// A reversible command in the editor
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub enum EditorCommand {
     /// Item(s) were placed on the map
@@ -17,6 +25,11 @@ pub enum EditorCommand {
         /// Entity ID, old transform, new transform
         transforms: Vec<(Entity, TransformData, TransformData)>,
     },
+    /// Item(s) were switched to a different asset
+    ReplaceAssets {
+        /// Entity ID, old asset, new asset
+        items: Vec<(Entity, ItemAssetData, ItemAssetData)>,
+    },
     /// A freehand path was created
     CreatePath { entity: Entity, path: PathData },
     /// A freehand path was deleted
@@ -25,8 +38,373 @@ pub enum EditorCommand {
     CreateLine { entity: Entity, line: LineData },
     /// A line was deleted
     DeleteLine { line: LineData },
+    /// A shape annotation was created
+    CreateShape { entity: Entity, shape: ShapeData },
+    /// A shape annotation was deleted
+    DeleteShape { shape: ShapeData },
     /// A text annotation was created
     CreateText { entity: Entity, text: TextData },
     /// A text annotation was deleted
     DeleteText { text: TextData },
+    /// Text annotation(s) were edited, moved or resized
+    EditText {
+        /// Entity ID, old text, new text
+        texts: Vec<(Entity, TextData, TextData)>,
+    },
+    /// Item(s) changed layer, z-index, transform or token status in place
+    EditItems {
+        /// Entity ID, old state, new state
+        items: Vec<(Entity, ItemStateData, ItemStateData)>,
+    },
+    /// Annotation(s) were restyled, reshaped or shown/hidden to players
+    EditAnnotations {
+        /// Entity ID, old annotation, new annotation
+        annotations: Vec<(Entity, AnnotationData, AnnotationData)>,
+    },
+    /// Layer visibility or lock, or the grid settings, changed
+    EditMapData { old: MapData, new: MapData },
+    /// GM notes were added, edited, moved or deleted
+    EditNotes { old: MapNotes, new: MapNotes },
+    /// Camera bookmarks were added, reordered or removed
+    EditBookmarks {
+        old: Vec<CameraBookmark>,
+        new: Vec<CameraBookmark>,
+    },
+    /// Initiative entries, rolls or the current turn changed
+    EditInitiative {
+        old: InitiativeTracker,
+        new: InitiativeTracker,
+    },
+    /// Fog was cleared from these cells (e.g. a fog brush stroke)
+    RevealFog { cells: Vec<(i32, i32)> },
+    /// Fog was cleared from these cells by the light of a token that moved
+    /// or brightened
+    LightRevealFog { cells: Vec<(i32, i32)> },
+    /// These cells were covered with fog again (e.g. Reset Fog)
+    HideFog { cells: Vec<(i32, i32)> },
+    /// Several commands applied as one step (e.g. an eraser stroke)
+    Batch { commands: Vec<EditorCommand> },
+}
+
+impl EditorCommand {
+    /// Fold `next` into this command when it continues the same in-place edit
+    /// of the same targets, e.g. successive frames of a DragValue drag. The
+    /// merged command keeps this command's old state and `next`'s new state.
+    pub fn absorb(&mut self, next: &EditorCommand) -> bool {
+        match (self, next) {
+            (EditorCommand::EditItems { items }, EditorCommand::EditItems { items: next })
+                if same_targets(items, next) =>
+            {
+                for (entry, (_, _, new)) in items.iter_mut().zip(next) {
+                    entry.2 = new.clone();
+                }
+                true
+            }
+            (
+                EditorCommand::EditAnnotations { annotations },
+                EditorCommand::EditAnnotations { annotations: next },
+            ) if same_targets(annotations, next) => {
+                for (entry, (_, _, new)) in annotations.iter_mut().zip(next) {
+                    entry.2 = new.clone();
+                }
+                true
+            }
+            (EditorCommand::EditNotes { new, .. }, EditorCommand::EditNotes { new: next, .. }) => {
+                *new = next.clone();
+                true
+            }
+            (
+                EditorCommand::EditBookmarks { new, .. },
+                EditorCommand::EditBookmarks { new: next, .. },
+            ) => {
+                *new = next.clone();
+                true
+            }
+            (
+                EditorCommand::EditInitiative { new, .. },
+                EditorCommand::EditInitiative { new: next, .. },
+            ) => {
+                *new = next.clone();
+                true
+            }
+            (
+                EditorCommand::LightRevealFog { cells },
+                EditorCommand::LightRevealFog { cells: next },
+            ) => {
+                for cell in next {
+                    if !cells.contains(cell) {
+                        cells.push(*cell);
+                    }
+                }
+                true
+            }
+            // Fog revealed by a token's light while its light is being
+            // edited joins that edit, so the pair undoes as one step
+            (this @ EditorCommand::EditItems { .. }, EditorCommand::LightRevealFog { .. })
+                if this.edits_tokens() =>
+            {
+                let edit = std::mem::replace(this, EditorCommand::Batch { commands: Vec::new() });
+                *this = EditorCommand::Batch {
+                    commands: vec![edit, next.clone()],
+                };
+                true
+            }
+            (EditorCommand::Batch { commands }, _) => match commands.as_mut_slice() {
+                [
+                    edit @ EditorCommand::EditItems { .. },
+                    reveal @ EditorCommand::LightRevealFog { .. },
+                ] => {
+                    if matches!(next, EditorCommand::LightRevealFog { .. }) {
+                        reveal.absorb(next)
+                    } else {
+                        edit.absorb(next)
+                    }
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether undoing this command would change nothing, e.g. after a drag
+    /// that was merged back to where it started
+    pub fn is_noop(&self) -> bool {
+        match self {
+            EditorCommand::EditItems { items } => items.iter().all(|(_, old, new)| old == new),
+            EditorCommand::EditAnnotations { annotations } => {
+                annotations.iter().all(|(_, old, new)| old == new)
+            }
+            EditorCommand::EditMapData { old, new } => old == new,
+            EditorCommand::EditNotes { old, new } => old == new,
+            EditorCommand::EditBookmarks { old, new } => old == new,
+            EditorCommand::EditInitiative { old, new } => old == new,
+            _ => false,
+        }
+    }
+
+    /// Whether this is an in-place edit of any token
+    fn edits_tokens(&self) -> bool {
+        match self {
+            EditorCommand::EditItems { items } => items
+                .iter()
+                .any(|(_, old, new)| old.token.is_some() || new.token.is_some()),
+            _ => false,
+        }
+    }
+
+    /// Point every stored reference to `old_path` at `new_path`, so undoing or
+    /// redoing after an asset rename doesn't bring back the old file name
+    pub fn rename_asset(&mut self, old_path: &str, new_path: &str) {
+        self.map_asset_paths(&mut |path: &str| (path == old_path).then(|| new_path.to_string()));
+    }
+
+    /// Replace each stored asset path for which `map` returns a new one
+    pub fn map_asset_paths(&mut self, map: &mut impl FnMut(&str) -> Option<String>) {
+        let mut rename = |path: &mut String| {
+            if let Some(new_path) = map(path) {
+                *path = new_path;
+            }
+        };
+        match self {
+            EditorCommand::PlaceItems { items } | EditorCommand::DeleteItems { items } => {
+                for item in items {
+                    rename(&mut item.asset_path);
+                }
+            }
+            EditorCommand::ReplaceAssets { items } => {
+                for (_, old, new) in items {
+                    rename(&mut old.asset_path);
+                    rename(&mut new.asset_path);
+                }
+            }
+            EditorCommand::Batch { commands } => {
+                for command in commands {
+                    command.map_asset_paths(map);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replace every stored entity with `map(entity)`, e.g. to follow items
+    /// that were despawned and respawned by a map switch
+    pub fn map_entities(&mut self, map: &mut impl FnMut(Entity) -> Entity) {
+        match self {
+            EditorCommand::PlaceItems { items } | EditorCommand::DeleteItems { items } => {
+                for item in items {
+                    item.entity = map(item.entity);
+                }
+            }
+            EditorCommand::MoveItems { transforms } => {
+                for (entity, ..) in transforms {
+                    *entity = map(*entity);
+                }
+            }
+            EditorCommand::ReplaceAssets { items } => {
+                for (entity, ..) in items {
+                    *entity = map(*entity);
+                }
+            }
+            EditorCommand::EditText { texts } => {
+                for (entity, ..) in texts {
+                    *entity = map(*entity);
+                }
+            }
+            EditorCommand::EditItems { items } => {
+                for (entity, ..) in items {
+                    *entity = map(*entity);
+                }
+            }
+            EditorCommand::EditAnnotations { annotations } => {
+                for (entity, ..) in annotations {
+                    *entity = map(*entity);
+                }
+            }
+            EditorCommand::EditInitiative { old, new } => {
+                for entry in old.entries.iter_mut().chain(&mut new.entries) {
+                    if let Some(token) = &mut entry.token {
+                        *token = map(*token);
+                    }
+                }
+            }
+            EditorCommand::CreatePath { entity, .. }
+            | EditorCommand::CreateLine { entity, .. }
+            | EditorCommand::CreateShape { entity, .. }
+            | EditorCommand::CreateText { entity, .. } => *entity = map(*entity),
+            EditorCommand::Batch { commands } => {
+                for command in commands {
+                    command.map_entities(map);
+                }
+            }
+            EditorCommand::DeletePath { .. }
+            | EditorCommand::DeleteLine { .. }
+            | EditorCommand::DeleteShape { .. }
+            | EditorCommand::DeleteText { .. }
+            | EditorCommand::EditMapData { .. }
+            | EditorCommand::EditNotes { .. }
+            | EditorCommand::EditBookmarks { .. }
+            | EditorCommand::RevealFog { .. }
+            | EditorCommand::LightRevealFog { .. }
+            | EditorCommand::HideFog { .. } => {}
+        }
+    }
+
+    /// A short description of the action, for the history panel. Commands on
+    /// the redo side of the history hold the reverse of what the user did, so
+    /// pass `undone` to describe the original action instead.
+    pub fn describe(&self, undone: bool) -> String {
+        // (done, undone) wording for commands that are each other's reverse
+        let pick = |done: &str, reverse: &str| (if undone { reverse } else { done }).to_string();
+        match self {
+            EditorCommand::PlaceItems { items } => {
+                pick("Place", "Delete") + " " + &count(items.len(), "item")
+            }
+            EditorCommand::DeleteItems { items } => {
+                pick("Delete", "Place") + " " + &count(items.len(), "item")
+            }
+            EditorCommand::MoveItems { transforms } => {
+                format!("Move {}", count(transforms.len(), "item"))
+            }
+            EditorCommand::ReplaceAssets { items } => {
+                format!("Replace asset of {}", count(items.len(), "item"))
+            }
+            EditorCommand::CreatePath { .. } => pick("Draw path", "Delete path"),
+            EditorCommand::DeletePath { .. } => pick("Delete path", "Draw path"),
+            EditorCommand::CreateLine { .. } => pick("Draw line", "Delete line"),
+            EditorCommand::DeleteLine { .. } => pick("Delete line", "Draw line"),
+            EditorCommand::CreateShape { shape, .. } => {
+                pick("Draw", "Delete") + " " + &shape.kind.display_name().to_lowercase()
+            }
+            EditorCommand::DeleteShape { shape } => {
+                pick("Delete", "Draw") + " " + &shape.kind.display_name().to_lowercase()
+            }
+            EditorCommand::CreateText { .. } => pick("Add text", "Delete text"),
+            EditorCommand::DeleteText { .. } => pick("Delete text", "Add text"),
+            EditorCommand::EditText { texts } => {
+                format!("Edit {}", count(texts.len(), "text"))
+            }
+            EditorCommand::EditItems { items } => {
+                let what = match items.first() {
+                    Some((_, old, new)) if old.layer != new.layer => "Change layer of",
+                    Some((_, old, new)) if old.z_index != new.z_index => "Change z-index of",
+                    Some((_, old, new)) if old.token != new.token => "Edit token status of",
+                    _ => "Transform",
+                };
+                format!("{} {}", what, count(items.len(), "item"))
+            }
+            EditorCommand::EditAnnotations { annotations } => {
+                format!("Edit {}", count(annotations.len(), "annotation"))
+            }
+            EditorCommand::EditMapData { old, new } => {
+                if old.grid_visible != new.grid_visible || old.grid_size != new.grid_size {
+                    "Change grid".to_string()
+                } else {
+                    "Change layer settings".to_string()
+                }
+            }
+            EditorCommand::EditNotes { old, new } => {
+                let moved = |(a, b): (&MapNote, &MapNote)| a.position != b.position;
+                match old.notes.len().cmp(&new.notes.len()) {
+                    Ordering::Less => pick("Add note", "Delete note"),
+                    Ordering::Greater => pick("Delete note", "Add note"),
+                    Ordering::Equal if old.notes.iter().zip(&new.notes).any(moved) => {
+                        "Move note".to_string()
+                    }
+                    Ordering::Equal => "Edit note".to_string(),
+                }
+            }
+            EditorCommand::EditBookmarks { old, new } => match old.len().cmp(&new.len()) {
+                Ordering::Less => pick("Add bookmark", "Remove bookmark"),
+                Ordering::Greater => pick("Remove bookmark", "Add bookmark"),
+                Ordering::Equal => "Reorder bookmarks".to_string(),
+            },
+            EditorCommand::EditInitiative { old, new } => {
+                match old.entries.len().cmp(&new.entries.len()) {
+                    Ordering::Less => pick("Add to initiative", "Remove from initiative"),
+                    Ordering::Greater => pick("Remove from initiative", "Add to initiative"),
+                    Ordering::Equal if old.round == 0 && new.round > 0 => {
+                        pick("Start combat", "End combat")
+                    }
+                    Ordering::Equal if old.round > 0 && new.round == 0 => {
+                        pick("End combat", "Start combat")
+                    }
+                    Ordering::Equal if (old.round, old.current) != (new.round, new.current) => {
+                        "Change turn".to_string()
+                    }
+                    Ordering::Equal => "Edit initiative".to_string(),
+                }
+            }
+            EditorCommand::RevealFog { .. } => pick("Reveal fog", "Hide fog"),
+            EditorCommand::LightRevealFog { .. } => "Reveal fog by light".to_string(),
+            EditorCommand::HideFog { .. } => pick("Hide fog", "Reveal fog"),
+            EditorCommand::Batch { commands } => {
+                let mut labels: Vec<String> = Vec::new();
+                for command in commands {
+                    let label = command.describe(undone);
+                    if !labels.contains(&label) {
+                        labels.push(label);
+                    }
+                }
+                match labels.len() {
+                    0 => "No changes".to_string(),
+                    1 => labels.remove(0),
+                    n => format!("{} and {} more", labels[0], n - 1),
+                }
+            }
+        }
+    }
+}
+
+/// "1 item", "3 items"
+fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
+
+/// Whether two edits touch the same entities in the same order
+fn same_targets<A, B>(a: &[(Entity, A, A)], b: &[(Entity, B, B)]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.0 == y.0)
 }
@@ -1,23 +1,54 @@
 //! Data types for serializing editor state in undo/redo commands.
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::map::Layer;
+use crate::map::{Layer, PlacedItem, TokenData};
 
-use super::super::annotations::{DrawnLine, DrawnPath};
+use super::super::annotations::{DrawnLine, DrawnPath, DrawnShape, ShapeKind, TextAnnotation};
 
 /// Serializable data for a placed item
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PlacedItemData {
     pub entity: Entity,
     pub asset_path: String,
     pub layer: Layer,
     pub z_index: i32,
     pub transform: TransformData,
+    pub token: Option<TokenData>,
+}
+
+/// Everything about a placed item that can be edited in place: its layer,
+/// stacking, transform and token status
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ItemStateData {
+    pub layer: Layer,
+    pub z_index: i32,
+    pub transform: TransformData,
+    pub token: Option<TokenData>,
+}
+
+impl ItemStateData {
+    /// Capture the current state of a placed item
+    pub fn capture(item: &PlacedItem, transform: &Transform, token: Option<&TokenData>) -> Self {
+        Self {
+            layer: item.layer,
+            z_index: item.z_index,
+            transform: TransformData::from(transform),
+            token: token.cloned(),
+        }
+    }
+}
+
+/// The asset a placed item shows and the scale it's drawn at
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemAssetData {
+    pub asset_path: String,
+    pub scale: Vec3,
 }
 
 /// Serializable transform data
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Serialize, Deserialize)]
 pub struct TransformData {
     pub translation: Vec3,
     pub rotation: Quat,
@@ -45,11 +76,12 @@ impl From<TransformData> for Transform {
 }
 
 /// Serializable data for a drawn path
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PathData {
     pub points: Vec<Vec2>,
     pub color: Color,
     pub stroke_width: f32,
+    pub show_to_players: bool,
 }
 
 impl From<&DrawnPath> for PathData {
@@ -58,17 +90,30 @@ impl From<&DrawnPath> for PathData {
             points: p.points.clone(),
             color: p.color,
             stroke_width: p.stroke_width,
+            show_to_players: false,
+        }
+    }
+}
+
+impl PathData {
+    /// The path component described by this data
+    pub fn to_path(&self) -> DrawnPath {
+        DrawnPath {
+            points: self.points.clone(),
+            color: self.color,
+            stroke_width: self.stroke_width,
         }
     }
 }
 
 /// Serializable data for a drawn line
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LineData {
     pub start: Vec2,
     pub end: Vec2,
     pub color: Color,
     pub stroke_width: f32,
+    pub show_to_players: bool,
 }
 
 impl From<&DrawnLine> for LineData {
@@ -78,15 +123,149 @@ impl From<&DrawnLine> for LineData {
             end: l.end,
             color: l.color,
             stroke_width: l.stroke_width,
+            show_to_players: false,
+        }
+    }
+}
+
+impl LineData {
+    /// The line component described by this data
+    pub fn to_line(&self) -> DrawnLine {
+        DrawnLine {
+            start: self.start,
+            end: self.end,
+            color: self.color,
+            stroke_width: self.stroke_width,
+        }
+    }
+}
+
+/// Serializable data for a shape annotation
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShapeData {
+    pub kind: ShapeKind,
+    pub points: Vec<Vec2>,
+    pub color: Color,
+    pub stroke_width: f32,
+    pub fill: Option<Color>,
+    pub dashed: bool,
+    pub show_to_players: bool,
+}
+
+impl From<&DrawnShape> for ShapeData {
+    fn from(s: &DrawnShape) -> Self {
+        Self {
+            kind: s.kind,
+            points: s.points.clone(),
+            color: s.color,
+            stroke_width: s.stroke_width,
+            fill: s.fill,
+            dashed: s.dashed,
+            show_to_players: false,
+        }
+    }
+}
+
+impl ShapeData {
+    /// The shape component described by this data
+    pub fn to_shape(&self) -> DrawnShape {
+        DrawnShape {
+            kind: self.kind,
+            points: self.points.clone(),
+            color: self.color,
+            stroke_width: self.stroke_width,
+            fill: self.fill,
+            dashed: self.dashed,
         }
     }
 }
 
 /// Serializable data for a text annotation
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TextData {
     pub text: String,
     pub position: Vec2,
     pub color: Color,
     pub font_size: f32,
+    pub background: Option<Color>,
+    pub show_to_players: bool,
+}
+
+impl TextData {
+    /// Capture a text annotation at the given position
+    pub fn from_annotation(position: Vec2, text: &TextAnnotation) -> Self {
+        Self {
+            text: text.content.clone(),
+            position,
+            color: text.color,
+            font_size: text.font_size,
+            background: text.background,
+            show_to_players: false,
+        }
+    }
+
+    /// The annotation component described by this data
+    pub fn to_annotation(&self) -> TextAnnotation {
+        TextAnnotation {
+            content: self.text.clone(),
+            font_size: self.font_size,
+            color: self.color,
+            background: self.background,
+        }
+    }
+}
+
+/// Any annotation, for edits that restyle or reshape annotations in place
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AnnotationData {
+    Path(PathData),
+    Line(LineData),
+    Shape(ShapeData),
+    Text(TextData),
+}
+
+impl AnnotationData {
+    /// Capture whichever annotation component the entity carries
+    pub fn capture(
+        show_to_players: bool,
+        path: Option<&DrawnPath>,
+        line: Option<&DrawnLine>,
+        shape: Option<&DrawnShape>,
+        text: Option<(&Transform, &TextAnnotation)>,
+    ) -> Option<Self> {
+        let data = if let Some(path) = path {
+            Self::Path(PathData::from(path))
+        } else if let Some(line) = line {
+            Self::Line(LineData::from(line))
+        } else if let Some(shape) = shape {
+            Self::Shape(ShapeData::from(shape))
+        } else {
+            let (transform, text) = text?;
+            Self::Text(TextData::from_annotation(
+                transform.translation.truncate(),
+                text,
+            ))
+        };
+        Some(data.with_show_to_players(show_to_players))
+    }
+
+    pub fn show_to_players(&self) -> bool {
+        match self {
+            Self::Path(data) => data.show_to_players,
+            Self::Line(data) => data.show_to_players,
+            Self::Shape(data) => data.show_to_players,
+            Self::Text(data) => data.show_to_players,
+        }
+    }
+
+    /// The same annotation, shown to or hidden from players
+    pub fn with_show_to_players(mut self, show: bool) -> Self {
+        match &mut self {
+            Self::Path(data) => data.show_to_players = show,
+            Self::Line(data) => data.show_to_players = show,
+            Self::Shape(data) => data.show_to_players = show,
+            Self::Text(data) => data.show_to_players = show,
+        }
+        self
+    }
 }
@@ -1,29 +1,53 @@
 //! Execute functions for undo and redo operations.
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
-use crate::map::PlacedItem;
+use crate::map::{
+    CameraBookmarks, FogOfWarData, InitiativeTracker, Layer, MapData, MapDirtyState, MapNotes,
+    PlacedItem, TokenData,
+};
 
-use super::super::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
+use super::super::annotations::{AnnotationMarker, ShowToPlayers, TextAnnotation};
 use super::commands::EditorCommand;
-use super::data_types::PlacedItemData;
-use super::spawn_helpers::{spawn_line, spawn_path, spawn_placed_item, spawn_text};
+use super::data_types::{AnnotationData, PlacedItemData};
+use super::spawn_helpers::{
+    item_render_layers, set_item_asset, spawn_line, spawn_path, spawn_placed_item, spawn_shape,
+    spawn_text,
+};
+
+/// Everything undoing or redoing a command may touch
+#[derive(SystemParam)]
+#[allow(clippy::type_complexity)]
+pub struct HistoryTargets<'w, 's> {
+    commands: Commands<'w, 's>,
+    asset_server: Res<'w, AssetServer>,
+    items_query: Query<'w, 's, (Entity, &'static Transform, &'static PlacedItem)>,
+    annotations_query: Query<'w, 's, (), With<AnnotationMarker>>,
+    texts_query: Query<
+        'w,
+        's,
+        (Entity, &'static Transform, &'static TextAnnotation),
+        With<AnnotationMarker>,
+    >,
+    map_data: ResMut<'w, MapData>,
+    fog_data: ResMut<'w, FogOfWarData>,
+    notes: ResMut<'w, MapNotes>,
+    bookmarks: ResMut<'w, CameraBookmarks>,
+    initiative: ResMut<'w, InitiativeTracker>,
+    dirty_state: ResMut<'w, MapDirtyState>,
+}
 
 /// Execute an undo operation and return the reverse command for redo
 pub fn execute_undo(
     command: &EditorCommand,
-    commands: &mut Commands,
-    asset_server: &AssetServer,
-    items_query: &Query<(Entity, &Transform, &PlacedItem)>,
-    _paths_query: &Query<(Entity, &DrawnPath), With<AnnotationMarker>>,
-    _lines_query: &Query<(Entity, &DrawnLine), With<AnnotationMarker>>,
-    _texts_query: &Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    targets: &mut HistoryTargets,
 ) -> Option<EditorCommand> {
     match command {
         EditorCommand::PlaceItems { items } => {
             // Undo placement = delete items
             for item in items {
-                commands.entity(item.entity).despawn();
+                targets.commands.entity(item.entity).despawn();
             }
             Some(EditorCommand::DeleteItems {
                 items: items.clone(),
@@ -33,13 +57,14 @@ pub fn execute_undo(
             // Undo deletion = recreate items
             let mut new_items = Vec::new();
             for item in items {
-                let entity = spawn_placed_item(commands, asset_server, item);
+                let entity = spawn_placed_item(&mut targets.commands, &targets.asset_server, item);
                 new_items.push(PlacedItemData {
                     entity,
                     asset_path: item.asset_path.clone(),
                     layer: item.layer,
                     z_index: item.z_index,
                     transform: item.transform,
+                    token: item.token.clone(),
                 });
             }
             Some(EditorCommand::PlaceItems { items: new_items })
@@ -48,8 +73,9 @@ pub fn execute_undo(
             // Undo move = restore old transforms
             let mut reverse_transforms = Vec::new();
             for (entity, old_transform, new_transform) in transforms {
-                if let Ok((_, _current_transform, _)) = items_query.get(*entity) {
-                    commands
+                if let Ok((_, _current_transform, _)) = targets.items_query.get(*entity) {
+                    targets
+                        .commands
                         .entity(*entity)
                         .insert(Transform::from(*old_transform));
                     reverse_transforms.push((*entity, *new_transform, *old_transform));
@@ -59,14 +85,34 @@ pub fn execute_undo(
                 transforms: reverse_transforms,
             })
         }
+        EditorCommand::ReplaceAssets { items } => {
+            // Undo replace = switch back to the old asset and scale
+            let mut reverse_items = Vec::new();
+            for (entity, old_asset, new_asset) in items {
+                if let Ok((_, current_transform, item)) = targets.items_query.get(*entity) {
+                    set_item_asset(
+                        &mut targets.commands,
+                        &targets.asset_server,
+                        *entity,
+                        current_transform,
+                        item,
+                        old_asset,
+                    );
+                    reverse_items.push((*entity, new_asset.clone(), old_asset.clone()));
+                }
+            }
+            Some(EditorCommand::ReplaceAssets {
+                items: reverse_items,
+            })
+        }
         EditorCommand::CreatePath { entity, path } => {
             // Undo path creation = delete path
-            commands.entity(*entity).despawn();
+            targets.commands.entity(*entity).despawn();
             Some(EditorCommand::DeletePath { path: path.clone() })
         }
         EditorCommand::DeletePath { path } => {
             // Undo path deletion = recreate path
-            let entity = spawn_path(commands, path);
+            let entity = spawn_path(&mut targets.commands, path);
             Some(EditorCommand::CreatePath {
                 entity,
                 path: path.clone(),
@@ -74,30 +120,190 @@ pub fn execute_undo(
         }
         EditorCommand::CreateLine { entity, line } => {
             // Undo line creation = delete line
-            commands.entity(*entity).despawn();
+            targets.commands.entity(*entity).despawn();
             Some(EditorCommand::DeleteLine { line: line.clone() })
         }
         EditorCommand::DeleteLine { line } => {
             // Undo line deletion = recreate line
-            let entity = spawn_line(commands, line);
+            let entity = spawn_line(&mut targets.commands, line);
             Some(EditorCommand::CreateLine {
                 entity,
                 line: line.clone(),
             })
         }
+        EditorCommand::CreateShape { entity, shape } => {
+            // Undo shape creation = delete shape
+            targets.commands.entity(*entity).despawn();
+            Some(EditorCommand::DeleteShape {
+                shape: shape.clone(),
+            })
+        }
+        EditorCommand::DeleteShape { shape } => {
+            // Undo shape deletion = recreate shape
+            let entity = spawn_shape(&mut targets.commands, shape);
+            Some(EditorCommand::CreateShape {
+                entity,
+                shape: shape.clone(),
+            })
+        }
         EditorCommand::CreateText { entity, text } => {
             // Undo text creation = delete text
-            commands.entity(*entity).despawn();
+            targets.commands.entity(*entity).despawn();
             Some(EditorCommand::DeleteText { text: text.clone() })
         }
         EditorCommand::DeleteText { text } => {
             // Undo text deletion = recreate text
-            let entity = spawn_text(commands, text);
+            let entity = spawn_text(&mut targets.commands, text);
             Some(EditorCommand::CreateText {
                 entity,
                 text: text.clone(),
             })
         }
+        EditorCommand::EditText { texts } => {
+            // Undo edit = restore old content, style and position
+            let mut reverse_texts = Vec::new();
+            for (entity, old_text, new_text) in texts {
+                if let Ok((_, current_transform, _)) = targets.texts_query.get(*entity) {
+                    let translation = old_text.position.extend(current_transform.translation.z);
+                    targets.commands.entity(*entity).insert((
+                        old_text.to_annotation(),
+                        Transform::from_translation(translation),
+                    ));
+                    reverse_texts.push((*entity, new_text.clone(), old_text.clone()));
+                }
+            }
+            Some(EditorCommand::EditText {
+                texts: reverse_texts,
+            })
+        }
+        EditorCommand::EditItems { items } => {
+            // Undo edit = restore old layer, stacking, transform and token status
+            let mut reverse_items = Vec::new();
+            for (entity, old_state, new_state) in items {
+                if let Ok((_, _, item)) = targets.items_query.get(*entity) {
+                    let mut entity_commands = targets.commands.entity(*entity);
+                    entity_commands.insert((
+                        Transform::from(old_state.transform),
+                        PlacedItem {
+                            layer: old_state.layer,
+                            z_index: old_state.z_index,
+                            ..item.clone()
+                        },
+                        item_render_layers(old_state.layer),
+                    ));
+                    match &old_state.token {
+                        Some(token) => entity_commands.insert(token.clone()),
+                        None => entity_commands.remove::<TokenData>(),
+                    };
+                    reverse_items.push((*entity, new_state.clone(), old_state.clone()));
+                }
+            }
+            Some(EditorCommand::EditItems {
+                items: reverse_items,
+            })
+        }
+        EditorCommand::EditAnnotations { annotations } => {
+            // Undo edit = restore old geometry, style and player visibility
+            let mut reverse_annotations = Vec::new();
+            for (entity, old_data, new_data) in annotations {
+                if !targets.annotations_query.contains(*entity) {
+                    continue;
+                }
+                let mut entity_commands = targets.commands.entity(*entity);
+                match old_data {
+                    AnnotationData::Path(path) => entity_commands.insert(path.to_path()),
+                    AnnotationData::Line(line) => entity_commands.insert(line.to_line()),
+                    AnnotationData::Shape(shape) => entity_commands.insert(shape.to_shape()),
+                    AnnotationData::Text(text) => entity_commands.insert((
+                        text.to_annotation(),
+                        Transform::from_translation(
+                            text.position.extend(Layer::Annotation.z_base()),
+                        ),
+                    )),
+                };
+                if old_data.show_to_players() {
+                    entity_commands.insert(ShowToPlayers);
+                } else {
+                    entity_commands.remove::<ShowToPlayers>();
+                }
+                reverse_annotations.push((*entity, new_data.clone(), old_data.clone()));
+            }
+            Some(EditorCommand::EditAnnotations {
+                annotations: reverse_annotations,
+            })
+        }
+        EditorCommand::EditMapData { old, new } => {
+            // Undo edit = restore the old layer and grid settings; the map
+            // keeps its current name
+            *targets.map_data = MapData {
+                name: targets.map_data.name.clone(),
+                ..old.clone()
+            };
+            targets.dirty_state.is_dirty = true;
+            Some(EditorCommand::EditMapData {
+                old: new.clone(),
+                new: old.clone(),
+            })
+        }
+        EditorCommand::EditNotes { old, new } => {
+            // Undo edit = restore the old notes
+            *targets.notes = old.clone();
+            targets.dirty_state.is_dirty = true;
+            Some(EditorCommand::EditNotes {
+                old: new.clone(),
+                new: old.clone(),
+            })
+        }
+        EditorCommand::EditBookmarks { old, new } => {
+            // Undo edit = restore the old bookmarks
+            targets.bookmarks.bookmarks = old.clone();
+            targets.dirty_state.is_dirty = true;
+            Some(EditorCommand::EditBookmarks {
+                old: new.clone(),
+                new: old.clone(),
+            })
+        }
+        EditorCommand::EditInitiative { old, new } => {
+            // Undo edit = restore the old entries, turn and round
+            *targets.initiative = old.clone();
+            targets.dirty_state.is_dirty = true;
+            Some(EditorCommand::EditInitiative {
+                old: new.clone(),
+                new: old.clone(),
+            })
+        }
+        EditorCommand::RevealFog { cells } | EditorCommand::LightRevealFog { cells } => {
+            // Undo reveal = cover the cells with fog again
+            for cell in cells {
+                targets.fog_data.fog_cell(*cell);
+            }
+            targets.dirty_state.is_dirty = true;
+            Some(EditorCommand::HideFog {
+                cells: cells.clone(),
+            })
+        }
+        EditorCommand::HideFog { cells } => {
+            // Undo hide = reveal the cells again
+            for cell in cells {
+                targets.fog_data.reveal_cell(*cell);
+            }
+            targets.dirty_state.is_dirty = true;
+            Some(EditorCommand::RevealFog {
+                cells: cells.clone(),
+            })
+        }
+        EditorCommand::Batch { commands: batch } => {
+            // Undo in reverse order; the reverses are collected in the order
+            // they ran, so undoing the reverse batch replays the original order
+            let reverse_commands = batch
+                .iter()
+                .rev()
+                .filter_map(|command| execute_undo(command, targets))
+                .collect();
+            Some(EditorCommand::Batch {
+                commands: reverse_commands,
+            })
+        }
     }
 }
 
@@ -111,20 +317,7 @@ pub fn execute_undo(
 /// separate forward implementation required.
 pub fn execute_redo(
     command: &EditorCommand,
-    commands: &mut Commands,
-    asset_server: &AssetServer,
-    items_query: &Query<(Entity, &Transform, &PlacedItem)>,
-    paths_query: &Query<(Entity, &DrawnPath), With<AnnotationMarker>>,
-    lines_query: &Query<(Entity, &DrawnLine), With<AnnotationMarker>>,
-    texts_query: &Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    targets: &mut HistoryTargets,
 ) -> Option<EditorCommand> {
-    execute_undo(
-        command,
-        commands,
-        asset_server,
-        items_query,
-        paths_query,
-        lines_query,
-        texts_query,
-    )
+    execute_undo(command, targets)
 }
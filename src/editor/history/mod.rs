@@ -13,14 +13,36 @@
 //!
 //! - Item placement and deletion
 //! - Item movement (transform changes)
-//! - Annotation creation and deletion (paths, lines, text)
+//! - Property panel edits: layer, z-index, position, rotation, scale and token status
+//! - Annotation creation and deletion (paths, lines, shapes, text)
+//! - Annotation moves, vertex drags, style and player visibility changes
+//! - Text edits, moves and resizes
+//! - Eraser strokes (a batch of deletions and re-creations)
+//! - Layer visibility and lock, grid visibility and fog enable toggles
+//! - Fog brush strokes and fog resets, and fog revealed by token light
+//! - GM notes, camera bookmarks and the initiative tracker
+//!
+//! Successive edits of the same targets within [`COALESCE_WINDOW_SECS`] are
+//! merged, so dragging a property value is undone in one step. Asset renames
+//! aren't undoable (they move files and rewrite saved maps), but they rewrite
+//! the asset paths stored in the history so later undos don't restore items
+//! under the old name.
+//!
+//! ## Branches and Persistence
+//!
+//! Making an edit after undoing sets the undone commands aside as a branch
+//! instead of discarding them, and the history panel can jump to any step of
+//! the current history or of a branch. Each open map keeps its own history,
+//! converted to a [`SavedHistory`] that refers to entities by position so it
+//! survives the map being despawned on a tab switch, and optionally written
+//! next to the map file.
 //!
 //! ## Module Structure
 //!
 //! - [`commands`] - EditorCommand enum defining all reversible operations
 //! - [`data_types`] - Serializable data types for undo/redo state
 //! - [`command_history`] - CommandHistory resource for tracking state
-//! - [`systems`] - Bevy systems for keyboard shortcuts
+//! - [`systems`] - Bevy systems for keyboard shortcuts and history jumps
 //! - [`execute`] - Execute functions for undo/redo operations
 //! - [`spawn_helpers`] - Helper functions for spawning entities
 
@@ -36,11 +58,23 @@ mod systems;
 mod tests;
 
 // Re-exports
-pub use command_history::CommandHistory;
+pub use command_history::{CommandHistory, HistoryPoint, SavedHistory};
 pub use commands::EditorCommand;
-pub use data_types::{LineData, PathData, PlacedItemData, TextData, TransformData};
+pub use data_types::{
+    AnnotationData, ItemAssetData, ItemStateData, LineData, PathData, PlacedItemData, ShapeData,
+    TextData, TransformData,
+};
 pub use recording::{record_commands, RecordEditorCommand};
-pub use systems::{handle_redo, handle_undo};
+pub(crate) use spawn_helpers::{
+    item_render_layers, set_item_asset, spawn_line, spawn_path, spawn_placed_item,
+};
+pub use systems::{handle_history_jumps, handle_redo, handle_undo, JumpToHistoryRequest};
 
 /// Maximum number of commands to keep in history
 pub(crate) const MAX_HISTORY_SIZE: usize = 100;
+
+/// Maximum number of set-aside branches to keep; the oldest go first
+pub(crate) const MAX_HISTORY_BRANCHES: usize = 20;
+
+/// Seconds within which successive edits of the same targets merge into one step
+pub(crate) const COALESCE_WINDOW_SECS: f64 = 1.0;
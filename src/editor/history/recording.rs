@@ -17,12 +17,14 @@ pub struct RecordEditorCommand {
     pub command: EditorCommand,
 }
 
-/// Applies recorded commands to the [`CommandHistory`].
+/// Applies recorded commands to the [`CommandHistory`], merging rapid edits
+/// of the same targets into one step.
 pub fn record_commands(
     mut history: ResMut<CommandHistory>,
     mut events: MessageReader<RecordEditorCommand>,
+    time: Res<Time>,
 ) {
     for event in events.read() {
-        history.push(event.command.clone());
+        history.record(event.command.clone(), time.elapsed_secs_f64());
     }
 }
@@ -5,8 +5,20 @@ use bevy::prelude::*;
 
 use crate::map::{Layer, PlacedItem};
 
-use super::super::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
-use super::data_types::{LineData, PathData, PlacedItemData, TextData};
+use super::super::annotations::{AnnotationMarker, ShowToPlayers};
+use super::data_types::{ItemAssetData, LineData, PathData, PlacedItemData, ShapeData, TextData};
+
+/// Render layers for an item on `layer`. Match placement: player-visible
+/// layers render on layer 0, editor-only layers (GM, FogOfWar) on layer 1.
+/// Using a fixed [0, 1] here would leak GM/fog items into the player view
+/// when an action is undone/redone.
+pub fn item_render_layers(layer: Layer) -> RenderLayers {
+    if layer.is_player_visible() {
+        RenderLayers::layer(0)
+    } else {
+        RenderLayers::layer(1)
+    }
+}
 
 /// Helper to spawn a placed item from PlacedItemData
 pub fn spawn_placed_item(
@@ -16,41 +28,55 @@ pub fn spawn_placed_item(
 ) -> Entity {
     let texture_handle: Handle<Image> = asset_server.load(&data.asset_path);
 
-    // Match placement: player-visible layers render on layer 0, editor-only
-    // layers (GM, FogOfWar) on layer 1. Using a fixed [0, 1] here would leak
-    // GM/fog items into the player view when an action is undone/redone.
-    let render_layer = if data.layer.is_player_visible() {
-        RenderLayers::layer(0)
-    } else {
-        RenderLayers::layer(1)
-    };
+    let mut entity = commands.spawn((
+        Sprite::from_image(texture_handle),
+        Transform::from(data.transform),
+        PlacedItem {
+            asset_path: data.asset_path.clone(),
+            layer: data.layer,
+            z_index: data.z_index,
+        },
+        item_render_layers(data.layer),
+    ));
+    if let Some(token) = &data.token {
+        entity.insert(token.clone());
+    }
+    entity.id()
+}
 
-    commands
-        .spawn((
-            Sprite::from_image(texture_handle),
-            Transform::from(data.transform),
-            PlacedItem {
-                asset_path: data.asset_path.clone(),
-                layer: data.layer,
-                z_index: data.z_index,
-            },
-            render_layer,
-        ))
-        .id()
+/// Helper to switch a placed item to another asset, keeping its entity,
+/// position, rotation, layer and token data
+pub fn set_item_asset(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    entity: Entity,
+    transform: &Transform,
+    item: &PlacedItem,
+    asset: &ItemAssetData,
+) {
+    let texture_handle: Handle<Image> = asset_server.load(&asset.asset_path);
+    commands.entity(entity).insert((
+        Sprite::from_image(texture_handle),
+        Transform {
+            scale: asset.scale,
+            ..*transform
+        },
+        PlacedItem {
+            asset_path: asset.asset_path.clone(),
+            ..item.clone()
+        },
+    ));
 }
 
 /// Helper to spawn a drawn path from PathData
 pub fn spawn_path(commands: &mut Commands, data: &PathData) -> Entity {
     commands
         .spawn((
-            DrawnPath {
-                points: data.points.clone(),
-                color: data.color,
-                stroke_width: data.stroke_width,
-            },
+            data.to_path(),
             Transform::from_xyz(0.0, 0.0, Layer::Annotation.z_base()),
             AnnotationMarker,
         ))
+        .insert_if(ShowToPlayers, || data.show_to_players)
         .id()
 }
 
@@ -58,15 +84,23 @@ pub fn spawn_path(commands: &mut Commands, data: &PathData) -> Entity {
 pub fn spawn_line(commands: &mut Commands, data: &LineData) -> Entity {
     commands
         .spawn((
-            DrawnLine {
-                start: data.start,
-                end: data.end,
-                color: data.color,
-                stroke_width: data.stroke_width,
-            },
+            data.to_line(),
+            Transform::from_xyz(0.0, 0.0, Layer::Annotation.z_base()),
+            AnnotationMarker,
+        ))
+        .insert_if(ShowToPlayers, || data.show_to_players)
+        .id()
+}
+
+/// Helper to spawn a shape annotation from ShapeData
+pub fn spawn_shape(commands: &mut Commands, data: &ShapeData) -> Entity {
+    commands
+        .spawn((
+            data.to_shape(),
             Transform::from_xyz(0.0, 0.0, Layer::Annotation.z_base()),
             AnnotationMarker,
         ))
+        .insert_if(ShowToPlayers, || data.show_to_players)
         .id()
 }
 
@@ -74,13 +108,10 @@ pub fn spawn_line(commands: &mut Commands, data: &LineData) -> Entity {
 pub fn spawn_text(commands: &mut Commands, data: &TextData) -> Entity {
     commands
         .spawn((
-            TextAnnotation {
-                content: data.text.clone(),
-                color: data.color,
-                font_size: data.font_size,
-            },
+            data.to_annotation(),
             Transform::from_xyz(data.position.x, data.position.y, Layer::Annotation.z_base()),
             AnnotationMarker,
         ))
+        .insert_if(ShowToPlayers, || data.show_to_players)
         .id()
 }
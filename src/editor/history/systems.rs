@@ -1,82 +1,99 @@
-//! Bevy systems for handling undo/redo keyboard shortcuts.
+//! Bevy systems for handling undo/redo keyboard shortcuts and history jumps.
 
 use bevy::prelude::*;
+use bevy_egui::EguiContexts;
 
-use crate::map::PlacedItem;
+use crate::editor::annotations::TextEditState;
+use crate::keybindings::{Action, ActionInput};
 
-use super::super::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
-use super::command_history::CommandHistory;
-use super::execute::{execute_redo, execute_undo};
+use super::command_history::{CommandHistory, HistoryPoint, HistoryStep};
+use super::execute::{execute_redo, execute_undo, HistoryTargets};
 
-/// System to handle undo keyboard shortcut (Ctrl+Z)
-#[allow(clippy::too_many_arguments)]
+/// Asks to undo or redo to a point in the history, e.g. from the history panel
+#[derive(Message)]
+pub struct JumpToHistoryRequest {
+    pub target: HistoryPoint,
+}
+
+/// System to handle the undo shortcut (Ctrl+Z by default)
 pub fn handle_undo(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
+    mut contexts: EguiContexts,
+    text_state: Res<TextEditState>,
     mut history: ResMut<CommandHistory>,
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    items_query: Query<(Entity, &Transform, &PlacedItem)>,
-    paths_query: Query<(Entity, &DrawnPath), With<AnnotationMarker>>,
-    lines_query: Query<(Entity, &DrawnLine), With<AnnotationMarker>>,
-    texts_query: Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    mut targets: HistoryTargets,
 ) {
-    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
-    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-
-    // Ctrl+Z (without shift) = undo
-    if ctrl
-        && !shift
-        && keyboard.just_pressed(KeyCode::KeyZ)
-        && let Some(command) = history.pop_undo()
-    {
-        let reverse_command = execute_undo(
-            &command,
-            &mut commands,
-            &asset_server,
-            &items_query,
-            &paths_query,
-            &lines_query,
-            &texts_query,
-        );
-        if let Some(reverse) = reverse_command {
-            history.push_redo(reverse);
-        }
+    let typing = contexts.ctx_mut().is_ok_and(|ctx| ctx.wants_keyboard_input());
+    if shortcut_applies(keys.just_pressed(Action::Undo), typing, &text_state) {
+        undo(&mut history, &mut targets);
     }
 }
 
-/// System to handle redo keyboard shortcut (Ctrl+Y or Ctrl+Shift+Z)
-#[allow(clippy::too_many_arguments)]
+/// System to handle the redo shortcut (Ctrl+Y or Ctrl+Shift+Z by default)
 pub fn handle_redo(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
+    mut contexts: EguiContexts,
+    text_state: Res<TextEditState>,
     mut history: ResMut<CommandHistory>,
+    mut targets: HistoryTargets,
+) {
+    let typing = contexts.ctx_mut().is_ok_and(|ctx| ctx.wants_keyboard_input());
+    if shortcut_applies(keys.just_pressed(Action::Redo), typing, &text_state) {
+        redo(&mut history, &mut targets);
+    }
+}
+
+/// Whether a pressed undo/redo shortcut is meant for the map. While typing in
+/// a text field, or editing a text annotation, the field handles it instead.
+pub(super) fn shortcut_applies(pressed: bool, typing: bool, text_state: &TextEditState) -> bool {
+    pressed && !typing && !text_state.is_editing()
+}
+
+/// System to handle history jumps. Each step runs as its own system, so its
+/// spawns and despawns are applied before the next step looks for them.
+pub fn handle_history_jumps(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    items_query: Query<(Entity, &Transform, &PlacedItem)>,
-    paths_query: Query<(Entity, &DrawnPath), With<AnnotationMarker>>,
-    lines_query: Query<(Entity, &DrawnLine), With<AnnotationMarker>>,
-    texts_query: Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
+    mut events: MessageReader<JumpToHistoryRequest>,
+    history: Res<CommandHistory>,
 ) {
-    let ctrl = keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
-    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    // Steps are planned from the current history, so only one jump per frame
+    let Some(event) = events.read().last() else {
+        return;
+    };
+    for step in history.steps_to(event.target) {
+        commands.run_system_cached_with(apply_history_step, step);
+    }
+}
+
+/// Apply one planned step of a history jump
+fn apply_history_step(
+    In(step): In<HistoryStep>,
+    mut history: ResMut<CommandHistory>,
+    mut targets: HistoryTargets,
+) {
+    match step {
+        HistoryStep::Undo => undo(&mut history, &mut targets),
+        HistoryStep::Redo => redo(&mut history, &mut targets),
+        HistoryStep::EnterBranch(index) => {
+            if !history.enter_branch(index) {
+                warn!("History branch {} doesn't split off here", index);
+            }
+        }
+    }
+}
 
-    // Ctrl+Y or Ctrl+Shift+Z = redo
-    let redo_pressed = (ctrl && keyboard.just_pressed(KeyCode::KeyY))
-        || (ctrl && shift && keyboard.just_pressed(KeyCode::KeyZ));
+fn undo(history: &mut CommandHistory, targets: &mut HistoryTargets) {
+    if let Some(command) = history.pop_undo()
+        && let Some(reverse) = execute_undo(&command, targets)
+    {
+        history.push_redo(reverse);
+    }
+}
 
-    if redo_pressed
-        && let Some(command) = history.pop_redo()
+fn redo(history: &mut CommandHistory, targets: &mut HistoryTargets) {
+    if let Some(command) = history.pop_redo()
+        && let Some(reverse) = execute_redo(&command, targets)
     {
-        let reverse_command = execute_redo(
-            &command,
-            &mut commands,
-            &asset_server,
-            &items_query,
-            &paths_query,
-            &lines_query,
-            &texts_query,
-        );
-        if let Some(reverse) = reverse_command {
-            history.push_undo(reverse);
-        }
+        history.push_undo(reverse);
     }
 }
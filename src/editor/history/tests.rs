@@ -2,10 +2,40 @@
 
 use bevy::prelude::*;
 
-use super::command_history::CommandHistory;
+use super::command_history::{CommandHistory, HistoryPoint, HistoryStep};
 use super::commands::EditorCommand;
-use super::data_types::TransformData;
-use super::MAX_HISTORY_SIZE;
+use super::data_types::{
+    ItemAssetData, ItemStateData, PlacedItemData, ShapeData, TextData, TransformData,
+};
+use super::systems::shortcut_applies;
+use crate::editor::annotations::{DrawnShape, ShapeKind, TextAnnotation, TextEditState};
+use crate::map::{InitiativeTracker, Layer, MapNotes, TokenData};
+use super::{COALESCE_WINDOW_SECS, MAX_HISTORY_SIZE};
+
+/// An EditItems command moving `entity` from x = `from` to x = `to`
+fn edit_x(entity: Entity, from: f32, to: f32) -> EditorCommand {
+    let state = |x: f32| ItemStateData {
+        layer: Layer::Token,
+        z_index: 0,
+        transform: TransformData::from(&Transform::from_xyz(x, 0.0, 0.0)),
+        token: None,
+    };
+    EditorCommand::EditItems {
+        items: vec![(entity, state(from), state(to))],
+    }
+}
+
+/// The (old x, new x) of the single item in the top undo command
+fn top_edit_x(history: &mut CommandHistory) -> (f32, f32) {
+    let command = history.pop_undo().unwrap();
+    let EditorCommand::EditItems { items } = &command else {
+        panic!("expected EditItems, got {:?}", command);
+    };
+    let (_, old, new) = &items[0];
+    let xs = (old.transform.translation.x, new.transform.translation.x);
+    history.push_undo(command);
+    xs
+}
 
 #[test]
 fn test_command_history_push() {
@@ -30,9 +60,10 @@ fn test_command_history_undo_clears_redo() {
     history.push_redo(EditorCommand::PlaceItems { items: vec![] });
     assert!(history.can_redo());
 
-    // Push a new command - should clear redo
+    // Push a new command - should set redo aside as a branch
     history.push(EditorCommand::PlaceItems { items: vec![] });
     assert!(!history.can_redo());
+    assert_eq!(history.branches().len(), 1);
 }
 
 #[test]
@@ -63,3 +94,413 @@ fn test_transform_data_conversion() {
     assert_eq!(transform.rotation, restored.rotation);
     assert_eq!(transform.scale, restored.scale);
 }
+
+#[test]
+fn test_text_data_round_trip() {
+    let text = TextAnnotation {
+        content: "Trap door\nDC 15".to_string(),
+        font_size: 18.0,
+        color: Color::srgb(1.0, 0.0, 0.0),
+        background: Some(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+    };
+
+    let data = TextData::from_annotation(Vec2::new(10.0, -20.0), &text);
+    assert_eq!(data.position, Vec2::new(10.0, -20.0));
+    assert_eq!(data.to_annotation(), text);
+}
+
+#[test]
+fn test_shape_data_round_trip() {
+    let shape = DrawnShape {
+        kind: ShapeKind::Polygon,
+        points: vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(5.0, 8.0)],
+        color: Color::srgb(0.0, 0.0, 1.0),
+        stroke_width: 4.0,
+        fill: Some(Color::srgba(0.0, 0.0, 1.0, 0.3)),
+        dashed: true,
+    };
+
+    assert_eq!(ShapeData::from(&shape).to_shape(), shape);
+}
+
+#[test]
+fn test_rapid_edits_coalesce() {
+    let entity = Entity::from_raw_u32(1).unwrap();
+    let mut history = CommandHistory::default();
+
+    // A DragValue drag records one edit per frame
+    history.record(edit_x(entity, 0.0, 1.0), 10.0);
+    history.record(edit_x(entity, 1.0, 2.0), 10.016);
+    history.record(edit_x(entity, 2.0, 5.0), 10.032);
+    assert_eq!(history.undo_count(), 1);
+    assert_eq!(top_edit_x(&mut history), (0.0, 5.0));
+
+    // A pause starts a new step
+    let later = 10.032 + COALESCE_WINDOW_SECS + 0.5;
+    history.record(edit_x(entity, 5.0, 6.0), later);
+    assert_eq!(history.undo_count(), 2);
+}
+
+#[test]
+fn test_coalesce_needs_same_targets() {
+    let a = Entity::from_raw_u32(1).unwrap();
+    let b = Entity::from_raw_u32(2).unwrap();
+    let mut history = CommandHistory::default();
+
+    history.record(edit_x(a, 0.0, 1.0), 1.0);
+    history.record(edit_x(b, 0.0, 1.0), 1.1);
+    // Other command kinds never merge
+    for (cell, now) in [((0, 0), 1.2), ((1, 0), 1.3)] {
+        let cells = vec![cell];
+        history.record(EditorCommand::RevealFog { cells }, now);
+    }
+    assert_eq!(history.undo_count(), 4);
+}
+
+#[test]
+fn test_edit_dragged_back_is_dropped() {
+    let entity = Entity::from_raw_u32(1).unwrap();
+    let mut history = CommandHistory::default();
+    history.push(EditorCommand::PlaceItems { items: vec![] });
+
+    history.record(edit_x(entity, 0.0, 3.0), 1.0);
+    history.record(edit_x(entity, 3.0, 0.0), 1.1);
+    assert_eq!(history.undo_count(), 1);
+
+    // A no-op on its own isn't recorded either
+    history.record(edit_x(entity, 2.0, 2.0), 5.0);
+    assert_eq!(history.undo_count(), 1);
+}
+
+#[test]
+fn test_undo_stops_coalescing() {
+    let entity = Entity::from_raw_u32(1).unwrap();
+    let mut history = CommandHistory::default();
+
+    history.record(edit_x(entity, 0.0, 1.0), 1.0);
+    let undone = history.pop_undo().unwrap();
+    history.push_redo(undone);
+    history.record(edit_x(entity, 0.0, 2.0), 1.1);
+    history.record(edit_x(entity, 2.0, 3.0), 1.2);
+
+    // The edits after the undo form their own step
+    assert_eq!(history.undo_count(), 1);
+    assert!(!history.can_redo());
+    assert_eq!(top_edit_x(&mut history), (0.0, 3.0));
+}
+
+#[test]
+fn test_rename_asset_rewrites_history() {
+    let entity = Entity::from_raw_u32(1).unwrap();
+    let item = PlacedItemData {
+        entity,
+        asset_path: "tokens/ogre.png".to_string(),
+        layer: Layer::Token,
+        z_index: 0,
+        transform: TransformData::from(&Transform::IDENTITY),
+        token: None,
+    };
+    let asset = |path: &str| ItemAssetData {
+        asset_path: path.to_string(),
+        scale: Vec3::ONE,
+    };
+    let mut history = CommandHistory::default();
+    history.push(EditorCommand::Batch {
+        commands: vec![EditorCommand::DeleteItems { items: vec![item] }],
+    });
+    history.push_redo(EditorCommand::ReplaceAssets {
+        items: vec![(entity, asset("tokens/ogre.png"), asset("tokens/troll.png"))],
+    });
+
+    history.rename_asset("tokens/ogre.png", "monsters/ogre.png");
+
+    let Some(EditorCommand::Batch { commands }) = history.pop_undo() else {
+        panic!("expected the batch");
+    };
+    let EditorCommand::DeleteItems { items } = &commands[0] else {
+        panic!("expected DeleteItems");
+    };
+    assert_eq!(items[0].asset_path, "monsters/ogre.png");
+
+    let Some(EditorCommand::ReplaceAssets { items }) = history.pop_redo() else {
+        panic!("expected ReplaceAssets");
+    };
+    assert_eq!(items[0].1.asset_path, "monsters/ogre.png");
+    assert_eq!(items[0].2.asset_path, "tokens/troll.png");
+}
+
+/// A distinct command for step `n` of a test history
+fn step(n: u32) -> EditorCommand {
+    edit_x(Entity::from_raw_u32(n).unwrap(), 0.0, n as f32)
+}
+
+/// Which step a command made by [`step`] is
+fn step_id(command: &EditorCommand) -> u32 {
+    let EditorCommand::EditItems { items } = command else {
+        panic!("expected EditItems, got {:?}", command);
+    };
+    items[0].0.index_u32()
+}
+
+/// Undo without executing anything, as the undo system would
+fn undo(history: &mut CommandHistory) {
+    let command = history.pop_undo().unwrap();
+    history.push_redo(command);
+}
+
+#[test]
+fn test_edit_after_undo_creates_branch() {
+    let mut history = CommandHistory::default();
+    for n in 1..=3 {
+        history.push(step(n));
+    }
+    undo(&mut history);
+    undo(&mut history);
+    history.push(step(4));
+
+    assert!(!history.can_redo());
+    let branch = &history.branches()[0];
+    assert_eq!(branch.fork, 1);
+    let ids: Vec<u32> = branch.commands.iter().map(step_id).collect();
+    assert_eq!(ids, vec![3, 2]);
+}
+
+#[test]
+fn test_steps_to_history_points() {
+    let mut history = CommandHistory::default();
+    for n in 1..=3 {
+        history.push(step(n));
+    }
+    undo(&mut history);
+    undo(&mut history);
+    history.push(step(4));
+
+    assert_eq!(
+        history.steps_to(HistoryPoint::Main(0)),
+        vec![HistoryStep::Undo, HistoryStep::Undo]
+    );
+    // Can't redo past the end of the current history
+    assert!(history.steps_to(HistoryPoint::Main(5)).is_empty());
+    assert_eq!(
+        history.steps_to(HistoryPoint::Branch {
+            branch: 0,
+            steps: 2
+        }),
+        vec![
+            HistoryStep::Undo,
+            HistoryStep::EnterBranch(0),
+            HistoryStep::Redo,
+            HistoryStep::Redo,
+        ]
+    );
+}
+
+#[test]
+fn test_enter_branch_swaps_redo() {
+    let mut history = CommandHistory::default();
+    for n in 1..=3 {
+        history.push(step(n));
+    }
+    undo(&mut history);
+    undo(&mut history);
+    history.push(step(4));
+
+    // Only possible at the fork
+    assert!(!history.enter_branch(0));
+    undo(&mut history);
+    assert!(history.enter_branch(0));
+
+    assert_eq!(
+        history.redo_commands().map(step_id).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    let branch = &history.branches()[0];
+    assert_eq!(branch.fork, 1);
+    assert_eq!(
+        branch.commands.iter().map(step_id).collect::<Vec<_>>(),
+        vec![4]
+    );
+}
+
+#[test]
+fn test_trimming_history_moves_branch_forks() {
+    let mut history = CommandHistory::default();
+    history.push(step(1));
+    undo(&mut history);
+    history.push(step(2));
+    undo(&mut history);
+    history.push(step(3));
+    assert_eq!(history.branches().len(), 2);
+    assert!(history.branches().iter().all(|b| b.fork == 0));
+
+    history.push(step(4));
+    undo(&mut history);
+    history.push(step(5));
+    assert_eq!(history.branches().last().unwrap().fork, 1);
+
+    for n in 6..(6 + MAX_HISTORY_SIZE as u32 - 2) {
+        history.push(step(n));
+    }
+    assert_eq!(history.undo_count(), MAX_HISTORY_SIZE);
+    assert_eq!(history.branches().len(), 3);
+
+    // Dropping the oldest step drops the branches from the start
+    history.push(step(1000));
+    assert_eq!(history.branches().len(), 1);
+    assert_eq!(history.branches()[0].fork, 0);
+}
+
+#[test]
+fn test_saved_history_round_trip() {
+    let placed = Entity::from_raw_u32(5).unwrap();
+    let gone = Entity::from_raw_u32(6).unwrap();
+    let mut history = CommandHistory::default();
+    history.push(edit_x(placed, 0.0, 1.0));
+    history.push(edit_x(gone, 0.0, 2.0));
+    undo(&mut history);
+
+    let saved = history.to_saved(|entity| (entity == placed).then_some(0));
+    let json = serde_json::to_string(&saved).unwrap();
+    let saved = serde_json::from_str(&json).unwrap();
+
+    let respawned = Entity::from_raw_u32(9).unwrap();
+    let mut restored = CommandHistory::from_saved(&saved, &[respawned]);
+    assert_eq!(restored.undo_count(), 1);
+    let Some(EditorCommand::EditItems { items }) = restored.pop_redo() else {
+        panic!("expected EditItems");
+    };
+    assert_eq!(items[0].0, Entity::PLACEHOLDER);
+    let Some(EditorCommand::EditItems { items }) = restored.pop_undo() else {
+        panic!("expected EditItems");
+    };
+    assert_eq!(items[0].0, respawned);
+}
+
+#[test]
+fn test_note_edits_coalesce_and_describe() {
+    let mut history = CommandHistory::default();
+    let empty = MapNotes::default();
+    let mut added = empty.clone();
+    let id = added.add(Vec2::ZERO);
+    let mut titled = added.clone();
+    titled.get_mut(id).unwrap().title = "Guard Room".to_string();
+
+    // Adding a note and typing its title is one step
+    history.record(
+        EditorCommand::EditNotes {
+            old: empty.clone(),
+            new: added.clone(),
+        },
+        1.0,
+    );
+    history.record(
+        EditorCommand::EditNotes {
+            old: added,
+            new: titled.clone(),
+        },
+        1.1,
+    );
+    assert_eq!(history.undo_count(), 1);
+
+    let command = history.pop_undo().unwrap();
+    assert_eq!(command.describe(false), "Add note");
+    let EditorCommand::EditNotes { old, new } = &command else {
+        panic!("expected EditNotes, got {:?}", command);
+    };
+    assert_eq!(*old, empty);
+    assert_eq!(*new, titled);
+
+    // The reverse on the redo stack still describes the original action
+    let reverse = EditorCommand::EditNotes {
+        old: titled,
+        new: empty,
+    };
+    assert_eq!(reverse.describe(true), "Add note");
+}
+
+#[test]
+fn test_initiative_edit_follows_token_entities() {
+    let goblin = Entity::from_raw_u32(3).unwrap();
+    let mut old = InitiativeTracker::default();
+    old.add_manual("Lair Action".to_string());
+    let mut new = old.clone();
+    new.add_token(goblin, "Goblin".to_string());
+
+    let mut history = CommandHistory::default();
+    history.push(EditorCommand::EditInitiative { old, new });
+    let saved = history.to_saved(|entity| (entity == goblin).then_some(0));
+
+    let respawned = Entity::from_raw_u32(8).unwrap();
+    let mut restored = CommandHistory::from_saved(&saved, &[respawned]);
+    let Some(EditorCommand::EditInitiative { old, new }) = restored.pop_undo() else {
+        panic!("expected EditInitiative");
+    };
+    assert_eq!(old.entries.len(), 1);
+    assert_eq!(old.entries[0].token, None);
+    assert_eq!(new.entries[1].token, Some(respawned));
+}
+
+#[test]
+fn test_light_reveal_joins_token_edit() {
+    let entity = Entity::from_raw_u32(1).unwrap();
+    let light = |feet: f32| ItemStateData {
+        layer: Layer::Token,
+        z_index: 0,
+        transform: TransformData::from(&Transform::IDENTITY),
+        token: Some(TokenData {
+            bright_light_ft: feet,
+            ..Default::default()
+        }),
+    };
+    let brighten = |from: f32, to: f32| EditorCommand::EditItems {
+        items: vec![(entity, light(from), light(to))],
+    };
+    let reveal = |cells: &[(i32, i32)]| EditorCommand::LightRevealFog {
+        cells: cells.to_vec(),
+    };
+    let mut history = CommandHistory::default();
+
+    // Dragging a token's light radius reveals more fog each frame
+    history.record(brighten(0.0, 10.0), 1.0);
+    history.record(reveal(&[(0, 0), (1, 0)]), 1.016);
+    history.record(brighten(10.0, 20.0), 1.032);
+    history.record(reveal(&[(1, 0), (2, 0)]), 1.048);
+    assert_eq!(history.undo_count(), 1);
+
+    let Some(EditorCommand::Batch { commands }) = history.pop_undo() else {
+        panic!("expected Batch");
+    };
+    let [EditorCommand::EditItems { items }, EditorCommand::LightRevealFog { cells }] =
+        commands.as_slice()
+    else {
+        panic!("expected an edit and a reveal, got {:?}", commands);
+    };
+    assert_eq!(items[0].1, light(0.0));
+    assert_eq!(items[0].2, light(20.0));
+    assert_eq!(cells, &vec![(0, 0), (1, 0), (2, 0)]);
+
+    // Fog revealed with the brush stays a separate step
+    history.record(brighten(0.0, 10.0), 5.0);
+    history.record(EditorCommand::RevealFog { cells: vec![(5, 5)] }, 5.1);
+    assert_eq!(history.undo_count(), 2);
+}
+
+#[test]
+fn test_undo_is_noop_while_editing_text() {
+    let mut text_state = TextEditState {
+        draft: Some(TextAnnotation {
+            content: "Trap door".to_string(),
+            font_size: 18.0,
+            color: Color::WHITE,
+            background: None,
+        }),
+        ..default()
+    };
+    assert!(!shortcut_applies(true, false, &text_state));
+
+    // A focused text field elsewhere, like the notes editor, keeps it too
+    text_state.draft = None;
+    assert!(!shortcut_applies(true, true, &text_state));
+    assert!(!shortcut_applies(false, false, &text_state));
+    assert!(shortcut_applies(true, false, &text_state));
+}
@@ -0,0 +1,102 @@
+//! Initiative tracker highlighting and token bookkeeping.
+//!
+//! The tracker itself lives in [`crate::map::InitiativeTracker`] so it can be
+//! saved with the map. This module draws a ring around the token whose turn it
+//! is and drops entries whose tokens have been deleted.
+//!
+//! ## Rendering
+//!
+//! Uses two gizmo groups:
+//! - [`InitiativeEditorGizmoGroup`]: Highlight in the editor view (RenderLayers::layer(1))
+//! - [`InitiativePlayerGizmoGroup`]: Highlight in the player view (RenderLayers::layer(2))
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::gizmos::config::{GizmoConfigGroup, GizmoConfigStore};
+use bevy::prelude::*;
+
+use super::fog::is_fog_layer_visible;
+use super::selection::get_sprite_half_size;
+use crate::map::{world_to_cell, FogOfWarData, InitiativeTracker, MapData, PlacedItem};
+use crate::session::LiveSessionState;
+use crate::theme;
+
+/// Padding between the token bounds and the highlight ring (in world units)
+const HIGHLIGHT_PADDING: f32 = 6.0;
+
+/// Gizmo group for the active-turn highlight in editor view
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct InitiativeEditorGizmoGroup;
+
+/// Gizmo group for the active-turn highlight in player view
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct InitiativePlayerGizmoGroup;
+
+/// Configure the initiative gizmo groups for their respective render layers
+pub fn configure_initiative_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (editor_config, _) = config_store.config_mut::<InitiativeEditorGizmoGroup>();
+    editor_config.render_layers = RenderLayers::layer(1);
+    editor_config.line.width = 3.0;
+
+    // Thicker ring for the player display so it reads from across the table
+    let (player_config, _) = config_store.config_mut::<InitiativePlayerGizmoGroup>();
+    player_config.render_layers = RenderLayers::layer(2);
+    player_config.line.width = 5.0;
+}
+
+/// Draw a ring around the token whose turn it is in both views
+#[allow(clippy::too_many_arguments)]
+pub fn draw_initiative_highlight(
+    mut editor_gizmos: Gizmos<InitiativeEditorGizmoGroup>,
+    mut player_gizmos: Gizmos<InitiativePlayerGizmoGroup>,
+    tracker: Res<InitiativeTracker>,
+    tokens: Query<(&Transform, &Sprite, &Visibility), With<PlacedItem>>,
+    images: Res<Assets<Image>>,
+    map_data: Res<MapData>,
+    fog_data: Res<FogOfWarData>,
+    session_state: Res<LiveSessionState>,
+) {
+    let Some(entity) = tracker.active_token() else {
+        return;
+    };
+    let Ok((transform, sprite, visibility)) = tokens.get(entity) else {
+        return;
+    };
+
+    let center = transform.translation.truncate();
+    let half_size = get_sprite_half_size(sprite, &images) * transform.scale.truncate().abs();
+    let radius = half_size.max_element() + HIGHLIGHT_PADDING;
+
+    editor_gizmos.circle_2d(
+        Isometry2d::from_translation(center),
+        radius,
+        theme::INITIATIVE_ACTIVE,
+    );
+
+    if !session_state.is_active || *visibility == Visibility::Hidden {
+        return;
+    }
+
+    // Don't give away a token's position through the fog
+    let cell = world_to_cell(center, map_data.grid_size);
+    if is_fog_layer_visible(&map_data) && !fog_data.is_cell_revealed(cell) {
+        return;
+    }
+
+    player_gizmos.circle_2d(
+        Isometry2d::from_translation(center),
+        radius,
+        theme::INITIATIVE_ACTIVE,
+    );
+}
+
+/// Remove initiative entries for tokens that were deleted
+pub fn prune_initiative_tokens(
+    mut removed: RemovedComponents<PlacedItem>,
+    mut tracker: ResMut<InitiativeTracker>,
+) {
+    for entity in removed.read() {
+        if tracker.contains_token(entity) {
+            tracker.remove_token(entity);
+        }
+    }
+}
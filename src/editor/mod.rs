@@ -1,28 +1,45 @@
 pub mod annotations;
-mod brush;
+mod auras;
+pub mod autotile;
+pub mod bookmarks;
+pub mod brush;
 mod camera;
 mod clipboard;
 pub mod conditions;
+pub mod fill;
 pub mod fog;
 mod grid;
 pub mod history;
+mod initiative;
+pub mod movement;
+pub mod notes;
 pub mod params;
 mod placement;
+pub mod replace_assets;
 mod selection;
+mod snapping;
 pub mod tools;
+mod tokens;
 
 pub use annotations::{
-    AnnotationMarker, AnnotationSettings, DrawnLine, DrawnPath, TextAnnotation,
+    AnnotationMarker, AnnotationSettings, DrawnLine, DrawnPath, DrawnShape, ShapeKind,
+    ShowToPlayers, TextAnnotation,
 };
 pub use camera::EditorCamera;
+pub use clipboard::{ArrayDuplicateRequest, ArrayLayout, DuplicateSelectionRequest};
 pub use conditions::{no_dialog_open, session_is_active, tool_is};
-pub use grid::GridSettings;
+pub use grid::{snap_to_footprint, GridSettings, RotationSnap, SnapMode};
+pub use selection::{
+    AlignOp, AlignSelectionRequest, BoxSelectSettings, SelectCommand, SelectRequest,
+};
+pub(crate) use selection::rotated_item_bounds;
+pub use tokens::TokenContextMenu;
 pub use tools::{CurrentTool, EditorTool, SelectedLayer};
 
-use bevy::input::common_conditions::{input_just_pressed, input_pressed};
+use bevy::input::common_conditions::input_pressed;
 use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
-// EguiPrimaryContextPass import removed - text tool disabled
+use bevy_egui::EguiPrimaryContextPass;
 
 use crate::map::{MapData, PlacedItem};
 
@@ -58,28 +75,61 @@ impl Plugin for EditorPlugin {
         app.init_resource::<tools::CurrentTool>()
             .init_resource::<tools::SelectedLayer>()
             .init_resource::<GridSettings>()
+            .init_resource::<snapping::SmartGuides>()
             .init_resource::<selection::DragState>()
             .init_resource::<selection::BoxSelectState>()
+            .init_resource::<selection::BoxSelectSettings>()
             .init_resource::<annotations::DrawState>()
             .init_resource::<annotations::LineDrawState>()
+            .init_resource::<annotations::ShapeDrawState>()
+            .init_resource::<annotations::EraserState>()
+            .init_resource::<annotations::SessionSketches>()
             .init_resource::<annotations::TextEditState>()
             .init_resource::<annotations::AnnotationSettings>()
             .init_resource::<clipboard::Clipboard>()
             .init_resource::<history::CommandHistory>()
             .add_message::<history::RecordEditorCommand>()
+            .add_message::<history::JumpToHistoryRequest>()
+            .add_message::<selection::AlignSelectionRequest>()
+            .add_message::<selection::SelectRequest>()
+            .add_message::<clipboard::DuplicateSelectionRequest>()
+            .add_message::<clipboard::ArrayDuplicateRequest>()
+            .add_message::<replace_assets::ReplaceAssetsRequest>()
             .init_resource::<fog::FogState>()
             .init_resource::<brush::BrushState>()
+            .init_resource::<brush::BrushMode>()
+            .init_resource::<brush::ScatterSettings>()
+            .init_resource::<autotile::AutoTileBrush>()
+            .init_resource::<fill::FillState>()
+            .init_resource::<tokens::TokenContextMenu>()
+            .init_resource::<movement::MovementSettings>()
+            .init_resource::<movement::TokenMoveMeasurement>()
+            .init_resource::<movement::MovementTrails>()
+            .init_resource::<notes::NotesState>()
+            .init_resource::<bookmarks::BookmarkSettings>()
+            .init_resource::<bookmarks::BookmarkPans>()
+            .add_message::<bookmarks::RecallBookmarkRequest>()
             // Register gizmo groups for editor-only rendering
             .init_gizmo_group::<annotations::AnnotationGizmoGroup>()
+            .init_gizmo_group::<annotations::AnnotationPlayerGizmoGroup>()
+            .init_gizmo_group::<auras::AuraEditorGizmoGroup>()
+            .init_gizmo_group::<auras::AuraPlayerGizmoGroup>()
             .init_gizmo_group::<fog::FogEditorGizmoGroup>()
             .init_gizmo_group::<fog::FogPlayerGizmoGroup>()
+            .init_gizmo_group::<initiative::InitiativeEditorGizmoGroup>()
+            .init_gizmo_group::<initiative::InitiativePlayerGizmoGroup>()
+            .init_gizmo_group::<movement::MovementEditorGizmoGroup>()
+            .init_gizmo_group::<movement::MovementPlayerGizmoGroup>()
             .init_gizmo_group::<selection::SelectionGizmoGroup>()
             .add_systems(
                 Startup,
                 (
                     camera::spawn_camera,
                     annotations::configure_annotation_gizmos,
+                    auras::configure_aura_gizmos,
                     fog::configure_fog_gizmos,
+                    initiative::configure_initiative_gizmos,
+                    movement::configure_movement_gizmos,
                     selection::configure_selection_gizmos,
                 ),
             )
@@ -89,12 +139,26 @@ impl Plugin for EditorPlugin {
                     camera::camera_pan.run_if(input_pressed(MouseButton::Middle)),
                     camera::camera_zoom.run_if(on_message::<MouseWheel>),
                     camera::apply_camera_zoom,
+                    bookmarks::handle_bookmark_shortcuts.run_if(no_dialog_open),
+                    bookmarks::handle_recall_bookmark
+                        .run_if(on_message::<bookmarks::RecallBookmarkRequest>)
+                        .after(bookmarks::handle_bookmark_shortcuts),
+                    bookmarks::animate_bookmark_pans
+                        .after(bookmarks::handle_recall_bookmark)
+                        .before(camera::apply_camera_zoom),
                     grid::draw_grid,
                     tools::handle_tool_shortcuts.run_if(no_dialog_open),
                     tools::update_cursor_icon,
                     placement::handle_placement
                         .run_if(tool_is(EditorTool::Place).and(no_dialog_open)),
                     brush::handle_brush.run_if(tool_is(EditorTool::Brush).and(no_dialog_open)),
+                    brush::draw_scatter_brush.run_if(tool_is(EditorTool::Brush)),
+                    autotile::handle_autotile_brush
+                        .run_if(tool_is(EditorTool::Brush).and(no_dialog_open)),
+                    autotile::draw_autotile_cursor.run_if(tool_is(EditorTool::Brush)),
+                    fill::handle_fill.run_if(tool_is(EditorTool::Fill).and(no_dialog_open)),
+                    fill::draw_fill_preview.run_if(tool_is(EditorTool::Fill)),
+                    snapping::draw_smart_guides,
                     update_layer_visibility.run_if(resource_changed::<MapData>),
                 ),
             )
@@ -109,34 +173,22 @@ impl Plugin for EditorPlugin {
                         .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
                     selection::draw_selection_indicators,
                     selection::draw_box_select_rect,
-                    selection::handle_fit_to_grid.run_if(
-                        input_just_pressed(KeyCode::KeyG)
-                            .and(tool_is(EditorTool::Select))
-                            .and(no_dialog_open),
-                    ),
-                    selection::handle_center_to_grid.run_if(
-                        input_just_pressed(KeyCode::KeyG)
-                            .and(tool_is(EditorTool::Select))
-                            .and(no_dialog_open),
-                    ),
-                    selection::handle_restore_aspect_ratio.run_if(
-                        input_just_pressed(KeyCode::KeyA)
-                            .and(tool_is(EditorTool::Select))
-                            .and(no_dialog_open),
-                    ),
-                    selection::handle_rotate_90.run_if(
-                        input_just_pressed(KeyCode::KeyR)
-                            .and(tool_is(EditorTool::Select))
-                            .and(no_dialog_open),
-                    ),
-                    selection::handle_deletion.run_if(
-                        input_just_pressed(KeyCode::Delete)
-                            .or(input_just_pressed(KeyCode::Backspace))
-                            .and(no_dialog_open),
-                    ),
-                    selection::handle_escape_clear_selection
-                        .run_if(input_just_pressed(KeyCode::Escape).and(no_dialog_open)),
+                    selection::handle_fit_to_grid
+                        .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
+                    selection::handle_center_to_grid
+                        .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
+                    selection::handle_restore_aspect_ratio
+                        .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
+                    selection::handle_rotate_90
+                        .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
+                    selection::handle_deletion.run_if(no_dialog_open),
+                    selection::handle_escape_clear_selection.run_if(no_dialog_open),
                     selection::update_selection_cursor.run_if(tool_is(EditorTool::Select)),
+                    selection::handle_align_shortcuts
+                        .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
+                    selection::handle_align_selection
+                        .run_if(on_message::<selection::AlignSelectionRequest>)
+                        .after(selection::handle_align_shortcuts),
                     clipboard::handle_copy.run_if(no_dialog_open),
                     clipboard::handle_cut.run_if(no_dialog_open),
                     clipboard::handle_paste.run_if(no_dialog_open),
@@ -145,6 +197,26 @@ impl Plugin for EditorPlugin {
                     history::record_commands,
                 ),
             )
+            .add_systems(
+                Update,
+                (
+                    clipboard::handle_duplicate_shortcut.run_if(no_dialog_open),
+                    clipboard::handle_duplicate
+                        .run_if(on_message::<clipboard::DuplicateSelectionRequest>)
+                        .after(clipboard::handle_duplicate_shortcut),
+                    clipboard::handle_array_duplicate
+                        .run_if(on_message::<clipboard::ArrayDuplicateRequest>),
+                    replace_assets::handle_replace_assets
+                        .run_if(on_message::<replace_assets::ReplaceAssetsRequest>),
+                    selection::handle_select_shortcuts
+                        .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
+                    selection::handle_select_commands
+                        .run_if(on_message::<selection::SelectRequest>)
+                        .after(selection::handle_select_shortcuts),
+                    history::handle_history_jumps
+                        .run_if(on_message::<history::JumpToHistoryRequest>),
+                ),
+            )
             .add_systems(
                 Update,
                 (
@@ -152,13 +224,33 @@ impl Plugin for EditorPlugin {
                         .run_if(tool_is(EditorTool::Draw).and(no_dialog_open)),
                     annotations::handle_line
                         .run_if(tool_is(EditorTool::Line).and(no_dialog_open)),
-                    // Text tool disabled - see TODO in tools.rs
-                    // annotations::handle_text
-                    //     .run_if(tool_is(EditorTool::Text).and(no_dialog_open)),
+                    annotations::handle_shape
+                        .run_if(tool_is(EditorTool::Shape).and(no_dialog_open)),
+                    annotations::handle_text
+                        .run_if(tool_is(EditorTool::Text).and(no_dialog_open)),
+                    annotations::handle_eraser.run_if(no_dialog_open),
+                    annotations::handle_text_double_click
+                        .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
+                    annotations::commit_text_edit_on_tool_change,
+                    annotations::sync_text_annotations,
+                    annotations::sync_shape_fills,
+                    annotations::update_annotation_visibility,
                     annotations::render_drawn_paths,
                     annotations::render_drawn_lines,
+                    annotations::render_drawn_shapes,
                     annotations::render_line_preview.run_if(tool_is(EditorTool::Line)),
                     annotations::render_draw_preview.run_if(tool_is(EditorTool::Draw)),
+                    annotations::render_shape_preview.run_if(tool_is(EditorTool::Shape)),
+                    annotations::render_eraser_preview.run_if(tool_is(EditorTool::Eraser)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    annotations::sync_annotation_render_layers
+                        .after(annotations::sync_text_annotations)
+                        .after(annotations::sync_shape_fills),
+                    annotations::draw_session_sketches,
                 ),
             )
             .add_systems(
@@ -168,16 +260,26 @@ impl Plugin for EditorPlugin {
                     fog::render_fog_editor,
                     fog::render_fog_player,
                     fog::render_fog_brush_preview.run_if(tool_is(EditorTool::Fog)),
+                    initiative::draw_initiative_highlight,
+                    initiative::prune_initiative_tokens,
+                    auras::draw_token_auras,
+                    auras::reveal_fog_from_token_light,
+                    movement::draw_move_measurement,
+                    movement::draw_movement_trails,
+                    tokens::rebuild_token_badges,
+                    tokens::sync_token_badges.after(tokens::rebuild_token_badges),
+                    tokens::handle_token_context_menu
+                        .run_if(tool_is(EditorTool::Select).and(no_dialog_open)),
+                    notes::handle_note_tool.run_if(tool_is(EditorTool::Note).and(no_dialog_open)),
+                    notes::focus_note_camera,
                 ),
             )
-            // Text annotation systems disabled - see TODO in tools.rs
-            // .add_systems(
-            //     EguiPrimaryContextPass,
-            //     (
-            //         annotations::text_annotation_input_ui,
-            //         annotations::render_text_annotations,
-            //     ),
-            // );
-            ;
+            .add_systems(
+                EguiPrimaryContextPass,
+                (
+                    annotations::text_annotation_input_ui,
+                    tokens::token_badge_hover_ui,
+                ),
+            );
     }
 }
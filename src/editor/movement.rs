@@ -0,0 +1,410 @@
+//! Token movement measurement and trails.
+//!
+//! While Token-layer items are dragged, [`super::selection`]'s drag handler
+//! fills [`TokenMoveMeasurement`] with the distance moved in feet and squares,
+//! counted along the path the token was dragged rather than straight from
+//! start to end. On release each moved token can leave a [`MovementTrail`]
+//! along that path that fades out in both views.
+//!
+//! ## Rendering
+//!
+//! Uses two gizmo groups:
+//! - [`MovementEditorGizmoGroup`]: Measurement line and trails in the editor view (RenderLayers::layer(1))
+//! - [`MovementPlayerGizmoGroup`]: Trails in the player view (RenderLayers::layer(2))
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::gizmos::config::{GizmoConfigGroup, GizmoConfigStore};
+use bevy::prelude::*;
+
+use super::fog::is_fog_layer_visible;
+use crate::map::{world_to_cell, FogOfWarData, MapData, FEET_PER_CELL};
+use crate::session::LiveSessionState;
+use crate::theme;
+
+/// How diagonal moves are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiagonalRule {
+    /// Every diagonal square costs 5 ft (5e default)
+    #[default]
+    Standard,
+    /// Diagonals alternate 5 ft and 10 ft (DMG variant)
+    Alternating,
+    /// Straight-line distance
+    Euclidean,
+}
+
+impl DiagonalRule {
+    pub fn all() -> &'static [DiagonalRule] {
+        &[
+            DiagonalRule::Standard,
+            DiagonalRule::Alternating,
+            DiagonalRule::Euclidean,
+        ]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DiagonalRule::Standard => "5 ft",
+            DiagonalRule::Alternating => "5/10/5",
+            DiagonalRule::Euclidean => "Euclidean",
+        }
+    }
+}
+
+/// Settings for token movement measurement
+#[derive(Resource)]
+pub struct MovementSettings {
+    pub diagonal_rule: DiagonalRule,
+    /// Whether moved tokens leave a fading trail
+    pub show_trails: bool,
+    /// Seconds a trail stays visible
+    pub trail_duration: f32,
+}
+
+impl Default for MovementSettings {
+    fn default() -> Self {
+        Self {
+            diagonal_rule: DiagonalRule::Standard,
+            show_trails: true,
+            trail_duration: 6.0,
+        }
+    }
+}
+
+/// Distance covered by a move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveDistance {
+    /// Grid squares moved (fractional only for Euclidean)
+    pub squares: f32,
+    pub feet: f32,
+}
+
+impl MoveDistance {
+    /// Label such as "30 ft (6 sq)"
+    pub fn label(&self) -> String {
+        if self.squares.fract() == 0.0 {
+            format!("{} ft ({} sq)", self.feet, self.squares)
+        } else {
+            format!("{} ft ({:.1} sq)", self.feet, self.squares)
+        }
+    }
+}
+
+/// Measure a move along the points it passed through, in world units.
+/// Squares are counted step by step, so a move around a wall costs more than
+/// a straight one between the same cells.
+pub fn measure_move(path: &[Vec2], grid_size: f32, rule: DiagonalRule) -> MoveDistance {
+    let Some(&start) = path.first().filter(|_| grid_size > 0.0) else {
+        return MoveDistance {
+            squares: 0.0,
+            feet: 0.0,
+        };
+    };
+
+    // Straight and diagonal steps between the cells along the path
+    let cell = |point: Vec2| ((point - start) / grid_size).round();
+    let (mut straight, mut diagonal) = (0.0, 0.0);
+    for step in path.windows(2) {
+        let offset = (cell(step[1]) - cell(step[0])).abs();
+        let (long, short) = (offset.max_element(), offset.min_element());
+        straight += long - short;
+        diagonal += short;
+    }
+
+    let squares = match rule {
+        DiagonalRule::Standard => straight + diagonal,
+        // Every second diagonal costs an extra square
+        DiagonalRule::Alternating => straight + diagonal + (diagonal / 2.0).floor(),
+        DiagonalRule::Euclidean => {
+            // Measured along the raw path, rounded to whole feet
+            let length: f32 = path.windows(2).map(|step| step[0].distance(step[1])).sum();
+            let feet = (length / grid_size * FEET_PER_CELL).round();
+            return MoveDistance {
+                squares: feet / FEET_PER_CELL,
+                feet,
+            };
+        }
+    };
+
+    MoveDistance {
+        squares,
+        feet: squares * FEET_PER_CELL,
+    }
+}
+
+/// The token move in progress, if any
+#[derive(Resource, Default)]
+pub struct TokenMoveMeasurement {
+    pub active: Option<ActiveMove>,
+}
+
+/// Measurement of a token drag in progress
+#[derive(Debug, Clone)]
+pub struct ActiveMove {
+    pub start: Vec2,
+    pub end: Vec2,
+    /// Drag offsets from `start` at which the token entered a new grid cell
+    pub path: Vec<Vec2>,
+    pub distance: MoveDistance,
+    /// Slowest speed among the dragged tokens that have one set
+    pub speed_ft: Option<f32>,
+}
+
+impl ActiveMove {
+    pub fn exceeds_speed(&self) -> bool {
+        self.speed_ft
+            .is_some_and(|speed| self.distance.feet > speed)
+    }
+
+    /// Points the drag passed through, from start to end
+    pub fn points(&self) -> Vec<Vec2> {
+        path_points(self.start, &self.path, self.end)
+    }
+}
+
+/// Points of a move from `start` to `end` through the drag offsets in `path`
+pub fn path_points(start: Vec2, path: &[Vec2], end: Vec2) -> Vec<Vec2> {
+    std::iter::once(start)
+        .chain(path.iter().map(|offset| start + *offset))
+        .chain(std::iter::once(end))
+        .collect()
+}
+
+/// Add a drag's latest offset to its path when it has entered another grid
+/// cell than the path's last point
+pub fn extend_move_path(path: &mut Vec<Vec2>, offset: Vec2, grid_size: f32) {
+    let last = path.last().copied().unwrap_or(Vec2::ZERO);
+    if grid_size > 0.0 && (offset / grid_size).round() != (last / grid_size).round() {
+        path.push(offset);
+    }
+}
+
+/// A fading line left by a token move
+pub struct MovementTrail {
+    /// Points the token moved through, from start to end
+    pub path: Vec<Vec2>,
+    /// Seconds since the move
+    pub age: f32,
+    /// Whether players may see the trail
+    pub player_visible: bool,
+}
+
+/// Recent movement trails
+#[derive(Resource, Default)]
+pub struct MovementTrails {
+    pub trails: Vec<MovementTrail>,
+}
+
+/// Gizmo group for measurement and trails in editor view
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct MovementEditorGizmoGroup;
+
+/// Gizmo group for trails in player view
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct MovementPlayerGizmoGroup;
+
+/// Configure the movement gizmo groups for their respective render layers
+pub fn configure_movement_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (editor_config, _) = config_store.config_mut::<MovementEditorGizmoGroup>();
+    editor_config.render_layers = RenderLayers::layer(1);
+    editor_config.line.width = 3.0;
+
+    let (player_config, _) = config_store.config_mut::<MovementPlayerGizmoGroup>();
+    player_config.render_layers = RenderLayers::layer(2);
+    player_config.line.width = 5.0;
+}
+
+/// Draw the line of the token move in progress (editor only)
+pub fn draw_move_measurement(
+    mut gizmos: Gizmos<MovementEditorGizmoGroup>,
+    measurement: Res<TokenMoveMeasurement>,
+    map_data: Res<MapData>,
+) {
+    let Some(active) = &measurement.active else {
+        return;
+    };
+
+    let color = if active.exceeds_speed() {
+        theme::MOVE_OVER_SPEED
+    } else {
+        theme::MOVE_MEASURE
+    };
+    let marker_radius = map_data.grid_size * 0.1;
+    gizmos.linestrip_2d(active.points(), color);
+    gizmos.circle_2d(
+        Isometry2d::from_translation(active.start),
+        marker_radius,
+        color,
+    );
+    gizmos.circle_2d(
+        Isometry2d::from_translation(active.end),
+        marker_radius,
+        color,
+    );
+}
+
+/// Age, fade and draw movement trails in both views
+#[allow(clippy::too_many_arguments)]
+pub fn draw_movement_trails(
+    mut editor_gizmos: Gizmos<MovementEditorGizmoGroup>,
+    mut player_gizmos: Gizmos<MovementPlayerGizmoGroup>,
+    mut trails: ResMut<MovementTrails>,
+    settings: Res<MovementSettings>,
+    time: Res<Time>,
+    map_data: Res<MapData>,
+    fog_data: Res<FogOfWarData>,
+    session_state: Res<LiveSessionState>,
+) {
+    if trails.trails.is_empty() {
+        return;
+    }
+
+    let delta = time.delta_secs();
+    let duration = settings.trail_duration.max(0.1);
+    trails.trails.retain_mut(|trail| {
+        trail.age += delta;
+        trail.age < duration
+    });
+
+    let fog_visible = is_fog_layer_visible(&map_data);
+    let revealed = |point: Vec2| {
+        !fog_visible || fog_data.is_cell_revealed(world_to_cell(point, map_data.grid_size))
+    };
+
+    for trail in &trails.trails {
+        let alpha = 1.0 - trail.age / duration;
+        let color = theme::MOVE_TRAIL.with_alpha(theme::MOVE_TRAIL.alpha() * alpha);
+        editor_gizmos.linestrip_2d(trail.path.iter().copied(), color);
+
+        // Don't reveal moves made through the fog
+        if session_state.is_active
+            && trail.player_visible
+            && trail.path.iter().all(|point| revealed(*point))
+        {
+            player_gizmos.linestrip_2d(trail.path.iter().copied(), color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: f32 = 70.0;
+
+    fn cells(x: f32, y: f32) -> Vec2 {
+        Vec2::new(x * GRID, y * GRID)
+    }
+
+    /// Path of a straight move by `offset`
+    fn straight(offset: Vec2) -> [Vec2; 2] {
+        [Vec2::ZERO, offset]
+    }
+
+    #[test]
+    fn test_straight_move() {
+        let distance = measure_move(&straight(cells(6.0, 0.0)), GRID, DiagonalRule::Standard);
+        assert_eq!(distance.squares, 6.0);
+        assert_eq!(distance.feet, 30.0);
+    }
+
+    #[test]
+    fn test_standard_diagonals_cost_one_square() {
+        let distance = measure_move(&straight(cells(4.0, -3.0)), GRID, DiagonalRule::Standard);
+        assert_eq!(distance.feet, 20.0);
+    }
+
+    #[test]
+    fn test_standard_measures_along_the_path() {
+        // Around a wall: up 3, across 4 and back down, not straight across
+        let path = [Vec2::ZERO, cells(0.0, 3.0), cells(4.0, 3.0), cells(4.0, 0.0)];
+        let distance = measure_move(&path, GRID, DiagonalRule::Standard);
+        assert_eq!(distance.squares, 10.0);
+        assert_eq!(distance.feet, 50.0);
+    }
+
+    #[test]
+    fn test_alternating_diagonals() {
+        // 5 + 10 + 5 for three diagonal squares
+        let distance = measure_move(&straight(cells(3.0, 3.0)), GRID, DiagonalRule::Alternating);
+        assert_eq!(distance.feet, 20.0);
+
+        let distance = measure_move(&straight(cells(4.0, 2.0)), GRID, DiagonalRule::Alternating);
+        assert_eq!(distance.feet, 25.0);
+    }
+
+    #[test]
+    fn test_alternating_measures_along_the_path() {
+        // Two diagonals out and two back: 5 + 10 + 5 + 10
+        let path = [Vec2::ZERO, cells(2.0, 2.0), cells(4.0, 0.0)];
+        let distance = measure_move(&path, GRID, DiagonalRule::Alternating);
+        assert_eq!(distance.feet, 30.0);
+
+        // The count of diagonals carries across the turns of a path
+        let path = [Vec2::ZERO, cells(1.0, 1.0), cells(2.0, 2.0), cells(3.0, 3.0)];
+        let distance = measure_move(&path, GRID, DiagonalRule::Alternating);
+        assert_eq!(distance.feet, 20.0);
+    }
+
+    #[test]
+    fn test_euclidean_distance() {
+        let distance = measure_move(&straight(cells(3.0, 4.0)), GRID, DiagonalRule::Euclidean);
+        assert_eq!(distance.feet, 25.0);
+        assert_eq!(distance.squares, 5.0);
+    }
+
+    #[test]
+    fn test_unsnapped_moves_round_to_squares() {
+        let offset = cells(2.0, 0.0) + Vec2::new(20.0, 10.0);
+        let distance = measure_move(&straight(offset), GRID, DiagonalRule::Standard);
+        assert_eq!(distance.squares, 2.0);
+    }
+
+    #[test]
+    fn test_drag_path_records_cell_changes() {
+        let mut path = Vec::new();
+        // Small moves within the start cell aren't recorded
+        extend_move_path(&mut path, Vec2::new(20.0, 10.0), GRID);
+        assert!(path.is_empty());
+
+        extend_move_path(&mut path, cells(0.0, 1.0), GRID);
+        extend_move_path(&mut path, cells(0.0, 1.0) + Vec2::new(10.0, 0.0), GRID);
+        extend_move_path(&mut path, cells(1.0, 1.0), GRID);
+        assert_eq!(path, vec![cells(0.0, 1.0), cells(1.0, 1.0)]);
+
+        let start = Vec2::new(35.0, 35.0);
+        assert_eq!(
+            path_points(start, &path, start + cells(1.0, 0.0)),
+            vec![
+                start,
+                start + cells(0.0, 1.0),
+                start + cells(1.0, 1.0),
+                start + cells(1.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exceeds_speed() {
+        let mut active = ActiveMove {
+            start: Vec2::ZERO,
+            end: cells(7.0, 0.0),
+            path: Vec::new(),
+            distance: measure_move(&straight(cells(7.0, 0.0)), GRID, DiagonalRule::Standard),
+            speed_ft: Some(30.0),
+        };
+        assert!(active.exceeds_speed());
+
+        active.speed_ft = None;
+        assert!(!active.exceeds_speed());
+    }
+
+    #[test]
+    fn test_distance_label() {
+        let distance = MoveDistance {
+            squares: 6.0,
+            feet: 30.0,
+        };
+        assert_eq!(distance.label(), "30 ft (6 sq)");
+    }
+}
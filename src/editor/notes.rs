@@ -0,0 +1,183 @@
+//! GM note pins and the Note tool.
+//!
+//! Notes live in [`crate::map::MapNotes`] so they are saved with the map. The
+//! Note tool adds pins, opens them in the notes panel and drags them around.
+//! Pins are painted by the UI rather than rendered in the world, so they never
+//! reach the player view.
+//!
+//! A text annotation whose content is a note's room key (e.g. "A3") links to
+//! that note: it previews on hover and opens with the Note tool.
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use super::annotations::{point_in_text, AnnotationMarker, TextAnnotation};
+use super::camera::EditorCamera;
+use super::history::{EditorCommand, RecordEditorCommand};
+use super::params::{is_cursor_over_ui, CameraWithProjection};
+use crate::map::{MapDirtyState, MapNote, MapNotes};
+
+/// Radius of a note pin on screen, in pixels
+pub const NOTE_PIN_RADIUS: f32 = 10.0;
+
+/// Notes panel and Note tool state
+#[derive(Resource, Default)]
+pub struct NotesState {
+    /// Whether the notes panel is showing
+    pub panel_open: bool,
+    /// Note shown in the panel
+    pub open_note: Option<u32>,
+    /// Show the open note as editable text instead of rendered Markdown
+    pub editing: bool,
+    /// Focus the title field once (set for freshly added notes)
+    pub focus_title: bool,
+    /// Search text for the notes list
+    pub search: String,
+    /// Center the editor camera here on the next frame
+    pub focus_request: Option<Vec2>,
+    /// The open note as it was before the current run of typing in its
+    /// key, title or body, for recording the edit
+    pub text_before: Option<MapNote>,
+    /// Note being dragged and the pin's offset from the cursor
+    dragging: Option<(u32, Vec2)>,
+}
+
+impl NotesState {
+    /// Show a note in the panel, rendered for reading
+    pub fn open(&mut self, id: u32) {
+        self.panel_open = true;
+        self.open_note = Some(id);
+        self.editing = false;
+    }
+}
+
+/// Note linked from the text annotation under `point`, if any
+pub fn linked_note_at<'a>(
+    notes: &MapNotes,
+    texts: impl IntoIterator<Item = (&'a Transform, &'a TextAnnotation)>,
+    point: Vec2,
+) -> Option<u32> {
+    texts
+        .into_iter()
+        .filter(|(transform, text)| point_in_text(point, transform, text))
+        .find_map(|(_, text)| notes.find_by_key(&text.content))
+        .map(|note| note.id)
+}
+
+/// Note tool: click to add a note or open one, drag to move a pin
+#[allow(clippy::too_many_arguments)]
+pub fn handle_note_tool(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut notes: ResMut<MapNotes>,
+    mut notes_state: ResMut<NotesState>,
+    mut dirty_state: ResMut<MapDirtyState>,
+    camera: CameraWithProjection,
+    mut contexts: EguiContexts,
+    texts: Query<(&Transform, &TextAnnotation), With<AnnotationMarker>>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    if mouse_button.just_released(MouseButton::Left) {
+        notes_state.dragging = None;
+    }
+
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return;
+    };
+
+    if mouse_button.just_pressed(MouseButton::Left) {
+        if is_cursor_over_ui(&mut contexts) {
+            return;
+        }
+
+        let pin_radius = NOTE_PIN_RADIUS * camera.zoom_scale();
+        if let Some(id) = notes.note_at(world_pos, pin_radius) {
+            let offset = notes.get(id).map_or(Vec2::ZERO, |n| n.position - world_pos);
+            notes_state.open(id);
+            notes_state.dragging = Some((id, offset));
+        } else if let Some(id) = linked_note_at(&notes, texts.iter(), world_pos) {
+            notes_state.open(id);
+        } else {
+            let old_notes = notes.clone();
+            let id = notes.add(world_pos);
+            notes_state.open(id);
+            notes_state.editing = true;
+            notes_state.focus_title = true;
+            dirty_state.is_dirty = true;
+            history_writer.write(RecordEditorCommand {
+                command: EditorCommand::EditNotes {
+                    old: old_notes,
+                    new: notes.clone(),
+                },
+            });
+        }
+        return;
+    }
+
+    if let Some((id, offset)) = notes_state.dragging
+        && mouse_button.pressed(MouseButton::Left)
+        && notes.get(id).is_some_and(|note| note.position != world_pos + offset)
+    {
+        let old_notes = notes.clone();
+        if let Some(note) = notes.get_mut(id) {
+            note.position = world_pos + offset;
+        }
+        dirty_state.is_dirty = true;
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditNotes {
+                old: old_notes,
+                new: notes.clone(),
+            },
+        });
+    }
+}
+
+/// Center the editor camera on a note picked from the notes list
+pub fn focus_note_camera(
+    mut notes_state: ResMut<NotesState>,
+    mut camera_query: Query<&mut Transform, With<EditorCamera>>,
+) {
+    let Some(target) = notes_state.focus_request.take() else {
+        return;
+    };
+    if let Ok(mut transform) = camera_query.single_mut() {
+        transform.translation.x = target.x;
+        transform.translation.y = target.y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linked_note_at() {
+        let mut notes = MapNotes::default();
+        let id = notes.add(Vec2::new(500.0, 500.0));
+        notes.get_mut(id).unwrap().key = "A3".to_string();
+
+        let transform = Transform::from_xyz(0.0, 0.0, 0.0);
+        let label = TextAnnotation {
+            content: "A3".to_string(),
+            font_size: 24.0,
+            color: Color::WHITE,
+            background: None,
+        };
+        let other = TextAnnotation {
+            content: "Beware".to_string(),
+            ..label.clone()
+        };
+
+        assert_eq!(
+            linked_note_at(&notes, [(&transform, &label)], Vec2::ZERO),
+            Some(id)
+        );
+        assert_eq!(
+            linked_note_at(&notes, [(&transform, &other)], Vec2::ZERO),
+            None
+        );
+        assert_eq!(
+            linked_note_at(&notes, [(&transform, &label)], Vec2::new(300.0, 0.0)),
+            None
+        );
+    }
+}
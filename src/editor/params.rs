@@ -9,8 +9,9 @@
 //!
 //! - [`CameraParams`]: Basic camera and window access for cursor-to-world conversion
 //! - [`CameraWithProjection`]: Extended camera access including zoom scale
-//! - [`AnnotationQueries`]: Read-only access to all annotation types (paths, lines, text)
+//! - [`AnnotationQueries`]: Read-only access to all annotation types (paths, lines, shapes, text)
 //! - [`SelectedAnnotationQueries`]: Access to selected annotations only
+//! - [`AnnotationQueriesMut`]: Mutable access to annotation geometry (for dragging)
 //!
 //! ## Helper Functions
 //!
@@ -21,9 +22,11 @@ use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::EguiContexts;
 
-use crate::map::Selected;
+use crate::map::{PlacedItem, Selected};
 
-use super::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
+use super::annotations::{
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, ShowToPlayers, TextAnnotation,
+};
 use super::EditorCamera;
 
 /// Bundled camera and window queries for cursor-to-world calculations
@@ -81,6 +84,7 @@ impl CameraWithProjection<'_, '_> {
 pub struct AnnotationQueries<'w, 's> {
     pub paths: Query<'w, 's, (Entity, &'static DrawnPath), With<AnnotationMarker>>,
     pub lines: Query<'w, 's, (Entity, &'static DrawnLine), With<AnnotationMarker>>,
+    pub shapes: Query<'w, 's, (Entity, &'static DrawnShape), With<AnnotationMarker>>,
     pub texts:
         Query<'w, 's, (Entity, &'static Transform, &'static TextAnnotation), With<AnnotationMarker>>,
 }
@@ -91,12 +95,37 @@ pub struct AnnotationQueries<'w, 's> {
 pub struct SelectedAnnotationQueries<'w, 's> {
     pub paths: Query<'w, 's, (Entity, &'static DrawnPath), (With<Selected>, With<AnnotationMarker>)>,
     pub lines: Query<'w, 's, (Entity, &'static DrawnLine), (With<Selected>, With<AnnotationMarker>)>,
+    pub shapes: Query<
+        'w,
+        's,
+        (Entity, &'static DrawnShape),
+        (With<Selected>, With<AnnotationMarker>),
+    >,
     pub texts: Query<
         'w,
         's,
         (Entity, &'static Transform, &'static TextAnnotation),
         (With<Selected>, With<AnnotationMarker>),
     >,
+    /// Annotations that are also drawn in the player view
+    pub player_visible: Query<'w, 's, (), With<ShowToPlayers>>,
+}
+
+/// Bundled mutable annotation queries for moving and resizing annotations
+#[derive(SystemParam)]
+#[allow(clippy::type_complexity)]
+pub struct AnnotationQueriesMut<'w, 's> {
+    pub paths: Query<'w, 's, &'static mut DrawnPath, With<AnnotationMarker>>,
+    pub lines: Query<'w, 's, &'static mut DrawnLine, With<AnnotationMarker>>,
+    pub shapes: Query<'w, 's, &'static mut DrawnShape, With<AnnotationMarker>>,
+    pub texts: Query<
+        'w,
+        's,
+        (&'static mut Transform, &'static mut TextAnnotation),
+        (With<AnnotationMarker>, Without<PlacedItem>),
+    >,
+    /// Whether each annotation is shown to players, for recording edits
+    pub shown: Query<'w, 's, Has<ShowToPlayers>, With<AnnotationMarker>>,
 }
 
 /// Check if the cursor is over egui UI
@@ -2,13 +2,14 @@ use bevy::camera::visibility::RenderLayers;
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
-use crate::assets::SelectedAsset;
-use crate::map::{MapData, PlacedItem};
+use crate::assets::{get_image_dimensions, AssetLibrary, LibraryAsset, SelectedAsset};
+use crate::map::{CreatureSize, Layer, MapData, PlacedItem, TokenData};
 
+use super::grid::{snap_point, snap_to_footprint};
 use super::history::{EditorCommand, PlacedItemData, RecordEditorCommand, TransformData};
-use super::params::{is_cursor_over_ui, CameraParams};
+use super::params::{is_cursor_over_ui, CameraWithProjection};
+use super::snapping::SnapParams;
 use super::tools::{CurrentTool, EditorTool, SelectedLayer};
-use super::GridSettings;
 
 #[allow(clippy::too_many_arguments)]
 pub fn handle_placement(
@@ -18,10 +19,11 @@ pub fn handle_placement(
     current_tool: Res<CurrentTool>,
     selected_layer: Res<SelectedLayer>,
     selected_asset: Res<SelectedAsset>,
-    grid_settings: Res<GridSettings>,
+    asset_library: Res<AssetLibrary>,
     map_data: Res<MapData>,
     asset_server: Res<AssetServer>,
-    camera: CameraParams,
+    camera: CameraWithProjection,
+    mut snap: SnapParams,
     mut contexts: EguiContexts,
     mut history_writer: MessageWriter<RecordEditorCommand>,
 ) {
@@ -29,11 +31,7 @@ pub fn handle_placement(
         return;
     }
 
-    if !mouse_button.just_pressed(MouseButton::Left) {
-        return;
-    }
-
-    // Don't place if clicking on UI
+    // Don't place (or show guides) while over UI
     if is_cursor_over_ui(&mut contexts) {
         return;
     }
@@ -49,15 +47,51 @@ pub fn handle_placement(
     // Snap to grid unless Shift is held (either Shift key)
     let shift_held =
         keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-    let snap_enabled = grid_settings.snap_enabled && !shift_held;
-    let final_pos = super::grid::snap_to_grid(world_pos, map_data.grid_size, snap_enabled);
+    let snap_enabled = snap.settings.snap_enabled && !shift_held;
+
+    // Creature-sized assets placed as tokens are fitted to their footprint
+    // and aligned with it
+    let creature_size = token_size(&asset_library, asset, selected_layer.layer);
+    let texture: Handle<Image> = asset_server.load(&asset.relative_path);
+    let (final_pos, scale) = match creature_size {
+        Some(size) => (
+            snap_to_footprint(
+                world_pos,
+                map_data.grid_size,
+                size.footprint_cells(),
+                snap_enabled,
+            ),
+            creature_fit_scale(size, asset, None, map_data.grid_size),
+        ),
+        None => {
+            let mut pos = if snap_enabled {
+                snap_point(world_pos, map_data.grid_size, snap.settings.snap_mode)
+            } else {
+                world_pos
+            };
+            // Line up with nearby items once the texture's size is known
+            let loaded_size = snap.images.get(&texture).map(|image| image.size().as_vec2());
+            if !shift_held && let Some(size) = loaded_size {
+                let half = size / 2.0;
+                pos += snap.snap_to_guides((pos - half, pos + half), camera.zoom_scale());
+            }
+            (pos, Vec3::ONE)
+        }
+    };
+
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let token = creature_size.map(|size| TokenData {
+        size: Some(size),
+        ..default()
+    });
 
     // Use the selected layer instead of deriving from asset category
     let layer = selected_layer.layer;
     let z = layer.z_base();
 
-    let texture: Handle<Image> = asset_server.load(&asset.relative_path);
-
     // Items on non-player-visible layers (GM, FogOfWar) go to render layer 1 (editor-only)
     let render_layer = if layer.is_player_visible() {
         RenderLayers::layer(0)
@@ -65,19 +99,25 @@ pub fn handle_placement(
         RenderLayers::layer(1)
     };
 
-    let transform = Transform::from_translation(final_pos.extend(z));
-    let entity = commands
-        .spawn((
-            Sprite::from_image(texture),
-            transform,
-            PlacedItem {
-                asset_path: asset.relative_path.clone(),
-                layer,
-                z_index: 0,
-            },
-            render_layer,
-        ))
-        .id();
+    let transform = Transform {
+        translation: final_pos.extend(z),
+        scale,
+        ..default()
+    };
+    let mut entity_commands = commands.spawn((
+        Sprite::from_image(texture),
+        transform,
+        PlacedItem {
+            asset_path: asset.relative_path.clone(),
+            layer,
+            z_index: 0,
+        },
+        render_layer,
+    ));
+    if let Some(token) = &token {
+        entity_commands.insert(token.clone());
+    }
+    let entity = entity_commands.id();
 
     history_writer.write(RecordEditorCommand {
         command: EditorCommand::PlaceItems {
@@ -87,7 +127,37 @@ pub fn handle_placement(
                 layer,
                 z_index: 0,
                 transform: TransformData::from(&transform),
+                token,
             }],
         },
     });
 }
+
+/// Creature size to place an asset with. Only items on the Token layer become
+/// tokens; on other layers a creature-sized asset is placed like any other.
+pub(super) fn token_size(
+    asset_library: &AssetLibrary,
+    asset: &LibraryAsset,
+    layer: Layer,
+) -> Option<CreatureSize> {
+    asset_library
+        .creature_size(asset)
+        .filter(|_| layer == Layer::Token)
+}
+
+/// Uniform scale that fits a creature-sized asset to its footprint. Reads the
+/// image header when the texture hasn't finished loading.
+pub(super) fn creature_fit_scale(
+    size: CreatureSize,
+    asset: &LibraryAsset,
+    loaded_size: Option<Vec2>,
+    grid_size: f32,
+) -> Vec3 {
+    let image_size = loaded_size.or_else(|| {
+        get_image_dimensions(&asset.full_path).map(|(w, h)| Vec2::new(w as f32, h as f32))
+    });
+    let scale = image_size
+        .and_then(|image_size| size.fit_scale(image_size, grid_size))
+        .unwrap_or(1.0);
+    Vec3::new(scale, scale, 1.0)
+}
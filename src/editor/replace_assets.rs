@@ -0,0 +1,399 @@
+//! Find and replace of placed assets.
+//!
+//! Every item showing one asset is switched to another, keeping its entity,
+//! position, rotation, layer and token data. The scale can be adjusted so the
+//! new asset fits the old one's footprint, optionally stretched to fill it
+//! exactly. On the open map the
+//! swap is a single `ReplaceAssets` undo step; maps open in other tabs are
+//! updated in memory, and map files in the library's `maps/` folder can be
+//! rewritten as well (which can't be undone). A rewritten map's saved history
+//! is pointed at the new asset too, so undo there doesn't bring the old one
+//! back.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::map::{history_path, AssetManifest, OpenMaps, PlacedItem, SavedMap};
+
+use super::history::{
+    set_item_asset, EditorCommand, ItemAssetData, RecordEditorCommand, SavedHistory,
+};
+
+/// Message to replace every placed instance of an asset on the open maps.
+/// Paths are Bevy asset paths, as stored on placed items.
+#[derive(Message)]
+pub struct ReplaceAssetsRequest {
+    pub from: String,
+    pub to: String,
+    /// Multiplier for each item's scale, to keep the old footprint
+    pub rescale: Option<Vec2>,
+    /// Also replace in maps open in other tabs
+    pub all_maps: bool,
+}
+
+/// Scale multiplier that fits an image of `to_size` inside the footprint of
+/// one of `from_size`, keeping its aspect ratio unless `stretch` is set, if
+/// both sizes are known
+pub fn footprint_rescale(
+    from_size: Option<(u32, u32)>,
+    to_size: Option<(u32, u32)>,
+    stretch: bool,
+) -> Option<Vec2> {
+    let (from_w, from_h) = from_size?;
+    let (to_w, to_h) = to_size?;
+    if to_w == 0 || to_h == 0 {
+        return None;
+    }
+    let ratio = Vec2::new(from_w as f32 / to_w as f32, from_h as f32 / to_h as f32);
+    if stretch {
+        Some(ratio)
+    } else {
+        Some(Vec2::splat(ratio.min_element()))
+    }
+}
+
+/// Replace `from` with `to` in a saved map, returning how many items changed
+pub fn replace_in_saved_map(
+    map: &mut SavedMap,
+    from: &str,
+    to: &str,
+    rescale: Option<Vec2>,
+) -> usize {
+    let mut replaced = 0;
+    for item in map
+        .placed_items
+        .iter_mut()
+        .filter(|item| item.asset_path == from)
+    {
+        item.asset_path = to.to_string();
+        if let Some(rescale) = rescale {
+            item.scale *= rescale;
+        }
+        replaced += 1;
+    }
+    if replaced > 0 {
+        map.asset_manifest = AssetManifest::from_items(map.placed_items.iter());
+    }
+    replaced
+}
+
+/// Map files in `maps_dir` other than `skip`, with their display names
+fn map_files(maps_dir: &Path, skip: Option<&Path>) -> Vec<(String, std::path::PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(maps_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter(|path| Some(path.as_path()) != skip)
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .and_then(|n| n.to_str())
+                .unwrap_or("map")
+                .to_string();
+            (name, path)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn read_map_file(path: &Path) -> Result<SavedMap, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read map file {:?}: {}", path, e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse map file {:?}: {}", path, e))
+}
+
+/// How many items use `asset` (a library-relative path) in each map file,
+/// leaving out maps that don't use it and unreadable files
+pub fn count_in_map_files(
+    maps_dir: &Path,
+    asset: &str,
+    skip: Option<&Path>,
+) -> Vec<(String, usize)> {
+    map_files(maps_dir, skip)
+        .into_iter()
+        .filter_map(|(name, path)| {
+            let map = read_map_file(&path).ok()?;
+            let count = map
+                .placed_items
+                .iter()
+                .filter(|item| item.asset_path == asset)
+                .count();
+            (count > 0).then_some((name, count))
+        })
+        .collect()
+}
+
+/// Outcome of replacing an asset in the map files
+#[derive(Debug, Default)]
+pub struct MapFilesReplaced {
+    /// Names of the maps that were rewritten
+    pub updated: Vec<String>,
+    /// Items changed across those maps
+    pub items: usize,
+    /// Maps that couldn't be read or written, with the error
+    pub failed: Vec<(String, String)>,
+}
+
+/// Replace `from` with `to` (library-relative paths) in every map file in
+/// `maps_dir` except `skip`. A map that can't be read or written is skipped,
+/// so one bad file doesn't stop the rest from being updated.
+pub fn replace_in_map_files(
+    maps_dir: &Path,
+    from: &str,
+    to: &str,
+    rescale: Option<Vec2>,
+    skip: Option<&Path>,
+) -> MapFilesReplaced {
+    let mut outcome = MapFilesReplaced::default();
+    for (name, path) in map_files(maps_dir, skip) {
+        let result = read_map_file(&path).and_then(|mut map| {
+            let replaced = replace_in_saved_map(&mut map, from, to, rescale);
+            if replaced > 0 {
+                let json = serde_json::to_string_pretty(&map)
+                    .map_err(|e| format!("Failed to serialize map {:?}: {}", path, e))?;
+                std::fs::write(&path, json)
+                    .map_err(|e| format!("Failed to update map file {:?}: {}", path, e))?;
+            }
+            Ok(replaced)
+        });
+        match result {
+            Ok(0) => {}
+            Ok(replaced) => {
+                info!("Replaced {} item(s) in map: {:?}", replaced, path);
+                replace_in_history_file(&path, from, to);
+                outcome.updated.push(name);
+                outcome.items += replaced;
+            }
+            Err(e) => {
+                warn!("Skipping map: {}", e);
+                outcome.failed.push((name, e));
+            }
+        }
+    }
+    outcome
+}
+
+/// Point the history saved next to a rewritten map at the new asset. The map
+/// is already written, so failures only warn.
+fn replace_in_history_file(map_path: &Path, from: &str, to: &str) {
+    let path = history_path(map_path);
+    let Ok(json) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    let result = serde_json::from_str::<SavedHistory>(&json)
+        .and_then(|mut history| {
+            history.map_asset_paths(|path| (path == from).then(|| to.to_string()));
+            serde_json::to_string(&history)
+        })
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Failed to update history file {:?}: {}", path, e);
+    }
+}
+
+/// Replace the asset on the open map (one undo step) and, if asked, in maps
+/// open in other tabs
+pub fn handle_replace_assets(
+    mut commands: Commands,
+    mut events: MessageReader<ReplaceAssetsRequest>,
+    asset_server: Res<AssetServer>,
+    items_query: Query<(Entity, &Transform, &PlacedItem)>,
+    mut open_maps: ResMut<OpenMaps>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    for event in events.read() {
+        let rescale = event.rescale.unwrap_or(Vec2::ONE).extend(1.0);
+        let mut swaps = Vec::new();
+        for (entity, transform, item) in items_query.iter() {
+            if item.asset_path != event.from {
+                continue;
+            }
+            let old_asset = ItemAssetData {
+                asset_path: item.asset_path.clone(),
+                scale: transform.scale,
+            };
+            let new_asset = ItemAssetData {
+                asset_path: event.to.clone(),
+                scale: transform.scale * rescale,
+            };
+            set_item_asset(
+                &mut commands,
+                &asset_server,
+                entity,
+                transform,
+                item,
+                &new_asset,
+            );
+            swaps.push((entity, old_asset, new_asset));
+        }
+        if !swaps.is_empty() {
+            history_writer.write(RecordEditorCommand {
+                command: EditorCommand::ReplaceAssets { items: swaps },
+            });
+        }
+
+        if !event.all_maps {
+            continue;
+        }
+        let active_id = open_maps.active_map_id;
+        for open_map in open_maps.maps.values_mut() {
+            if Some(open_map.id) == active_id {
+                continue;
+            }
+            let Some(saved) = open_map.saved_state.as_mut() else {
+                continue;
+            };
+            if replace_in_saved_map(saved, &event.from, &event.to, event.rescale) > 0 {
+                open_map.is_dirty = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::history::{PlacedItemData, TransformData};
+    use crate::map::{Layer, MapData, SavedPlacedItem};
+
+    fn saved_item(asset_path: &str) -> SavedPlacedItem {
+        SavedPlacedItem {
+            asset_path: asset_path.to_string(),
+            position: Vec2::new(10.0, 20.0),
+            rotation: 0.5,
+            scale: Vec2::new(-1.0, 2.0),
+            layer: Layer::Doodad,
+            z_index: 3,
+            token: None,
+        }
+    }
+
+    fn saved_map(items: Vec<SavedPlacedItem>) -> SavedMap {
+        SavedMap {
+            asset_manifest: AssetManifest::from_items(items.iter()),
+            map_data: MapData::default(),
+            placed_items: items,
+            annotations: Default::default(),
+            fog_of_war: Default::default(),
+            initiative: Default::default(),
+            notes: Vec::new(),
+            camera_bookmarks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_footprint_rescale() {
+        assert_eq!(
+            footprint_rescale(Some((70, 140)), Some((140, 70)), false),
+            Some(Vec2::splat(0.5))
+        );
+        assert_eq!(
+            footprint_rescale(Some((70, 140)), Some((140, 70)), true),
+            Some(Vec2::new(0.5, 2.0))
+        );
+        assert_eq!(footprint_rescale(None, Some((70, 70)), false), None);
+        assert_eq!(footprint_rescale(Some((70, 70)), Some((0, 70)), false), None);
+    }
+
+    #[test]
+    fn test_footprint_rescale_non_square_to_square() {
+        // A 2x1 wall swapped for a square tile fits its shorter side and
+        // keeps the tile square
+        let rescale = footprint_rescale(Some((280, 140)), Some((70, 70)), false).unwrap();
+        assert_eq!(rescale, Vec2::splat(2.0));
+        assert_eq!(rescale.x, rescale.y);
+
+        // Stretching fills the whole footprint instead
+        assert_eq!(
+            footprint_rescale(Some((280, 140)), Some((70, 70)), true),
+            Some(Vec2::new(4.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn test_replace_in_saved_map_keeps_transforms() {
+        let mut map = saved_map(vec![
+            saved_item("old.png"),
+            saved_item("other.png"),
+            saved_item("old.png"),
+        ]);
+
+        let replaced = replace_in_saved_map(&mut map, "old.png", "new.png", Some(Vec2::splat(0.5)));
+        assert_eq!(replaced, 2);
+
+        let item = &map.placed_items[0];
+        assert_eq!(item.asset_path, "new.png");
+        assert_eq!(item.position, Vec2::new(10.0, 20.0));
+        assert_eq!(item.rotation, 0.5);
+        // Flips survive the rescale
+        assert_eq!(item.scale, Vec2::new(-0.5, 1.0));
+        assert_eq!(map.placed_items[1].asset_path, "other.png");
+        assert_eq!(map.asset_manifest.assets, vec!["new.png", "other.png"]);
+
+        assert_eq!(
+            replace_in_saved_map(&mut map, "old.png", "new.png", None),
+            0
+        );
+    }
+
+    #[test]
+    fn test_replace_in_map_files_skips_corrupt_maps() {
+        let maps_dir = std::env::temp_dir()
+            .join(format!("rustforged_replace_assets_{}", std::process::id()));
+        std::fs::create_dir_all(&maps_dir).unwrap();
+        let write_map = |name: &str, map: &SavedMap| {
+            let json = serde_json::to_string(map).unwrap();
+            std::fs::write(maps_dir.join(name), json).unwrap();
+        };
+        write_map("a_cave.json", &saved_map(vec![saved_item("old.png")]));
+        std::fs::write(maps_dir.join("b_broken.json"), "{ not a map").unwrap();
+        write_map(
+            "c_keep.json",
+            &saved_map(vec![saved_item("old.png"), saved_item("old.png")]),
+        );
+        write_map("d_other.json", &saved_map(vec![saved_item("other.png")]));
+
+        let deleted = PlacedItemData {
+            entity: Entity::from_raw_u32(0).unwrap(),
+            asset_path: "old.png".to_string(),
+            layer: Layer::Doodad,
+            z_index: 0,
+            transform: TransformData::from(&Transform::IDENTITY),
+            token: None,
+        };
+        let history = SavedHistory {
+            undo_stack: vec![EditorCommand::DeleteItems {
+                items: vec![deleted],
+            }],
+            ..default()
+        };
+        let history_file = history_path(&maps_dir.join("a_cave.json"));
+        std::fs::write(&history_file, serde_json::to_string(&history).unwrap()).unwrap();
+
+        let outcome = replace_in_map_files(&maps_dir, "old.png", "new.png", None, None);
+        assert_eq!(outcome.updated, vec!["a_cave", "c_keep"]);
+        assert_eq!(outcome.items, 3);
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.failed[0].0, "b_broken");
+
+        // Maps after the corrupt one were still rewritten
+        let keep = read_map_file(&maps_dir.join("c_keep.json")).unwrap();
+        assert!(keep.placed_items.iter().all(|i| i.asset_path == "new.png"));
+
+        // So was the history saved next to the first map
+        let json = std::fs::read_to_string(&history_file).unwrap();
+        let history: SavedHistory = serde_json::from_str(&json).unwrap();
+        let EditorCommand::DeleteItems { items } = &history.undo_stack[0] else {
+            panic!("expected DeleteItems");
+        };
+        assert_eq!(items[0].asset_path, "new.png");
+
+        std::fs::remove_dir_all(&maps_dir).unwrap();
+    }
+}
@@ -0,0 +1,251 @@
+//! Align and distribute commands for multi-selections.
+//!
+//! Items are lined up by their rotated bounds, so a turned wall segment aligns
+//! by the edge you can see rather than its unrotated sprite. Each command moves
+//! the whole selection as one `MoveItems` undo step.
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::editor::history::{RecordEditorCommand, TransformData};
+use crate::keybindings::{Action, ActionInput};
+use crate::map::{PlacedItem, Selected};
+
+use super::hit_detection::rotated_item_bounds;
+use super::shortcuts::record_moves;
+
+/// A way of lining up the selected items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignOp {
+    Left,
+    /// Centers on one vertical line
+    Center,
+    Right,
+    Top,
+    /// Centers on one horizontal line
+    Middle,
+    Bottom,
+    /// Even gaps between items, left to right
+    DistributeHorizontally,
+    /// Even gaps between items, bottom to top
+    DistributeVertically,
+}
+
+impl AlignOp {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AlignOp::Left => "Left",
+            AlignOp::Center => "Center",
+            AlignOp::Right => "Right",
+            AlignOp::Top => "Top",
+            AlignOp::Middle => "Middle",
+            AlignOp::Bottom => "Bottom",
+            AlignOp::DistributeHorizontally => "Distribute Horizontally",
+            AlignOp::DistributeVertically => "Distribute Vertically",
+        }
+    }
+
+    /// The keyboard action for this op
+    pub fn action(&self) -> Action {
+        match self {
+            AlignOp::Left => Action::AlignLeft,
+            AlignOp::Center => Action::AlignCenter,
+            AlignOp::Right => Action::AlignRight,
+            AlignOp::Top => Action::AlignTop,
+            AlignOp::Middle => Action::AlignMiddle,
+            AlignOp::Bottom => Action::AlignBottom,
+            AlignOp::DistributeHorizontally => Action::DistributeHorizontally,
+            AlignOp::DistributeVertically => Action::DistributeVertically,
+        }
+    }
+
+    pub fn all() -> &'static [AlignOp] {
+        &[
+            AlignOp::Left,
+            AlignOp::Center,
+            AlignOp::Right,
+            AlignOp::Top,
+            AlignOp::Middle,
+            AlignOp::Bottom,
+            AlignOp::DistributeHorizontally,
+            AlignOp::DistributeVertically,
+        ]
+    }
+
+    /// Distributing needs a first and last item that stay put plus one
+    /// between them
+    pub fn min_items(&self) -> usize {
+        match self {
+            AlignOp::DistributeHorizontally | AlignOp::DistributeVertically => 3,
+            _ => 2,
+        }
+    }
+}
+
+/// Message to align or distribute the selected items
+#[derive(Message)]
+pub struct AlignSelectionRequest {
+    pub op: AlignOp,
+}
+
+/// How far to move each item, given the (min, max) bounds of every item
+pub fn align_offsets(bounds: &[(Vec2, Vec2)], op: AlignOp) -> Vec<Vec2> {
+    if bounds.len() < op.min_items() {
+        return vec![Vec2::ZERO; bounds.len()];
+    }
+
+    let (sel_min, sel_max) = bounds.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), (item_min, item_max)| (min.min(*item_min), max.max(*item_max)),
+    );
+    let sel_center = (sel_min + sel_max) / 2.0;
+
+    match op {
+        AlignOp::Left => bounds
+            .iter()
+            .map(|(min, _)| Vec2::new(sel_min.x - min.x, 0.0))
+            .collect(),
+        AlignOp::Right => bounds
+            .iter()
+            .map(|(_, max)| Vec2::new(sel_max.x - max.x, 0.0))
+            .collect(),
+        AlignOp::Center => bounds
+            .iter()
+            .map(|(min, max)| Vec2::new(sel_center.x - (min.x + max.x) / 2.0, 0.0))
+            .collect(),
+        AlignOp::Top => bounds
+            .iter()
+            .map(|(_, max)| Vec2::new(0.0, sel_max.y - max.y))
+            .collect(),
+        AlignOp::Bottom => bounds
+            .iter()
+            .map(|(min, _)| Vec2::new(0.0, sel_min.y - min.y))
+            .collect(),
+        AlignOp::Middle => bounds
+            .iter()
+            .map(|(min, max)| Vec2::new(0.0, sel_center.y - (min.y + max.y) / 2.0))
+            .collect(),
+        AlignOp::DistributeHorizontally => distribute(bounds, Vec2::X),
+        AlignOp::DistributeVertically => distribute(bounds, Vec2::Y),
+    }
+}
+
+/// Space items evenly along `axis`, keeping the first and last in place
+fn distribute(bounds: &[(Vec2, Vec2)], axis: Vec2) -> Vec<Vec2> {
+    let along = |v: Vec2| v.dot(axis);
+    let mut order: Vec<usize> = (0..bounds.len()).collect();
+    order.sort_by(|a, b| {
+        let center = |i: usize| along(bounds[i].0 + bounds[i].1);
+        center(*a).total_cmp(&center(*b))
+    });
+
+    let first = bounds[order[0]];
+    let last = bounds[order[order.len() - 1]];
+    let total_size: f32 = bounds.iter().map(|(min, max)| along(*max - *min)).sum();
+    let gap = (along(last.1) - along(first.0) - total_size) / (bounds.len() - 1) as f32;
+
+    let mut offsets = vec![Vec2::ZERO; bounds.len()];
+    let mut cursor = along(first.1) + gap;
+    for &index in &order[1..order.len() - 1] {
+        let (min, max) = bounds[index];
+        offsets[index] = axis * (cursor - along(min));
+        cursor += along(max - min) + gap;
+    }
+    offsets
+}
+
+/// Send align requests for the align and distribute shortcuts
+pub fn handle_align_shortcuts(
+    keys: ActionInput,
+    mut contexts: EguiContexts,
+    mut align_writer: MessageWriter<AlignSelectionRequest>,
+) {
+    // Don't trigger if typing in UI
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_keyboard_input()
+    {
+        return;
+    }
+
+    if let Some(op) = AlignOp::all()
+        .iter()
+        .copied()
+        .find(|op| keys.just_pressed(op.action()))
+    {
+        align_writer.write(AlignSelectionRequest { op });
+    }
+}
+
+/// Move the selected items for each align request
+#[allow(clippy::type_complexity)]
+pub fn handle_align_selection(
+    mut events: MessageReader<AlignSelectionRequest>,
+    mut selected_query: Query<
+        (Entity, &mut Transform, &Sprite),
+        (With<Selected>, With<PlacedItem>),
+    >,
+    images: Res<Assets<Image>>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) {
+    for event in events.read() {
+        let bounds: Vec<(Vec2, Vec2)> = selected_query
+            .iter()
+            .map(|(_, transform, sprite)| rotated_item_bounds(transform, sprite, &images))
+            .collect();
+        let offsets = align_offsets(&bounds, event.op);
+
+        let mut moves = Vec::new();
+        for ((entity, mut transform, _), offset) in selected_query.iter_mut().zip(offsets) {
+            let old = TransformData::from(&*transform);
+            transform.translation += offset.extend(0.0);
+            moves.push((entity, old, TransformData::from(&*transform)));
+        }
+        record_moves(&mut history_writer, moves);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> (Vec2, Vec2) {
+        (Vec2::new(x, y), Vec2::new(x + size, y + size))
+    }
+
+    #[test]
+    fn test_align_edges_and_centers() {
+        let bounds = [square(0.0, 0.0, 10.0), square(30.0, 50.0, 20.0)];
+
+        let left = align_offsets(&bounds, AlignOp::Left);
+        assert_eq!(left, vec![Vec2::ZERO, Vec2::new(-30.0, 0.0)]);
+
+        let top = align_offsets(&bounds, AlignOp::Top);
+        assert_eq!(top, vec![Vec2::new(0.0, 60.0), Vec2::ZERO]);
+
+        // Selection spans x 0..50, so both centers move to 25
+        let center = align_offsets(&bounds, AlignOp::Center);
+        assert_eq!(center, vec![Vec2::new(20.0, 0.0), Vec2::new(-15.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_distribute_evens_gaps() {
+        // Widths 10, 20, 10 across 0..100 leave 60 of space: gaps of 30
+        let bounds = [
+            square(90.0, 0.0, 10.0),
+            square(0.0, 0.0, 10.0),
+            square(15.0, 0.0, 20.0),
+        ];
+        let offsets = align_offsets(&bounds, AlignOp::DistributeHorizontally);
+        assert_eq!(offsets[0], Vec2::ZERO);
+        assert_eq!(offsets[1], Vec2::ZERO);
+        assert_eq!(offsets[2], Vec2::new(25.0, 0.0));
+    }
+
+    #[test]
+    fn test_too_few_items_stay_put() {
+        let bounds = [square(0.0, 0.0, 10.0), square(40.0, 0.0, 10.0)];
+        let offsets = align_offsets(&bounds, AlignOp::DistributeVertically);
+        assert!(offsets.iter().all(|offset| *offset == Vec2::ZERO));
+        assert_eq!(align_offsets(&bounds[..1], AlignOp::Left), vec![Vec2::ZERO]);
+    }
+}
@@ -1,18 +1,74 @@
-//! Box selection handling.
+//! Box and lasso selection handling.
 
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
 use crate::editor::annotations::{
-    is_annotation_layer_locked, is_annotation_layer_visible, line_overlaps_rect, path_overlaps_rect,
-    text_overlaps_rect,
+    line_bounds, path_bounds, point_in_polygon, shape_bounds, text_bounds,
 };
 use crate::editor::params::{is_cursor_over_ui, AnnotationQueries, CameraParams};
 use crate::editor::tools::{CurrentTool, EditorTool};
-use crate::map::{MapData, PlacedItem, Selected};
+use crate::map::{Layer, MapData, PlacedItem, Selected};
 
-use super::hit_detection::item_overlaps_rect;
-use super::BoxSelectState;
+use super::hit_detection::item_bounds;
+use super::{BoxSelectSettings, BoxSelectState};
+
+/// Minimum distance between recorded lasso points (in world units)
+const LASSO_POINT_SPACING: f32 = 4.0;
+
+/// The area dragged out on empty space
+#[derive(Debug, Clone, PartialEq)]
+enum SelectRegion {
+    Rect { min: Vec2, max: Vec2 },
+    Lasso(Vec<Vec2>),
+}
+
+impl SelectRegion {
+    /// A rectangle picks anything it touches; a lasso picks what has its
+    /// center inside, so a loose loop doesn't grab the neighbours
+    fn picks(&self, (min, max): (Vec2, Vec2)) -> bool {
+        match self {
+            SelectRegion::Rect {
+                min: rect_min,
+                max: rect_max,
+            } => {
+                rect_min.x < max.x && rect_max.x > min.x && rect_min.y < max.y && rect_max.y > min.y
+            }
+            SelectRegion::Lasso(points) => point_in_polygon((min + max) / 2.0, points),
+        }
+    }
+}
+
+/// Whether items on `layer` can be selected (the layer is visible and unlocked)
+pub(super) fn layer_selectable(map_data: &MapData, layer: Layer) -> bool {
+    map_data
+        .layers
+        .iter()
+        .find(|ld| ld.layer_type == layer)
+        .map(|ld| ld.visible && !ld.locked)
+        .unwrap_or(true)
+}
+
+/// Bounds of every annotation
+pub(super) fn annotation_bounds(annotations: &AnnotationQueries) -> Vec<(Entity, (Vec2, Vec2))> {
+    let paths = annotations
+        .paths
+        .iter()
+        .map(|(entity, path)| (entity, path_bounds(path)));
+    let lines = annotations
+        .lines
+        .iter()
+        .map(|(entity, line)| (entity, line_bounds(line)));
+    let shapes = annotations
+        .shapes
+        .iter()
+        .map(|(entity, shape)| (entity, shape_bounds(shape)));
+    let texts = annotations
+        .texts
+        .iter()
+        .map(|(entity, transform, text)| (entity, text_bounds(transform, text)));
+    paths.chain(lines).chain(shapes).chain(texts).collect()
+}
 
 #[allow(clippy::too_many_arguments)]
 pub fn handle_box_select(
@@ -24,6 +80,7 @@ pub fn handle_box_select(
     items_query: Query<(Entity, &Transform, &Sprite, &PlacedItem)>,
     selected_query: Query<Entity, With<Selected>>,
     mut box_select_state: ResMut<BoxSelectState>,
+    settings: Res<BoxSelectSettings>,
     mut contexts: EguiContexts,
     images: Res<Assets<Image>>,
     map_data: Res<MapData>,
@@ -49,20 +106,46 @@ pub fn handle_box_select(
 
     // Update current position
     box_select_state.current_world = world_pos;
+    if settings.lasso
+        && box_select_state
+            .lasso_points
+            .last()
+            .is_none_or(|last| last.distance(world_pos) >= LASSO_POINT_SPACING)
+    {
+        box_select_state.lasso_points.push(world_pos);
+    }
 
-    // On release, select all items in the box
+    // On release, select everything in the box or lasso
     if mouse_button.just_released(MouseButton::Left) {
         box_select_state.is_selecting = false;
-
-        let rect_min = box_select_state.start_world.min(box_select_state.current_world);
-        let rect_max = box_select_state.start_world.max(box_select_state.current_world);
-
-        // Only process if we dragged a meaningful distance (not just a click)
-        let drag_distance =
-            (box_select_state.current_world - box_select_state.start_world).length();
-        if drag_distance < 5.0 {
-            return;
-        }
+        let lasso_points = std::mem::take(&mut box_select_state.lasso_points);
+
+        let region = if settings.lasso {
+            let (min, max) = lasso_points.iter().fold(
+                (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                |(min, max), p| (min.min(*p), max.max(*p)),
+            );
+            // Only process a real loop, not a click
+            if lasso_points.len() < 3 || (max - min).length() < 5.0 {
+                return;
+            }
+            SelectRegion::Lasso(lasso_points)
+        } else {
+            // Only process if we dragged a meaningful distance (not just a click)
+            let drag_distance =
+                (box_select_state.current_world - box_select_state.start_world).length();
+            if drag_distance < 5.0 {
+                return;
+            }
+            SelectRegion::Rect {
+                min: box_select_state
+                    .start_world
+                    .min(box_select_state.current_world),
+                max: box_select_state
+                    .start_world
+                    .max(box_select_state.current_world),
+            }
+        };
 
         let ctrl_held =
             keyboard.pressed(KeyCode::ControlLeft) || keyboard.pressed(KeyCode::ControlRight);
@@ -74,66 +157,78 @@ pub fn handle_box_select(
             }
         }
 
-        // Select all items that overlap with the selection rectangle
-        // Filter by visible and unlocked layers
-        for (entity, transform, sprite, placed_item) in items_query.iter() {
-            // Check if layer is visible and unlocked
-            let layer_selectable = map_data
-                .layers
-                .iter()
-                .find(|ld| ld.layer_type == placed_item.layer)
-                .map(|ld| ld.visible && !ld.locked)
-                .unwrap_or(true);
-
-            if !layer_selectable {
+        // Items on visible, unlocked layers that the filter lets through
+        let selectable =
+            |layer: Layer| settings.filter.allows(layer) && layer_selectable(&map_data, layer);
+        let mut candidates: Vec<(Entity, (Vec2, Vec2))> = items_query
+            .iter()
+            .filter(|(_, _, _, placed_item)| selectable(placed_item.layer))
+            .map(|(entity, transform, sprite, _)| (entity, item_bounds(transform, sprite, &images)))
+            .collect();
+        if selectable(Layer::Annotation) {
+            candidates.extend(annotation_bounds(&annotations));
+        }
+
+        for (entity, bounds) in candidates {
+            if !region.picks(bounds) {
                 continue;
             }
-
-            if item_overlaps_rect(rect_min, rect_max, transform, sprite, &images) {
-                if ctrl_held && selected_query.contains(entity) {
-                    // Ctrl + box select: toggle (deselect if already selected)
-                    commands.entity(entity).remove::<Selected>();
-                } else {
-                    commands.entity(entity).insert(Selected);
-                }
+            if ctrl_held && selected_query.contains(entity) {
+                // Ctrl + box select: toggle (deselect if already selected)
+                commands.entity(entity).remove::<Selected>();
+            } else {
+                commands.entity(entity).insert(Selected);
             }
         }
+    }
+}
 
-        // Select annotations that overlap with the selection rectangle
-        // Only if annotation layer is visible and not locked
-        let annotation_selectable =
-            is_annotation_layer_visible(&map_data) && !is_annotation_layer_locked(&map_data);
-
-        if annotation_selectable {
-            for (entity, path) in annotations.paths.iter() {
-                if path_overlaps_rect(rect_min, rect_max, path) {
-                    if ctrl_held && selected_query.contains(entity) {
-                        commands.entity(entity).remove::<Selected>();
-                    } else {
-                        commands.entity(entity).insert(Selected);
-                    }
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::super::SelectionFilter;
+    use super::*;
+
+    #[test]
+    fn test_rect_picks_anything_it_touches() {
+        let region = SelectRegion::Rect {
+            min: Vec2::ZERO,
+            max: Vec2::splat(10.0),
+        };
+        assert!(region.picks((Vec2::splat(8.0), Vec2::splat(20.0))));
+        assert!(!region.picks((Vec2::splat(11.0), Vec2::splat(20.0))));
+    }
 
-            for (entity, line) in annotations.lines.iter() {
-                if line_overlaps_rect(rect_min, rect_max, line) {
-                    if ctrl_held && selected_query.contains(entity) {
-                        commands.entity(entity).remove::<Selected>();
-                    } else {
-                        commands.entity(entity).insert(Selected);
-                    }
-                }
-            }
+    #[test]
+    fn test_lasso_picks_by_center() {
+        // An L-shaped loop: the notch at the top right is outside
+        let region = SelectRegion::Lasso(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(20.0, 0.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 20.0),
+            Vec2::new(0.0, 20.0),
+        ]);
+        assert!(region.picks((Vec2::new(2.0, 2.0), Vec2::new(8.0, 8.0))));
+        assert!(region.picks((Vec2::new(12.0, 2.0), Vec2::new(18.0, 8.0))));
+        // Overlaps the loop, but its center is in the notch
+        assert!(!region.picks((Vec2::new(8.0, 12.0), Vec2::new(22.0, 18.0))));
+    }
 
-            for (entity, transform, text) in annotations.texts.iter() {
-                if text_overlaps_rect(rect_min, rect_max, transform, text) {
-                    if ctrl_held && selected_query.contains(entity) {
-                        commands.entity(entity).remove::<Selected>();
-                    } else {
-                        commands.entity(entity).insert(Selected);
-                    }
-                }
-            }
-        }
+    #[test]
+    fn test_selection_filter() {
+        let mut filter = SelectionFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.allows(Layer::Token));
+
+        filter.annotations_only();
+        assert!(filter.is_active());
+        assert!(filter.allows(Layer::Annotation));
+        assert!(!filter.allows(Layer::Token));
+
+        filter.set_allowed(Layer::Token, true);
+        assert!(filter.allows(Layer::Token));
+        filter.allow_all();
+        assert!(!filter.is_active());
     }
 }
@@ -3,34 +3,46 @@
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
-use crate::editor::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
-use crate::editor::history::{EditorCommand, RecordEditorCommand, TransformData};
-use crate::editor::params::{is_cursor_over_ui, CameraParams};
+use crate::editor::annotations::{text_half_size, TextAnnotation, TEXT_FONT_SIZE_RANGE};
+use crate::editor::history::{
+    AnnotationData, EditorCommand, LineData, PathData, RecordEditorCommand, ShapeData, TextData,
+    TransformData,
+};
+use crate::editor::movement::{
+    extend_move_path, measure_move, path_points, ActiveMove, MovementSettings, MovementTrail,
+    MovementTrails, TokenMoveMeasurement,
+};
+use crate::editor::grid::snap_point;
+use crate::editor::params::{is_cursor_over_ui, AnnotationQueriesMut, CameraWithProjection};
+use crate::editor::snapping::SnapParams;
 use crate::editor::tools::{CurrentTool, EditorTool};
-use crate::map::{MapData, PlacedItem};
+use crate::map::{Layer, MapData, PlacedItem, Selected, TokenData};
 
-use super::{AnnotationDragData, DragState, SelectionDragMode, ROTATION_SNAP_INCREMENT};
+use super::hit_detection::rotated_item_bounds;
+use super::{AnnotationDragData, DragState, SelectionDragMode};
 
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_drag(
     mouse_button: Res<ButtonInput<MouseButton>>,
     keyboard: Res<ButtonInput<KeyCode>>,
     current_tool: Res<CurrentTool>,
-    camera: CameraParams,
-    mut items_query: Query<&mut Transform, With<PlacedItem>>,
+    camera: CameraWithProjection,
+    mut items_query: Query<(&mut Transform, &Sprite), (With<PlacedItem>, With<Selected>)>,
     mut drag_state: ResMut<DragState>,
     map_data: Res<MapData>,
+    mut snap: SnapParams,
     mut contexts: EguiContexts,
     mut history_writer: MessageWriter<RecordEditorCommand>,
     // Mutable annotation queries for moving
-    mut paths_query: Query<&mut DrawnPath, With<AnnotationMarker>>,
-    mut lines_query: Query<&mut DrawnLine, With<AnnotationMarker>>,
-    mut text_transforms_query: Query<
-        &mut Transform,
-        (With<TextAnnotation>, With<AnnotationMarker>, Without<PlacedItem>),
-    >,
+    mut annotations: AnnotationQueriesMut,
+    // Token movement measurement
+    tokens_query: Query<(&PlacedItem, Option<&TokenData>, &Visibility)>,
+    movement_settings: Res<MovementSettings>,
+    mut move_measurement: ResMut<TokenMoveMeasurement>,
+    mut movement_trails: ResMut<MovementTrails>,
 ) {
     if current_tool.tool != EditorTool::Select {
+        move_measurement.active = None;
         drag_state.is_dragging = false;
         drag_state.mode = SelectionDragMode::None;
         drag_state.original_bounds = None;
@@ -42,7 +54,7 @@ pub fn handle_drag(
     if mouse_button.just_released(MouseButton::Left) {
         let mut transforms = Vec::new();
         for (entity, start) in &drag_state.entity_start_transforms {
-            if let Ok(current) = items_query.get(*entity) {
+            if let Ok((current, _)) = items_query.get(*entity) {
                 // Only record entities that actually changed.
                 if current.translation != start.translation
                     || current.rotation != start.rotation
@@ -56,16 +68,144 @@ pub fn handle_drag(
                 }
             }
         }
+
+        // Leave a trail along the drag behind each token that moved
+        if let Some(active) = move_measurement.active.take()
+            && movement_settings.show_trails
+        {
+            for (entity, start, end) in &transforms {
+                let Ok((item, _, visibility)) = tokens_query.get(*entity) else {
+                    continue;
+                };
+                let (start, end) = (start.translation.truncate(), end.translation.truncate());
+                if item.layer != Layer::Token || start == end {
+                    continue;
+                }
+                movement_trails.trails.push(MovementTrail {
+                    path: path_points(start, &active.path, end),
+                    age: 0.0,
+                    player_visible: item.layer.is_player_visible()
+                        && *visibility != Visibility::Hidden,
+                });
+            }
+        }
+
         if !transforms.is_empty() {
             history_writer.write(RecordEditorCommand {
                 command: EditorCommand::MoveItems { transforms },
             });
         }
 
+        // Text annotations moved or resized
+        let mut texts = Vec::new();
+        for (entity, drag_data) in &drag_state.annotation_drag_data {
+            if let AnnotationDragData::Text {
+                original_position,
+                original_font_size,
+            } = drag_data
+                && let Ok((transform, text)) = annotations.texts.get(*entity)
+            {
+                let position = transform.translation.truncate();
+                if position != *original_position || text.font_size != *original_font_size {
+                    let original = TextAnnotation {
+                        font_size: *original_font_size,
+                        ..text.clone()
+                    };
+                    texts.push((
+                        *entity,
+                        TextData::from_annotation(*original_position, &original),
+                        TextData::from_annotation(position, text),
+                    ));
+                }
+            }
+        }
+        if !texts.is_empty() {
+            history_writer.write(RecordEditorCommand {
+                command: EditorCommand::EditText { texts },
+            });
+        }
+
+        // Paths, lines and shapes moved or reshaped by a vertex drag
+        let mut reshaped = Vec::new();
+        for (entity, drag_data) in &drag_state.annotation_drag_data {
+            let show_to_players = annotations.shown.get(*entity).unwrap_or(false);
+            let (old_data, new_data) = match drag_data {
+                AnnotationDragData::Path { original_points } => {
+                    let Ok(path) = annotations.paths.get(*entity) else {
+                        continue;
+                    };
+                    let new_data = PathData {
+                        show_to_players,
+                        ..PathData::from(path)
+                    };
+                    let old_data = PathData {
+                        points: original_points.clone(),
+                        ..new_data.clone()
+                    };
+                    (
+                        AnnotationData::Path(old_data),
+                        AnnotationData::Path(new_data),
+                    )
+                }
+                AnnotationDragData::Line {
+                    original_start,
+                    original_end,
+                } => {
+                    let Ok(line) = annotations.lines.get(*entity) else {
+                        continue;
+                    };
+                    let new_data = LineData {
+                        show_to_players,
+                        ..LineData::from(line)
+                    };
+                    let old_data = LineData {
+                        start: *original_start,
+                        end: *original_end,
+                        ..new_data.clone()
+                    };
+                    (
+                        AnnotationData::Line(old_data),
+                        AnnotationData::Line(new_data),
+                    )
+                }
+                AnnotationDragData::Shape { original_points } => {
+                    let Ok(shape) = annotations.shapes.get(*entity) else {
+                        continue;
+                    };
+                    let new_data = ShapeData {
+                        show_to_players,
+                        ..ShapeData::from(shape)
+                    };
+                    let old_data = ShapeData {
+                        points: original_points.clone(),
+                        ..new_data.clone()
+                    };
+                    (
+                        AnnotationData::Shape(old_data),
+                        AnnotationData::Shape(new_data),
+                    )
+                }
+                // Recorded as EditText above
+                AnnotationDragData::Text { .. } => continue,
+            };
+            if old_data != new_data {
+                reshaped.push((*entity, old_data, new_data));
+            }
+        }
+        if !reshaped.is_empty() {
+            history_writer.write(RecordEditorCommand {
+                command: EditorCommand::EditAnnotations {
+                    annotations: reshaped,
+                },
+            });
+        }
+
         drag_state.is_dragging = false;
         drag_state.mode = SelectionDragMode::None;
         drag_state.original_bounds = None;
         drag_state.entity_start_transforms.clear();
+        drag_state.annotation_drag_data.clear();
+        drag_state.vertex_index = None;
         return;
     }
 
@@ -85,29 +225,89 @@ pub fn handle_drag(
     // Calculate drag offset
     let mut drag_offset = world_pos - drag_state.drag_start_world;
 
-    // Shift = snap the offset to grid increments (for move mode)
+    // Shift = snap to the chosen grid points (for move and vertex modes);
+    // otherwise moved items line up with their neighbors
     let shift_held = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-    if shift_held && drag_state.mode == SelectionDragMode::Move {
-        drag_offset.x = (drag_offset.x / map_data.grid_size).round() * map_data.grid_size;
-        drag_offset.y = (drag_offset.y / map_data.grid_size).round() * map_data.grid_size;
+    if matches!(
+        drag_state.mode,
+        SelectionDragMode::Move | SelectionDragMode::Vertex
+    ) {
+        if shift_held {
+            if let Some(anchor) = snap_anchor(&drag_state) {
+                let snapped = snap_point(
+                    anchor + drag_offset,
+                    map_data.grid_size,
+                    snap.settings.snap_mode,
+                );
+                drag_offset = snapped - anchor;
+            }
+        } else if drag_state.mode == SelectionDragMode::Move {
+            let moving = drag_state
+                .entity_start_transforms
+                .iter()
+                .filter_map(|(entity, start)| {
+                    let (_, sprite) = items_query.get(*entity).ok()?;
+                    Some(rotated_item_bounds(start, sprite, &snap.images))
+                })
+                .reduce(|(a_min, a_max), (b_min, b_max)| (a_min.min(b_min), a_max.max(b_max)));
+            if let Some((min, max)) = moving {
+                let bounds = (min + drag_offset, max + drag_offset);
+                drag_offset += snap.snap_to_guides(bounds, camera.zoom_scale());
+            }
+        }
     }
 
     match drag_state.mode {
         SelectionDragMode::Move => {
             // Apply offset to each placed item, maintaining relative positions
             for (entity, start_pos) in &drag_state.entity_start_positions {
-                if let Ok(mut transform) = items_query.get_mut(*entity) {
+                if let Ok((mut transform, _)) = items_query.get_mut(*entity) {
                     let new_pos = *start_pos + drag_offset;
                     transform.translation.x = new_pos.x;
                     transform.translation.y = new_pos.y;
                 }
             }
 
+            // Measure the move when tokens are being dragged
+            let mut token_start = None;
+            let mut speed_ft: Option<f32> = None;
+            for (entity, start_pos) in &drag_state.entity_start_positions {
+                if let Ok((item, token, _)) = tokens_query.get(*entity)
+                    && item.layer == Layer::Token
+                {
+                    token_start.get_or_insert(*start_pos);
+                    if let Some(speed) = token.map(|t| t.speed_ft).filter(|s| *s > 0.0) {
+                        speed_ft = Some(speed_ft.map_or(speed, |s| s.min(speed)));
+                    }
+                }
+            }
+            // Measured along the cells the drag has passed through so far
+            let mut path = move_measurement
+                .active
+                .take()
+                .map(|active| active.path)
+                .unwrap_or_default();
+            extend_move_path(&mut path, drag_offset, map_data.grid_size);
+            move_measurement.active = token_start.map(|start| {
+                let end = start + drag_offset;
+                ActiveMove {
+                    start,
+                    end,
+                    distance: measure_move(
+                        &path_points(start, &path, end),
+                        map_data.grid_size,
+                        movement_settings.diagonal_rule,
+                    ),
+                    path,
+                    speed_ft,
+                }
+            });
+
             // Apply offset to annotations
             for (entity, drag_data) in &drag_state.annotation_drag_data {
                 match drag_data {
                     AnnotationDragData::Path { original_points } => {
-                        if let Ok(mut path) = paths_query.get_mut(*entity) {
+                        if let Ok(mut path) = annotations.paths.get_mut(*entity) {
                             path.points =
                                 original_points.iter().map(|p| *p + drag_offset).collect();
                         }
@@ -116,13 +316,21 @@ pub fn handle_drag(
                         original_start,
                         original_end,
                     } => {
-                        if let Ok(mut line) = lines_query.get_mut(*entity) {
+                        if let Ok(mut line) = annotations.lines.get_mut(*entity) {
                             line.start = *original_start + drag_offset;
                             line.end = *original_end + drag_offset;
                         }
                     }
-                    AnnotationDragData::Text { original_position } => {
-                        if let Ok(mut transform) = text_transforms_query.get_mut(*entity) {
+                    AnnotationDragData::Shape { original_points } => {
+                        if let Ok(mut shape) = annotations.shapes.get_mut(*entity) {
+                            shape.points =
+                                original_points.iter().map(|p| *p + drag_offset).collect();
+                        }
+                    }
+                    AnnotationDragData::Text {
+                        original_position, ..
+                    } => {
+                        if let Ok((mut transform, _)) = annotations.texts.get_mut(*entity) {
                             let new_pos = *original_position + drag_offset;
                             transform.translation.x = new_pos.x;
                             transform.translation.y = new_pos.y;
@@ -273,7 +481,7 @@ pub fn handle_drag(
                     new_center_local.x * sin_a + new_center_local.y * cos_a,
                 );
 
-                if let Ok(mut transform) = items_query.get_mut(*entity) {
+                if let Ok((mut transform, _)) = items_query.get_mut(*entity) {
                     // Apply new position (original position + rotated center offset)
                     let new_pos = *orig_pos + world_center_offset;
                     transform.translation.x = new_pos.x;
@@ -282,6 +490,41 @@ pub fn handle_drag(
                     transform.scale.y = orig_scale.y * scale_y;
                 }
             }
+
+            // Text annotations scale their font size, anchored at the opposite corner
+            for (entity, drag_data) in &drag_state.annotation_drag_data {
+                let AnnotationDragData::Text {
+                    original_position,
+                    original_font_size,
+                } = drag_data
+                else {
+                    continue;
+                };
+                let Ok((mut transform, mut text)) = annotations.texts.get_mut(*entity) else {
+                    continue;
+                };
+
+                let original = TextAnnotation {
+                    font_size: *original_font_size,
+                    ..text.clone()
+                };
+                let min_scale = TEXT_FONT_SIZE_RANGE.start() / original_font_size;
+                let max_scale = TEXT_FONT_SIZE_RANGE.end() / original_font_size;
+                let (center, scale) = resize_text_box(
+                    *original_position,
+                    text_half_size(&original),
+                    drag_state.mode,
+                    world_pos,
+                    min_scale..=max_scale,
+                );
+
+                let font_size = original_font_size * scale;
+                if text.font_size != font_size {
+                    text.font_size = font_size;
+                }
+                transform.translation.x = center.x;
+                transform.translation.y = center.y;
+            }
         }
         SelectionDragMode::Rotate => {
             // Get original bounds and start angle - required for rotation
@@ -296,19 +539,123 @@ pub fn handle_drag(
             let current_angle = (world_pos - center).to_angle();
             let mut angle_delta = current_angle - start_angle;
 
-            // Shift = snap to 15° increments
+            // Shift = snap to the chosen angle step
             if shift_held {
-                let snap_rad = ROTATION_SNAP_INCREMENT.to_radians();
+                let snap_rad = snap.settings.rotation_snap.degrees().to_radians();
                 angle_delta = (angle_delta / snap_rad).round() * snap_rad;
             }
 
             // Apply rotation to each entity around its own center
             for (entity, original_rotation) in &drag_state.entity_start_rotations {
-                if let Ok(mut transform) = items_query.get_mut(*entity) {
+                if let Ok((mut transform, _)) = items_query.get_mut(*entity) {
                     transform.rotation = *original_rotation * Quat::from_rotation_z(angle_delta);
                 }
             }
         }
+        SelectionDragMode::Vertex => {
+            let Some(index) = drag_state.vertex_index else {
+                return;
+            };
+            for (entity, drag_data) in &drag_state.annotation_drag_data {
+                match drag_data {
+                    AnnotationDragData::Path { original_points } => {
+                        if let Ok(mut path) = annotations.paths.get_mut(*entity)
+                            && let (Some(point), Some(original)) =
+                                (path.points.get_mut(index), original_points.get(index))
+                        {
+                            *point = *original + drag_offset;
+                        }
+                    }
+                    AnnotationDragData::Line {
+                        original_start,
+                        original_end,
+                    } => {
+                        if let Ok(mut line) = annotations.lines.get_mut(*entity) {
+                            if index == 0 {
+                                line.start = *original_start + drag_offset;
+                            } else {
+                                line.end = *original_end + drag_offset;
+                            }
+                        }
+                    }
+                    AnnotationDragData::Shape { original_points } => {
+                        if let Ok(mut shape) = annotations.shapes.get_mut(*entity)
+                            && let (Some(point), Some(original)) =
+                                (shape.points.get_mut(index), original_points.get(index))
+                        {
+                            *point = *original + drag_offset;
+                        }
+                    }
+                    AnnotationDragData::Text { .. } => {}
+                }
+            }
+        }
         SelectionDragMode::None => {}
     }
 }
+
+/// Point that Shift-snapping lines up with the grid: the dragged vertex, or
+/// the first dragged item's center
+fn snap_anchor(drag_state: &DragState) -> Option<Vec2> {
+    if let Some(&(_, start)) = drag_state.entity_start_positions.first() {
+        return Some(start);
+    }
+    let (_, drag_data) = drag_state.annotation_drag_data.first()?;
+    let vertex = drag_state.vertex_index.unwrap_or(0);
+    match drag_data {
+        AnnotationDragData::Path { original_points }
+        | AnnotationDragData::Shape { original_points } => original_points.get(vertex).copied(),
+        AnnotationDragData::Line {
+            original_start,
+            original_end,
+        } => Some(if vertex == 0 {
+            *original_start
+        } else {
+            *original_end
+        }),
+        AnnotationDragData::Text {
+            original_position, ..
+        } => Some(*original_position),
+    }
+}
+
+/// Uniformly scale a text box by dragging one of its handles, keeping the
+/// opposite corner or edge fixed. Returns the new center and scale factor.
+fn resize_text_box(
+    center: Vec2,
+    half_size: Vec2,
+    mode: SelectionDragMode,
+    cursor: Vec2,
+    scale_range: std::ops::RangeInclusive<f32>,
+) -> (Vec2, f32) {
+    // Direction from the fixed side towards the dragged handle
+    let direction = match mode {
+        SelectionDragMode::ResizeNE => Vec2::new(1.0, 1.0),
+        SelectionDragMode::ResizeNW => Vec2::new(-1.0, 1.0),
+        SelectionDragMode::ResizeSE => Vec2::new(1.0, -1.0),
+        SelectionDragMode::ResizeSW => Vec2::new(-1.0, -1.0),
+        SelectionDragMode::ResizeN => Vec2::Y,
+        SelectionDragMode::ResizeS => Vec2::NEG_Y,
+        SelectionDragMode::ResizeE => Vec2::X,
+        SelectionDragMode::ResizeW => Vec2::NEG_X,
+        _ => return (center, 1.0),
+    };
+
+    let anchor = center - direction * half_size;
+    let reach = (cursor - anchor) * direction;
+    let scale_x = if direction.x != 0.0 {
+        reach.x / (half_size.x * 2.0)
+    } else {
+        0.0
+    };
+    let scale_y = if direction.y != 0.0 {
+        reach.y / (half_size.y * 2.0)
+    } else {
+        0.0
+    };
+    let scale = scale_x
+        .max(scale_y)
+        .clamp(*scale_range.start(), *scale_range.end());
+
+    (anchor + direction * half_size * scale, scale)
+}
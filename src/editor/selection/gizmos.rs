@@ -5,13 +5,15 @@ use bevy::gizmos::config::{GizmoConfigGroup, GizmoConfigStore};
 use bevy::prelude::*;
 
 use crate::editor::annotations::{
-    is_annotation_layer_visible, line_bounds, path_bounds, text_bounds, AnnotationMarker,
-    DrawnLine, DrawnPath, TextAnnotation,
+    is_annotation_layer_visible, line_bounds, path_bounds, shape_bounds, text_bounds,
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, TextAnnotation,
 };
 use crate::map::{MapData, Selected};
 
 use super::hit_detection::{get_sprite_half_size, rotate_point};
-use super::{BoxSelectState, ROTATION_HANDLE_OFFSET, ROTATION_HANDLE_RADIUS};
+use super::{
+    BoxSelectSettings, BoxSelectState, ROTATION_HANDLE_OFFSET, ROTATION_HANDLE_RADIUS,
+};
 
 /// Custom gizmo group for selection indicators (editor-only rendering)
 #[derive(Default, Reflect, GizmoConfigGroup)]
@@ -33,6 +35,7 @@ pub fn draw_selection_indicators(
     // Annotation queries
     selected_paths_query: Query<&DrawnPath, (With<Selected>, With<AnnotationMarker>)>,
     selected_lines_query: Query<&DrawnLine, (With<Selected>, With<AnnotationMarker>)>,
+    selected_shapes_query: Query<&DrawnShape, (With<Selected>, With<AnnotationMarker>)>,
     selected_texts_query: Query<
         (&Transform, &TextAnnotation),
         (With<Selected>, With<AnnotationMarker>),
@@ -44,7 +47,7 @@ pub fn draw_selection_indicators(
     // Draw selection for sprites (placed items)
     for (transform, sprite) in selected_sprites_query.iter() {
         let pos = transform.translation.truncate();
-        let scale = transform.scale.truncate();
+        let scale = transform.scale.truncate().abs();
         let half_size = get_sprite_half_size(sprite, &images);
         let scaled_half = half_size * scale;
 
@@ -117,6 +120,13 @@ pub fn draw_selection_indicators(
         return;
     }
 
+    // A lone selected path shows its vertices, which can be dragged
+    if let Ok(path) = selected_paths_query.single() {
+        for point in &path.points {
+            gizmos.circle_2d(Isometry2d::from_translation(*point), 3.0, selection_color);
+        }
+    }
+
     // Draw selection for paths
     for path in selected_paths_query.iter() {
         let (min, max) = path_bounds(path);
@@ -163,6 +173,25 @@ pub fn draw_selection_indicators(
         );
     }
 
+    // Draw selection for shapes
+    for shape in selected_shapes_query.iter() {
+        let (min, max) = shape_bounds(shape);
+        let center = (min + max) / 2.0;
+        let size = (max - min).max(Vec2::splat(10.0));
+
+        gizmos.rect_2d(Isometry2d::from_translation(center), size, selection_color);
+
+        // Draw handles at the shape's defining points
+        let handle_size = 4.0;
+        for point in &shape.points {
+            gizmos.rect_2d(
+                Isometry2d::from_translation(*point),
+                Vec2::splat(handle_size * 2.0),
+                selection_color,
+            );
+        }
+    }
+
     // Draw selection for text annotations
     for (transform, text) in selected_texts_query.iter() {
         let (min, max) = text_bounds(transform, text);
@@ -185,7 +214,11 @@ pub fn draw_selection_indicators(
     }
 }
 
-pub fn draw_box_select_rect(mut gizmos: Gizmos<SelectionGizmoGroup>, box_select_state: Res<BoxSelectState>) {
+pub fn draw_box_select_rect(
+    mut gizmos: Gizmos<SelectionGizmoGroup>,
+    box_select_state: Res<BoxSelectState>,
+    settings: Res<BoxSelectSettings>,
+) {
     if !box_select_state.is_selecting {
         return;
     }
@@ -193,6 +226,16 @@ pub fn draw_box_select_rect(mut gizmos: Gizmos<SelectionGizmoGroup>, box_select_
     let box_color = Color::srgba(0.2, 0.6, 1.0, 0.8);
     let fill_color = Color::srgba(0.2, 0.6, 1.0, 0.1);
 
+    if settings.lasso {
+        // Outline the lasso, with a faint closing edge back to the start
+        let points = &box_select_state.lasso_points;
+        gizmos.linestrip_2d(points.iter().copied(), box_color);
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            gizmos.line_2d(*last, *first, fill_color);
+        }
+        return;
+    }
+
     let start = box_select_state.start_world;
     let current = box_select_state.current_world;
 
@@ -5,7 +5,7 @@ use bevy_egui::EguiContexts;
 
 use crate::editor::annotations::{
     is_annotation_layer_locked, is_annotation_layer_visible, AnnotationMarker, DrawnLine,
-    DrawnPath, TextAnnotation,
+    DrawnPath, DrawnShape, TextAnnotation,
 };
 use crate::editor::params::{is_cursor_over_ui, AnnotationQueries, CameraWithProjection};
 use crate::editor::tools::{CurrentTool, EditorTool};
@@ -14,7 +14,8 @@ use crate::session::{get_handle_at_position, LiveSessionState, ViewportDragMode}
 
 use super::hit_detection::{
     check_rotation_handle_hit, compute_selection_bounds, find_clicked_annotation,
-    get_selection_handle_at_position, get_sprite_half_size, point_in_item,
+    get_selection_handle_at_position, get_sprite_half_size, get_text_handle_at_position,
+    get_vertex_at_position, point_in_item,
 };
 use super::{AnnotationDragData, BoxSelectState, DragState, SelectionDragMode};
 
@@ -68,6 +69,9 @@ pub fn handle_selection(
         // First, check if we clicked on a selection handle or inside the selection bounds
         // This takes priority over clicking on individual items
         // Check rotation handle first (it's per-item and accounts for rotation)
+        let annotation_selectable =
+            is_annotation_layer_visible(&map_data) && !is_annotation_layer_locked(&map_data);
+
         let handle_mode = if check_rotation_handle_hit(
             world_pos,
             camera_scale,
@@ -76,12 +80,24 @@ pub fn handle_selection(
         ) {
             SelectionDragMode::Rotate
         } else {
-            get_selection_handle_at_position(
+            match get_selection_handle_at_position(
                 world_pos,
                 &selected_sprites_query,
                 &images,
                 camera_scale,
-            )
+            ) {
+                // Selected text boxes resize from their corners
+                SelectionDragMode::None if annotation_selectable => get_text_handle_at_position(
+                    world_pos,
+                    annotations
+                        .texts
+                        .iter()
+                        .filter(|(entity, _, _)| selected_query.contains(*entity))
+                        .map(|(_, transform, text)| (transform, text)),
+                    camera_scale,
+                ),
+                mode => mode,
+            }
         };
 
         if handle_mode != SelectionDragMode::None && !ctrl_held {
@@ -98,11 +114,45 @@ pub fn handle_selection(
                 &images,
                 &annotations.paths,
                 &annotations.lines,
+                &annotations.shapes,
                 &annotations.texts,
             );
             return;
         }
 
+        // With a single path, line or shape selected, its points can be
+        // dragged individually
+        let mut selected = selected_query.iter();
+        if annotation_selectable
+            && !ctrl_held
+            && let (Some(entity), None) = (selected.next(), selected.next())
+        {
+            let points = if let Ok((_, path)) = annotations.paths.get(entity) {
+                path.points.clone()
+            } else if let Ok((_, line)) = annotations.lines.get(entity) {
+                vec![line.start, line.end]
+            } else if let Ok((_, shape)) = annotations.shapes.get(entity) {
+                shape.points.clone()
+            } else {
+                Vec::new()
+            };
+            if let Some(index) = get_vertex_at_position(world_pos, &points, camera_scale) {
+                start_drag_for_entity(
+                    &mut drag_state,
+                    world_pos,
+                    entity,
+                    &items_query,
+                    &annotations.paths,
+                    &annotations.lines,
+                    &annotations.shapes,
+                    &annotations.texts,
+                );
+                drag_state.mode = SelectionDragMode::Vertex;
+                drag_state.vertex_index = Some(index);
+                return;
+            }
+        }
+
         // Find what item (if any) we clicked on
         // Filter by visible and unlocked layers
         let mut items: Vec<_> = items_query
@@ -124,14 +174,12 @@ pub fn handle_selection(
 
         // Check annotations (they're on top, z=350, so check them first)
         // Only check if annotation layer is visible and not locked
-        let annotation_selectable =
-            is_annotation_layer_visible(&map_data) && !is_annotation_layer_locked(&map_data);
-
         let clicked_annotation = if annotation_selectable {
             find_clicked_annotation(
                 world_pos,
                 &annotations.paths,
                 &annotations.lines,
+                &annotations.shapes,
                 &annotations.texts,
             )
         } else {
@@ -161,6 +209,7 @@ pub fn handle_selection(
                     &images,
                     &annotations.paths,
                     &annotations.lines,
+                    &annotations.shapes,
                     &annotations.texts,
                 );
             } else {
@@ -178,6 +227,7 @@ pub fn handle_selection(
                     &items_query,
                     &annotations.paths,
                     &annotations.lines,
+                    &annotations.shapes,
                     &annotations.texts,
                 );
             }
@@ -203,6 +253,7 @@ pub fn handle_selection(
                     &images,
                     &annotations.paths,
                     &annotations.lines,
+                    &annotations.shapes,
                     &annotations.texts,
                 );
             } else {
@@ -235,6 +286,7 @@ pub fn handle_selection(
             box_select_state.is_selecting = true;
             box_select_state.start_world = world_pos;
             box_select_state.current_world = world_pos;
+            box_select_state.lasso_points = vec![world_pos];
         }
     }
 }
@@ -251,6 +303,7 @@ fn start_selection_drag(
     images: &Assets<Image>,
     paths_query: &Query<(Entity, &DrawnPath), With<AnnotationMarker>>,
     lines_query: &Query<(Entity, &DrawnLine), With<AnnotationMarker>>,
+    shapes_query: &Query<(Entity, &DrawnShape), With<AnnotationMarker>>,
     texts_query: &Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
 ) {
     drag_state.is_dragging = true;
@@ -286,7 +339,7 @@ fn start_selection_drag(
                 .entity_start_rotations
                 .push((entity, t.rotation));
             // Store original half-size (sprite size * scale) for rotation-aware resizing
-            let half_size = get_sprite_half_size(sprite, images) * t.scale.truncate();
+            let half_size = get_sprite_half_size(sprite, images) * t.scale.truncate().abs();
             drag_state.entity_start_half_sizes.push((entity, half_size));
         }
         // Check if it's a path
@@ -308,12 +361,22 @@ fn start_selection_drag(
                 },
             ));
         }
+        // Check if it's a shape
+        else if let Ok((_, shape)) = shapes_query.get(entity) {
+            drag_state.annotation_drag_data.push((
+                entity,
+                AnnotationDragData::Shape {
+                    original_points: shape.points.clone(),
+                },
+            ));
+        }
         // Check if it's a text annotation
-        else if let Ok((_, t, _)) = texts_query.get(entity) {
+        else if let Ok((_, t, text)) = texts_query.get(entity) {
             drag_state.annotation_drag_data.push((
                 entity,
                 AnnotationDragData::Text {
                     original_position: t.translation.truncate(),
+                    original_font_size: text.font_size,
                 },
             ));
         }
@@ -321,6 +384,7 @@ fn start_selection_drag(
 }
 
 /// Start dragging a single entity
+#[allow(clippy::too_many_arguments)]
 fn start_drag_for_entity(
     drag_state: &mut ResMut<DragState>,
     world_pos: Vec2,
@@ -328,6 +392,7 @@ fn start_drag_for_entity(
     items_query: &Query<(Entity, &Transform, &Sprite, &PlacedItem)>,
     paths_query: &Query<(Entity, &DrawnPath), With<AnnotationMarker>>,
     lines_query: &Query<(Entity, &DrawnLine), With<AnnotationMarker>>,
+    shapes_query: &Query<(Entity, &DrawnShape), With<AnnotationMarker>>,
     texts_query: &Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
 ) {
     drag_state.is_dragging = true;
@@ -365,12 +430,22 @@ fn start_drag_for_entity(
             },
         ));
     }
+    // Check if it's a shape
+    else if let Ok((_, shape)) = shapes_query.get(entity) {
+        drag_state.annotation_drag_data.push((
+            entity,
+            AnnotationDragData::Shape {
+                original_points: shape.points.clone(),
+            },
+        ));
+    }
     // Check if it's a text annotation
-    else if let Ok((_, t, _)) = texts_query.get(entity) {
+    else if let Ok((_, t, text)) = texts_query.get(entity) {
         drag_state.annotation_drag_data.push((
             entity,
             AnnotationDragData::Text {
                 original_position: t.translation.truncate(),
+                original_font_size: text.font_size,
             },
         ));
     }
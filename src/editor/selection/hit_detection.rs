@@ -3,8 +3,8 @@
 use bevy::prelude::*;
 
 use crate::editor::annotations::{
-    point_in_text, point_near_line, point_near_path, AnnotationMarker, DrawnLine, DrawnPath,
-    TextAnnotation,
+    point_in_text, point_near_line, point_near_path, point_near_shape, text_bounds,
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, TextAnnotation,
 };
 use crate::map::Selected;
 
@@ -38,6 +38,23 @@ pub(crate) fn get_sprite_half_size(sprite: &Sprite, images: &Assets<Image>) -> V
     Vec2::splat(32.0)
 }
 
+/// Axis-aligned (min, max) bounds of an item, enclosing its rotated sprite
+pub(crate) fn rotated_item_bounds(
+    transform: &Transform,
+    sprite: &Sprite,
+    images: &Assets<Image>,
+) -> (Vec2, Vec2) {
+    let pos = transform.translation.truncate();
+    let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate().abs();
+    let (angle, _, _) = transform.rotation.to_euler(EulerRot::ZYX);
+    let (sin_a, cos_a) = angle.sin_cos();
+    let extents = Vec2::new(
+        half_size.x * cos_a.abs() + half_size.y * sin_a.abs(),
+        half_size.x * sin_a.abs() + half_size.y * cos_a.abs(),
+    );
+    (pos - extents, pos + extents)
+}
+
 /// Check if a point is inside an item's bounds, accounting for rotation
 pub(crate) fn point_in_item(
     world_pos: Vec2,
@@ -46,7 +63,7 @@ pub(crate) fn point_in_item(
     images: &Assets<Image>,
 ) -> bool {
     let item_pos = transform.translation.truncate();
-    let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate();
+    let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate().abs();
 
     // Transform the world position into the item's local coordinate space
     // by applying the inverse rotation
@@ -63,25 +80,15 @@ pub(crate) fn point_in_item(
     local_diff.x.abs() < half_size.x && local_diff.y.abs() < half_size.y
 }
 
-/// Check if an item overlaps with a rectangle (defined by two corners)
-pub(crate) fn item_overlaps_rect(
-    rect_min: Vec2,
-    rect_max: Vec2,
+/// Unrotated bounding box (min, max) of an item's sprite
+pub(crate) fn item_bounds(
     transform: &Transform,
     sprite: &Sprite,
     images: &Assets<Image>,
-) -> bool {
+) -> (Vec2, Vec2) {
     let item_pos = transform.translation.truncate();
-    let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate();
-
-    let item_min = item_pos - half_size;
-    let item_max = item_pos + half_size;
-
-    // Check for overlap (AABB intersection)
-    rect_min.x < item_max.x
-        && rect_max.x > item_min.x
-        && rect_min.y < item_max.y
-        && rect_max.y > item_min.y
+    let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate().abs();
+    (item_pos - half_size, item_pos + half_size)
 }
 
 /// Compute the combined bounding box for all selected placed items
@@ -95,7 +102,7 @@ pub fn compute_selection_bounds(
 
     for (transform, sprite) in selected_query.iter() {
         let pos = transform.translation.truncate();
-        let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate();
+        let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate().abs();
         let item_min = pos - half_size;
         let item_max = pos + half_size;
 
@@ -122,7 +129,7 @@ pub fn check_rotation_handle_hit(
 
     for (transform, sprite) in selected_query.iter() {
         let pos = transform.translation.truncate();
-        let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate();
+        let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate().abs();
         let (angle, _, _) = transform.rotation.to_euler(EulerRot::ZYX);
 
         // Calculate the rotated rotation handle position
@@ -151,7 +158,7 @@ pub fn get_selection_handle_at_position(
     // Check each selected item's rotated handles
     for (transform, sprite) in selected_query.iter() {
         let pos = transform.translation.truncate();
-        let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate();
+        let half_size = get_sprite_half_size(sprite, images) * transform.scale.truncate().abs();
         let (angle, _, _) = transform.rotation.to_euler(EulerRot::ZYX);
 
         // Local corner positions (before rotation)
@@ -196,11 +203,57 @@ pub fn get_selection_handle_at_position(
     SelectionDragMode::None
 }
 
+/// Determine which corner handle (if any) of the given text annotations is under
+/// the cursor. Text scales uniformly, so only corners are handles.
+pub fn get_text_handle_at_position<'a>(
+    world_pos: Vec2,
+    texts: impl Iterator<Item = (&'a Transform, &'a TextAnnotation)>,
+    camera_scale: f32,
+) -> SelectionDragMode {
+    let hit_size = HANDLE_SIZE * camera_scale * 1.5;
+
+    for (transform, text) in texts {
+        let (min, max) = text_bounds(transform, text);
+        let corners = [
+            (min, SelectionDragMode::ResizeSW),
+            (Vec2::new(max.x, min.y), SelectionDragMode::ResizeSE),
+            (max, SelectionDragMode::ResizeNE),
+            (Vec2::new(min.x, max.y), SelectionDragMode::ResizeNW),
+        ];
+        for (corner, mode) in corners {
+            if (world_pos - corner).length() < hit_size {
+                return mode;
+            }
+        }
+    }
+
+    SelectionDragMode::None
+}
+
+/// Determine which of an annotation's points (path vertices, line endpoints
+/// or shape points) is under the cursor, preferring the closest
+pub(crate) fn get_vertex_at_position(
+    world_pos: Vec2,
+    points: &[Vec2],
+    camera_scale: f32,
+) -> Option<usize> {
+    let hit_size = HANDLE_SIZE * camera_scale;
+
+    points
+        .iter()
+        .enumerate()
+        .map(|(index, point)| (index, point.distance(world_pos)))
+        .filter(|(_, distance)| *distance < hit_size)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
 /// Find which annotation (if any) was clicked
 pub(crate) fn find_clicked_annotation(
     world_pos: Vec2,
     paths_query: &Query<(Entity, &DrawnPath), With<AnnotationMarker>>,
     lines_query: &Query<(Entity, &DrawnLine), With<AnnotationMarker>>,
+    shapes_query: &Query<(Entity, &DrawnShape), With<AnnotationMarker>>,
     texts_query: &Query<(Entity, &Transform, &TextAnnotation), With<AnnotationMarker>>,
 ) -> Option<Entity> {
     // Check text annotations first (they have clear bounds)
@@ -224,5 +277,12 @@ pub(crate) fn find_clicked_annotation(
         }
     }
 
+    // Check shapes last so strokes drawn over a filled shape stay clickable
+    for (entity, shape) in shapes_query.iter() {
+        if point_near_shape(world_pos, shape) {
+            return Some(entity);
+        }
+    }
+
     None
 }
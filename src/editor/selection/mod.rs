@@ -1,27 +1,36 @@
 //! Selection tool module for the editor.
 //!
 //! This module handles all selection-related functionality including:
-//! - Click and box selection
+//! - Click, box and lasso selection, with a layer filter
+//! - Select All, Invert, Same Asset and by-layer commands
 //! - Drag, resize, and rotate operations
 //! - Selection gizmo rendering
 //! - Keyboard shortcuts for selected items
 
+mod align;
 mod box_select;
 mod cursor;
 mod drag;
 mod gizmos;
 mod handle;
 mod hit_detection;
+mod select_commands;
 mod shortcuts;
 
+use std::collections::HashSet;
+
 use bevy::prelude::*;
 
 use crate::common::DragMode;
+use crate::map::Layer;
 
 /// Type alias for selection drag mode (uses common DragMode)
 pub type SelectionDragMode = DragMode;
 
 // Re-export public items
+pub use align::{
+    handle_align_selection, handle_align_shortcuts, AlignOp, AlignSelectionRequest,
+};
 pub use box_select::handle_box_select;
 pub use cursor::update_selection_cursor;
 pub use drag::handle_drag;
@@ -30,7 +39,12 @@ pub use gizmos::{
     SelectionGizmoGroup,
 };
 pub use handle::handle_selection;
-// hit_detection items are used internally by submodules but not re-exported
+// hit_detection items are mostly internal to submodules; sprite sizing,
+// bounds and point hit-testing are shared with token tools and snapping
+pub(crate) use hit_detection::{get_sprite_half_size, point_in_item, rotated_item_bounds};
+pub use select_commands::{
+    handle_select_commands, handle_select_shortcuts, SelectCommand, SelectRequest,
+};
 pub use shortcuts::{
     handle_center_to_grid, handle_deletion, handle_escape_clear_selection, handle_fit_to_grid,
     handle_restore_aspect_ratio, handle_rotate_90,
@@ -40,8 +54,6 @@ pub use shortcuts::{
 pub(crate) const ROTATION_HANDLE_OFFSET: f32 = 25.0;
 /// Radius of the rotation handle circle (in world units)
 pub(crate) const ROTATION_HANDLE_RADIUS: f32 = 6.0;
-/// Snap increment for rotation when holding Shift (in degrees)
-pub(crate) const ROTATION_SNAP_INCREMENT: f32 = 15.0;
 /// Handle size for resize handles (in world units, will be scaled by camera)
 pub(crate) const HANDLE_SIZE: f32 = 8.0;
 
@@ -50,7 +62,11 @@ pub(crate) const HANDLE_SIZE: f32 = 8.0;
 pub enum AnnotationDragData {
     Path { original_points: Vec<Vec2> },
     Line { original_start: Vec2, original_end: Vec2 },
-    Text { original_position: Vec2 },
+    Shape { original_points: Vec<Vec2> },
+    Text {
+        original_position: Vec2,
+        original_font_size: f32,
+    },
 }
 
 
@@ -77,6 +93,9 @@ pub struct DragState {
     pub rotation_start_angle: Option<f32>,
     /// Maps entity to its annotation drag data when drag began
     pub annotation_drag_data: Vec<(Entity, AnnotationDragData)>,
+    /// Index of the point being moved in Vertex mode (for lines, 0 is the
+    /// start and 1 the end)
+    pub vertex_index: Option<usize>,
 }
 
 #[derive(Resource, Default)]
@@ -84,4 +103,52 @@ pub struct BoxSelectState {
     pub is_selecting: bool,
     pub start_world: Vec2,
     pub current_world: Vec2,
+    /// Points of the lasso being drawn, in world space
+    pub lasso_points: Vec<Vec2>,
+}
+
+/// Which layers box and lasso selection pick from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectionFilter {
+    excluded: HashSet<Layer>,
+}
+
+impl SelectionFilter {
+    pub fn allows(&self, layer: Layer) -> bool {
+        !self.excluded.contains(&layer)
+    }
+
+    pub fn set_allowed(&mut self, layer: Layer, allowed: bool) {
+        if allowed {
+            self.excluded.remove(&layer);
+        } else {
+            self.excluded.insert(layer);
+        }
+    }
+
+    pub fn allow_all(&mut self) {
+        self.excluded.clear();
+    }
+
+    /// Pick only annotations
+    pub fn annotations_only(&mut self) {
+        self.excluded = Layer::all()
+            .iter()
+            .copied()
+            .filter(|layer| *layer != Layer::Annotation)
+            .collect();
+    }
+
+    /// Whether any layer is filtered out
+    pub fn is_active(&self) -> bool {
+        !self.excluded.is_empty()
+    }
+}
+
+/// Settings for dragging out a selection on empty space
+#[derive(Resource, Default)]
+pub struct BoxSelectSettings {
+    /// Draw a freeform lasso instead of a rectangle
+    pub lasso: bool,
+    pub filter: SelectionFilter,
 }
@@ -0,0 +1,240 @@
+//! Selection commands that pick items by what they are rather than where.
+//!
+//! Like box selection, the commands skip hidden and locked layers. Select All
+//! and Select Layer replace the selection; Same Asset and Same Layer grow it.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use crate::editor::annotations::AnnotationMarker;
+use crate::keybindings::{Action, ActionInput};
+use crate::map::{Layer, MapData, PlacedItem, Selected};
+
+use super::box_select::layer_selectable;
+
+/// A way of picking a selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectCommand {
+    /// Everything on every layer
+    All,
+    /// Everything on one layer
+    Layer(Layer),
+    /// Everything on the layers the selection is on
+    SameLayer,
+    /// Every item showing an asset the selection uses
+    SameAsset,
+    /// Swap what is and isn't selected
+    Invert,
+}
+
+impl SelectCommand {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SelectCommand::All => "Select All",
+            SelectCommand::Layer(layer) => layer.display_name(),
+            SelectCommand::SameLayer => "Select Same Layer",
+            SelectCommand::SameAsset => "Select Same Asset",
+            SelectCommand::Invert => "Invert Selection",
+        }
+    }
+
+    /// The keyboard action for this command, if it has one
+    pub fn action(&self) -> Option<Action> {
+        match self {
+            SelectCommand::All => Some(Action::SelectAll),
+            SelectCommand::Invert => Some(Action::InvertSelection),
+            _ => None,
+        }
+    }
+}
+
+/// Message to change the selection with a [`SelectCommand`]
+#[derive(Message)]
+pub struct SelectRequest {
+    pub command: SelectCommand,
+}
+
+/// Something a select command can pick
+#[derive(Debug, Clone)]
+pub struct SelectCandidate<'a> {
+    pub entity: Entity,
+    pub layer: Layer,
+    /// Asset shown, for placed items
+    pub asset_path: Option<&'a str>,
+    pub selected: bool,
+}
+
+/// The entities that should be selected after `command`
+pub fn select_targets(command: SelectCommand, candidates: &[SelectCandidate]) -> HashSet<Entity> {
+    let selected = candidates.iter().filter(|c| c.selected);
+    let pick = |keep: &dyn Fn(&SelectCandidate) -> bool| {
+        candidates
+            .iter()
+            .filter(|c| keep(c))
+            .map(|c| c.entity)
+            .collect()
+    };
+
+    match command {
+        SelectCommand::All => pick(&|_| true),
+        SelectCommand::Layer(layer) => pick(&|c| c.layer == layer),
+        SelectCommand::Invert => pick(&|c| !c.selected),
+        SelectCommand::SameLayer => {
+            let layers: HashSet<Layer> = selected.map(|c| c.layer).collect();
+            pick(&|c| c.selected || layers.contains(&c.layer))
+        }
+        SelectCommand::SameAsset => {
+            let assets: HashSet<&str> = selected.filter_map(|c| c.asset_path).collect();
+            pick(&|c| c.selected || c.asset_path.is_some_and(|path| assets.contains(path)))
+        }
+    }
+}
+
+/// Send select requests for the Select All and Invert Selection shortcuts
+pub fn handle_select_shortcuts(
+    keys: ActionInput,
+    mut contexts: EguiContexts,
+    mut select_writer: MessageWriter<SelectRequest>,
+) {
+    // Don't trigger if typing in UI
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_keyboard_input()
+    {
+        return;
+    }
+
+    let command = if keys.just_pressed(Action::SelectAll) {
+        Some(SelectCommand::All)
+    } else if keys.just_pressed(Action::InvertSelection) {
+        Some(SelectCommand::Invert)
+    } else {
+        None
+    };
+
+    if let Some(command) = command {
+        select_writer.write(SelectRequest { command });
+    }
+}
+
+/// Apply select requests to the items and annotations on selectable layers
+pub fn handle_select_commands(
+    mut commands: Commands,
+    mut events: MessageReader<SelectRequest>,
+    items_query: Query<(Entity, &PlacedItem, Has<Selected>)>,
+    annotations_query: Query<(Entity, Has<Selected>), With<AnnotationMarker>>,
+    selected_query: Query<Entity, With<Selected>>,
+    map_data: Res<MapData>,
+) {
+    // Selection changes land at the end of the frame, so only the last
+    // request would see an up to date selection
+    let Some(event) = events.read().last() else {
+        return;
+    };
+
+    let mut candidates: Vec<SelectCandidate> = items_query
+        .iter()
+        .filter(|(_, item, _)| layer_selectable(&map_data, item.layer))
+        .map(|(entity, item, selected)| SelectCandidate {
+            entity,
+            layer: item.layer,
+            asset_path: Some(item.asset_path.as_str()),
+            selected,
+        })
+        .collect();
+    if layer_selectable(&map_data, Layer::Annotation) {
+        candidates.extend(
+            annotations_query
+                .iter()
+                .map(|(entity, selected)| SelectCandidate {
+                    entity,
+                    layer: Layer::Annotation,
+                    asset_path: None,
+                    selected,
+                }),
+        );
+    }
+
+    let targets = select_targets(event.command, &candidates);
+    for entity in selected_query.iter() {
+        if !targets.contains(&entity) {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+    for candidate in &candidates {
+        if !candidate.selected && targets.contains(&candidate.entity) {
+            commands.entity(candidate.entity).insert(Selected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        index: u32,
+        layer: Layer,
+        asset_path: Option<&'static str>,
+        selected: bool,
+    ) -> SelectCandidate<'static> {
+        SelectCandidate {
+            entity: Entity::from_raw_u32(index).unwrap(),
+            layer,
+            asset_path,
+            selected,
+        }
+    }
+
+    fn entities(indices: &[u32]) -> HashSet<Entity> {
+        indices
+            .iter()
+            .map(|index| Entity::from_raw_u32(*index).unwrap())
+            .collect()
+    }
+
+    fn sample() -> Vec<SelectCandidate<'static>> {
+        vec![
+            candidate(1, Layer::Doodad, Some("tree.png"), true),
+            candidate(2, Layer::Doodad, Some("rock.png"), false),
+            candidate(3, Layer::Terrain, Some("tree.png"), false),
+            candidate(4, Layer::Annotation, None, false),
+        ]
+    }
+
+    #[test]
+    fn test_select_all_and_invert() {
+        let candidates = sample();
+        assert_eq!(
+            select_targets(SelectCommand::All, &candidates),
+            entities(&[1, 2, 3, 4])
+        );
+        assert_eq!(
+            select_targets(SelectCommand::Invert, &candidates),
+            entities(&[2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn test_select_by_layer() {
+        let candidates = sample();
+        assert_eq!(
+            select_targets(SelectCommand::Layer(Layer::Annotation), &candidates),
+            entities(&[4])
+        );
+        assert_eq!(
+            select_targets(SelectCommand::SameLayer, &candidates),
+            entities(&[1, 2])
+        );
+    }
+
+    #[test]
+    fn test_select_same_asset_across_layers() {
+        let candidates = sample();
+        assert_eq!(
+            select_targets(SelectCommand::SameAsset, &candidates),
+            entities(&[1, 3])
+        );
+    }
+}
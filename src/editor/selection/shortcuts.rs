@@ -3,16 +3,20 @@
 use bevy::prelude::*;
 use bevy_egui::EguiContexts;
 
-use crate::editor::annotations::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
+use crate::editor::annotations::{
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, ShowToPlayers, TextAnnotation,
+};
 use crate::editor::history::{
-    EditorCommand, LineData, PathData, PlacedItemData, RecordEditorCommand, TextData, TransformData,
+    EditorCommand, LineData, PathData, PlacedItemData, RecordEditorCommand, ShapeData, TextData,
+    TransformData,
 };
-use crate::map::{MapData, PlacedItem, Selected};
+use crate::keybindings::{Action, ActionInput};
+use crate::map::{MapData, PlacedItem, Selected, TokenData};
 
 use super::hit_detection::get_sprite_half_size;
 
 pub fn handle_fit_to_grid(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     mut selected_query: Query<(Entity, &mut Transform, &Sprite), With<Selected>>,
     map_data: Res<MapData>,
     images: Res<Assets<Image>>,
@@ -26,9 +30,7 @@ pub fn handle_fit_to_grid(
         return;
     }
 
-    // G = fit to grid (but not Shift+G, which is center to grid)
-    let shift_held = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-    if !keyboard.just_pressed(KeyCode::KeyG) || shift_held {
+    if !keys.just_pressed(Action::FitToGrid) {
         return;
     }
 
@@ -53,9 +55,9 @@ pub fn handle_fit_to_grid(
     record_moves(&mut history_writer, moves);
 }
 
-/// Center selected items to the nearest grid cell center when Shift+G is pressed
+/// Center selected items to the nearest grid cell center (Shift+G by default)
 pub fn handle_center_to_grid(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     mut selected_query: Query<(Entity, &mut Transform, Has<PlacedItem>), With<Selected>>,
     map_data: Res<MapData>,
     mut contexts: EguiContexts,
@@ -68,9 +70,7 @@ pub fn handle_center_to_grid(
         return;
     }
 
-    // Shift+G = center to grid
-    let shift_held = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-    if !keyboard.just_pressed(KeyCode::KeyG) || !shift_held {
+    if !keys.just_pressed(Action::CenterToGrid) {
         return;
     }
 
@@ -96,10 +96,10 @@ pub fn handle_center_to_grid(
     record_moves(&mut history_writer, moves);
 }
 
-/// Restore selected items to their original aspect ratio when A is pressed
+/// Restore selected items to their original aspect ratio (A by default)
 /// Uses the larger of the two scale values to preserve the largest dimension
 pub fn handle_restore_aspect_ratio(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     mut selected_query: Query<(Entity, &mut Transform, Has<PlacedItem>), With<Selected>>,
     mut contexts: EguiContexts,
     mut history_writer: MessageWriter<RecordEditorCommand>,
@@ -111,8 +111,7 @@ pub fn handle_restore_aspect_ratio(
         return;
     }
 
-    // A = restore aspect ratio
-    if !keyboard.just_pressed(KeyCode::KeyA) {
+    if !keys.just_pressed(Action::RestoreAspectRatio) {
         return;
     }
 
@@ -132,9 +131,9 @@ pub fn handle_restore_aspect_ratio(
     record_moves(&mut history_writer, moves);
 }
 
-/// Rotate selected items by 90 degrees when R is pressed (clockwise) or Shift+R (counter-clockwise)
+/// Rotate selected items by 90 degrees, clockwise (R) or counter-clockwise (Shift+R)
 pub fn handle_rotate_90(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     mut selected_query: Query<(Entity, &mut Transform, Has<PlacedItem>), With<Selected>>,
     mut contexts: EguiContexts,
     mut history_writer: MessageWriter<RecordEditorCommand>,
@@ -146,10 +145,14 @@ pub fn handle_rotate_90(
         return;
     }
 
-    let shift_held = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-
-    // Rotate 90 degrees: clockwise (negative) or counter-clockwise (positive) with Shift
-    let angle = if shift_held { 90.0_f32 } else { -90.0_f32 };
+    // Rotate 90 degrees: clockwise (negative) or counter-clockwise (positive)
+    let angle = if keys.just_pressed(Action::RotateClockwise) {
+        -90.0_f32
+    } else if keys.just_pressed(Action::RotateCounterClockwise) {
+        90.0_f32
+    } else {
+        return;
+    };
     let rotation_delta = Quat::from_rotation_z(angle.to_radians());
 
     let mut moves = Vec::new();
@@ -165,7 +168,7 @@ pub fn handle_rotate_90(
 }
 
 /// Emit a MoveItems command for any entries whose transform actually changed.
-fn record_moves(
+pub(super) fn record_moves(
     history_writer: &mut MessageWriter<RecordEditorCommand>,
     transforms: Vec<(Entity, TransformData, TransformData)>,
 ) {
@@ -190,12 +193,25 @@ fn record_moves(
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_deletion(
     mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     selected_query: Query<Entity, With<Selected>>,
-    selected_items: Query<(Entity, &Transform, &PlacedItem), With<Selected>>,
-    selected_paths: Query<(Entity, &DrawnPath), (With<Selected>, With<AnnotationMarker>)>,
-    selected_lines: Query<(Entity, &DrawnLine), (With<Selected>, With<AnnotationMarker>)>,
-    selected_texts: Query<(Entity, &Transform, &TextAnnotation), (With<Selected>, With<AnnotationMarker>)>,
+    selected_items: Query<(Entity, &Transform, &PlacedItem, Option<&TokenData>), With<Selected>>,
+    selected_paths: Query<
+        (&DrawnPath, Has<ShowToPlayers>),
+        (With<Selected>, With<AnnotationMarker>),
+    >,
+    selected_lines: Query<
+        (&DrawnLine, Has<ShowToPlayers>),
+        (With<Selected>, With<AnnotationMarker>),
+    >,
+    selected_shapes: Query<
+        (&DrawnShape, Has<ShowToPlayers>),
+        (With<Selected>, With<AnnotationMarker>),
+    >,
+    selected_texts: Query<
+        (&Transform, &TextAnnotation, Has<ShowToPlayers>),
+        (With<Selected>, With<AnnotationMarker>),
+    >,
     mut contexts: EguiContexts,
     mut history_writer: MessageWriter<RecordEditorCommand>,
 ) {
@@ -206,10 +222,7 @@ pub fn handle_deletion(
         return;
     }
 
-    let should_delete =
-        keyboard.just_pressed(KeyCode::Delete) || keyboard.just_pressed(KeyCode::Backspace);
-
-    if !should_delete {
+    if !keys.just_pressed(Action::DeleteSelection) {
         return;
     }
 
@@ -217,12 +230,13 @@ pub fn handle_deletion(
     // each annotation becomes its own Delete* command.
     let deleted_items: Vec<PlacedItemData> = selected_items
         .iter()
-        .map(|(entity, transform, item)| PlacedItemData {
+        .map(|(entity, transform, item, token)| PlacedItemData {
             entity,
             asset_path: item.asset_path.clone(),
             layer: item.layer,
             z_index: item.z_index,
             transform: TransformData::from(transform),
+            token: token.cloned(),
         })
         .collect();
 
@@ -234,30 +248,45 @@ pub fn handle_deletion(
         });
     }
 
-    for (_, path) in selected_paths.iter() {
+    for (path, show_to_players) in selected_paths.iter() {
         history_writer.write(RecordEditorCommand {
             command: EditorCommand::DeletePath {
-                path: PathData::from(path),
+                path: PathData {
+                    show_to_players,
+                    ..PathData::from(path)
+                },
             },
         });
     }
 
-    for (_, line) in selected_lines.iter() {
+    for (line, show_to_players) in selected_lines.iter() {
         history_writer.write(RecordEditorCommand {
             command: EditorCommand::DeleteLine {
-                line: LineData::from(line),
+                line: LineData {
+                    show_to_players,
+                    ..LineData::from(line)
+                },
+            },
+        });
+    }
+
+    for (shape, show_to_players) in selected_shapes.iter() {
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::DeleteShape {
+                shape: ShapeData {
+                    show_to_players,
+                    ..ShapeData::from(shape)
+                },
             },
         });
     }
 
-    for (_, transform, text) in selected_texts.iter() {
+    for (transform, text, show_to_players) in selected_texts.iter() {
         history_writer.write(RecordEditorCommand {
             command: EditorCommand::DeleteText {
                 text: TextData {
-                    text: text.content.clone(),
-                    position: transform.translation.truncate(),
-                    color: text.color,
-                    font_size: text.font_size,
+                    show_to_players,
+                    ..TextData::from_annotation(transform.translation.truncate(), text)
                 },
             },
         });
@@ -268,10 +297,10 @@ pub fn handle_deletion(
     }
 }
 
-/// Clear selection when Escape is pressed
+/// Clear selection when the clear selection shortcut (Escape) is pressed
 pub fn handle_escape_clear_selection(
     mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     selected_query: Query<Entity, With<Selected>>,
     mut contexts: EguiContexts,
 ) {
@@ -282,7 +311,7 @@ pub fn handle_escape_clear_selection(
         return;
     }
 
-    if keyboard.just_pressed(KeyCode::Escape) {
+    if keys.just_pressed(Action::ClearSelection) {
         for entity in selected_query.iter() {
             commands.entity(entity).remove::<Selected>();
         }
@@ -0,0 +1,157 @@
+//! Smart guides for lining items up with their neighbors.
+//!
+//! While an item is dragged, placed or brushed, its left/center/right and
+//! bottom/center/top lines are compared with those of the other visible items.
+//! The closest match within a few screen pixels pulls the item into line on
+//! that axis, and guide lines show what it lined up with. Guides win over the
+//! grid on the axes they snap.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::map::{PlacedItem, Selected};
+
+use super::grid::GridSettings;
+use super::selection::{rotated_item_bounds, SelectionGizmoGroup};
+
+/// Screen-space distance (pixels) within which an item snaps to a guide
+pub const GUIDE_SNAP_DISTANCE: f32 = 8.0;
+
+/// Color of smart guide lines
+const GUIDE_COLOR: Color = Color::srgba(1.0, 0.3, 0.8, 0.9);
+
+/// Guide lines to draw this frame
+#[derive(Resource, Default)]
+pub struct SmartGuides {
+    pub lines: Vec<(Vec2, Vec2)>,
+}
+
+/// Result of snapping a box to guides
+#[derive(Debug, Default, PartialEq)]
+pub struct GuideSnap {
+    /// Offset that lines the box up
+    pub offset: Vec2,
+    /// Guide lines between the snapped box and what it lined up with
+    pub lines: Vec<(Vec2, Vec2)>,
+}
+
+/// Min, center and max of a box along one axis
+fn lines_along(bounds: (Vec2, Vec2), axis: usize) -> [f32; 3] {
+    let (min, max) = (bounds.0[axis], bounds.1[axis]);
+    [min, (min + max) / 2.0, max]
+}
+
+/// Snap `moving` (min, max) to the edges and centers of `targets`
+pub fn guide_snap(moving: (Vec2, Vec2), targets: &[(Vec2, Vec2)], threshold: f32) -> GuideSnap {
+    let mut snap = GuideSnap::default();
+
+    for axis in 0..2 {
+        let own = lines_along(moving, axis);
+        let best = targets
+            .iter()
+            .flat_map(|target| lines_along(*target, axis))
+            .flat_map(|line| own.iter().map(move |value| line - value))
+            .filter(|delta| delta.abs() <= threshold)
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()));
+        let Some(delta) = best else {
+            continue;
+        };
+        snap.offset[axis] = delta;
+
+        // Draw a guide through every target line the snapped box now meets
+        let snapped = (moving.0 + snap.offset, moving.1 + snap.offset);
+        let snapped_lines = lines_along(snapped, axis);
+        let across = 1 - axis;
+        for target in targets {
+            for line in lines_along(*target, axis) {
+                if !snapped_lines.iter().any(|value| (line - value).abs() < 0.5) {
+                    continue;
+                }
+                let from = snapped.0[across].min(target.0[across]);
+                let to = snapped.1[across].max(target.1[across]);
+                let mut start = Vec2::ZERO;
+                let mut end = Vec2::ZERO;
+                start[axis] = line;
+                end[axis] = line;
+                start[across] = from;
+                end[across] = to;
+                snap.lines.push((start, end));
+            }
+        }
+    }
+    snap
+}
+
+/// Grid settings, guide state and the items guides line up with
+#[derive(SystemParam)]
+#[allow(clippy::type_complexity)]
+pub struct SnapParams<'w, 's> {
+    pub settings: Res<'w, GridSettings>,
+    pub guides: ResMut<'w, SmartGuides>,
+    pub images: Res<'w, Assets<Image>>,
+    others: Query<
+        'w,
+        's,
+        (&'static Transform, &'static Sprite, &'static Visibility),
+        (With<PlacedItem>, Without<Selected>),
+    >,
+}
+
+impl SnapParams<'_, '_> {
+    /// Line `bounds` up with nearby unselected items if smart guides are on,
+    /// updating the guide lines. Returns the offset to apply.
+    pub fn snap_to_guides(&mut self, bounds: (Vec2, Vec2), zoom_scale: f32) -> Vec2 {
+        if !self.settings.smart_guides {
+            return Vec2::ZERO;
+        }
+        let targets: Vec<(Vec2, Vec2)> = self
+            .others
+            .iter()
+            .filter(|(_, _, visibility)| **visibility != Visibility::Hidden)
+            .map(|(transform, sprite, _)| rotated_item_bounds(transform, sprite, &self.images))
+            .collect();
+        let snap = guide_snap(bounds, &targets, GUIDE_SNAP_DISTANCE * zoom_scale);
+        self.guides.lines = snap.lines;
+        snap.offset
+    }
+}
+
+/// Draw the smart guide lines, then clear them. Tools set them again each
+/// frame they're snapping, so guides vanish as soon as snapping stops.
+pub fn draw_smart_guides(mut gizmos: Gizmos<SelectionGizmoGroup>, mut guides: ResMut<SmartGuides>) {
+    for (start, end) in guides.lines.drain(..) {
+        gizmos.line_2d(start, end, GUIDE_COLOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> (Vec2, Vec2) {
+        (Vec2::new(x, y), Vec2::new(x + size, y + size))
+    }
+
+    #[test]
+    fn test_guide_snap_to_nearest_edge() {
+        let targets = [square(0.0, 0.0, 10.0)];
+        // One unit right of the target, far above it
+        let snap = guide_snap(square(1.0, 100.0, 10.0), &targets, 5.0);
+        assert_eq!(snap.offset, Vec2::new(-1.0, 0.0));
+        // Left, center and right all line up, each with a vertical guide
+        assert_eq!(snap.lines.len(), 3);
+        assert!(snap.lines.iter().all(|(a, b)| a.x == b.x));
+        assert_eq!(snap.lines[0], (Vec2::new(0.0, 0.0), Vec2::new(0.0, 110.0)));
+    }
+
+    #[test]
+    fn test_guide_snap_centers_and_threshold() {
+        let targets = [square(0.0, 0.0, 20.0)];
+        // A 4-wide box whose center is just right of the target's center
+        let snap = guide_snap((Vec2::new(8.5, 50.0), Vec2::new(12.5, 54.0)), &targets, 2.0);
+        assert_eq!(snap.offset, Vec2::new(-0.5, 0.0));
+
+        let far = guide_snap(square(50.0, 50.0, 10.0), &targets, 2.0);
+        assert_eq!(far, GuideSnap::default());
+    }
+}
@@ -0,0 +1,302 @@
+//! Token status badges and the token context menu.
+//!
+//! Conditions, concentration, exhaustion and custom markers live in the
+//! [`TokenData`] component on Token-layer items. This module mirrors that data
+//! as small labelled badges along the top edge of each token, names the badge
+//! under the cursor, and opens a context menu when a token is right-clicked
+//! with the Select tool.
+//!
+//! Badges are separate entities rather than children so they keep a constant
+//! size and stay upright when the token is scaled or rotated. They copy the
+//! token's render layers and visibility, so they show in the player view
+//! exactly when the token does.
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::params::{is_cursor_over_ui, CameraParams, CameraWithProjection};
+use super::selection::{get_sprite_half_size, point_in_item};
+use crate::map::{Layer, MapData, PlacedItem, TokenData};
+
+/// Badge size relative to the token's bounding radius
+const BADGE_SIZE_FACTOR: f32 = 0.35;
+/// Smallest and largest badge size (in world units)
+const BADGE_MIN_SIZE: f32 = 12.0;
+const BADGE_MAX_SIZE: f32 = 32.0;
+/// Gap between neighbouring badges (in world units)
+const BADGE_GAP: f32 = 2.0;
+/// Badges sit just above their token
+const BADGE_Z_OFFSET: f32 = 0.5;
+
+/// A status badge drawn over a token
+#[derive(Component)]
+pub struct TokenBadge {
+    /// The token this badge belongs to
+    pub token: Entity,
+    /// Position of the badge in the token's badge row
+    pub index: usize,
+    /// Full name of the status, shown on hover
+    pub name: String,
+}
+
+/// Marker for the text label inside a badge
+#[derive(Component)]
+pub struct TokenBadgeLabel;
+
+/// State for the token right-click context menu
+#[derive(Resource, Default)]
+pub struct TokenContextMenu {
+    /// Token the menu is open for
+    pub target: Option<Entity>,
+    /// Screen position where the menu opens (logical pixels)
+    pub screen_pos: Vec2,
+}
+
+impl TokenContextMenu {
+    pub fn close(&mut self) {
+        self.target = None;
+    }
+}
+
+/// Respawn badges for tokens whose status data changed
+pub fn rebuild_token_badges(
+    mut commands: Commands,
+    changed_tokens: Query<(Entity, &TokenData, &RenderLayers), Changed<TokenData>>,
+    mut removed_data: RemovedComponents<TokenData>,
+    badges: Query<(Entity, &TokenBadge)>,
+) {
+    let mut stale: Vec<Entity> = removed_data.read().collect();
+    stale.extend(changed_tokens.iter().map(|(entity, _, _)| entity));
+    if stale.is_empty() {
+        return;
+    }
+
+    for (badge_entity, badge) in badges.iter() {
+        if stale.contains(&badge.token) {
+            commands.entity(badge_entity).despawn();
+        }
+    }
+
+    for (token, data, render_layers) in changed_tokens.iter() {
+        for (index, status) in data.badges().into_iter().enumerate() {
+            // Positioned by sync_token_badges once the token size is known
+            commands.spawn((
+                Sprite::from_color(status.color, Vec2::ONE),
+                Transform::default(),
+                Visibility::Hidden,
+                render_layers.clone(),
+                TokenBadge {
+                    token,
+                    index,
+                    name: status.name,
+                },
+                children![(
+                    Text2d::new(status.label),
+                    TextColor(Color::WHITE),
+                    Transform::from_xyz(0.0, 0.0, 0.1),
+                    render_layers.clone(),
+                    TokenBadgeLabel,
+                )],
+            ));
+        }
+    }
+}
+
+/// Keep badges positioned, sized and visible with their tokens
+#[allow(clippy::type_complexity)]
+pub fn sync_token_badges(
+    mut commands: Commands,
+    tokens: Query<(&Transform, &Sprite, &Visibility, &RenderLayers), With<TokenData>>,
+    mut badges: Query<
+        (
+            &TokenBadge,
+            &mut Transform,
+            &mut Sprite,
+            &mut Visibility,
+            &mut RenderLayers,
+            &Children,
+        ),
+        Without<TokenData>,
+    >,
+    mut labels: Query<&mut TextFont, With<TokenBadgeLabel>>,
+    images: Res<Assets<Image>>,
+) {
+    for (badge, mut transform, mut sprite, mut visibility, mut layers, children) in
+        badges.iter_mut()
+    {
+        let Ok((token_transform, token_sprite, token_visibility, token_layers)) =
+            tokens.get(badge.token)
+        else {
+            // Token was deleted; rebuild_token_badges despawns the badge
+            continue;
+        };
+
+        let center = token_transform.translation.truncate();
+        let half_size =
+            get_sprite_half_size(token_sprite, &images) * token_transform.scale.truncate().abs();
+        let radius = half_size.max_element();
+        let size = (radius * BADGE_SIZE_FACTOR).clamp(BADGE_MIN_SIZE, BADGE_MAX_SIZE);
+
+        let offset = badge_offset(badge.index, radius, size);
+        let translation = (center + offset).extend(token_transform.translation.z + BADGE_Z_OFFSET);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+        if sprite.custom_size != Some(Vec2::splat(size)) {
+            sprite.custom_size = Some(Vec2::splat(size));
+            for child in children.iter() {
+                if let Ok(mut font) = labels.get_mut(child) {
+                    font.font_size = size * 0.6;
+                }
+            }
+        }
+        if *visibility != *token_visibility {
+            *visibility = *token_visibility;
+        }
+        if *layers != *token_layers {
+            *layers = token_layers.clone();
+            for child in children.iter() {
+                commands.entity(child).insert(token_layers.clone());
+            }
+        }
+    }
+}
+
+/// Show the full name of the badge under the cursor
+pub fn token_badge_hover_ui(
+    mut contexts: EguiContexts,
+    camera: CameraParams,
+    badges: Query<(&TokenBadge, &Transform, &Sprite, &Visibility)>,
+) -> Result {
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+    let Some(pointer) = ctx.pointer_hover_pos() else {
+        return Ok(());
+    };
+    if ctx.is_pointer_over_area() {
+        return Ok(());
+    }
+
+    let hovered = badges
+        .iter()
+        .filter(|(_, _, _, visibility)| **visibility != Visibility::Hidden)
+        .filter(|(_, transform, sprite, _)| {
+            let half_size = sprite.custom_size.unwrap_or(Vec2::ZERO) / 2.0;
+            let offset = (world_pos - transform.translation.truncate()).abs();
+            offset.x <= half_size.x && offset.y <= half_size.y
+        })
+        .max_by(|a, b| a.1.translation.z.total_cmp(&b.1.translation.z));
+    let Some((badge, _, _, _)) = hovered else {
+        return Ok(());
+    };
+
+    egui::Area::new(egui::Id::new("token_badge_hover"))
+        .fixed_pos(pointer + egui::vec2(16.0, 16.0))
+        .order(egui::Order::Tooltip)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(badge.name.as_str());
+            });
+        });
+
+    Ok(())
+}
+
+/// Offset of a badge from the token center. Badges fill rows left to right
+/// along the top edge of the token's bounding square, wrapping downwards.
+pub(crate) fn badge_offset(index: usize, radius: f32, size: f32) -> Vec2 {
+    let step = size + BADGE_GAP;
+    let per_row = (((radius * 2.0) + BADGE_GAP) / step).floor().max(1.0) as usize;
+    let column = (index % per_row) as f32;
+    let row = (index / per_row) as f32;
+
+    Vec2::new(
+        -radius + size / 2.0 + column * step,
+        radius - size / 2.0 - row * step,
+    )
+}
+
+/// Open the context menu when a token is right-clicked with the Select tool
+pub fn handle_token_context_menu(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    camera: CameraWithProjection,
+    items_query: Query<(Entity, &Transform, &Sprite, &PlacedItem)>,
+    images: Res<Assets<Image>>,
+    map_data: Res<MapData>,
+    mut menu: ResMut<TokenContextMenu>,
+    mut contexts: EguiContexts,
+) {
+    if !mouse_button.just_pressed(MouseButton::Right) || is_cursor_over_ui(&mut contexts) {
+        return;
+    }
+
+    let Some(world_pos) = camera.cursor_world_pos() else {
+        return;
+    };
+    let Some(screen_pos) = camera
+        .window
+        .single()
+        .ok()
+        .and_then(|w| w.cursor_position())
+    else {
+        return;
+    };
+
+    let token_layer_visible = map_data
+        .layers
+        .iter()
+        .find(|ld| ld.layer_type == Layer::Token)
+        .map(|ld| ld.visible)
+        .unwrap_or(true);
+    if !token_layer_visible {
+        menu.close();
+        return;
+    }
+
+    // Topmost token under the cursor
+    let clicked = items_query
+        .iter()
+        .filter(|(_, _, _, item)| item.layer == Layer::Token)
+        .filter(|(_, transform, sprite, _)| point_in_item(world_pos, transform, sprite, &images))
+        .max_by(|a, b| a.1.translation.z.total_cmp(&b.1.translation.z));
+
+    // Right-clicking empty space dismisses the menu
+    let Some((entity, _, _, _)) = clicked else {
+        menu.close();
+        return;
+    };
+
+    menu.target = Some(entity);
+    menu.screen_pos = screen_pos;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_badge_in_top_left_corner() {
+        let offset = badge_offset(0, 50.0, 20.0);
+        assert_eq!(offset, Vec2::new(-40.0, 40.0));
+    }
+
+    #[test]
+    fn test_badges_wrap_to_next_row() {
+        // 100 wide token with 20 wide badges and 2 gaps fits 4 per row
+        assert_eq!(badge_offset(3, 50.0, 20.0).y, 40.0);
+        let wrapped = badge_offset(4, 50.0, 20.0);
+        assert_eq!(wrapped, Vec2::new(-40.0, 18.0));
+    }
+
+    #[test]
+    fn test_tiny_token_still_places_one_per_row() {
+        let first = badge_offset(0, 4.0, 12.0);
+        let second = badge_offset(1, 4.0, 12.0);
+        assert_eq!(first.x, second.x);
+        assert!(second.y < first.y);
+    }
+}
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use bevy::window::{CursorIcon, PrimaryWindow, SystemCursorIcon};
 use bevy_egui::EguiContexts;
 
+use crate::keybindings::{Action, ActionInput};
 use crate::map::{Layer, Selected};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -10,23 +11,47 @@ pub enum EditorTool {
     Select,
     Place,
     Brush,
+    Fill,
     Draw,
     Line,
-    #[allow(dead_code)] // Text tool disabled - see TODO in all()
+    Shape,
     Text,
+    Eraser,
     Fog,
+    Note,
 }
 
 impl EditorTool {
     pub fn display_name(&self) -> &'static str {
         match self {
-            EditorTool::Select => "Select (V)",
-            EditorTool::Place => "Place (P)",
-            EditorTool::Brush => "Brush (B)",
-            EditorTool::Draw => "Draw (D)",
-            EditorTool::Line => "Line (L)",
-            EditorTool::Text => "Text (T)",
-            EditorTool::Fog => "Fog (F)",
+            EditorTool::Select => "Select",
+            EditorTool::Place => "Place",
+            EditorTool::Brush => "Brush",
+            EditorTool::Fill => "Fill",
+            EditorTool::Draw => "Draw",
+            EditorTool::Line => "Line",
+            EditorTool::Shape => "Shape",
+            EditorTool::Text => "Text",
+            EditorTool::Eraser => "Eraser",
+            EditorTool::Fog => "Fog",
+            EditorTool::Note => "Note",
+        }
+    }
+
+    /// The action that switches to this tool
+    pub fn action(&self) -> Action {
+        match self {
+            EditorTool::Select => Action::ToolSelect,
+            EditorTool::Place => Action::ToolPlace,
+            EditorTool::Brush => Action::ToolBrush,
+            EditorTool::Fill => Action::ToolFill,
+            EditorTool::Draw => Action::ToolDraw,
+            EditorTool::Line => Action::ToolLine,
+            EditorTool::Shape => Action::ToolShape,
+            EditorTool::Text => Action::ToolText,
+            EditorTool::Eraser => Action::ToolEraser,
+            EditorTool::Fog => Action::ToolFog,
+            EditorTool::Note => Action::ToolNote,
         }
     }
 
@@ -35,29 +60,47 @@ impl EditorTool {
             EditorTool::Select => CursorIcon::System(SystemCursorIcon::Default),
             EditorTool::Place => CursorIcon::System(SystemCursorIcon::Crosshair),
             EditorTool::Brush => CursorIcon::System(SystemCursorIcon::Crosshair),
+            EditorTool::Fill => CursorIcon::System(SystemCursorIcon::Crosshair),
             EditorTool::Draw => CursorIcon::System(SystemCursorIcon::Crosshair),
             EditorTool::Line => CursorIcon::System(SystemCursorIcon::Crosshair),
+            EditorTool::Shape => CursorIcon::System(SystemCursorIcon::Crosshair),
             EditorTool::Text => CursorIcon::System(SystemCursorIcon::Text),
+            EditorTool::Eraser => CursorIcon::System(SystemCursorIcon::Crosshair),
             EditorTool::Fog => CursorIcon::System(SystemCursorIcon::Crosshair),
+            EditorTool::Note => CursorIcon::System(SystemCursorIcon::Pointer),
         }
     }
 
-    // TODO: Rewire Text tool when implementation is ready
     pub fn all() -> &'static [EditorTool] {
         &[
             EditorTool::Select,
             EditorTool::Place,
             EditorTool::Brush,
+            EditorTool::Fill,
             EditorTool::Draw,
             EditorTool::Line,
-            // EditorTool::Text, // Disabled - see TODO above
+            EditorTool::Shape,
+            EditorTool::Text,
+            EditorTool::Eraser,
             EditorTool::Fog,
+            EditorTool::Note,
         ]
     }
 
+    /// Tools that place the selected asset on the selected layer
+    pub fn places_assets(&self) -> bool {
+        matches!(self, EditorTool::Place | EditorTool::Brush | EditorTool::Fill)
+    }
+
     pub fn is_annotation_tool(&self) -> bool {
-        // Text tool disabled - see TODO above
-        matches!(self, EditorTool::Draw | EditorTool::Line)
+        matches!(
+            self,
+            EditorTool::Draw
+                | EditorTool::Line
+                | EditorTool::Shape
+                | EditorTool::Text
+                | EditorTool::Eraser
+        )
     }
 }
 
@@ -81,7 +124,7 @@ impl Default for SelectedLayer {
 
 pub fn handle_tool_shortcuts(
     mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     mut current_tool: ResMut<CurrentTool>,
     mut selected_layer: ResMut<SelectedLayer>,
     selected_query: Query<Entity, With<Selected>>,
@@ -94,22 +137,10 @@ pub fn handle_tool_shortcuts(
         return;
     }
 
-    let new_tool = if keyboard.just_pressed(KeyCode::KeyV) || keyboard.just_pressed(KeyCode::KeyS) {
-        Some(EditorTool::Select)
-    } else if keyboard.just_pressed(KeyCode::KeyP) {
-        Some(EditorTool::Place)
-    } else if keyboard.just_pressed(KeyCode::KeyB) {
-        Some(EditorTool::Brush)
-    } else if keyboard.just_pressed(KeyCode::KeyD) {
-        Some(EditorTool::Draw)
-    } else if keyboard.just_pressed(KeyCode::KeyL) {
-        Some(EditorTool::Line)
-    // T key for Text tool disabled - see TODO in all()
-    } else if keyboard.just_pressed(KeyCode::KeyF) {
-        Some(EditorTool::Fog)
-    } else {
-        None
-    };
+    let new_tool = EditorTool::all()
+        .iter()
+        .copied()
+        .find(|tool| keys.just_pressed(tool.action()));
 
     if let Some(tool) = new_tool {
         // Clear selection when switching tools
@@ -121,24 +152,17 @@ pub fn handle_tool_shortcuts(
         current_tool.tool = tool;
     }
 
-    // Handle C/Shift+C to cycle layers when Place or Brush tool is active
-    if (current_tool.tool == EditorTool::Place || current_tool.tool == EditorTool::Brush)
-        && keyboard.just_pressed(KeyCode::KeyC)
-    {
-        let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    // Cycle layers when an asset placing tool is active
+    if current_tool.tool.places_assets() {
         let layers = Layer::all();
         let current_idx = layers.iter().position(|l| *l == selected_layer.layer).unwrap_or(0);
 
-        let new_idx = if shift {
-            // Shift+C: go backwards
-            if current_idx == 0 {
-                layers.len() - 1
-            } else {
-                current_idx - 1
-            }
-        } else {
-            // C: go forwards
+        let new_idx = if keys.just_pressed(Action::NextLayer) {
             (current_idx + 1) % layers.len()
+        } else if keys.just_pressed(Action::PreviousLayer) {
+            (current_idx + layers.len() - 1) % layers.len()
+        } else {
+            return;
         };
 
         selected_layer.layer = layers[new_idx];
@@ -174,37 +198,45 @@ mod tests {
 
     #[test]
     fn test_display_names() {
-        assert_eq!(EditorTool::Select.display_name(), "Select (V)");
-        assert_eq!(EditorTool::Place.display_name(), "Place (P)");
-        assert_eq!(EditorTool::Brush.display_name(), "Brush (B)");
-        assert_eq!(EditorTool::Draw.display_name(), "Draw (D)");
-        assert_eq!(EditorTool::Line.display_name(), "Line (L)");
-        assert_eq!(EditorTool::Text.display_name(), "Text (T)");
-        assert_eq!(EditorTool::Fog.display_name(), "Fog (F)");
+        assert_eq!(EditorTool::Select.display_name(), "Select");
+        assert_eq!(EditorTool::Place.display_name(), "Place");
+        assert_eq!(EditorTool::Brush.display_name(), "Brush");
+        assert_eq!(EditorTool::Fill.display_name(), "Fill");
+        assert_eq!(EditorTool::Draw.display_name(), "Draw");
+        assert_eq!(EditorTool::Line.display_name(), "Line");
+        assert_eq!(EditorTool::Shape.display_name(), "Shape");
+        assert_eq!(EditorTool::Text.display_name(), "Text");
+        assert_eq!(EditorTool::Eraser.display_name(), "Eraser");
+        assert_eq!(EditorTool::Fog.display_name(), "Fog");
+        assert_eq!(EditorTool::Note.display_name(), "Note");
     }
 
     #[test]
-    fn test_display_names_contain_shortcuts() {
-        // Each display name should contain its keyboard shortcut in parentheses
+    fn test_every_tool_has_a_default_shortcut() {
         for tool in EditorTool::all() {
-            let name = tool.display_name();
-            assert!(name.contains('('), "Display name should contain shortcut: {}", name);
-            assert!(name.contains(')'), "Display name should contain shortcut: {}", name);
+            assert!(
+                !tool.action().default_bindings().is_empty(),
+                "{} should have a shortcut",
+                tool.display_name()
+            );
         }
     }
 
     #[test]
     fn test_all_returns_all_tools() {
         let all = EditorTool::all();
-        // Text tool is disabled - see TODO in all()
-        assert_eq!(all.len(), 6);
+        assert_eq!(all.len(), 11);
         assert!(all.contains(&EditorTool::Select));
         assert!(all.contains(&EditorTool::Place));
         assert!(all.contains(&EditorTool::Brush));
+        assert!(all.contains(&EditorTool::Fill));
         assert!(all.contains(&EditorTool::Draw));
         assert!(all.contains(&EditorTool::Line));
-        // assert!(all.contains(&EditorTool::Text)); // Disabled
+        assert!(all.contains(&EditorTool::Shape));
+        assert!(all.contains(&EditorTool::Text));
+        assert!(all.contains(&EditorTool::Eraser));
         assert!(all.contains(&EditorTool::Fog));
+        assert!(all.contains(&EditorTool::Note));
     }
 
     #[test]
@@ -213,13 +245,25 @@ mod tests {
         assert!(!EditorTool::Select.is_annotation_tool());
         assert!(!EditorTool::Place.is_annotation_tool());
         assert!(!EditorTool::Brush.is_annotation_tool());
+        assert!(!EditorTool::Fill.is_annotation_tool());
         assert!(!EditorTool::Fog.is_annotation_tool());
-        // Text tool disabled - see TODO in all()
-        assert!(!EditorTool::Text.is_annotation_tool());
+        assert!(!EditorTool::Note.is_annotation_tool());
 
         // Annotation tools
         assert!(EditorTool::Draw.is_annotation_tool());
         assert!(EditorTool::Line.is_annotation_tool());
+        assert!(EditorTool::Shape.is_annotation_tool());
+        assert!(EditorTool::Text.is_annotation_tool());
+        assert!(EditorTool::Eraser.is_annotation_tool());
+    }
+
+    #[test]
+    fn test_places_assets() {
+        assert!(EditorTool::Place.places_assets());
+        assert!(EditorTool::Brush.places_assets());
+        assert!(EditorTool::Fill.places_assets());
+        assert!(!EditorTool::Select.places_assets());
+        assert!(!EditorTool::Draw.places_assets());
     }
 
     #[test]
@@ -250,7 +294,7 @@ mod tests {
 
     #[test]
     fn test_placement_tools_have_crosshair() {
-        // Place, Brush, Draw, Line, and Fog tools should use crosshair
+        // Place, Brush, Draw, Line, Shape, and Fog tools should use crosshair
         assert_eq!(
             EditorTool::Place.cursor_icon(),
             CursorIcon::System(SystemCursorIcon::Crosshair)
@@ -267,6 +311,10 @@ mod tests {
             EditorTool::Line.cursor_icon(),
             CursorIcon::System(SystemCursorIcon::Crosshair)
         );
+        assert_eq!(
+            EditorTool::Shape.cursor_icon(),
+            CursorIcon::System(SystemCursorIcon::Crosshair)
+        );
         assert_eq!(
             EditorTool::Fog.cursor_icon(),
             CursorIcon::System(SystemCursorIcon::Crosshair)
@@ -0,0 +1,448 @@
+//! Every keyboard action and its default bindings.
+
+use bevy::prelude::*;
+
+use super::binding::KeyBinding;
+
+/// Where an action is listed in the help popup and the settings dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionCategory {
+    Tools,
+    Selection,
+    Edit,
+    Camera,
+    Combat,
+    Assets,
+    File,
+}
+
+impl ActionCategory {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ActionCategory::Tools => "Tools",
+            ActionCategory::Selection => "Selection",
+            ActionCategory::Edit => "Edit",
+            ActionCategory::Camera => "Camera",
+            ActionCategory::Combat => "Combat",
+            ActionCategory::Assets => "Assets",
+            ActionCategory::File => "File",
+        }
+    }
+
+    pub fn all() -> &'static [ActionCategory] {
+        &[
+            ActionCategory::Tools,
+            ActionCategory::Selection,
+            ActionCategory::Edit,
+            ActionCategory::Camera,
+            ActionCategory::Combat,
+            ActionCategory::Assets,
+            ActionCategory::File,
+        ]
+    }
+}
+
+/// Something a keyboard shortcut can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    ToolSelect,
+    ToolPlace,
+    ToolBrush,
+    ToolFill,
+    ToolDraw,
+    ToolLine,
+    ToolShape,
+    ToolText,
+    ToolEraser,
+    ToolFog,
+    ToolNote,
+    NextLayer,
+    PreviousLayer,
+    SelectAll,
+    InvertSelection,
+    ClearSelection,
+    DeleteSelection,
+    FitToGrid,
+    CenterToGrid,
+    RestoreAspectRatio,
+    RotateClockwise,
+    RotateCounterClockwise,
+    AlignLeft,
+    AlignRight,
+    AlignTop,
+    AlignBottom,
+    AlignCenter,
+    AlignMiddle,
+    DistributeHorizontally,
+    DistributeVertically,
+    Copy,
+    Cut,
+    Paste,
+    Duplicate,
+    Undo,
+    Redo,
+    RecallBookmark1,
+    RecallBookmark2,
+    RecallBookmark3,
+    RecallBookmark4,
+    RecallBookmark5,
+    RecallBookmark6,
+    RecallBookmark7,
+    RecallBookmark8,
+    RecallBookmark9,
+    ToggleInitiative,
+    RenameAsset,
+    RenameMap,
+    RenameLibrary,
+    NewMap,
+    SaveMap,
+    SaveMapAs,
+    ToggleHelp,
+}
+
+impl Action {
+    pub fn all() -> &'static [Action] {
+        &[
+            Action::ToolSelect,
+            Action::ToolPlace,
+            Action::ToolBrush,
+            Action::ToolFill,
+            Action::ToolDraw,
+            Action::ToolLine,
+            Action::ToolShape,
+            Action::ToolText,
+            Action::ToolEraser,
+            Action::ToolFog,
+            Action::ToolNote,
+            Action::NextLayer,
+            Action::PreviousLayer,
+            Action::SelectAll,
+            Action::InvertSelection,
+            Action::ClearSelection,
+            Action::DeleteSelection,
+            Action::FitToGrid,
+            Action::CenterToGrid,
+            Action::RestoreAspectRatio,
+            Action::RotateClockwise,
+            Action::RotateCounterClockwise,
+            Action::AlignLeft,
+            Action::AlignRight,
+            Action::AlignTop,
+            Action::AlignBottom,
+            Action::AlignCenter,
+            Action::AlignMiddle,
+            Action::DistributeHorizontally,
+            Action::DistributeVertically,
+            Action::Copy,
+            Action::Cut,
+            Action::Paste,
+            Action::Duplicate,
+            Action::Undo,
+            Action::Redo,
+            Action::RecallBookmark1,
+            Action::RecallBookmark2,
+            Action::RecallBookmark3,
+            Action::RecallBookmark4,
+            Action::RecallBookmark5,
+            Action::RecallBookmark6,
+            Action::RecallBookmark7,
+            Action::RecallBookmark8,
+            Action::RecallBookmark9,
+            Action::ToggleInitiative,
+            Action::RenameAsset,
+            Action::RenameMap,
+            Action::RenameLibrary,
+            Action::NewMap,
+            Action::SaveMap,
+            Action::SaveMapAs,
+            Action::ToggleHelp,
+        ]
+    }
+
+    /// Stable name used in the config file
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::ToolSelect => "tool_select",
+            Action::ToolPlace => "tool_place",
+            Action::ToolBrush => "tool_brush",
+            Action::ToolFill => "tool_fill",
+            Action::ToolDraw => "tool_draw",
+            Action::ToolLine => "tool_line",
+            Action::ToolShape => "tool_shape",
+            Action::ToolText => "tool_text",
+            Action::ToolEraser => "tool_eraser",
+            Action::ToolFog => "tool_fog",
+            Action::ToolNote => "tool_note",
+            Action::NextLayer => "next_layer",
+            Action::PreviousLayer => "previous_layer",
+            Action::SelectAll => "select_all",
+            Action::InvertSelection => "invert_selection",
+            Action::ClearSelection => "clear_selection",
+            Action::DeleteSelection => "delete_selection",
+            Action::FitToGrid => "fit_to_grid",
+            Action::CenterToGrid => "center_to_grid",
+            Action::RestoreAspectRatio => "restore_aspect_ratio",
+            Action::RotateClockwise => "rotate_clockwise",
+            Action::RotateCounterClockwise => "rotate_counter_clockwise",
+            Action::AlignLeft => "align_left",
+            Action::AlignRight => "align_right",
+            Action::AlignTop => "align_top",
+            Action::AlignBottom => "align_bottom",
+            Action::AlignCenter => "align_center",
+            Action::AlignMiddle => "align_middle",
+            Action::DistributeHorizontally => "distribute_horizontally",
+            Action::DistributeVertically => "distribute_vertically",
+            Action::Copy => "copy",
+            Action::Cut => "cut",
+            Action::Paste => "paste",
+            Action::Duplicate => "duplicate",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::RecallBookmark1 => "recall_bookmark_1",
+            Action::RecallBookmark2 => "recall_bookmark_2",
+            Action::RecallBookmark3 => "recall_bookmark_3",
+            Action::RecallBookmark4 => "recall_bookmark_4",
+            Action::RecallBookmark5 => "recall_bookmark_5",
+            Action::RecallBookmark6 => "recall_bookmark_6",
+            Action::RecallBookmark7 => "recall_bookmark_7",
+            Action::RecallBookmark8 => "recall_bookmark_8",
+            Action::RecallBookmark9 => "recall_bookmark_9",
+            Action::ToggleInitiative => "toggle_initiative",
+            Action::RenameAsset => "rename_asset",
+            Action::RenameMap => "rename_map",
+            Action::RenameLibrary => "rename_library",
+            Action::NewMap => "new_map",
+            Action::SaveMap => "save_map",
+            Action::SaveMapAs => "save_map_as",
+            Action::ToggleHelp => "toggle_help",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Action> {
+        Action::all()
+            .iter()
+            .copied()
+            .find(|action| action.id() == id)
+    }
+
+    /// The number-key action that recalls the camera bookmark at `index`
+    pub fn recall_bookmark(index: usize) -> Option<Action> {
+        [
+            Action::RecallBookmark1,
+            Action::RecallBookmark2,
+            Action::RecallBookmark3,
+            Action::RecallBookmark4,
+            Action::RecallBookmark5,
+            Action::RecallBookmark6,
+            Action::RecallBookmark7,
+            Action::RecallBookmark8,
+            Action::RecallBookmark9,
+        ]
+        .get(index)
+        .copied()
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Action::ToolSelect => "Select - Click to select items, drag to move",
+            Action::ToolPlace => "Place - Single-click to place selected asset",
+            Action::ToolBrush => "Brush - Drag to continuously place assets",
+            Action::ToolFill => "Fill - Tile a rectangle or polygon of cells",
+            Action::ToolDraw => "Draw - Freehand annotation paths",
+            Action::ToolLine => "Line - Straight line annotations",
+            Action::ToolShape => "Shape - Rectangles, ellipses, polygons and arrows",
+            Action::ToolText => "Text - Multi-line text labels",
+            Action::ToolEraser => "Eraser - Erase parts of paths and lines",
+            Action::ToolFog => "Fog - Reveal/hide fog of war areas",
+            Action::ToolNote => "Note - Pin GM notes to the map",
+            Action::NextLayer => "Next layer (Place/Brush/Fill tools)",
+            Action::PreviousLayer => "Previous layer (Place/Brush/Fill tools)",
+            Action::SelectAll => "Select all",
+            Action::InvertSelection => "Invert selection",
+            Action::ClearSelection => "Clear selection",
+            Action::DeleteSelection => "Delete selected items",
+            Action::FitToGrid => "Fit selected to grid cell",
+            Action::CenterToGrid => "Center selected to grid",
+            Action::RestoreAspectRatio => "Restore aspect ratio",
+            Action::RotateClockwise => "Rotate 90° CW",
+            Action::RotateCounterClockwise => "Rotate 90° CCW",
+            Action::AlignLeft => "Align selected left",
+            Action::AlignRight => "Align selected right",
+            Action::AlignTop => "Align selected top",
+            Action::AlignBottom => "Align selected bottom",
+            Action::AlignCenter => "Align centers",
+            Action::AlignMiddle => "Align middles",
+            Action::DistributeHorizontally => "Distribute horizontally",
+            Action::DistributeVertically => "Distribute vertically",
+            Action::Copy => "Copy selected items",
+            Action::Cut => "Cut selected items",
+            Action::Paste => "Paste items",
+            Action::Duplicate => "Duplicate by the last paste offset",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::RecallBookmark1 => "Go to camera bookmark 1",
+            Action::RecallBookmark2 => "Go to camera bookmark 2",
+            Action::RecallBookmark3 => "Go to camera bookmark 3",
+            Action::RecallBookmark4 => "Go to camera bookmark 4",
+            Action::RecallBookmark5 => "Go to camera bookmark 5",
+            Action::RecallBookmark6 => "Go to camera bookmark 6",
+            Action::RecallBookmark7 => "Go to camera bookmark 7",
+            Action::RecallBookmark8 => "Go to camera bookmark 8",
+            Action::RecallBookmark9 => "Go to camera bookmark 9",
+            Action::ToggleInitiative => "Toggle initiative tracker",
+            Action::RenameAsset => "Rename selected asset",
+            Action::RenameMap => "Rename current map",
+            Action::RenameLibrary => "Rename library",
+            Action::NewMap => "New map",
+            Action::SaveMap => "Save map",
+            Action::SaveMapAs => "Save as...",
+            Action::ToggleHelp => "Toggle the help window",
+        }
+    }
+
+    pub fn category(&self) -> ActionCategory {
+        match self {
+            Action::ToolSelect
+            | Action::ToolPlace
+            | Action::ToolBrush
+            | Action::ToolFill
+            | Action::ToolDraw
+            | Action::ToolLine
+            | Action::ToolShape
+            | Action::ToolText
+            | Action::ToolEraser
+            | Action::ToolFog
+            | Action::ToolNote
+            | Action::NextLayer
+            | Action::PreviousLayer => ActionCategory::Tools,
+            Action::SelectAll
+            | Action::InvertSelection
+            | Action::ClearSelection
+            | Action::DeleteSelection
+            | Action::FitToGrid
+            | Action::CenterToGrid
+            | Action::RestoreAspectRatio
+            | Action::RotateClockwise
+            | Action::RotateCounterClockwise
+            | Action::AlignLeft
+            | Action::AlignRight
+            | Action::AlignTop
+            | Action::AlignBottom
+            | Action::AlignCenter
+            | Action::AlignMiddle
+            | Action::DistributeHorizontally
+            | Action::DistributeVertically => ActionCategory::Selection,
+            Action::Copy
+            | Action::Cut
+            | Action::Paste
+            | Action::Duplicate
+            | Action::Undo
+            | Action::Redo => ActionCategory::Edit,
+            Action::RecallBookmark1
+            | Action::RecallBookmark2
+            | Action::RecallBookmark3
+            | Action::RecallBookmark4
+            | Action::RecallBookmark5
+            | Action::RecallBookmark6
+            | Action::RecallBookmark7
+            | Action::RecallBookmark8
+            | Action::RecallBookmark9 => ActionCategory::Camera,
+            Action::ToggleInitiative => ActionCategory::Combat,
+            Action::RenameAsset | Action::RenameMap | Action::RenameLibrary => {
+                ActionCategory::Assets
+            }
+            Action::NewMap | Action::SaveMap | Action::SaveMapAs | Action::ToggleHelp => {
+                ActionCategory::File
+            }
+        }
+    }
+
+    pub fn default_bindings(&self) -> Vec<KeyBinding> {
+        let key = KeyBinding::key;
+        match self {
+            Action::ToolSelect => vec![key(KeyCode::KeyV), key(KeyCode::KeyS)],
+            Action::ToolPlace => vec![key(KeyCode::KeyP)],
+            Action::ToolBrush => vec![key(KeyCode::KeyB)],
+            Action::ToolFill => vec![key(KeyCode::KeyK)],
+            Action::ToolDraw => vec![key(KeyCode::KeyD)],
+            Action::ToolLine => vec![key(KeyCode::KeyL)],
+            Action::ToolShape => vec![key(KeyCode::KeyU)],
+            Action::ToolText => vec![key(KeyCode::KeyT)],
+            Action::ToolEraser => vec![key(KeyCode::KeyE)],
+            Action::ToolFog => vec![key(KeyCode::KeyF)],
+            Action::ToolNote => vec![key(KeyCode::KeyN)],
+            Action::NextLayer => vec![key(KeyCode::KeyC)],
+            Action::PreviousLayer => vec![key(KeyCode::KeyC).shift()],
+            Action::SelectAll => vec![key(KeyCode::KeyA).ctrl()],
+            Action::InvertSelection => vec![key(KeyCode::KeyI).ctrl().shift()],
+            Action::ClearSelection => vec![key(KeyCode::Escape)],
+            Action::DeleteSelection => vec![key(KeyCode::Delete), key(KeyCode::Backspace)],
+            Action::FitToGrid => vec![key(KeyCode::KeyG)],
+            Action::CenterToGrid => vec![key(KeyCode::KeyG).shift()],
+            Action::RestoreAspectRatio => vec![key(KeyCode::KeyA)],
+            Action::RotateClockwise => vec![key(KeyCode::KeyR)],
+            Action::RotateCounterClockwise => vec![key(KeyCode::KeyR).shift()],
+            Action::AlignLeft => vec![key(KeyCode::ArrowLeft).alt()],
+            Action::AlignRight => vec![key(KeyCode::ArrowRight).alt()],
+            Action::AlignTop => vec![key(KeyCode::ArrowUp).alt()],
+            Action::AlignBottom => vec![key(KeyCode::ArrowDown).alt()],
+            Action::AlignCenter => vec![
+                key(KeyCode::ArrowLeft).alt().shift(),
+                key(KeyCode::ArrowRight).alt().shift(),
+            ],
+            Action::AlignMiddle => vec![
+                key(KeyCode::ArrowUp).alt().shift(),
+                key(KeyCode::ArrowDown).alt().shift(),
+            ],
+            Action::DistributeHorizontally => vec![key(KeyCode::KeyH).alt().shift()],
+            Action::DistributeVertically => vec![key(KeyCode::KeyV).alt().shift()],
+            Action::Copy => vec![key(KeyCode::KeyC).ctrl()],
+            Action::Cut => vec![key(KeyCode::KeyX).ctrl()],
+            Action::Paste => vec![key(KeyCode::KeyV).ctrl()],
+            Action::Duplicate => vec![key(KeyCode::KeyD).ctrl()],
+            Action::Undo => vec![key(KeyCode::KeyZ).ctrl()],
+            Action::Redo => vec![key(KeyCode::KeyY).ctrl(), key(KeyCode::KeyZ).ctrl().shift()],
+            Action::RecallBookmark1 => vec![key(KeyCode::Digit1)],
+            Action::RecallBookmark2 => vec![key(KeyCode::Digit2)],
+            Action::RecallBookmark3 => vec![key(KeyCode::Digit3)],
+            Action::RecallBookmark4 => vec![key(KeyCode::Digit4)],
+            Action::RecallBookmark5 => vec![key(KeyCode::Digit5)],
+            Action::RecallBookmark6 => vec![key(KeyCode::Digit6)],
+            Action::RecallBookmark7 => vec![key(KeyCode::Digit7)],
+            Action::RecallBookmark8 => vec![key(KeyCode::Digit8)],
+            Action::RecallBookmark9 => vec![key(KeyCode::Digit9)],
+            Action::ToggleInitiative => vec![key(KeyCode::KeyI)],
+            Action::RenameAsset => vec![key(KeyCode::F2)],
+            Action::RenameMap => vec![key(KeyCode::F3)],
+            Action::RenameLibrary => vec![key(KeyCode::F4)],
+            Action::NewMap => vec![key(KeyCode::KeyN).ctrl()],
+            Action::SaveMap => vec![key(KeyCode::KeyS).ctrl()],
+            Action::SaveMapAs => vec![key(KeyCode::KeyS).ctrl().shift()],
+            Action::ToggleHelp => vec![key(KeyCode::KeyH)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_round_trip() {
+        for action in Action::all() {
+            assert_eq!(Action::from_id(action.id()), Some(*action));
+        }
+        assert_eq!(Action::from_id("not_an_action"), None);
+    }
+
+    #[test]
+    fn test_recall_bookmark_actions() {
+        assert_eq!(Action::recall_bookmark(0), Some(Action::RecallBookmark1));
+        assert_eq!(Action::recall_bookmark(8), Some(Action::RecallBookmark9));
+        assert_eq!(Action::recall_bookmark(9), None);
+        assert_eq!(
+            Action::RecallBookmark9.default_bindings(),
+            vec![KeyBinding::key(KeyCode::Digit9)]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! A single key combination and its text form.
+
+use std::fmt;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+
+/// Keys that can be bound, with the names used in labels and the config file
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::KeyA, "A"),
+    (KeyCode::KeyB, "B"),
+    (KeyCode::KeyC, "C"),
+    (KeyCode::KeyD, "D"),
+    (KeyCode::KeyE, "E"),
+    (KeyCode::KeyF, "F"),
+    (KeyCode::KeyG, "G"),
+    (KeyCode::KeyH, "H"),
+    (KeyCode::KeyI, "I"),
+    (KeyCode::KeyJ, "J"),
+    (KeyCode::KeyK, "K"),
+    (KeyCode::KeyL, "L"),
+    (KeyCode::KeyM, "M"),
+    (KeyCode::KeyN, "N"),
+    (KeyCode::KeyO, "O"),
+    (KeyCode::KeyP, "P"),
+    (KeyCode::KeyQ, "Q"),
+    (KeyCode::KeyR, "R"),
+    (KeyCode::KeyS, "S"),
+    (KeyCode::KeyT, "T"),
+    (KeyCode::KeyU, "U"),
+    (KeyCode::KeyV, "V"),
+    (KeyCode::KeyW, "W"),
+    (KeyCode::KeyX, "X"),
+    (KeyCode::KeyY, "Y"),
+    (KeyCode::KeyZ, "Z"),
+    (KeyCode::Digit0, "0"),
+    (KeyCode::Digit1, "1"),
+    (KeyCode::Digit2, "2"),
+    (KeyCode::Digit3, "3"),
+    (KeyCode::Digit4, "4"),
+    (KeyCode::Digit5, "5"),
+    (KeyCode::Digit6, "6"),
+    (KeyCode::Digit7, "7"),
+    (KeyCode::Digit8, "8"),
+    (KeyCode::Digit9, "9"),
+    (KeyCode::F1, "F1"),
+    (KeyCode::F2, "F2"),
+    (KeyCode::F3, "F3"),
+    (KeyCode::F4, "F4"),
+    (KeyCode::F5, "F5"),
+    (KeyCode::F6, "F6"),
+    (KeyCode::F7, "F7"),
+    (KeyCode::F8, "F8"),
+    (KeyCode::F9, "F9"),
+    (KeyCode::F10, "F10"),
+    (KeyCode::F11, "F11"),
+    (KeyCode::F12, "F12"),
+    (KeyCode::ArrowLeft, "Left"),
+    (KeyCode::ArrowRight, "Right"),
+    (KeyCode::ArrowUp, "Up"),
+    (KeyCode::ArrowDown, "Down"),
+    (KeyCode::Escape, "Escape"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Space, "Space"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Minus, "-"),
+    (KeyCode::Equal, "="),
+    (KeyCode::BracketLeft, "["),
+    (KeyCode::BracketRight, "]"),
+    (KeyCode::Semicolon, ";"),
+    (KeyCode::Quote, "'"),
+    (KeyCode::Comma, ","),
+    (KeyCode::Period, "."),
+    (KeyCode::Slash, "/"),
+    (KeyCode::Backslash, "\\"),
+    (KeyCode::Backquote, "`"),
+];
+
+fn key_name(key: KeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(code, _)| *code == key)
+        .map(|(_, name)| *name)
+}
+
+fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, key_name)| key_name.eq_ignore_ascii_case(name))
+        .map(|(code, _)| *code)
+}
+
+/// A key pressed with an exact set of modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub const fn key(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub const fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// The modifiers currently held, as (ctrl, shift, alt)
+    fn held_modifiers(keyboard: &ButtonInput<KeyCode>) -> (bool, bool, bool) {
+        (
+            keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+        )
+    }
+
+    /// Whether the key was just pressed with exactly these modifiers, so G
+    /// and Shift+G can do different things
+    pub fn just_pressed(&self, keyboard: &ButtonInput<KeyCode>) -> bool {
+        keyboard.just_pressed(self.key)
+            && Self::held_modifiers(keyboard) == (self.ctrl, self.shift, self.alt)
+    }
+
+    /// The bindable key just pressed along with the modifiers held, for
+    /// recording a new binding
+    pub fn capture(keyboard: &ButtonInput<KeyCode>) -> Option<Self> {
+        let key = keyboard
+            .get_just_pressed()
+            .copied()
+            .find(|key| key_name(*key).is_some())?;
+        let (ctrl, shift, alt) = Self::held_modifiers(keyboard);
+        Some(Self {
+            key,
+            ctrl,
+            shift,
+            alt,
+        })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        match key_name(self.key) {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{:?}", self.key),
+        }
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        // "Ctrl++" would split into an empty last part; there's no Plus key
+        let key_part = parts.pop().filter(|part| !part.is_empty());
+        let key = key_part
+            .and_then(key_from_name)
+            .ok_or_else(|| format!("Unknown key in shortcut '{}'", text))?;
+
+        let mut binding = Self::key(key);
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => binding.ctrl = true,
+                "shift" => binding.shift = true,
+                "alt" => binding.alt = true,
+                _ => {
+                    return Err(format!(
+                        "Unknown modifier '{}' in shortcut '{}'",
+                        modifier, text
+                    ))
+                }
+            }
+        }
+        Ok(binding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binding_text_round_trip() {
+        let binding = KeyBinding::key(KeyCode::ArrowLeft).alt().shift();
+        assert_eq!(binding.to_string(), "Shift+Alt+Left");
+        assert_eq!("Shift+Alt+Left".parse::<KeyBinding>(), Ok(binding));
+        assert_eq!(
+            "ctrl + z".parse::<KeyBinding>(),
+            Ok(KeyBinding::key(KeyCode::KeyZ).ctrl())
+        );
+        assert!("Ctrl+".parse::<KeyBinding>().is_err());
+        assert!("Hyper+A".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_modifiers_must_match_exactly() {
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        keyboard.press(KeyCode::ShiftLeft);
+        keyboard.press(KeyCode::KeyG);
+
+        assert!(KeyBinding::key(KeyCode::KeyG)
+            .shift()
+            .just_pressed(&keyboard));
+        assert!(!KeyBinding::key(KeyCode::KeyG).just_pressed(&keyboard));
+        assert_eq!(
+            KeyBinding::capture(&keyboard),
+            Some(KeyBinding::key(KeyCode::KeyG).shift())
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Keyboard shortcuts.
+//!
+//! Every shortcut is an [`Action`] with default bindings. Users can rebind
+//! actions in the settings dialog; only actions whose bindings differ from
+//! the defaults are stored in the config file. Systems check actions through
+//! [`ActionInput`] instead of reading key codes, so a rebinding applies
+//! everywhere at once, including the help popup and the toolbar labels.
+
+mod action;
+mod binding;
+
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::config::{AppConfig, ConfigLoaded};
+
+pub use action::{Action, ActionCategory};
+pub use binding::KeyBinding;
+
+/// The live binding of every action
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Keybindings {
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            bindings: Action::all()
+                .iter()
+                .map(|action| (*action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl Keybindings {
+    /// Defaults with the overrides from the config file applied. Unknown
+    /// actions and keys are skipped so an old or hand-edited config still
+    /// loads.
+    pub fn from_config(overrides: &BTreeMap<String, Vec<String>>) -> Self {
+        let mut keybindings = Self::default();
+        for (id, keys) in overrides {
+            let Some(action) = Action::from_id(id) else {
+                warn!("Ignoring shortcut for unknown action '{}'", id);
+                continue;
+            };
+            let bindings = keys
+                .iter()
+                .filter_map(|key| {
+                    key.parse()
+                        .inspect_err(|e| warn!("Ignoring shortcut for '{}': {}", id, e))
+                        .ok()
+                })
+                .collect();
+            keybindings.set(action, bindings);
+        }
+        keybindings
+    }
+
+    /// Bindings that differ from the defaults, in config file form
+    pub fn to_config(&self) -> BTreeMap<String, Vec<String>> {
+        Action::all()
+            .iter()
+            .filter(|action| !self.is_default(**action))
+            .map(|action| {
+                let keys = self.get(*action).iter().map(|b| b.to_string()).collect();
+                (action.id().to_string(), keys)
+            })
+            .collect()
+    }
+
+    pub fn get(&self, action: Action) -> &[KeyBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn set(&mut self, action: Action, bindings: Vec<KeyBinding>) {
+        self.bindings.insert(action, bindings);
+    }
+
+    /// Add a binding, unless the action already has it
+    pub fn add(&mut self, action: Action, binding: KeyBinding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn remove(&mut self, action: Action, binding: KeyBinding) {
+        if let Some(bindings) = self.bindings.get_mut(&action) {
+            bindings.retain(|b| *b != binding);
+        }
+    }
+
+    pub fn reset(&mut self, action: Action) {
+        self.set(action, action.default_bindings());
+    }
+
+    pub fn is_default(&self, action: Action) -> bool {
+        self.get(action) == action.default_bindings().as_slice()
+    }
+
+    /// The bindings as shown to users, e.g. "Delete / Backspace"
+    pub fn label(&self, action: Action) -> String {
+        let bindings = self.get(action);
+        if bindings.is_empty() {
+            return "Unbound".to_string();
+        }
+        bindings
+            .iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    /// Other actions that `binding` also triggers
+    pub fn conflicts(&self, action: Action, binding: KeyBinding) -> Vec<Action> {
+        Action::all()
+            .iter()
+            .copied()
+            .filter(|other| *other != action && self.get(*other).contains(&binding))
+            .collect()
+    }
+
+    /// Whether any key combination is bound to more than one action
+    pub fn has_conflicts(&self) -> bool {
+        Action::all().iter().any(|action| {
+            self.get(*action)
+                .iter()
+                .any(|binding| !self.conflicts(*action, *binding).is_empty())
+        })
+    }
+
+    pub fn just_pressed(&self, action: Action, keyboard: &ButtonInput<KeyCode>) -> bool {
+        self.get(action).iter().any(|b| b.just_pressed(keyboard))
+    }
+}
+
+/// Keyboard input read through the live bindings
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    bindings: Res<'w, Keybindings>,
+}
+
+impl ActionInput<'_> {
+    pub fn bindings(&self) -> &Keybindings {
+        &self.bindings
+    }
+
+    /// Whether a binding of `action` was pressed this frame
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.bindings.just_pressed(action, &self.keyboard)
+    }
+}
+
+/// Apply the bindings saved in the config file
+fn load_keybindings(config: Res<AppConfig>, mut keybindings: ResMut<Keybindings>) {
+    *keybindings = Keybindings::from_config(&config.data.keybindings);
+}
+
+pub struct KeybindingsPlugin;
+
+impl Plugin for KeybindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Keybindings>()
+            .add_systems(Startup, load_keybindings.after(ConfigLoaded));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_have_no_conflicts() {
+        let keybindings = Keybindings::default();
+        assert!(!keybindings.has_conflicts());
+        assert!(keybindings.to_config().is_empty());
+        assert_eq!(
+            keybindings.label(Action::DeleteSelection),
+            "Delete / Backspace"
+        );
+    }
+
+    #[test]
+    fn test_overrides_round_trip_through_config() {
+        let mut keybindings = Keybindings::default();
+        keybindings.set(Action::ToolFog, vec![KeyBinding::key(KeyCode::KeyQ)]);
+        keybindings.set(Action::ToggleHelp, Vec::new());
+
+        let config = keybindings.to_config();
+        assert_eq!(config.len(), 2);
+        assert_eq!(config["tool_fog"], vec!["Q".to_string()]);
+        assert_eq!(Keybindings::from_config(&config), keybindings);
+        assert_eq!(keybindings.label(Action::ToggleHelp), "Unbound");
+    }
+
+    #[test]
+    fn test_from_config_skips_unknown_entries() {
+        let mut config = BTreeMap::new();
+        config.insert("no_such_action".to_string(), vec!["Q".to_string()]);
+        config.insert(
+            "undo".to_string(),
+            vec!["Ctrl+Z".to_string(), "Ctrl+Nope".to_string()],
+        );
+        let keybindings = Keybindings::from_config(&config);
+        assert!(keybindings.is_default(Action::Undo));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut keybindings = Keybindings::default();
+        let g = KeyBinding::key(KeyCode::KeyG);
+        keybindings.add(Action::ToolFog, g);
+        assert_eq!(
+            keybindings.conflicts(Action::ToolFog, g),
+            vec![Action::FitToGrid]
+        );
+        assert!(keybindings.has_conflicts());
+
+        keybindings.remove(Action::ToolFog, g);
+        assert!(!keybindings.has_conflicts());
+    }
+}
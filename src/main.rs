@@ -5,7 +5,9 @@ mod assets;
 mod common;
 mod config;
 mod constants;
+mod dice;
 mod editor;
+mod keybindings;
 mod map;
 mod paths;
 mod session;
@@ -112,6 +114,7 @@ fn main() {
         )
         .add_plugins(EguiPlugin::default())
         .add_plugins(config::ConfigPlugin)
+        .add_plugins(keybindings::KeybindingsPlugin)
         .add_plugins(editor::EditorPlugin)
         .add_plugins(assets::AssetLibraryPlugin)
         .add_plugins(map::MapPlugin)
@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The player viewport as it was when a bookmark was saved
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BookmarkViewport {
+    pub center: Vec2,
+    /// Width in world units; the height follows the player monitor's aspect
+    /// ratio, which may differ between sessions
+    pub width: f32,
+    pub rotation_degrees: i32,
+}
+
+/// A named camera position, e.g. "Tavern" or "Boss room"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    pub name: String,
+    /// World position at the center of the editor camera
+    pub position: Vec2,
+    /// Editor camera zoom (orthographic scale)
+    pub zoom: f32,
+    /// Player viewport, when the bookmark was saved during a live session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<BookmarkViewport>,
+}
+
+/// Camera bookmarks for the current map. The first nine are recalled with
+/// the number keys, so their order matters.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CameraBookmarks {
+    pub bookmarks: Vec<CameraBookmark>,
+}
+
+impl CameraBookmarks {
+    /// Add a bookmark, naming it "View N" if `name` is blank
+    pub fn add(
+        &mut self,
+        name: &str,
+        position: Vec2,
+        zoom: f32,
+        viewport: Option<BookmarkViewport>,
+    ) {
+        let name = match name.trim() {
+            "" => format!("View {}", self.bookmarks.len() + 1),
+            name => name.to_string(),
+        };
+        self.bookmarks.push(CameraBookmark {
+            name,
+            position,
+            zoom,
+            viewport,
+        });
+    }
+
+    pub fn get(&self, index: usize) -> Option<&CameraBookmark> {
+        self.bookmarks.get(index)
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.bookmarks.len() {
+            self.bookmarks.remove(index);
+        }
+    }
+
+    /// Swap a bookmark with the one before it, changing its number key
+    pub fn move_up(&mut self, index: usize) {
+        if index > 0 && index < self.bookmarks.len() {
+            self.bookmarks.swap(index - 1, index);
+        }
+    }
+
+    /// Remove all bookmarks
+    pub fn clear(&mut self) {
+        self.bookmarks.clear();
+    }
+
+    /// Convert to the persistence format
+    pub fn to_saved(&self) -> Vec<CameraBookmark> {
+        self.bookmarks.clone()
+    }
+
+    /// Restore from the persistence format
+    pub fn from_saved(saved: &[CameraBookmark]) -> Self {
+        Self {
+            bookmarks: saved.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_names_blank_bookmarks() {
+        let mut bookmarks = CameraBookmarks::default();
+        bookmarks.add(" Tavern ", Vec2::ZERO, 1.0, None);
+        bookmarks.add("  ", Vec2::ONE, 2.0, None);
+
+        assert_eq!(bookmarks.get(0).unwrap().name, "Tavern");
+        assert_eq!(bookmarks.get(1).unwrap().name, "View 2");
+        assert!(bookmarks.get(2).is_none());
+    }
+
+    #[test]
+    fn test_move_up_and_remove() {
+        let mut bookmarks = CameraBookmarks::default();
+        for name in ["A", "B", "C"] {
+            bookmarks.add(name, Vec2::ZERO, 1.0, None);
+        }
+        bookmarks.move_up(2);
+        bookmarks.move_up(0);
+        bookmarks.remove(5);
+        let names: Vec<&str> = bookmarks
+            .bookmarks
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(names, ["A", "C", "B"]);
+
+        bookmarks.remove(0);
+        assert_eq!(bookmarks.get(0).unwrap().name, "C");
+    }
+
+    #[test]
+    fn test_saved_round_trip() {
+        let mut bookmarks = CameraBookmarks::default();
+        bookmarks.add("Tavern", Vec2::new(120.0, -40.0), 0.5, None);
+        bookmarks.add(
+            "Boss room",
+            Vec2::new(900.0, 300.0),
+            1.5,
+            Some(BookmarkViewport {
+                center: Vec2::new(880.0, 310.0),
+                width: 700.0,
+                rotation_degrees: 90,
+            }),
+        );
+
+        let json = serde_json::to_string(&bookmarks.to_saved()).unwrap();
+        // Bookmarks saved outside a session don't store a viewport
+        assert_eq!(json.matches("viewport").count(), 1);
+
+        let saved: Vec<CameraBookmark> = serde_json::from_str(&json).unwrap();
+        let restored = CameraBookmarks::from_saved(&saved);
+        assert_eq!(restored.bookmarks, bookmarks.bookmarks);
+    }
+}
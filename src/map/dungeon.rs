@@ -0,0 +1,597 @@
+//! Procedural dungeon generation.
+//!
+//! The map area is split with a binary space partition until there is one
+//! leaf per room, a room is carved inside each leaf, and the two halves of
+//! every split are joined by an L-shaped corridor between their closest
+//! rooms. Corridors get a door where they leave a room, and every empty cell
+//! touching the floor becomes wall.
+//!
+//! Floors go on the Terrain layer with walls above them, and doors on the
+//! Doodad layer. Generation is a pure function of its parameters, so the same
+//! seed always gives the same map.
+
+use bevy::prelude::*;
+
+use super::fog::cell_to_world;
+use super::layer::Layer;
+use super::map_data::{AssetManifest, MapData, SavedMap, SavedPlacedItem};
+
+/// Smallest and largest dungeon side, in cells
+pub const DUNGEON_SIZE_RANGE: std::ops::RangeInclusive<u32> = 12..=120;
+
+/// Most rooms a dungeon may ask for
+pub const MAX_DUNGEON_ROOMS: u32 = 40;
+
+/// Smallest room side, in floor cells
+const MIN_ROOM_CELLS: i32 = 3;
+
+/// Smallest BSP leaf side: a room plus a wall on each side
+const MIN_LEAF_CELLS: i32 = MIN_ROOM_CELLS + 2;
+
+/// Settings for a generated dungeon
+#[derive(Debug, Clone, PartialEq)]
+pub struct DungeonParams {
+    /// Map width in grid cells
+    pub width: u32,
+    /// Map height in grid cells
+    pub height: u32,
+    /// Rooms to aim for; small maps may fit fewer
+    pub room_count: u32,
+    pub seed: u64,
+}
+
+impl Default for DungeonParams {
+    fn default() -> Self {
+        Self {
+            width: 40,
+            height: 30,
+            room_count: 8,
+            seed: 1,
+        }
+    }
+}
+
+/// An asset the generator may place
+#[derive(Debug, Clone, PartialEq)]
+pub struct DungeonAsset {
+    pub asset_path: String,
+    /// Image size in pixels, used to fit the asset to a cell
+    pub size: Option<Vec2>,
+}
+
+/// Assets to build the dungeon from. Each cell picks one of its kind at
+/// random; a kind with no assets isn't placed.
+#[derive(Debug, Clone, Default)]
+pub struct DungeonAssets {
+    pub floors: Vec<DungeonAsset>,
+    pub walls: Vec<DungeonAsset>,
+    pub doors: Vec<DungeonAsset>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Empty,
+    Floor,
+    Wall,
+    Door,
+}
+
+impl Tile {
+    fn is_walkable(self) -> bool {
+        matches!(self, Tile::Floor | Tile::Door)
+    }
+}
+
+/// The generated cells, row by row from the top
+#[derive(Debug)]
+struct DungeonLayout {
+    width: i32,
+    height: i32,
+    tiles: Vec<Tile>,
+    rooms: Vec<IRect>,
+}
+
+impl DungeonLayout {
+    fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![Tile::Empty; (width * height) as usize],
+            rooms: Vec::new(),
+        }
+    }
+
+    fn tile(&self, cell: IVec2) -> Tile {
+        if cell.x < 0 || cell.y < 0 || cell.x >= self.width || cell.y >= self.height {
+            return Tile::Empty;
+        }
+        self.tiles[(cell.y * self.width + cell.x) as usize]
+    }
+
+    fn set(&mut self, cell: IVec2, tile: Tile) {
+        self.tiles[(cell.y * self.width + cell.x) as usize] = tile;
+    }
+
+    fn in_room(&self, cell: IVec2) -> bool {
+        self.rooms
+            .iter()
+            .any(|room| cell.cmpge(room.min).all() && cell.cmplt(room.max).all())
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (IVec2, Tile)> + '_ {
+        (0..self.height).flat_map(move |y| {
+            (0..self.width).map(move |x| {
+                let cell = IVec2::new(x, y);
+                (cell, self.tile(cell))
+            })
+        })
+    }
+}
+
+/// A BSP node: a leaf until it's split in two
+struct BspNode {
+    area: IRect,
+    children: Option<(usize, usize)>,
+}
+
+/// Generate a dungeon map. The result is an ordinary map: every floor, wall
+/// and door is a placed item that can be edited like any other.
+pub fn generate_dungeon(params: &DungeonParams, assets: &DungeonAssets) -> SavedMap {
+    let mut rng = fastrand::Rng::with_seed(params.seed);
+    let layout = generate_layout(params, &mut rng);
+
+    let map_data = MapData {
+        name: format!("Dungeon {}", params.seed),
+        ..MapData::default()
+    };
+    let grid_size = map_data.grid_size;
+    // Centered on the origin, with the first row at the top
+    let offset = IVec2::new(layout.width / 2, layout.height / 2);
+    let position =
+        |cell: IVec2| cell_to_world((cell.x - offset.x, offset.y - 1 - cell.y), grid_size);
+    let mut place = |choices: &[DungeonAsset], cell: IVec2, layer, z_index, rotation| {
+        if choices.is_empty() {
+            return None;
+        }
+        let asset = &choices[rng.usize(..choices.len())];
+        let fit = asset
+            .size
+            .filter(|size| size.x > 0.0 && size.y > 0.0)
+            .map_or(1.0, |size| (grid_size / size).min_element());
+        Some(SavedPlacedItem {
+            asset_path: asset.asset_path.clone(),
+            position: position(cell),
+            rotation,
+            scale: Vec2::splat(fit),
+            layer,
+            z_index,
+            token: None,
+        })
+    };
+
+    let mut items = Vec::new();
+    for (cell, tile) in layout.cells() {
+        match tile {
+            Tile::Empty => {}
+            Tile::Floor => items.extend(place(&assets.floors, cell, Layer::Terrain, 0, 0.0)),
+            Tile::Wall => items.extend(place(&assets.walls, cell, Layer::Terrain, 1, 0.0)),
+            Tile::Door => {
+                items.extend(place(&assets.floors, cell, Layer::Terrain, 0, 0.0));
+                // Door images span a passage running up and down
+                let across = layout.tile(cell + IVec2::X).is_walkable();
+                let rotation = if across {
+                    std::f32::consts::FRAC_PI_2
+                } else {
+                    0.0
+                };
+                items.extend(place(&assets.doors, cell, Layer::Doodad, 0, rotation));
+            }
+        }
+    }
+
+    SavedMap {
+        asset_manifest: AssetManifest::from_items(items.iter()),
+        map_data,
+        placed_items: items,
+        annotations: Default::default(),
+        fog_of_war: Default::default(),
+        initiative: Default::default(),
+        notes: Vec::new(),
+        camera_bookmarks: Vec::new(),
+    }
+}
+
+/// Lay out rooms, corridors, doors and walls
+fn generate_layout(params: &DungeonParams, rng: &mut fastrand::Rng) -> DungeonLayout {
+    let side = |cells: u32| cells.clamp(*DUNGEON_SIZE_RANGE.start(), *DUNGEON_SIZE_RANGE.end());
+    let mut layout = DungeonLayout::new(side(params.width) as i32, side(params.height) as i32);
+
+    let nodes = split_area(
+        IRect::new(0, 0, layout.width, layout.height),
+        params.room_count.clamp(1, MAX_DUNGEON_ROOMS) as usize,
+        rng,
+    );
+
+    // One room per leaf, keeping a cell of wall inside the leaf all around
+    let mut leaf_rooms = vec![None; nodes.len()];
+    for (index, node) in nodes.iter().enumerate() {
+        if node.children.is_some() {
+            continue;
+        }
+        let inner = IRect::from_corners(node.area.min + 1, node.area.max - 1);
+        let size = IVec2::new(
+            rng.i32(MIN_ROOM_CELLS..=inner.width()),
+            rng.i32(MIN_ROOM_CELLS..=inner.height()),
+        );
+        let min = IVec2::new(
+            rng.i32(inner.min.x..=inner.max.x - size.x),
+            rng.i32(inner.min.y..=inner.max.y - size.y),
+        );
+        let room = IRect::from_corners(min, min + size);
+        leaf_rooms[index] = Some(layout.rooms.len());
+        layout.rooms.push(room);
+        for y in room.min.y..room.max.y {
+            for x in room.min.x..room.max.x {
+                layout.set(IVec2::new(x, y), Tile::Floor);
+            }
+        }
+    }
+
+    // Join the two halves of every split through their closest rooms
+    let mut door_candidates = Vec::new();
+    for node in &nodes {
+        let Some((a, b)) = node.children else {
+            continue;
+        };
+        let rooms_a = subtree_rooms(&nodes, &leaf_rooms, a);
+        let rooms_b = subtree_rooms(&nodes, &leaf_rooms, b);
+        let Some((from, to)) = rooms_a
+            .iter()
+            .flat_map(|&ra| rooms_b.iter().map(move |&rb| (ra, rb)))
+            .min_by_key(|&(ra, rb)| {
+                let d = layout.rooms[ra].center() - layout.rooms[rb].center();
+                d.x.abs() + d.y.abs()
+            })
+        else {
+            continue;
+        };
+        let path = corridor_path(
+            layout.rooms[from].center(),
+            layout.rooms[to].center(),
+            rng.bool(),
+        );
+        for pair in path.windows(2) {
+            let (prev, cur) = (pair[0], pair[1]);
+            match (layout.in_room(prev), layout.in_room(cur)) {
+                (true, false) => door_candidates.push(cur),
+                (false, true) => door_candidates.push(prev),
+                _ => {}
+            }
+        }
+        for cell in path {
+            layout.set(cell, Tile::Floor);
+        }
+    }
+
+    // Doors only fit a one-cell passage between two walls
+    for cell in door_candidates {
+        if layout.tile(cell) != Tile::Floor || layout.in_room(cell) {
+            continue;
+        }
+        let walkable = |offset: IVec2| layout.tile(cell + offset).is_walkable();
+        let passage_across = walkable(IVec2::X)
+            && walkable(IVec2::NEG_X)
+            && !walkable(IVec2::Y)
+            && !walkable(IVec2::NEG_Y);
+        let passage_down = walkable(IVec2::Y)
+            && walkable(IVec2::NEG_Y)
+            && !walkable(IVec2::X)
+            && !walkable(IVec2::NEG_X);
+        let next_to_door = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .iter()
+            .any(|&offset| layout.tile(cell + offset) == Tile::Door);
+        if (passage_across || passage_down) && !next_to_door {
+            layout.set(cell, Tile::Door);
+        }
+    }
+
+    // Wall in everything that touches the floor, diagonals included
+    let walls: Vec<IVec2> = layout
+        .cells()
+        .filter(|&(cell, tile)| {
+            tile == Tile::Empty
+                && (-1..=1).any(|dy| {
+                    (-1..=1).any(|dx| layout.tile(cell + IVec2::new(dx, dy)).is_walkable())
+                })
+        })
+        .map(|(cell, _)| cell)
+        .collect();
+    for cell in walls {
+        layout.set(cell, Tile::Wall);
+    }
+
+    layout
+}
+
+/// Split `area` until it has `leaf_count` leaves or no leaf is big enough to
+/// split, always splitting the largest leaf next. Nodes are returned parents
+/// first.
+fn split_area(area: IRect, leaf_count: usize, rng: &mut fastrand::Rng) -> Vec<BspNode> {
+    let mut nodes = vec![BspNode {
+        area,
+        children: None,
+    }];
+    let mut unsplittable = vec![false];
+    let mut leaves = 1;
+
+    while leaves < leaf_count {
+        let Some(index) = (0..nodes.len())
+            .filter(|&i| nodes[i].children.is_none() && !unsplittable[i])
+            .max_by_key(|&i| nodes[i].area.width() * nodes[i].area.height())
+        else {
+            break;
+        };
+        let Some((a, b)) = split_rect(nodes[index].area, rng) else {
+            unsplittable[index] = true;
+            continue;
+        };
+        nodes[index].children = Some((nodes.len(), nodes.len() + 1));
+        for half in [a, b] {
+            nodes.push(BspNode {
+                area: half,
+                children: None,
+            });
+            unsplittable.push(false);
+        }
+        leaves += 1;
+    }
+    nodes
+}
+
+/// Cut a rectangle in two across its longer side, or either way when it's
+/// roughly square. `None` when neither half would fit a room.
+fn split_rect(rect: IRect, rng: &mut fastrand::Rng) -> Option<(IRect, IRect)> {
+    let (w, h) = (rect.width(), rect.height());
+    let vertical = if w * 4 > h * 5 {
+        true
+    } else if h * 4 > w * 5 {
+        false
+    } else {
+        rng.bool()
+    };
+    let cut = |vertical: bool, rng: &mut fastrand::Rng| {
+        let (lo, hi) = if vertical {
+            (rect.min.x, rect.max.x)
+        } else {
+            (rect.min.y, rect.max.y)
+        };
+        if hi - lo < MIN_LEAF_CELLS * 2 {
+            return None;
+        }
+        let at = rng.i32(lo + MIN_LEAF_CELLS..=hi - MIN_LEAF_CELLS);
+        Some(if vertical {
+            (
+                IRect::new(rect.min.x, rect.min.y, at, rect.max.y),
+                IRect::new(at, rect.min.y, rect.max.x, rect.max.y),
+            )
+        } else {
+            (
+                IRect::new(rect.min.x, rect.min.y, rect.max.x, at),
+                IRect::new(rect.min.x, at, rect.max.x, rect.max.y),
+            )
+        })
+    };
+    cut(vertical, rng).or_else(|| cut(!vertical, rng))
+}
+
+/// Rooms in the leaves under a node
+fn subtree_rooms(nodes: &[BspNode], leaf_rooms: &[Option<usize>], index: usize) -> Vec<usize> {
+    match nodes[index].children {
+        Some((a, b)) => {
+            let mut rooms = subtree_rooms(nodes, leaf_rooms, a);
+            rooms.extend(subtree_rooms(nodes, leaf_rooms, b));
+            rooms
+        }
+        None => leaf_rooms[index].into_iter().collect(),
+    }
+}
+
+/// Cells of an L-shaped corridor, going across first or down first
+fn corridor_path(from: IVec2, to: IVec2, across_first: bool) -> Vec<IVec2> {
+    let corner = if across_first {
+        IVec2::new(to.x, from.y)
+    } else {
+        IVec2::new(from.x, to.y)
+    };
+    let mut path = vec![from];
+    for target in [corner, to] {
+        let mut cell = *path.last().unwrap();
+        let step = (target - cell).signum();
+        while cell != target {
+            cell += step;
+            path.push(cell);
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(path: &str) -> DungeonAsset {
+        DungeonAsset {
+            asset_path: path.to_string(),
+            size: Some(Vec2::splat(140.0)),
+        }
+    }
+
+    fn test_assets() -> DungeonAssets {
+        DungeonAssets {
+            floors: vec![asset("floors/a.png"), asset("floors/b.png")],
+            walls: vec![asset("walls/stone.png")],
+            doors: vec![asset("doors/wood.png")],
+        }
+    }
+
+    fn layout_for(params: &DungeonParams) -> DungeonLayout {
+        generate_layout(params, &mut fastrand::Rng::with_seed(params.seed))
+    }
+
+    fn count(layout: &DungeonLayout, tile: Tile) -> usize {
+        layout.tiles.iter().filter(|&&t| t == tile).count()
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_map() {
+        let params = DungeonParams::default();
+        let first = serde_json::to_string(&generate_dungeon(&params, &test_assets())).unwrap();
+        let second = serde_json::to_string(&generate_dungeon(&params, &test_assets())).unwrap();
+        assert_eq!(first, second);
+
+        let other = DungeonParams { seed: 2, ..params };
+        let third = serde_json::to_string(&generate_dungeon(&other, &test_assets())).unwrap();
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn test_room_count() {
+        let layout = layout_for(&DungeonParams::default());
+        assert_eq!(layout.rooms.len(), 8);
+
+        // A small map fits fewer rooms than asked for
+        let small = layout_for(&DungeonParams {
+            width: 12,
+            height: 12,
+            room_count: 20,
+            seed: 3,
+        });
+        assert!(!small.rooms.is_empty());
+        assert!(small.rooms.len() < 20);
+    }
+
+    #[test]
+    fn test_every_floor_is_reachable() {
+        for seed in 0..20 {
+            let layout = layout_for(&DungeonParams {
+                seed,
+                ..DungeonParams::default()
+            });
+            let start = layout.rooms[0].center();
+            let mut seen = vec![start];
+            let mut queue = vec![start];
+            while let Some(cell) = queue.pop() {
+                for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                    let next = cell + offset;
+                    if layout.tile(next).is_walkable() && !seen.contains(&next) {
+                        seen.push(next);
+                        queue.push(next);
+                    }
+                }
+            }
+            let walkable = layout.tiles.iter().filter(|t| t.is_walkable()).count();
+            assert_eq!(seen.len(), walkable, "seed {} has unreachable floor", seed);
+        }
+    }
+
+    #[test]
+    fn test_floor_is_walled_in() {
+        let layout = layout_for(&DungeonParams::default());
+        for (cell, tile) in layout.cells() {
+            if !tile.is_walkable() {
+                continue;
+            }
+            assert!(cell.cmpgt(IVec2::ZERO).all());
+            assert!(
+                cell.cmplt(IVec2::new(layout.width - 1, layout.height - 1))
+                    .all()
+            );
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    assert_ne!(layout.tile(cell + IVec2::new(dx, dy)), Tile::Empty);
+                }
+            }
+        }
+        assert!(count(&layout, Tile::Door) > 0);
+    }
+
+    #[test]
+    fn test_items_go_on_their_layers() {
+        let params = DungeonParams::default();
+        let layout = layout_for(&params);
+        let map = generate_dungeon(&params, &test_assets());
+
+        let floors: Vec<_> = map
+            .placed_items
+            .iter()
+            .filter(|item| item.asset_path.starts_with("floors/"))
+            .collect();
+        assert_eq!(
+            floors.len(),
+            count(&layout, Tile::Floor) + count(&layout, Tile::Door)
+        );
+        assert!(
+            floors
+                .iter()
+                .all(|item| item.layer == Layer::Terrain && item.z_index == 0)
+        );
+
+        let walls = map
+            .placed_items
+            .iter()
+            .filter(|item| item.asset_path == "walls/stone.png");
+        assert!(
+            walls
+                .clone()
+                .all(|item| item.layer == Layer::Terrain && item.z_index == 1)
+        );
+        assert_eq!(walls.count(), count(&layout, Tile::Wall));
+
+        // Every door sits on a floor tile
+        for door in map
+            .placed_items
+            .iter()
+            .filter(|item| item.asset_path == "doors/wood.png")
+        {
+            assert_eq!(door.layer, Layer::Doodad);
+            assert!(floors.iter().any(|floor| floor.position == door.position));
+        }
+
+        // Fitted to the grid: 140 px assets on a 70 unit grid
+        assert!(
+            map.placed_items
+                .iter()
+                .all(|item| item.scale == Vec2::splat(0.5))
+        );
+        assert_eq!(map.asset_manifest.len(), 4);
+    }
+
+    #[test]
+    fn test_missing_assets_are_skipped() {
+        let assets = DungeonAssets {
+            floors: vec![asset("floors/a.png")],
+            ..Default::default()
+        };
+        let map = generate_dungeon(&DungeonParams::default(), &assets);
+        assert!(!map.placed_items.is_empty());
+        assert!(
+            map.placed_items
+                .iter()
+                .all(|item| item.asset_path == "floors/a.png")
+        );
+    }
+
+    #[test]
+    fn test_corridor_path() {
+        let path = corridor_path(IVec2::new(0, 0), IVec2::new(2, 1), true);
+        assert_eq!(
+            path,
+            vec![
+                IVec2::new(0, 0),
+                IVec2::new(1, 0),
+                IVec2::new(2, 0),
+                IVec2::new(2, 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,532 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A single combatant in the initiative order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InitiativeEntry {
+    /// Display name shown in the tracker
+    pub name: String,
+    /// Rolled or entered initiative value
+    pub initiative: i32,
+    /// Initiative modifier (added to d20 rolls, used to break ties)
+    pub modifier: i32,
+    /// Token this entry belongs to (None for manual entries like lair actions)
+    pub token: Option<Entity>,
+}
+
+/// Runtime state for the initiative tracker
+///
+/// The tracker is "in combat" while `round > 0`. Entries are kept in turn
+/// order once combat starts; `current` indexes the creature whose turn it is.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InitiativeTracker {
+    pub entries: Vec<InitiativeEntry>,
+    /// Index of the active entry (only meaningful while in combat)
+    pub current: usize,
+    /// Current combat round, 0 = combat not started
+    pub round: u32,
+}
+
+impl InitiativeTracker {
+    /// Check if combat is currently running
+    pub fn in_combat(&self) -> bool {
+        self.round > 0 && !self.entries.is_empty()
+    }
+
+    /// Get the entry whose turn it is
+    pub fn active_entry(&self) -> Option<&InitiativeEntry> {
+        if !self.in_combat() {
+            return None;
+        }
+        self.entries.get(self.current)
+    }
+
+    /// Get the token whose turn it is
+    pub fn active_token(&self) -> Option<Entity> {
+        self.active_entry().and_then(|e| e.token)
+    }
+
+    /// Check if a token already has an entry
+    pub fn contains_token(&self, entity: Entity) -> bool {
+        self.entries.iter().any(|e| e.token == Some(entity))
+    }
+
+    /// Add an entry for a token (no-op if the token is already tracked)
+    pub fn add_token(&mut self, entity: Entity, name: String) -> bool {
+        if self.contains_token(entity) {
+            return false;
+        }
+        self.entries.push(InitiativeEntry {
+            name,
+            initiative: 0,
+            modifier: 0,
+            token: Some(entity),
+        });
+        true
+    }
+
+    /// Add a manual entry not tied to any token
+    pub fn add_manual(&mut self, name: String) {
+        self.entries.push(InitiativeEntry {
+            name,
+            initiative: 0,
+            modifier: 0,
+            token: None,
+        });
+    }
+
+    /// Remove the entry at `index`, keeping the active turn on the same creature
+    pub fn remove(&mut self, index: usize) {
+        if index >= self.entries.len() {
+            return;
+        }
+        self.entries.remove(index);
+
+        if self.entries.is_empty() {
+            self.current = 0;
+            self.round = 0;
+        } else if index < self.current {
+            self.current -= 1;
+        } else if self.current >= self.entries.len() {
+            // Removed the last entry while it was active: wrap to the next round
+            self.current = 0;
+            if self.round > 0 {
+                self.round += 1;
+            }
+        }
+    }
+
+    /// Remove the entry linked to a token (used when the token is deleted)
+    pub fn remove_token(&mut self, entity: Entity) {
+        if let Some(index) = self.entries.iter().position(|e| e.token == Some(entity)) {
+            self.remove(index);
+        }
+    }
+
+    /// Sort entries by initiative (highest first), breaking ties by modifier.
+    /// The active creature stays active if combat is running.
+    pub fn sort(&mut self) {
+        // Sort positions rather than entries so the active one can be found
+        // again even when several entries are identical. Stable sort keeps
+        // insertion order for exact ties.
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.entries[a], &self.entries[b]);
+            b.initiative
+                .cmp(&a.initiative)
+                .then(b.modifier.cmp(&a.modifier))
+        });
+        if let Some(index) = order.iter().position(|&i| i == self.current) {
+            self.current = index;
+        }
+        self.entries = order.iter().map(|&i| self.entries[i].clone()).collect();
+    }
+
+    /// Roll d20 + modifier for every entry, then sort
+    pub fn roll_all(&mut self, rng: &mut fastrand::Rng) {
+        for entry in &mut self.entries {
+            entry.initiative = roll_d20(rng) + entry.modifier;
+        }
+        self.sort();
+    }
+
+    /// Sort the order and begin combat at round 1
+    pub fn start_combat(&mut self) {
+        if self.entries.is_empty() {
+            return;
+        }
+        self.sort();
+        self.current = 0;
+        self.round = 1;
+    }
+
+    /// End combat, keeping the entries for the next encounter
+    pub fn end_combat(&mut self) {
+        self.round = 0;
+        self.current = 0;
+    }
+
+    /// Advance to the next turn, incrementing the round after the last entry
+    pub fn next_turn(&mut self) {
+        if !self.in_combat() {
+            return;
+        }
+        self.current += 1;
+        if self.current >= self.entries.len() {
+            self.current = 0;
+            self.round += 1;
+        }
+    }
+
+    /// Go back one turn, decrementing the round when wrapping (never below round 1)
+    pub fn previous_turn(&mut self) {
+        if !self.in_combat() {
+            return;
+        }
+        if self.current > 0 {
+            self.current -= 1;
+        } else if self.round > 1 {
+            self.current = self.entries.len() - 1;
+            self.round -= 1;
+        }
+    }
+
+    /// Clear all entries and end combat
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Convert to the persistence format.
+    ///
+    /// `item_index` maps a token entity to its index in `SavedMap::placed_items`.
+    pub fn to_saved(&self, item_index: impl Fn(Entity) -> Option<usize>) -> SavedInitiative {
+        SavedInitiative {
+            entries: self
+                .entries
+                .iter()
+                .map(|e| SavedInitiativeEntry {
+                    name: e.name.clone(),
+                    initiative: e.initiative,
+                    modifier: e.modifier,
+                    item_index: e.token.and_then(&item_index),
+                })
+                .collect(),
+            current: self.current,
+            round: self.round,
+        }
+    }
+
+    /// Restore from the persistence format.
+    ///
+    /// `items` holds the spawned entity for each entry of `SavedMap::placed_items`,
+    /// in the same order.
+    pub fn from_saved(saved: &SavedInitiative, items: &[Entity]) -> Self {
+        let entries: Vec<InitiativeEntry> = saved
+            .entries
+            .iter()
+            .map(|e| InitiativeEntry {
+                name: e.name.clone(),
+                initiative: e.initiative,
+                modifier: e.modifier,
+                token: e.item_index.and_then(|i| items.get(i).copied()),
+            })
+            .collect();
+        let current = if saved.current < entries.len() {
+            saved.current
+        } else {
+            0
+        };
+        let round = if entries.is_empty() { 0 } else { saved.round };
+        Self {
+            entries,
+            current,
+            round,
+        }
+    }
+}
+
+/// Persistence format for a single initiative entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedInitiativeEntry {
+    pub name: String,
+    pub initiative: i32,
+    #[serde(default)]
+    pub modifier: i32,
+    /// Index of the linked token in `SavedMap::placed_items`
+    #[serde(default)]
+    pub item_index: Option<usize>,
+}
+
+/// Persistence format for the initiative tracker
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedInitiative {
+    #[serde(default)]
+    pub entries: Vec<SavedInitiativeEntry>,
+    #[serde(default)]
+    pub current: usize,
+    #[serde(default)]
+    pub round: u32,
+}
+
+/// Roll a single d20
+pub fn roll_d20(rng: &mut fastrand::Rng) -> i32 {
+    rng.i32(1..=20)
+}
+
+/// Derive a display name for a token from its asset path ("tokens/goblin_archer.png" -> "Goblin Archer")
+pub fn token_display_name(asset_path: &str) -> String {
+    let stem = std::path::Path::new(asset_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(asset_path);
+
+    stem.split(['_', '-', ' '])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(index: u32) -> Entity {
+        Entity::from_raw_u32(index).unwrap()
+    }
+
+    fn tracker_with(values: &[(&str, i32, i32)]) -> InitiativeTracker {
+        let mut tracker = InitiativeTracker::default();
+        for (name, initiative, modifier) in values {
+            tracker.entries.push(InitiativeEntry {
+                name: name.to_string(),
+                initiative: *initiative,
+                modifier: *modifier,
+                token: None,
+            });
+        }
+        tracker
+    }
+
+    fn names(tracker: &InitiativeTracker) -> Vec<&str> {
+        tracker.entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_orders_by_initiative_then_modifier() {
+        let mut tracker = tracker_with(&[("Goblin", 12, 2), ("Fighter", 18, 1), ("Wizard", 12, 3)]);
+        tracker.sort();
+        assert_eq!(names(&tracker), vec!["Fighter", "Wizard", "Goblin"]);
+    }
+
+    #[test]
+    fn test_start_combat_begins_at_round_one() {
+        let mut tracker = tracker_with(&[("Goblin", 5, 0), ("Fighter", 15, 0)]);
+        assert!(!tracker.in_combat());
+
+        tracker.start_combat();
+        assert!(tracker.in_combat());
+        assert_eq!(tracker.round, 1);
+        assert_eq!(tracker.active_entry().unwrap().name, "Fighter");
+    }
+
+    #[test]
+    fn test_start_combat_with_no_entries_does_nothing() {
+        let mut tracker = InitiativeTracker::default();
+        tracker.start_combat();
+        assert!(!tracker.in_combat());
+        assert_eq!(tracker.round, 0);
+    }
+
+    #[test]
+    fn test_next_turn_wraps_and_increments_round() {
+        let mut tracker = tracker_with(&[("A", 20, 0), ("B", 10, 0)]);
+        tracker.start_combat();
+
+        tracker.next_turn();
+        assert_eq!(tracker.active_entry().unwrap().name, "B");
+        assert_eq!(tracker.round, 1);
+
+        tracker.next_turn();
+        assert_eq!(tracker.active_entry().unwrap().name, "A");
+        assert_eq!(tracker.round, 2);
+    }
+
+    #[test]
+    fn test_previous_turn_wraps_back_a_round() {
+        let mut tracker = tracker_with(&[("A", 20, 0), ("B", 10, 0)]);
+        tracker.start_combat();
+        tracker.next_turn();
+        tracker.next_turn();
+        assert_eq!(tracker.round, 2);
+
+        tracker.previous_turn();
+        assert_eq!(tracker.active_entry().unwrap().name, "B");
+        assert_eq!(tracker.round, 1);
+    }
+
+    #[test]
+    fn test_previous_turn_stops_at_start_of_combat() {
+        let mut tracker = tracker_with(&[("A", 20, 0), ("B", 10, 0)]);
+        tracker.start_combat();
+        tracker.previous_turn();
+        assert_eq!(tracker.current, 0);
+        assert_eq!(tracker.round, 1);
+    }
+
+    #[test]
+    fn test_remove_before_active_keeps_active_creature() {
+        let mut tracker = tracker_with(&[("A", 20, 0), ("B", 15, 0), ("C", 10, 0)]);
+        tracker.start_combat();
+        tracker.next_turn();
+        tracker.next_turn();
+        assert_eq!(tracker.active_entry().unwrap().name, "C");
+
+        tracker.remove(0);
+        assert_eq!(tracker.active_entry().unwrap().name, "C");
+    }
+
+    #[test]
+    fn test_remove_last_active_entry_wraps_to_next_round() {
+        let mut tracker = tracker_with(&[("A", 20, 0), ("B", 10, 0)]);
+        tracker.start_combat();
+        tracker.next_turn();
+
+        tracker.remove(1);
+        assert_eq!(tracker.active_entry().unwrap().name, "A");
+        assert_eq!(tracker.round, 2);
+    }
+
+    #[test]
+    fn test_remove_all_entries_ends_combat() {
+        let mut tracker = tracker_with(&[("A", 20, 0)]);
+        tracker.start_combat();
+        tracker.remove(0);
+        assert!(!tracker.in_combat());
+        assert_eq!(tracker.round, 0);
+    }
+
+    #[test]
+    fn test_sort_during_combat_keeps_active_creature() {
+        let mut tracker = tracker_with(&[("A", 20, 0), ("B", 10, 0)]);
+        tracker.start_combat();
+        tracker.next_turn();
+
+        // B's initiative gets corrected upward mid-combat
+        tracker.entries[1].initiative = 25;
+        tracker.sort();
+        assert_eq!(names(&tracker), vec!["B", "A"]);
+        assert_eq!(tracker.active_entry().unwrap().name, "B");
+    }
+
+    #[test]
+    fn test_sort_keeps_active_entry_among_duplicates() {
+        // Two identical goblins; the second one is taking its turn
+        let mut tracker = tracker_with(&[("Goblin", 10, 0), ("Goblin", 10, 0), ("A", 5, 0)]);
+        tracker.start_combat();
+        tracker.next_turn();
+        assert_eq!(tracker.current, 1);
+
+        // A rolls higher, so both goblins move down one place
+        tracker.entries[2].initiative = 20;
+        tracker.sort();
+        assert_eq!(names(&tracker), vec!["A", "Goblin", "Goblin"]);
+        assert_eq!(tracker.current, 2);
+    }
+
+    #[test]
+    fn test_add_token_is_idempotent() {
+        let mut tracker = InitiativeTracker::default();
+        assert!(tracker.add_token(entity(1), "Goblin".to_string()));
+        assert!(!tracker.add_token(entity(1), "Goblin".to_string()));
+        assert_eq!(tracker.entries.len(), 1);
+        assert!(tracker.contains_token(entity(1)));
+    }
+
+    #[test]
+    fn test_active_token() {
+        let mut tracker = InitiativeTracker::default();
+        tracker.add_token(entity(7), "Goblin".to_string());
+        tracker.add_manual("Lair Action".to_string());
+        tracker.entries[0].initiative = 10;
+        tracker.entries[1].initiative = 20;
+
+        // No active token before combat
+        assert_eq!(tracker.active_token(), None);
+
+        tracker.start_combat();
+        assert_eq!(tracker.active_token(), None); // Lair action goes first
+        tracker.next_turn();
+        assert_eq!(tracker.active_token(), Some(entity(7)));
+    }
+
+    #[test]
+    fn test_roll_all_is_deterministic_with_seed() {
+        let mut a = tracker_with(&[("A", 0, 2), ("B", 0, -1), ("C", 0, 5)]);
+        let mut b = a.clone();
+
+        a.roll_all(&mut fastrand::Rng::with_seed(42));
+        b.roll_all(&mut fastrand::Rng::with_seed(42));
+        assert_eq!(a.entries, b.entries);
+
+        for entry in &a.entries {
+            let roll = entry.initiative - entry.modifier;
+            assert!((1..=20).contains(&roll));
+        }
+    }
+
+    #[test]
+    fn test_saved_round_trip_maps_tokens_by_index() {
+        let mut tracker = InitiativeTracker::default();
+        tracker.add_token(entity(10), "Goblin".to_string());
+        tracker.add_manual("Lair Action".to_string());
+        tracker.add_token(entity(11), "Orc".to_string());
+        tracker.start_combat();
+        tracker.next_turn();
+
+        // Saved item order: [entity 11, entity 10]
+        let saved = tracker.to_saved(|e| {
+            if e == entity(11) {
+                Some(0)
+            } else if e == entity(10) {
+                Some(1)
+            } else {
+                None
+            }
+        });
+        assert_eq!(saved.entries[0].item_index, Some(1));
+        assert_eq!(saved.entries[1].item_index, None);
+        assert_eq!(saved.entries[2].item_index, Some(0));
+
+        // Freshly spawned entities on load
+        let restored = InitiativeTracker::from_saved(&saved, &[entity(20), entity(21)]);
+        assert_eq!(restored.entries[0].token, Some(entity(21)));
+        assert_eq!(restored.entries[1].token, None);
+        assert_eq!(restored.entries[2].token, Some(entity(20)));
+        assert_eq!(restored.round, 1);
+        assert_eq!(restored.current, 1);
+    }
+
+    #[test]
+    fn test_from_saved_clamps_invalid_current() {
+        let saved = SavedInitiative {
+            entries: vec![SavedInitiativeEntry {
+                name: "A".to_string(),
+                initiative: 10,
+                modifier: 0,
+                item_index: Some(5), // Out of range
+            }],
+            current: 3,
+            round: 2,
+        };
+        let restored = InitiativeTracker::from_saved(&saved, &[]);
+        assert_eq!(restored.current, 0);
+        assert_eq!(restored.entries[0].token, None);
+    }
+
+    #[test]
+    fn test_saved_initiative_defaults_for_old_files() {
+        let saved: SavedInitiative = serde_json::from_str("{}").unwrap();
+        assert!(saved.entries.is_empty());
+        assert_eq!(saved.round, 0);
+    }
+
+    #[test]
+    fn test_token_display_name() {
+        assert_eq!(
+            token_display_name("tokens/goblin_archer.png"),
+            "Goblin Archer"
+        );
+        assert_eq!(
+            token_display_name("library/tokens/red-dragon.webp"),
+            "Red Dragon"
+        );
+        assert_eq!(token_display_name("orc.png"), "Orc");
+    }
+}
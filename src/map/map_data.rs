@@ -2,9 +2,12 @@ use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-use super::{Layer, PlacedItem, SavedFogOfWar};
+use super::{
+    CameraBookmark, Layer, PlacedItem, SavedFogOfWar, SavedInitiative, SavedNote, TokenData,
+};
+use crate::editor::ShapeKind;
 
-#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MapData {
     pub name: String,
     pub grid_size: f32,
@@ -31,7 +34,7 @@ impl Default for MapData {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LayerData {
     pub layer_type: Layer,
     pub visible: bool,
@@ -46,10 +49,17 @@ pub struct SavedPlacedItem {
     pub scale: Vec2,
     pub layer: Layer,
     pub z_index: i32,
+    /// Token conditions and markers (omitted for items without any)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<TokenData>,
 }
 
 impl SavedPlacedItem {
-    pub fn from_entity(item: &PlacedItem, transform: &Transform) -> Self {
+    pub fn from_entity(
+        item: &PlacedItem,
+        transform: &Transform,
+        token: Option<&TokenData>,
+    ) -> Self {
         Self {
             asset_path: item.asset_path.clone(),
             position: transform.translation.truncate(),
@@ -57,6 +67,7 @@ impl SavedPlacedItem {
             scale: transform.scale.truncate(),
             layer: item.layer,
             z_index: item.z_index,
+            token: token.filter(|t| !t.is_empty()).cloned(),
         }
     }
 }
@@ -105,6 +116,12 @@ pub struct SavedMap {
     pub annotations: SavedAnnotations,
     #[serde(default)]
     pub fog_of_war: SavedFogOfWar,
+    #[serde(default)]
+    pub initiative: SavedInitiative,
+    #[serde(default)]
+    pub notes: Vec<SavedNote>,
+    #[serde(default)]
+    pub camera_bookmarks: Vec<CameraBookmark>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -112,6 +129,8 @@ pub struct SavedAnnotations {
     pub paths: Vec<SavedPath>,
     pub lines: Vec<SavedLine>,
     pub text_boxes: Vec<SavedTextBox>,
+    #[serde(default)]
+    pub shapes: Vec<SavedShape>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,6 +138,8 @@ pub struct SavedPath {
     pub points: Vec<Vec2>,
     pub color: [f32; 4],
     pub stroke_width: f32,
+    #[serde(default)]
+    pub show_to_players: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,6 +148,22 @@ pub struct SavedLine {
     pub end: Vec2,
     pub color: [f32; 4],
     pub stroke_width: f32,
+    #[serde(default)]
+    pub show_to_players: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedShape {
+    pub kind: ShapeKind,
+    pub points: Vec<Vec2>,
+    pub color: [f32; 4],
+    pub stroke_width: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<[f32; 4]>,
+    #[serde(default)]
+    pub dashed: bool,
+    #[serde(default)]
+    pub show_to_players: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -135,11 +172,16 @@ pub struct SavedTextBox {
     pub content: String,
     pub font_size: f32,
     pub color: [f32; 4],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<[f32; 4]>,
+    #[serde(default)]
+    pub show_to_players: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::map::Condition;
 
     // MapData tests
     #[test]
@@ -231,7 +273,7 @@ mod tests {
             scale: Vec3::new(2.0, 2.0, 1.0),
         };
 
-        let saved = SavedPlacedItem::from_entity(&placed_item, &transform);
+        let saved = SavedPlacedItem::from_entity(&placed_item, &transform, None);
 
         assert_eq!(saved.asset_path, "library/tokens/hero.png");
         assert_eq!(saved.position, Vec2::new(100.0, 200.0));
@@ -251,6 +293,7 @@ mod tests {
             scale: Vec2::new(1.0, 1.0),
             layer: Layer::Doodad,
             z_index: 3,
+            token: None,
         };
 
         let json = serde_json::to_string(&saved).unwrap();
@@ -264,6 +307,31 @@ mod tests {
         assert_eq!(saved.z_index, deserialized.z_index);
     }
 
+    #[test]
+    fn test_saved_placed_item_token_data() {
+        let placed_item = PlacedItem {
+            asset_path: "tokens/goblin.png".to_string(),
+            layer: Layer::Token,
+            z_index: 0,
+        };
+
+        // Empty token data isn't written to the file
+        let empty = TokenData::default();
+        let saved = SavedPlacedItem::from_entity(&placed_item, &Transform::IDENTITY, Some(&empty));
+        assert!(saved.token.is_none());
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(!json.contains("token\""));
+
+        let mut token = TokenData::default();
+        token.set_condition(Condition::Prone, true);
+        token.concentrating = true;
+        let saved = SavedPlacedItem::from_entity(&placed_item, &Transform::IDENTITY, Some(&token));
+
+        let json = serde_json::to_string(&saved).unwrap();
+        let deserialized: SavedPlacedItem = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.token, Some(token));
+    }
+
     // SavedAnnotations tests
     #[test]
     fn test_saved_annotations_default() {
@@ -271,6 +339,7 @@ mod tests {
         assert!(annotations.paths.is_empty());
         assert!(annotations.lines.is_empty());
         assert!(annotations.text_boxes.is_empty());
+        assert!(annotations.shapes.is_empty());
     }
 
     #[test]
@@ -279,6 +348,7 @@ mod tests {
             points: vec![Vec2::new(0.0, 0.0), Vec2::new(100.0, 100.0)],
             color: [1.0, 0.0, 0.0, 1.0],
             stroke_width: 3.0,
+            show_to_players: true,
         };
 
         let json = serde_json::to_string(&path).unwrap();
@@ -287,6 +357,7 @@ mod tests {
         assert_eq!(path.points, deserialized.points);
         assert_eq!(path.color, deserialized.color);
         assert_eq!(path.stroke_width, deserialized.stroke_width);
+        assert!(deserialized.show_to_players);
     }
 
     #[test]
@@ -296,6 +367,7 @@ mod tests {
             end: Vec2::new(100.0, 100.0),
             color: [0.0, 1.0, 0.0, 1.0],
             stroke_width: 2.0,
+            show_to_players: false,
         };
 
         let json = serde_json::to_string(&line).unwrap();
@@ -307,6 +379,35 @@ mod tests {
         assert_eq!(line.stroke_width, deserialized.stroke_width);
     }
 
+    #[test]
+    fn test_saved_shape_serialization() {
+        let shape = SavedShape {
+            kind: ShapeKind::Ellipse,
+            points: vec![Vec2::new(0.0, 0.0), Vec2::new(80.0, 40.0)],
+            color: [0.0, 0.0, 1.0, 1.0],
+            stroke_width: 2.0,
+            fill: Some([0.0, 0.0, 1.0, 0.25]),
+            dashed: true,
+            show_to_players: false,
+        };
+
+        let json = serde_json::to_string(&shape).unwrap();
+        let deserialized: SavedShape = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(shape.kind, deserialized.kind);
+        assert_eq!(shape.points, deserialized.points);
+        assert_eq!(shape.fill, deserialized.fill);
+        assert!(deserialized.dashed);
+    }
+
+    #[test]
+    fn test_saved_annotations_without_shapes() {
+        // Maps saved before shape annotations existed
+        let json = r#"{"paths":[],"lines":[],"text_boxes":[]}"#;
+        let annotations: SavedAnnotations = serde_json::from_str(json).unwrap();
+        assert!(annotations.shapes.is_empty());
+    }
+
     #[test]
     fn test_saved_text_box_serialization() {
         let text_box = SavedTextBox {
@@ -314,6 +415,8 @@ mod tests {
             content: "Hello World".to_string(),
             font_size: 16.0,
             color: [1.0, 1.0, 1.0, 1.0],
+            background: Some([0.0, 0.0, 0.0, 0.5]),
+            show_to_players: false,
         };
 
         let json = serde_json::to_string(&text_box).unwrap();
@@ -323,6 +426,17 @@ mod tests {
         assert_eq!(text_box.content, deserialized.content);
         assert_eq!(text_box.font_size, deserialized.font_size);
         assert_eq!(text_box.color, deserialized.color);
+        assert_eq!(text_box.background, deserialized.background);
+    }
+
+    #[test]
+    fn test_saved_text_box_without_background() {
+        // Maps saved before text backgrounds existed
+        let json = r#"{"position":[0.0,0.0],"content":"Note","font_size":24.0,"color":[1.0,0.0,0.0,1.0]}"#;
+        let text_box: SavedTextBox = serde_json::from_str(json).unwrap();
+        assert_eq!(text_box.background, None);
+        assert!(!text_box.show_to_players);
+        assert!(!serde_json::to_string(&text_box).unwrap().contains("background"));
     }
 
     // AssetManifest tests
@@ -343,6 +457,7 @@ mod tests {
                 scale: Vec2::ONE,
                 layer: Layer::Token,
                 z_index: 0,
+                token: None,
             },
             SavedPlacedItem {
                 asset_path: "terrain/grass.png".to_string(),
@@ -351,6 +466,7 @@ mod tests {
                 scale: Vec2::ONE,
                 layer: Layer::Terrain,
                 z_index: 0,
+                token: None,
             },
             SavedPlacedItem {
                 asset_path: "tokens/hero.png".to_string(), // Duplicate
@@ -359,6 +475,7 @@ mod tests {
                 scale: Vec2::ONE,
                 layer: Layer::Token,
                 z_index: 1,
+                token: None,
             },
         ];
 
@@ -380,6 +497,7 @@ mod tests {
                 scale: Vec2::ONE,
                 layer: Layer::Token,
                 z_index: 0,
+                token: None,
             },
             SavedPlacedItem {
                 asset_path: "a_first.png".to_string(),
@@ -388,6 +506,7 @@ mod tests {
                 scale: Vec2::ONE,
                 layer: Layer::Token,
                 z_index: 0,
+                token: None,
             },
         ];
 
@@ -419,6 +538,9 @@ mod tests {
             placed_items: vec![],
             annotations: SavedAnnotations::default(),
             fog_of_war: SavedFogOfWar::default(),
+            initiative: SavedInitiative::default(),
+            notes: vec![],
+            camera_bookmarks: vec![],
         };
 
         let json = serde_json::to_string(&saved_map).unwrap();
@@ -439,6 +561,7 @@ mod tests {
                 scale: Vec2::ONE,
                 layer: Layer::Token,
                 z_index: 0,
+                token: None,
             },
             SavedPlacedItem {
                 asset_path: "item2.png".to_string(),
@@ -447,6 +570,7 @@ mod tests {
                 scale: Vec2::splat(2.0),
                 layer: Layer::Doodad,
                 z_index: 1,
+                token: None,
             },
         ];
         let manifest = AssetManifest::from_items(items.iter());
@@ -460,6 +584,9 @@ mod tests {
             placed_items: items,
             annotations: SavedAnnotations::default(),
             fog_of_war: SavedFogOfWar::default(),
+            initiative: SavedInitiative::default(),
+            notes: vec![],
+            camera_bookmarks: vec![],
         };
 
         let json = serde_json::to_string(&saved_map).unwrap();
@@ -491,5 +618,9 @@ mod tests {
         assert!(deserialized.annotations.lines.is_empty());
         assert!(deserialized.annotations.text_boxes.is_empty());
         assert!(deserialized.asset_manifest.is_empty());
+        assert!(deserialized.initiative.entries.is_empty());
+        assert_eq!(deserialized.initiative.round, 0);
+        assert!(deserialized.notes.is_empty());
+        assert!(deserialized.camera_bookmarks.is_empty());
     }
 }
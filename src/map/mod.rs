@@ -1,21 +1,36 @@
+mod bookmarks;
+mod dungeon;
 mod fog;
+mod initiative;
 mod layer;
 mod map_data;
+mod notes;
 pub mod persistence;
 mod placed_item;
+mod token;
 
+pub use bookmarks::{BookmarkViewport, CameraBookmark, CameraBookmarks};
+pub use dungeon::{
+    generate_dungeon, DungeonAsset, DungeonAssets, DungeonParams, DUNGEON_SIZE_RANGE,
+    MAX_DUNGEON_ROOMS,
+};
 pub use fog::{cell_to_world, cells_in_radius, world_to_cell, FogOfWarData, SavedFogOfWar};
+pub use initiative::{roll_d20, token_display_name, InitiativeTracker, SavedInitiative};
 pub use layer::Layer;
 pub use map_data::{
     AssetManifest, MapData, SavedAnnotations, SavedLine, SavedMap, SavedPath, SavedPlacedItem,
-    SavedTextBox,
+    SavedShape, SavedTextBox,
 };
+pub use notes::{MapNote, MapNotes, SavedNote};
 pub use persistence::{
-    AsyncMapOperation, CurrentMapFile, LoadMapRequest, LoadValidationWarning, MapDirtyState,
-    MapLoadError, MapSaveError, NewMapRequest, OpenMaps, SaveMapRequest, SaveValidationWarning,
-    SwitchMapRequest, UnsavedChangesDialog,
+    history_path, AsyncMapOperation, CurrentMapFile, GenerateDungeonRequest, LoadMapRequest,
+    LoadValidationWarning, MapDirtyState, MapLoadError, MapSaveError, NewMapRequest, OpenMaps,
+    SaveMapRequest, SaveValidationWarning, SwitchMapRequest, UnsavedChangesDialog,
 };
 pub use placed_item::{MissingAsset, PlacedItem, Selected};
+pub use token::{
+    feet_to_world, Condition, CreatureSize, TokenAura, TokenData, FEET_PER_CELL, MAX_EXHAUSTION,
+};
 
 use bevy::prelude::*;
 
@@ -25,6 +40,9 @@ impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<MapData>()
             .init_resource::<FogOfWarData>()
+            .init_resource::<InitiativeTracker>()
+            .init_resource::<MapNotes>()
+            .init_resource::<CameraBookmarks>()
             .init_resource::<MapLoadError>()
             .init_resource::<MapSaveError>()
             .init_resource::<SaveValidationWarning>()
@@ -38,6 +56,7 @@ impl Plugin for MapPlugin {
             .add_message::<LoadMapRequest>()
             .add_message::<NewMapRequest>()
             .add_message::<SwitchMapRequest>()
+            .add_message::<GenerateDungeonRequest>()
             .add_systems(Startup, persistence::ensure_maps_directory)
             .add_systems(
                 Update,
@@ -46,15 +65,19 @@ impl Plugin for MapPlugin {
                     persistence::load_map_system.run_if(on_message::<LoadMapRequest>),
                     persistence::new_map_system.run_if(on_message::<NewMapRequest>),
                     persistence::switch_map_system.run_if(on_message::<SwitchMapRequest>),
+                    persistence::generate_dungeon_system
+                        .run_if(on_message::<GenerateDungeonRequest>),
                     persistence::poll_save_tasks,
                     persistence::poll_load_tasks,
                     // Change detection using Bevy's Added/Changed/Removed filters.
-                    // Chained so suppression decays only after all three have
+                    // Chained so suppression decays only after all detectors have
                     // observed the current frame's suppression value.
                     (
                         persistence::detect_item_additions,
                         persistence::detect_item_removals,
                         persistence::detect_item_transforms,
+                        persistence::detect_token_changes,
+                        persistence::detect_annotation_changes,
                         persistence::decay_dirty_suppression,
                     )
                         .chain(),
@@ -0,0 +1,220 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A GM note pinned to a spot on the map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapNote {
+    /// Identifies the note while the map is open (not saved)
+    pub id: u32,
+    /// Short room key shown on the pin, e.g. "A3" (may be empty)
+    pub key: String,
+    pub title: String,
+    /// Note text, formatted as Markdown
+    pub body: String,
+    /// World position of the pin
+    pub position: Vec2,
+}
+
+impl MapNote {
+    /// Label for lists and previews: "A3 - Guard Room", or just the title
+    pub fn label(&self) -> String {
+        match (self.key.trim(), self.title.trim()) {
+            ("", "") => "Untitled note".to_string(),
+            ("", title) => title.to_string(),
+            (key, "") => key.to_string(),
+            (key, title) => format!("{} - {}", key, title),
+        }
+    }
+
+    /// Check whether the key, title or body contains `query` (case-insensitive)
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.key.to_lowercase().contains(&query)
+            || self.title.to_lowercase().contains(&query)
+            || self.body.to_lowercase().contains(&query)
+    }
+}
+
+/// GM notes for the current map, in creation order
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MapNotes {
+    pub notes: Vec<MapNote>,
+    next_id: u32,
+}
+
+impl MapNotes {
+    /// Add an empty note at `position` and return its id
+    pub fn add(&mut self, position: Vec2) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.notes.push(MapNote {
+            id,
+            key: String::new(),
+            title: String::new(),
+            body: String::new(),
+            position,
+        });
+        id
+    }
+
+    pub fn get(&self, id: u32) -> Option<&MapNote> {
+        self.notes.iter().find(|note| note.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut MapNote> {
+        self.notes.iter_mut().find(|note| note.id == id)
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        self.notes.retain(|note| note.id != id);
+    }
+
+    /// Find the note with the given room key (case-insensitive, ignoring
+    /// surrounding whitespace)
+    pub fn find_by_key(&self, key: &str) -> Option<&MapNote> {
+        let key = key.trim();
+        if key.is_empty() {
+            return None;
+        }
+        self.notes
+            .iter()
+            .find(|note| note.key.trim().eq_ignore_ascii_case(key))
+    }
+
+    /// Topmost (most recently added) note whose pin is within `radius` of `point`
+    pub fn note_at(&self, point: Vec2, radius: f32) -> Option<u32> {
+        self.notes
+            .iter()
+            .rev()
+            .find(|note| note.position.distance(point) <= radius)
+            .map(|note| note.id)
+    }
+
+    /// Remove all notes
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Convert to the persistence format
+    pub fn to_saved(&self) -> Vec<SavedNote> {
+        self.notes
+            .iter()
+            .map(|note| SavedNote {
+                key: note.key.clone(),
+                title: note.title.clone(),
+                body: note.body.clone(),
+                position: note.position,
+            })
+            .collect()
+    }
+
+    /// Restore from the persistence format, assigning fresh ids
+    pub fn from_saved(saved: &[SavedNote]) -> Self {
+        let notes: Vec<MapNote> = saved
+            .iter()
+            .zip(0..)
+            .map(|(note, id)| MapNote {
+                id,
+                key: note.key.clone(),
+                title: note.title.clone(),
+                body: note.body.clone(),
+                position: note.position,
+            })
+            .collect();
+        Self {
+            next_id: notes.len() as u32,
+            notes,
+        }
+    }
+}
+
+/// Persistence format for a GM note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedNote {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub key: String,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub position: Vec2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(notes: &mut MapNotes, key: &str, title: &str, position: Vec2) -> u32 {
+        let id = notes.add(position);
+        let note = notes.get_mut(id).unwrap();
+        note.key = key.to_string();
+        note.title = title.to_string();
+        id
+    }
+
+    #[test]
+    fn test_add_assigns_unique_ids() {
+        let mut notes = MapNotes::default();
+        let a = notes.add(Vec2::ZERO);
+        let b = notes.add(Vec2::ONE);
+        assert_ne!(a, b);
+        notes.remove(a);
+        let c = notes.add(Vec2::ZERO);
+        assert_ne!(b, c);
+        assert_eq!(notes.notes.len(), 2);
+    }
+
+    #[test]
+    fn test_find_by_key() {
+        let mut notes = MapNotes::default();
+        let id = note(&mut notes, "A3", "Guard Room", Vec2::ZERO);
+        note(&mut notes, "", "Untitled", Vec2::ZERO);
+
+        assert_eq!(notes.find_by_key(" a3 ").map(|n| n.id), Some(id));
+        assert!(notes.find_by_key("A4").is_none());
+        // An empty key never links to notes without one
+        assert!(notes.find_by_key("").is_none());
+    }
+
+    #[test]
+    fn test_note_at_prefers_topmost() {
+        let mut notes = MapNotes::default();
+        note(&mut notes, "A1", "", Vec2::ZERO);
+        let top = note(&mut notes, "A2", "", Vec2::new(4.0, 0.0));
+
+        assert_eq!(notes.note_at(Vec2::new(2.0, 0.0), 10.0), Some(top));
+        assert_eq!(notes.note_at(Vec2::new(50.0, 0.0), 10.0), None);
+    }
+
+    #[test]
+    fn test_label_and_search() {
+        let mut notes = MapNotes::default();
+        let id = note(&mut notes, "A3", "Guard Room", Vec2::ZERO);
+        notes.get_mut(id).unwrap().body = "Two **hobgoblins** play dice.".to_string();
+        let note = notes.get(id).unwrap();
+
+        assert_eq!(note.label(), "A3 - Guard Room");
+        assert!(note.matches("guard"));
+        assert!(note.matches("HOBGOBLIN"));
+        assert!(note.matches(""));
+        assert!(!note.matches("dragon"));
+    }
+
+    #[test]
+    fn test_saved_roundtrip() {
+        let mut notes = MapNotes::default();
+        note(&mut notes, "A3", "Guard Room", Vec2::new(70.0, 140.0));
+        note(&mut notes, "", "Trap", Vec2::ZERO);
+
+        let json = serde_json::to_string(&notes.to_saved()).unwrap();
+        let saved: Vec<SavedNote> = serde_json::from_str(&json).unwrap();
+        let mut restored = MapNotes::from_saved(&saved);
+
+        assert_eq!(restored.notes.len(), 2);
+        assert_eq!(restored.notes[0].label(), "A3 - Guard Room");
+        assert_eq!(restored.notes[0].position, Vec2::new(70.0, 140.0));
+        // New notes don't reuse restored ids
+        let id = restored.add(Vec2::ZERO);
+        assert!(restored.notes[..2].iter().all(|note| note.id != id));
+    }
+}
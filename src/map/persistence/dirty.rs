@@ -2,8 +2,10 @@
 
 use bevy::prelude::*;
 
-use crate::editor::AnnotationMarker;
-use crate::map::PlacedItem;
+use crate::editor::{
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, ShowToPlayers, TextAnnotation,
+};
+use crate::map::{PlacedItem, TokenData};
 
 use super::resources::{MapDirtyState, OpenMaps};
 
@@ -80,6 +82,66 @@ pub fn detect_item_transforms(
     }
 }
 
+/// System that detects when token conditions or markers change
+pub fn detect_token_changes(
+    mut dirty_state: ResMut<MapDirtyState>,
+    mut open_maps: ResMut<OpenMaps>,
+    changed_tokens: Query<Entity, Changed<TokenData>>,
+) {
+    // Tokens loaded with saved conditions also count as Changed
+    if dirty_state.suppress_detection > 0 {
+        return;
+    }
+
+    if changed_tokens.is_empty() {
+        return;
+    }
+
+    dirty_state.is_dirty = true;
+    if let Some(active_map) = open_maps.active_map_mut() {
+        active_map.is_dirty = true;
+    }
+}
+
+/// System that detects when annotations are edited, moved or resized
+#[allow(clippy::type_complexity)]
+pub fn detect_annotation_changes(
+    mut dirty_state: ResMut<MapDirtyState>,
+    mut open_maps: ResMut<OpenMaps>,
+    changed_annotations: Query<
+        Entity,
+        (
+            With<AnnotationMarker>,
+            Or<(
+                Changed<DrawnPath>,
+                Changed<DrawnLine>,
+                Changed<DrawnShape>,
+                Changed<TextAnnotation>,
+                Changed<Transform>,
+                Changed<ShowToPlayers>,
+            )>,
+        ),
+    >,
+    mut hidden_from_players: RemovedComponents<ShowToPlayers>,
+) {
+    // Drain removals every frame so stale ones don't fire later
+    let hidden = hidden_from_players.read().count() > 0;
+
+    // Freshly spawned annotations also count as Changed
+    if dirty_state.suppress_detection > 0 {
+        return;
+    }
+
+    if changed_annotations.is_empty() && !hidden {
+        return;
+    }
+
+    dirty_state.is_dirty = true;
+    if let Some(active_map) = open_maps.active_map_mut() {
+        active_map.is_dirty = true;
+    }
+}
+
 /// System that ticks down the change-detection suppression window. Runs after
 /// the detection systems each frame so they observe the current value first.
 pub fn decay_dirty_suppression(mut dirty_state: ResMut<MapDirtyState>) {
@@ -1,7 +1,7 @@
 //! Helper functions for map persistence.
 
 use bevy::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn color_to_array(color: Color) -> [f32; 4] {
     let srgba = color.to_srgba();
@@ -20,3 +20,9 @@ pub fn ensure_maps_directory() {
         warn!("Failed to create maps directory: {}", e);
     }
 }
+
+/// Where a map's undo history is saved: next to the map file, with a
+/// `.history` extension so it isn't listed as a map
+pub fn history_path(map_path: &Path) -> PathBuf {
+    map_path.with_extension("history")
+}
@@ -1,17 +1,25 @@
 //! Map load system and task polling.
 
 use bevy::camera::visibility::RenderLayers;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::tasks::IoTaskPool;
 use futures_lite::future;
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 use crate::assets::AssetLibrary;
-use crate::config::UpdateLastMapPathRequest;
-use crate::editor::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
-use crate::map::{FogOfWarData, Layer, MapData, PlacedItem, SavedMap};
+use crate::config::{AppConfig, UpdateLastMapPathRequest};
+use crate::editor::history::{CommandHistory, SavedHistory};
+use crate::editor::{
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, ShowToPlayers, TextAnnotation,
+};
+use crate::map::{
+    CameraBookmarks, FogOfWarData, InitiativeTracker, Layer, MapData, MapNotes, PlacedItem,
+    SavedMap,
+};
 
-use super::helpers::array_to_color;
+use super::helpers::{array_to_color, history_path};
 use super::messages::LoadMapRequest;
 use super::resources::{
     AsyncMapOperation, CurrentMapFile, LoadMapTask, LoadValidationWarning, MapDirtyState,
@@ -24,6 +32,7 @@ pub fn load_map_system(
     mut commands: Commands,
     mut events: MessageReader<LoadMapRequest>,
     mut async_op: ResMut<AsyncMapOperation>,
+    config: Res<AppConfig>,
 ) {
     for event in events.read() {
         // Don't start a new load if one is already in progress
@@ -42,6 +51,7 @@ pub fn load_map_system(
         // Mark as loading
         async_op.is_loading = true;
         async_op.operation_description = Some(format!("Loading {}...", map_name));
+        let load_history = config.data.save_history;
 
         // Spawn async task for file I/O and parsing
         let task_pool = IoTaskPool::get();
@@ -53,6 +63,7 @@ pub fn load_map_system(
                     return LoadResult {
                         path,
                         saved_map: None,
+                        history: None,
                         error: Some(format!("Failed to read file: {}", e)),
                     };
                 }
@@ -60,14 +71,19 @@ pub fn load_map_system(
 
             // Parse JSON
             match serde_json::from_str::<SavedMap>(&json) {
-                Ok(saved_map) => LoadResult {
-                    path,
-                    saved_map: Some(saved_map),
-                    error: None,
-                },
+                Ok(saved_map) => {
+                    let history = load_history.then(|| read_history_file(&path)).flatten();
+                    LoadResult {
+                        path,
+                        saved_map: Some(saved_map),
+                        history,
+                        error: None,
+                    }
+                }
                 Err(e) => LoadResult {
                     path,
                     saved_map: None,
+                    history: None,
                     error: Some(format!("Failed to parse map file: {}", e)),
                 },
             }
@@ -77,20 +93,49 @@ pub fn load_map_system(
     }
 }
 
+/// Read the history saved next to a map. A missing or unreadable file just
+/// means the map opens with an empty history.
+fn read_history_file(map_path: &Path) -> Option<SavedHistory> {
+    let path = history_path(map_path);
+    let json = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&json) {
+        Ok(history) => Some(history),
+        Err(e) => {
+            warn!("Ignoring unreadable history file {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Error and missing-asset reports shown after a load
+#[derive(SystemParam)]
+pub struct LoadFeedback<'w> {
+    error: ResMut<'w, MapLoadError>,
+    warning: ResMut<'w, LoadValidationWarning>,
+}
+
+/// Per-map state restored alongside the map's entities
+#[derive(SystemParam)]
+pub struct LoadedMapState<'w> {
+    initiative: ResMut<'w, InitiativeTracker>,
+    notes: ResMut<'w, MapNotes>,
+    bookmarks: ResMut<'w, CameraBookmarks>,
+    history: ResMut<'w, CommandHistory>,
+}
+
 /// Polls load tasks and handles completion (spawns entities synchronously)
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn poll_load_tasks(
     mut commands: Commands,
     mut tasks: Query<(Entity, &mut LoadMapTask)>,
     mut async_op: ResMut<AsyncMapOperation>,
     mut map_data: ResMut<MapData>,
     mut fog_data: ResMut<FogOfWarData>,
-    mut load_error: ResMut<MapLoadError>,
-    mut load_warning: ResMut<LoadValidationWarning>,
+    mut state: LoadedMapState,
+    mut feedback: LoadFeedback,
     asset_library: Res<AssetLibrary>,
     asset_server: Res<AssetServer>,
-    existing_items: Query<Entity, With<PlacedItem>>,
-    existing_annotations: Query<Entity, With<AnnotationMarker>>,
+    existing_entities: Query<Entity, Or<(With<PlacedItem>, With<AnnotationMarker>)>>,
     mut current_map_file: ResMut<CurrentMapFile>,
     mut config_events: MessageWriter<UpdateLastMapPathRequest>,
     mut dirty_state: ResMut<MapDirtyState>,
@@ -101,11 +146,11 @@ pub fn poll_load_tasks(
             // Clear async state
             async_op.is_loading = false;
             async_op.operation_description = None;
-            load_error.message = None;
+            feedback.error.message = None;
 
             // Handle error
             if let Some(error) = result.error {
-                load_error.message = Some(error.clone());
+                feedback.error.message = Some(error.clone());
                 error!("{}", error);
                 commands.entity(entity).despawn();
                 continue;
@@ -165,9 +210,9 @@ pub fn poll_load_tasks(
 
             if !missing_assets.is_empty() {
                 // Show warning dialog with missing assets
-                load_warning.show = true;
-                load_warning.missing_assets = missing_assets.clone();
-                load_warning.map_path = Some(result.path.clone());
+                feedback.warning.show = true;
+                feedback.warning.missing_assets = missing_assets.clone();
+                feedback.warning.map_path = Some(result.path.clone());
 
                 warn!(
                     "Cannot load map {:?}: {} missing assets",
@@ -186,13 +231,8 @@ pub fn poll_load_tasks(
                 }
             }
 
-            // Clear existing items
-            for existing in existing_items.iter() {
-                commands.entity(existing).despawn();
-            }
-
-            // Clear existing annotations
-            for existing in existing_annotations.iter() {
+            // Clear existing items and annotations
+            for existing in existing_entities.iter() {
                 commands.entity(existing).despawn();
             }
 
@@ -202,7 +242,8 @@ pub fn poll_load_tasks(
             // Load fog of war data
             *fog_data = FogOfWarData::from(saved_map.fog_of_war);
 
-            // Spawn placed items
+            // Spawn placed items, remembering entities for initiative links
+            let mut item_entities = Vec::with_capacity(saved_map.placed_items.len());
             for item in saved_map.placed_items {
                 let texture: Handle<Image> = asset_server.load(&item.asset_path);
                 // Clamp z_index to valid range (for migration from old maps with larger ranges)
@@ -216,7 +257,7 @@ pub fn poll_load_tasks(
                     RenderLayers::layer(1)
                 };
 
-                commands.spawn((
+                let mut entity = commands.spawn((
                     Sprite::from_image(texture),
                     Transform {
                         translation: item.position.extend(z),
@@ -230,46 +271,108 @@ pub fn poll_load_tasks(
                     },
                     render_layer,
                 ));
+                if let Some(token) = item.token {
+                    entity.insert(token);
+                }
+                item_entities.push(entity.id());
             }
 
+            // Load initiative tracker (resumes an interrupted combat)
+            *state.initiative =
+                InitiativeTracker::from_saved(&saved_map.initiative, &item_entities);
+
+            // Load GM notes
+            *state.notes = MapNotes::from_saved(&saved_map.notes);
+
+            // Load camera bookmarks
+            *state.bookmarks = CameraBookmarks::from_saved(&saved_map.camera_bookmarks);
+
             // Spawn annotations
             let z = Layer::Annotation.z_base();
+            let mut annotation_entities = Vec::new();
 
             for path in saved_map.annotations.paths {
-                commands.spawn((
-                    Transform::from_translation(Vec3::new(0.0, 0.0, z)),
-                    DrawnPath {
-                        points: path.points,
-                        color: array_to_color(path.color),
-                        stroke_width: path.stroke_width,
-                    },
-                    AnnotationMarker,
-                ));
+                let entity = commands
+                    .spawn((
+                        Transform::from_translation(Vec3::new(0.0, 0.0, z)),
+                        DrawnPath {
+                            points: path.points,
+                            color: array_to_color(path.color),
+                            stroke_width: path.stroke_width,
+                        },
+                        AnnotationMarker,
+                    ))
+                    .insert_if(ShowToPlayers, || path.show_to_players)
+                    .id();
+                annotation_entities.push(entity);
             }
 
             for line in saved_map.annotations.lines {
-                commands.spawn((
-                    Transform::from_translation(Vec3::new(0.0, 0.0, z)),
-                    DrawnLine {
-                        start: line.start,
-                        end: line.end,
-                        color: array_to_color(line.color),
-                        stroke_width: line.stroke_width,
-                    },
-                    AnnotationMarker,
-                ));
+                let entity = commands
+                    .spawn((
+                        Transform::from_translation(Vec3::new(0.0, 0.0, z)),
+                        DrawnLine {
+                            start: line.start,
+                            end: line.end,
+                            color: array_to_color(line.color),
+                            stroke_width: line.stroke_width,
+                        },
+                        AnnotationMarker,
+                    ))
+                    .insert_if(ShowToPlayers, || line.show_to_players)
+                    .id();
+                annotation_entities.push(entity);
+            }
+
+            for shape in saved_map.annotations.shapes {
+                let entity = commands
+                    .spawn((
+                        Transform::from_translation(Vec3::new(0.0, 0.0, z)),
+                        DrawnShape {
+                            kind: shape.kind,
+                            points: shape.points,
+                            color: array_to_color(shape.color),
+                            stroke_width: shape.stroke_width,
+                            fill: shape.fill.map(array_to_color),
+                            dashed: shape.dashed,
+                        },
+                        AnnotationMarker,
+                    ))
+                    .insert_if(ShowToPlayers, || shape.show_to_players)
+                    .id();
+                annotation_entities.push(entity);
             }
 
             for text in saved_map.annotations.text_boxes {
-                commands.spawn((
-                    Transform::from_translation(text.position.extend(z)),
-                    TextAnnotation {
-                        content: text.content,
-                        font_size: text.font_size,
-                        color: array_to_color(text.color),
-                    },
-                    AnnotationMarker,
-                ));
+                let entity = commands
+                    .spawn((
+                        Transform::from_translation(text.position.extend(z)),
+                        TextAnnotation {
+                            content: text.content,
+                            font_size: text.font_size,
+                            color: array_to_color(text.color),
+                            background: text.background.map(array_to_color),
+                        },
+                        AnnotationMarker,
+                    ))
+                    .insert_if(ShowToPlayers, || text.show_to_players)
+                    .id();
+                annotation_entities.push(entity);
+            }
+
+            // Load undo history, following the same asset path rewrites as the items
+            match result.history {
+                Some(mut history) => {
+                    history.map_asset_paths(|path| {
+                        path_mapping
+                            .get(path)
+                            .cloned()
+                            .or_else(|| relative_to_bevy.get(path).map(|p| p.to_string()))
+                    });
+                    item_entities.extend(annotation_entities);
+                    *state.history = CommandHistory::from_saved(&history, &item_entities);
+                }
+                None => state.history.clear(),
             }
 
             info!("Map loaded from {:?}", result.path);
@@ -325,6 +428,7 @@ pub fn poll_load_tasks(
                         path: Some(result.path.clone()),
                         is_dirty: false,
                         saved_state: None,
+                        history: SavedHistory::default(),
                     },
                 );
                 open_maps.active_map_id = Some(new_id);
@@ -1,16 +1,24 @@
-//! Map state management: new map, switch map, and state capture.
+//! Map state management: new map, switch map, generated dungeons, and state capture.
+
+use std::collections::HashMap;
 
 use bevy::camera::visibility::RenderLayers;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
-use crate::editor::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
+use crate::assets::{get_image_dimensions, AssetLibrary, LibraryAsset};
+use crate::editor::history::{CommandHistory, SavedHistory};
+use crate::editor::{
+    AnnotationMarker, DrawnLine, DrawnPath, DrawnShape, ShowToPlayers, TextAnnotation,
+};
 use crate::map::{
-    AssetManifest, FogOfWarData, Layer, MapData, PlacedItem, SavedAnnotations, SavedFogOfWar,
-    SavedLine, SavedMap, SavedPath, SavedPlacedItem, SavedTextBox,
+    generate_dungeon, AssetManifest, CameraBookmarks, DungeonAsset, DungeonAssets, FogOfWarData,
+    InitiativeTracker, Layer, MapData, MapNotes, PlacedItem, SavedAnnotations, SavedFogOfWar,
+    SavedLine, SavedMap, SavedPath, SavedPlacedItem, SavedShape, SavedTextBox, TokenData,
 };
 
 use super::helpers::{array_to_color, color_to_array};
-use super::messages::{NewMapRequest, SwitchMapRequest};
+use super::messages::{GenerateDungeonRequest, NewMapRequest, SwitchMapRequest};
 use super::resources::{CurrentMapFile, MapDirtyState, OpenMap, OpenMaps};
 
 #[allow(clippy::too_many_arguments)]
@@ -19,6 +27,10 @@ pub fn new_map_system(
     mut events: MessageReader<NewMapRequest>,
     mut map_data: ResMut<MapData>,
     mut fog_data: ResMut<FogOfWarData>,
+    mut initiative: ResMut<InitiativeTracker>,
+    mut notes: ResMut<MapNotes>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut history: ResMut<CommandHistory>,
     existing_items: Query<Entity, With<PlacedItem>>,
     existing_annotations: Query<Entity, With<AnnotationMarker>>,
     mut current_map_file: ResMut<CurrentMapFile>,
@@ -42,6 +54,12 @@ pub fn new_map_system(
         // Reset fog of war to default (empty = fully fogged)
         *fog_data = FogOfWarData::default();
 
+        // A new map has no combatants, notes, bookmarks or history
+        initiative.clear();
+        notes.clear();
+        bookmarks.clear();
+        history.clear();
+
         // Clear current map file (new map has no file yet)
         current_map_file.path = None;
 
@@ -71,6 +89,7 @@ pub fn new_map_system(
                 path: None,
                 is_dirty: false,
                 saved_state: None,
+                history: SavedHistory::default(),
             },
         );
         open_maps.active_map_id = Some(new_id);
@@ -79,52 +98,175 @@ pub fn new_map_system(
     }
 }
 
-/// Helper to capture current map state as a SavedMap
-fn capture_current_map_state(
+/// Whether an asset is in a library folder or one of its subfolders
+fn in_folder(asset: &LibraryAsset, folder: &str) -> bool {
+    let path = asset.folder_path.replace('\\', "/");
+    !folder.is_empty()
+        && (path == folder
+            || path
+                .strip_prefix(folder)
+                .is_some_and(|rest| rest.starts_with('/')))
+}
+
+/// System to generate a dungeon and open it in a new map tab
+pub fn generate_dungeon_system(
+    mut events: MessageReader<GenerateDungeonRequest>,
+    library: Res<AssetLibrary>,
+    mut open_maps: ResMut<OpenMaps>,
+    mut switch_events: MessageWriter<SwitchMapRequest>,
+) {
+    for event in events.read() {
+        let folder_assets = |folder: &str| -> Vec<DungeonAsset> {
+            library
+                .assets
+                .iter()
+                .filter(|asset| in_folder(asset, folder))
+                .map(|asset| DungeonAsset {
+                    asset_path: asset.relative_path.clone(),
+                    size: get_image_dimensions(&asset.full_path)
+                        .map(|(w, h)| Vec2::new(w as f32, h as f32)),
+                })
+                .collect()
+        };
+        let assets = DungeonAssets {
+            floors: folder_assets(&event.floor_folder),
+            walls: folder_assets(&event.wall_folder),
+            doors: folder_assets(&event.door_folder),
+        };
+        let saved_map = generate_dungeon(&event.params, &assets);
+
+        // Open it like an unsaved map; switching to it spawns the items
+        let new_id = open_maps.next_id;
+        open_maps.next_id += 1;
+        open_maps.maps.insert(
+            new_id,
+            OpenMap {
+                id: new_id,
+                name: saved_map.map_data.name.clone(),
+                path: None,
+                is_dirty: true,
+                saved_state: Some(saved_map),
+                history: SavedHistory::default(),
+            },
+        );
+        switch_events.write(SwitchMapRequest { map_id: new_id });
+
+        info!(
+            "Generated dungeon with seed {} ({} floor, {} wall, {} door assets)",
+            event.params.seed,
+            assets.floors.len(),
+            assets.walls.len(),
+            assets.doors.len()
+        );
+    }
+}
+
+/// Annotations read when capturing the current map state
+#[derive(SystemParam)]
+pub struct AnnotationQueries<'w, 's> {
+    paths: Query<'w, 's, (Entity, &'static DrawnPath, Has<ShowToPlayers>)>,
+    lines: Query<'w, 's, (Entity, &'static DrawnLine, Has<ShowToPlayers>)>,
+    shapes: Query<'w, 's, (Entity, &'static DrawnShape, Has<ShowToPlayers>)>,
+    texts: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Transform,
+            &'static TextAnnotation,
+            Has<ShowToPlayers>,
+        ),
+    >,
+}
+
+/// Helper to capture current map state as a SavedMap, along with the undo
+/// history. The history refers to entities by their position in the saved
+/// map: placed items first, then paths, lines, shapes and text boxes.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn capture_current_map_state(
     map_data: &MapData,
     fog_data: &FogOfWarData,
-    placed_items: &Query<(&PlacedItem, &Transform)>,
-    paths: &Query<&DrawnPath>,
-    lines: &Query<&DrawnLine>,
-    texts: &Query<(&Transform, &TextAnnotation)>,
-) -> SavedMap {
+    initiative: &InitiativeTracker,
+    notes: &MapNotes,
+    bookmarks: &CameraBookmarks,
+    history: &CommandHistory,
+    placed_items: &Query<(Entity, &PlacedItem, &Transform, Option<&TokenData>)>,
+    annotations: &AnnotationQueries,
+) -> (SavedMap, SavedHistory) {
+    let mut item_entities: Vec<Entity> = Vec::new();
     let items: Vec<SavedPlacedItem> = placed_items
         .iter()
-        .map(|(item, transform)| SavedPlacedItem::from_entity(item, transform))
+        .map(|(entity, item, transform, token)| {
+            item_entities.push(entity);
+            SavedPlacedItem::from_entity(item, transform, token)
+        })
         .collect();
 
-    let saved_paths: Vec<SavedPath> = paths
+    let saved_paths: Vec<SavedPath> = annotations
+        .paths
         .iter()
-        .map(|p| SavedPath {
+        .map(|(_, p, show_to_players)| SavedPath {
             points: p.points.clone(),
             color: color_to_array(p.color),
             stroke_width: p.stroke_width,
+            show_to_players,
         })
         .collect();
 
-    let saved_lines: Vec<SavedLine> = lines
+    let saved_lines: Vec<SavedLine> = annotations
+        .lines
         .iter()
-        .map(|l| SavedLine {
+        .map(|(_, l, show_to_players)| SavedLine {
             start: l.start,
             end: l.end,
             color: color_to_array(l.color),
             stroke_width: l.stroke_width,
+            show_to_players,
         })
         .collect();
 
-    let saved_texts: Vec<SavedTextBox> = texts
+    let saved_shapes: Vec<SavedShape> = annotations
+        .shapes
         .iter()
-        .map(|(transform, t)| SavedTextBox {
+        .map(|(_, s, show_to_players)| SavedShape {
+            kind: s.kind,
+            points: s.points.clone(),
+            color: color_to_array(s.color),
+            stroke_width: s.stroke_width,
+            fill: s.fill.map(color_to_array),
+            dashed: s.dashed,
+            show_to_players,
+        })
+        .collect();
+
+    let saved_texts: Vec<SavedTextBox> = annotations
+        .texts
+        .iter()
+        .map(|(_, transform, t, show_to_players)| SavedTextBox {
             position: transform.translation.truncate(),
             content: t.content.clone(),
             font_size: t.font_size,
             color: color_to_array(t.color),
+            background: t.background.map(color_to_array),
+            show_to_players,
         })
         .collect();
 
     let asset_manifest = AssetManifest::from_items(items.iter());
 
-    SavedMap {
+    let entity_index: HashMap<Entity, usize> = item_entities
+        .iter()
+        .copied()
+        .chain(annotations.paths.iter().map(|(entity, ..)| entity))
+        .chain(annotations.lines.iter().map(|(entity, ..)| entity))
+        .chain(annotations.shapes.iter().map(|(entity, ..)| entity))
+        .chain(annotations.texts.iter().map(|(entity, ..)| entity))
+        .enumerate()
+        .map(|(index, entity)| (entity, index))
+        .collect();
+    let saved_history = history.to_saved(|entity| entity_index.get(&entity).copied());
+
+    let saved_map = SavedMap {
         asset_manifest,
         map_data: map_data.clone(),
         placed_items: items,
@@ -132,28 +274,34 @@ fn capture_current_map_state(
             paths: saved_paths,
             lines: saved_lines,
             text_boxes: saved_texts,
+            shapes: saved_shapes,
         },
         fog_of_war: SavedFogOfWar::from(fog_data),
-    }
+        initiative: initiative.to_saved(|e| item_entities.iter().position(|&i| i == e)),
+        notes: notes.to_saved(),
+        camera_bookmarks: bookmarks.to_saved(),
+    };
+    (saved_map, saved_history)
 }
 
 /// System to handle switching between open maps
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn switch_map_system(
     mut commands: Commands,
     mut events: MessageReader<SwitchMapRequest>,
     mut map_data: ResMut<MapData>,
     mut fog_data: ResMut<FogOfWarData>,
+    mut initiative: ResMut<InitiativeTracker>,
+    mut notes: ResMut<MapNotes>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut history: ResMut<CommandHistory>,
     mut open_maps: ResMut<OpenMaps>,
     mut current_map_file: ResMut<CurrentMapFile>,
     mut dirty_state: ResMut<MapDirtyState>,
     asset_server: Res<AssetServer>,
-    placed_items_query: Query<(&PlacedItem, &Transform)>,
-    existing_items: Query<Entity, With<PlacedItem>>,
-    existing_annotations: Query<Entity, With<AnnotationMarker>>,
-    paths: Query<&DrawnPath>,
-    lines: Query<&DrawnLine>,
-    texts: Query<(&Transform, &TextAnnotation)>,
+    placed_items_query: Query<(Entity, &PlacedItem, &Transform, Option<&TokenData>)>,
+    existing_entities: Query<Entity, Or<(With<PlacedItem>, With<AnnotationMarker>)>>,
+    annotations: AnnotationQueries,
 ) {
     for event in events.read() {
         let target_id = event.map_id;
@@ -165,31 +313,29 @@ pub fn switch_map_system(
 
         // First, save the current map state
         if let Some(current_id) = open_maps.active_map_id {
-            let current_state = capture_current_map_state(
+            let (current_state, current_history) = capture_current_map_state(
                 &map_data,
                 &fog_data,
+                &initiative,
+                &notes,
+                &bookmarks,
+                &history,
                 &placed_items_query,
-                &paths,
-                &lines,
-                &texts,
+                &annotations,
             );
             let current_dirty = dirty_state.is_dirty;
 
             if let Some(current_map) = open_maps.maps.get_mut(&current_id) {
                 current_map.saved_state = Some(current_state);
+                current_map.history = current_history;
                 current_map.is_dirty = current_dirty;
             }
         }
 
         // Now load the target map
         if let Some(target_map) = open_maps.maps.get(&target_id).cloned() {
-            // Clear existing items
-            for entity in existing_items.iter() {
-                commands.entity(entity).despawn();
-            }
-
-            // Clear existing annotations
-            for entity in existing_annotations.iter() {
+            // Clear existing items and annotations
+            for entity in existing_entities.iter() {
                 commands.entity(entity).despawn();
             }
 
@@ -201,7 +347,8 @@ pub fn switch_map_system(
                 // Restore fog of war data
                 *fog_data = FogOfWarData::from(saved_state.fog_of_war.clone());
 
-                // Spawn placed items
+                // Spawn placed items, remembering entities for initiative links
+                let mut item_entities = Vec::with_capacity(saved_state.placed_items.len());
                 for item in &saved_state.placed_items {
                     let texture: Handle<Image> = asset_server.load(&item.asset_path);
                     let z_index = item.z_index.clamp(0, Layer::max_z_index());
@@ -213,7 +360,7 @@ pub fn switch_map_system(
                         RenderLayers::layer(1)
                     };
 
-                    commands.spawn((
+                    let mut entity = commands.spawn((
                         Sprite::from_image(texture),
                         Transform {
                             translation: item.position.extend(z),
@@ -227,53 +374,108 @@ pub fn switch_map_system(
                         },
                         render_layer,
                     ));
+                    if let Some(token) = &item.token {
+                        entity.insert(token.clone());
+                    }
+                    item_entities.push(entity.id());
                 }
 
+                // Restore initiative tracker
+                *initiative =
+                    InitiativeTracker::from_saved(&saved_state.initiative, &item_entities);
+
+                // Restore GM notes
+                *notes = MapNotes::from_saved(&saved_state.notes);
+
+                // Restore camera bookmarks
+                *bookmarks = CameraBookmarks::from_saved(&saved_state.camera_bookmarks);
+
                 // Spawn annotations
                 let z = Layer::Annotation.z_base();
+                let mut annotation_entities = Vec::new();
 
                 for path in &saved_state.annotations.paths {
-                    commands.spawn((
-                        Transform::from_translation(Vec3::new(0.0, 0.0, z)),
-                        DrawnPath {
-                            points: path.points.clone(),
-                            color: array_to_color(path.color),
-                            stroke_width: path.stroke_width,
-                        },
-                        AnnotationMarker,
-                    ));
+                    let entity = commands
+                        .spawn((
+                            Transform::from_translation(Vec3::new(0.0, 0.0, z)),
+                            DrawnPath {
+                                points: path.points.clone(),
+                                color: array_to_color(path.color),
+                                stroke_width: path.stroke_width,
+                            },
+                            AnnotationMarker,
+                        ))
+                        .insert_if(ShowToPlayers, || path.show_to_players)
+                        .id();
+                    annotation_entities.push(entity);
                 }
 
                 for line in &saved_state.annotations.lines {
-                    commands.spawn((
-                        Transform::from_translation(Vec3::new(0.0, 0.0, z)),
-                        DrawnLine {
-                            start: line.start,
-                            end: line.end,
-                            color: array_to_color(line.color),
-                            stroke_width: line.stroke_width,
-                        },
-                        AnnotationMarker,
-                    ));
+                    let entity = commands
+                        .spawn((
+                            Transform::from_translation(Vec3::new(0.0, 0.0, z)),
+                            DrawnLine {
+                                start: line.start,
+                                end: line.end,
+                                color: array_to_color(line.color),
+                                stroke_width: line.stroke_width,
+                            },
+                            AnnotationMarker,
+                        ))
+                        .insert_if(ShowToPlayers, || line.show_to_players)
+                        .id();
+                    annotation_entities.push(entity);
+                }
+
+                for shape in &saved_state.annotations.shapes {
+                    let entity = commands
+                        .spawn((
+                            Transform::from_translation(Vec3::new(0.0, 0.0, z)),
+                            DrawnShape {
+                                kind: shape.kind,
+                                points: shape.points.clone(),
+                                color: array_to_color(shape.color),
+                                stroke_width: shape.stroke_width,
+                                fill: shape.fill.map(array_to_color),
+                                dashed: shape.dashed,
+                            },
+                            AnnotationMarker,
+                        ))
+                        .insert_if(ShowToPlayers, || shape.show_to_players)
+                        .id();
+                    annotation_entities.push(entity);
                 }
 
                 for text in &saved_state.annotations.text_boxes {
-                    commands.spawn((
-                        Transform::from_translation(text.position.extend(z)),
-                        TextAnnotation {
-                            content: text.content.clone(),
-                            font_size: text.font_size,
-                            color: array_to_color(text.color),
-                        },
-                        AnnotationMarker,
-                    ));
+                    let entity = commands
+                        .spawn((
+                            Transform::from_translation(text.position.extend(z)),
+                            TextAnnotation {
+                                content: text.content.clone(),
+                                font_size: text.font_size,
+                                color: array_to_color(text.color),
+                                background: text.background.map(array_to_color),
+                            },
+                            AnnotationMarker,
+                        ))
+                        .insert_if(ShowToPlayers, || text.show_to_players)
+                        .id();
+                    annotation_entities.push(entity);
                 }
+
+                // Restore undo history against the respawned entities
+                item_entities.extend(annotation_entities);
+                *history = CommandHistory::from_saved(&target_map.history, &item_entities);
             } else {
                 // No saved state, start with empty/default map
                 *map_data = MapData::default();
                 map_data.name = target_map.name.clone();
                 // Reset fog of war to default (empty = fully fogged)
                 *fog_data = FogOfWarData::default();
+                initiative.clear();
+                notes.clear();
+                bookmarks.clear();
+                history.clear();
             }
 
             // Update current map file
@@ -3,6 +3,8 @@
 use bevy::prelude::*;
 use std::path::PathBuf;
 
+use crate::map::DungeonParams;
+
 #[derive(Message)]
 pub struct SaveMapRequest {
     pub path: PathBuf,
@@ -22,3 +24,13 @@ pub struct NewMapRequest;
 pub struct SwitchMapRequest {
     pub map_id: u64,
 }
+
+/// Message to generate a dungeon and open it as a new map
+#[derive(Message)]
+pub struct GenerateDungeonRequest {
+    pub params: DungeonParams,
+    /// Library folders to take floor, wall and door assets from (empty = none)
+    pub floor_folder: String,
+    pub wall_folder: String,
+    pub door_folder: String,
+}
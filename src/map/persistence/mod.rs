@@ -14,7 +14,7 @@
 //! - [`helpers`] - Utility functions (color conversion, directory creation)
 //! - [`save`] - Save system and task polling
 //! - [`load`] - Load system and task polling
-//! - [`map_state`] - New map and switch map systems
+//! - [`map_state`] - New map, switch map and dungeon generation systems
 //! - [`dirty`] - Dirty state detection systems
 //!
 //! ## Key Types
@@ -31,6 +31,7 @@
 //! - [`poll_load_tasks`] - Polls load task completion
 //! - [`new_map_system`] - Creates a new blank map
 //! - [`switch_map_system`] - Switches between open maps
+//! - [`generate_dungeon_system`] - Opens a generated dungeon as a new map
 
 mod dirty;
 mod helpers;
@@ -45,7 +46,9 @@ mod save;
 mod tests;
 
 // Re-exports - Messages
-pub use messages::{LoadMapRequest, NewMapRequest, SaveMapRequest, SwitchMapRequest};
+pub use messages::{
+    GenerateDungeonRequest, LoadMapRequest, NewMapRequest, SaveMapRequest, SwitchMapRequest,
+};
 
 // Re-exports - Resources
 pub use resources::{
@@ -54,12 +57,13 @@ pub use resources::{
 };
 
 // Re-exports - Helpers
-pub use helpers::ensure_maps_directory;
+pub use helpers::{ensure_maps_directory, history_path};
 
 // Re-exports - Systems
 pub use dirty::{
-    decay_dirty_suppression, detect_item_additions, detect_item_removals, detect_item_transforms,
+    decay_dirty_suppression, detect_annotation_changes, detect_item_additions,
+    detect_item_removals, detect_item_transforms, detect_token_changes,
 };
 pub use load::{load_map_system, poll_load_tasks};
-pub use map_state::{new_map_system, switch_map_system};
+pub use map_state::{generate_dungeon_system, new_map_system, switch_map_system};
 pub use save::{poll_save_tasks, save_map_system};
@@ -5,6 +5,8 @@ use bevy::tasks::Task;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::editor::history::SavedHistory;
+
 use super::results::{LoadResult, SaveResult};
 
 #[derive(Resource, Default)]
@@ -102,6 +104,9 @@ pub struct OpenMap {
     pub path: Option<PathBuf>,
     pub is_dirty: bool,
     pub saved_state: Option<crate::map::SavedMap>,
+    /// Undo history while another map is shown, with entities stored in the
+    /// order of `saved_state`
+    pub history: SavedHistory,
 }
 
 /// Resource tracking all open maps
@@ -124,6 +129,7 @@ impl Default for OpenMaps {
                 path: None,
                 is_dirty: false,
                 saved_state: None,
+                history: SavedHistory::default(),
             },
         );
         Self {
@@ -2,6 +2,7 @@
 
 use std::path::PathBuf;
 
+use crate::editor::history::SavedHistory;
 use crate::map::SavedMap;
 
 /// Result of an async save operation
@@ -15,5 +16,7 @@ pub struct SaveResult {
 pub struct LoadResult {
     pub path: PathBuf,
     pub saved_map: Option<SavedMap>,
+    /// Undo history saved next to the map, if it was read
+    pub history: Option<SavedHistory>,
     pub error: Option<String>,
 }
@@ -3,16 +3,19 @@
 use bevy::prelude::*;
 use bevy::tasks::IoTaskPool;
 use futures_lite::future;
+use std::path::Path;
 
 use crate::assets::AssetLibrary;
-use crate::config::UpdateLastMapPathRequest;
-use crate::editor::{AnnotationMarker, DrawnLine, DrawnPath, TextAnnotation};
+use crate::config::{AppConfig, UpdateLastMapPathRequest};
+use crate::editor::history::{CommandHistory, SavedHistory};
+use crate::editor::AnnotationMarker;
 use crate::map::{
-    AssetManifest, FogOfWarData, MapData, PlacedItem, SavedAnnotations, SavedFogOfWar, SavedLine,
-    SavedMap, SavedPath, SavedPlacedItem, SavedTextBox,
+    AssetManifest, CameraBookmarks, FogOfWarData, InitiativeTracker, MapData, MapNotes,
+    PlacedItem, TokenData,
 };
 
-use super::helpers::color_to_array;
+use super::helpers::history_path;
+use super::map_state::{capture_current_map_state, AnnotationQueries};
 use super::messages::SaveMapRequest;
 use super::resources::{
     AsyncMapOperation, CurrentMapFile, MapDirtyState, MapSaveError, OpenMaps, SaveMapTask,
@@ -26,12 +29,15 @@ pub fn save_map_system(
     mut events: MessageReader<SaveMapRequest>,
     map_data: Res<MapData>,
     fog_data: Res<FogOfWarData>,
-    placed_items: Query<(&PlacedItem, &Transform)>,
-    paths: Query<&DrawnPath>,
-    lines: Query<&DrawnLine>,
-    texts: Query<(&Transform, &TextAnnotation)>,
+    initiative: Res<InitiativeTracker>,
+    notes: Res<MapNotes>,
+    bookmarks: Res<CameraBookmarks>,
+    history: Res<CommandHistory>,
+    placed_items: Query<(Entity, &PlacedItem, &Transform, Option<&TokenData>)>,
+    annotations: AnnotationQueries,
     mut async_op: ResMut<AsyncMapOperation>,
     asset_library: Res<AssetLibrary>,
+    config: Res<AppConfig>,
 ) {
     for event in events.read() {
         // Don't start a new save if one is already in progress
@@ -40,64 +46,32 @@ pub fn save_map_system(
             continue;
         }
 
-        let mut items: Vec<SavedPlacedItem> = placed_items
-            .iter()
-            .map(|(item, transform)| SavedPlacedItem::from_entity(item, transform))
-            .collect();
+        let (mut saved_map, mut saved_history) = capture_current_map_state(
+            &map_data,
+            &fog_data,
+            &initiative,
+            &notes,
+            &bookmarks,
+            &history,
+            &placed_items,
+            &annotations,
+        );
 
         // Convert asset paths from Bevy-loadable to library-relative for portability
         let bevy_to_relative = asset_library.build_bevy_to_relative_map();
-        for item in &mut items {
+        for item in &mut saved_map.placed_items {
             if let Some(relative) = bevy_to_relative.get(item.asset_path.as_str()) {
                 item.asset_path = relative.clone();
             }
             // If no mapping found (e.g., deleted asset), keep the original path as fallback
         }
+        saved_map.asset_manifest = AssetManifest::from_items(saved_map.placed_items.iter());
 
-        // Collect annotations
-        let saved_paths: Vec<SavedPath> = paths
-            .iter()
-            .map(|p| SavedPath {
-                points: p.points.clone(),
-                color: color_to_array(p.color),
-                stroke_width: p.stroke_width,
-            })
-            .collect();
-
-        let saved_lines: Vec<SavedLine> = lines
-            .iter()
-            .map(|l| SavedLine {
-                start: l.start,
-                end: l.end,
-                color: color_to_array(l.color),
-                stroke_width: l.stroke_width,
-            })
-            .collect();
-
-        let saved_texts: Vec<SavedTextBox> = texts
-            .iter()
-            .map(|(transform, t)| SavedTextBox {
-                position: transform.translation.truncate(),
-                content: t.content.clone(),
-                font_size: t.font_size,
-                color: color_to_array(t.color),
-            })
-            .collect();
-
-        // Build asset manifest from placed items
-        let asset_manifest = AssetManifest::from_items(items.iter());
-
-        let saved_map = SavedMap {
-            asset_manifest,
-            map_data: map_data.clone(),
-            placed_items: items,
-            annotations: SavedAnnotations {
-                paths: saved_paths,
-                lines: saved_lines,
-                text_boxes: saved_texts,
-            },
-            fog_of_war: SavedFogOfWar::from(&*fog_data),
-        };
+        // Only written when enabled; otherwise a stale history file is removed
+        let saved_history = config.data.save_history.then(|| {
+            saved_history.map_asset_paths(|path| bevy_to_relative.get(path).cloned());
+            saved_history
+        });
 
         let path = event.path.clone();
         let map_name = path
@@ -124,6 +98,7 @@ pub fn save_map_system(
                             error: Some(format!("Failed to write file: {}", e)),
                         }
                     } else {
+                        save_history_file(&path, saved_history.as_ref());
                         SaveResult {
                             path,
                             success: true,
@@ -143,6 +118,28 @@ pub fn save_map_system(
     }
 }
 
+/// Write the history file next to a saved map, or remove it when history
+/// isn't being saved. The map is already written, so failures only warn.
+fn save_history_file(map_path: &Path, history: Option<&SavedHistory>) {
+    let path = history_path(map_path);
+    match history {
+        Some(history) => {
+            let result = serde_json::to_string(history)
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                warn!("Failed to save history to {:?}: {}", path, e);
+            }
+        }
+        None if path.exists() => {
+            if let Err(e) = std::fs::remove_file(&path) {
+                warn!("Failed to remove history file {:?}: {}", path, e);
+            }
+        }
+        None => {}
+    }
+}
+
 /// Polls save tasks and handles completion
 #[allow(clippy::too_many_arguments)]
 pub fn poll_save_tasks(
@@ -0,0 +1,558 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Highest 5e exhaustion level (level 6 is death)
+pub const MAX_EXHAUSTION: u8 = 6;
+
+/// Maximum length of a custom marker label
+pub const MAX_CUSTOM_MARKER_LEN: usize = 24;
+
+/// Feet covered by one grid cell (the 5e standard square)
+pub const FEET_PER_CELL: f32 = 5.0;
+
+/// Badge background for custom markers, unlike any condition color
+const CUSTOM_MARKER_COLOR: Color = Color::srgb(0.1, 0.5, 0.5);
+
+/// Convert a distance in feet to world units for the given grid size
+pub fn feet_to_world(feet: f32, grid_size: f32) -> f32 {
+    feet / FEET_PER_CELL * grid_size
+}
+
+/// The 5e SRD conditions (exhaustion is tracked separately as a level)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Condition {
+    Blinded,
+    Charmed,
+    Deafened,
+    Frightened,
+    Grappled,
+    Incapacitated,
+    Invisible,
+    Paralyzed,
+    Petrified,
+    Poisoned,
+    Prone,
+    Restrained,
+    Stunned,
+    Unconscious,
+}
+
+impl Condition {
+    pub fn all() -> &'static [Condition] {
+        &[
+            Condition::Blinded,
+            Condition::Charmed,
+            Condition::Deafened,
+            Condition::Frightened,
+            Condition::Grappled,
+            Condition::Incapacitated,
+            Condition::Invisible,
+            Condition::Paralyzed,
+            Condition::Petrified,
+            Condition::Poisoned,
+            Condition::Prone,
+            Condition::Restrained,
+            Condition::Stunned,
+            Condition::Unconscious,
+        ]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Condition::Blinded => "Blinded",
+            Condition::Charmed => "Charmed",
+            Condition::Deafened => "Deafened",
+            Condition::Frightened => "Frightened",
+            Condition::Grappled => "Grappled",
+            Condition::Incapacitated => "Incapacitated",
+            Condition::Invisible => "Invisible",
+            Condition::Paralyzed => "Paralyzed",
+            Condition::Petrified => "Petrified",
+            Condition::Poisoned => "Poisoned",
+            Condition::Prone => "Prone",
+            Condition::Restrained => "Restrained",
+            Condition::Stunned => "Stunned",
+            Condition::Unconscious => "Unconscious",
+        }
+    }
+
+    /// Two-letter label drawn on the token badge
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Condition::Blinded => "Bl",
+            Condition::Charmed => "Ch",
+            Condition::Deafened => "De",
+            Condition::Frightened => "Fr",
+            Condition::Grappled => "Gr",
+            Condition::Incapacitated => "In",
+            Condition::Invisible => "Iv",
+            Condition::Paralyzed => "Pa",
+            Condition::Petrified => "Pe",
+            Condition::Poisoned => "Po",
+            Condition::Prone => "Pr",
+            Condition::Restrained => "Re",
+            Condition::Stunned => "St",
+            Condition::Unconscious => "Un",
+        }
+    }
+
+    /// Badge background color
+    pub fn color(&self) -> Color {
+        match self {
+            Condition::Blinded => Color::srgb(0.35, 0.35, 0.35),
+            Condition::Charmed => Color::srgb(0.85, 0.35, 0.65),
+            Condition::Deafened => Color::srgb(0.45, 0.45, 0.6),
+            Condition::Frightened => Color::srgb(0.55, 0.2, 0.7),
+            Condition::Grappled => Color::srgb(0.6, 0.4, 0.2),
+            Condition::Incapacitated => Color::srgb(0.7, 0.5, 0.1),
+            Condition::Invisible => Color::srgb(0.5, 0.75, 0.85),
+            Condition::Paralyzed => Color::srgb(0.9, 0.8, 0.1),
+            Condition::Petrified => Color::srgb(0.55, 0.55, 0.5),
+            Condition::Poisoned => Color::srgb(0.3, 0.65, 0.2),
+            Condition::Prone => Color::srgb(0.6, 0.3, 0.25),
+            Condition::Restrained => Color::srgb(0.75, 0.45, 0.2),
+            Condition::Stunned => Color::srgb(0.95, 0.6, 0.1),
+            Condition::Unconscious => Color::srgb(0.15, 0.15, 0.3),
+        }
+    }
+}
+
+/// 5e creature size categories
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CreatureSize {
+    Tiny,
+    Small,
+    Medium,
+    Large,
+    Huge,
+    Gargantuan,
+}
+
+impl CreatureSize {
+    pub fn all() -> &'static [CreatureSize] {
+        &[
+            CreatureSize::Tiny,
+            CreatureSize::Small,
+            CreatureSize::Medium,
+            CreatureSize::Large,
+            CreatureSize::Huge,
+            CreatureSize::Gargantuan,
+        ]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CreatureSize::Tiny => "Tiny",
+            CreatureSize::Small => "Small",
+            CreatureSize::Medium => "Medium",
+            CreatureSize::Large => "Large",
+            CreatureSize::Huge => "Huge",
+            CreatureSize::Gargantuan => "Gargantuan",
+        }
+    }
+
+    /// Width of the creature's space in grid cells (5 ft squares)
+    pub fn footprint_cells(&self) -> f32 {
+        match self {
+            CreatureSize::Tiny => 0.5,
+            CreatureSize::Small | CreatureSize::Medium => 1.0,
+            CreatureSize::Large => 2.0,
+            CreatureSize::Huge => 3.0,
+            CreatureSize::Gargantuan => 4.0,
+        }
+    }
+
+    /// Uniform scale that fits an image of the given size into the footprint
+    pub fn fit_scale(&self, image_size: Vec2, grid_size: f32) -> Option<f32> {
+        if image_size.x <= 0.0 || image_size.y <= 0.0 {
+            return None;
+        }
+        let footprint = self.footprint_cells() * grid_size;
+        Some((footprint / image_size.x).min(footprint / image_size.y))
+    }
+}
+
+/// A status badge drawn around a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusBadge {
+    /// Short label drawn on the badge
+    pub label: String,
+    /// Full name shown when hovering the badge
+    pub name: String,
+    pub color: Color,
+}
+
+/// A colored ring around a token (e.g. Spirit Guardians, Aura of Protection)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenAura {
+    pub name: String,
+    /// Reach in feet, measured from the edge of the token's space
+    pub radius_ft: f32,
+    /// RGBA color in sRGB space
+    pub color: [f32; 4],
+    /// Whether the ring is drawn in the player view
+    #[serde(default)]
+    pub player_visible: bool,
+}
+
+impl Default for TokenAura {
+    fn default() -> Self {
+        Self {
+            name: "Aura".to_string(),
+            radius_ft: 10.0,
+            color: [0.95, 0.8, 0.3, 1.0],
+            player_visible: false,
+        }
+    }
+}
+
+/// Per-token game data (only meaningful on Token-layer items)
+#[derive(Component, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenData {
+    /// Active conditions, kept in `Condition::all()` order
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    /// Whether the creature is concentrating on a spell
+    #[serde(default)]
+    pub concentrating: bool,
+    /// Exhaustion level (0 = none, up to MAX_EXHAUSTION)
+    #[serde(default)]
+    pub exhaustion: u8,
+    /// Free-form markers (e.g. "Hexed", "Bless")
+    #[serde(default)]
+    pub custom_markers: Vec<String>,
+    /// Creature size, which sets the token's footprint on the grid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<CreatureSize>,
+    /// Bright light radius in feet (0 = none)
+    #[serde(default)]
+    pub bright_light_ft: f32,
+    /// Dim light in feet beyond the bright radius, as written in 5e
+    /// ("bright light in a 20-foot radius and dim light for an additional 20 feet")
+    #[serde(default)]
+    pub dim_light_ft: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auras: Vec<TokenAura>,
+    /// Walking speed in feet per turn (0 = not set)
+    #[serde(default)]
+    pub speed_ft: f32,
+}
+
+impl TokenData {
+    /// Check if the token carries no data worth saving
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the token shows any status badges
+    pub fn has_status(&self) -> bool {
+        !self.conditions.is_empty()
+            || self.concentrating
+            || self.exhaustion > 0
+            || !self.custom_markers.is_empty()
+    }
+
+    /// Clear conditions and markers, keeping size, speed, light and auras
+    pub fn clear_status(&mut self) {
+        self.conditions.clear();
+        self.concentrating = false;
+        self.exhaustion = 0;
+        self.custom_markers.clear();
+    }
+
+    /// Whether the token sheds any light
+    pub fn emits_light(&self) -> bool {
+        self.bright_light_ft > 0.0 || self.dim_light_ft > 0.0
+    }
+
+    /// Outer edge of the light (bright plus dim) in feet
+    pub fn light_reach_ft(&self) -> f32 {
+        self.bright_light_ft.max(0.0) + self.dim_light_ft.max(0.0)
+    }
+
+    pub fn has_condition(&self, condition: Condition) -> bool {
+        self.conditions.contains(&condition)
+    }
+
+    /// Add or remove a condition
+    pub fn set_condition(&mut self, condition: Condition, active: bool) {
+        if active {
+            if !self.has_condition(condition) {
+                self.conditions.push(condition);
+                self.conditions.sort();
+            }
+        } else {
+            self.conditions.retain(|c| *c != condition);
+        }
+    }
+
+    /// Set the exhaustion level, clamped to the valid range
+    pub fn set_exhaustion(&mut self, level: u8) {
+        self.exhaustion = level.min(MAX_EXHAUSTION);
+    }
+
+    /// Add a custom marker. Returns false for blank or duplicate labels.
+    pub fn add_custom_marker(&mut self, label: &str) -> bool {
+        let label: String = label.trim().chars().take(MAX_CUSTOM_MARKER_LEN).collect();
+        if label.is_empty()
+            || self
+                .custom_markers
+                .iter()
+                .any(|m| m.eq_ignore_ascii_case(&label))
+        {
+            return false;
+        }
+        self.custom_markers.push(label);
+        true
+    }
+
+    pub fn remove_custom_marker(&mut self, index: usize) {
+        if index < self.custom_markers.len() {
+            self.custom_markers.remove(index);
+        }
+    }
+
+    /// Build the list of badges to draw, in display order
+    pub fn badges(&self) -> Vec<StatusBadge> {
+        let mut badges: Vec<StatusBadge> = self
+            .conditions
+            .iter()
+            .map(|c| StatusBadge {
+                label: c.abbreviation().to_string(),
+                name: c.display_name().to_string(),
+                color: c.color(),
+            })
+            .collect();
+
+        if self.concentrating {
+            badges.push(StatusBadge {
+                label: "C".to_string(),
+                name: "Concentrating".to_string(),
+                color: Color::srgb(0.2, 0.45, 0.9),
+            });
+        }
+
+        if self.exhaustion > 0 {
+            badges.push(StatusBadge {
+                label: format!("E{}", self.exhaustion),
+                name: format!("Exhaustion {}", self.exhaustion),
+                color: Color::srgb(0.5, 0.3, 0.15),
+            });
+        }
+
+        // The "*" keeps custom labels apart from the condition abbreviations
+        for marker in &self.custom_markers {
+            badges.push(StatusBadge {
+                label: format!("*{}", marker.chars().next().unwrap_or_default()),
+                name: marker.clone(),
+                color: CUSTOM_MARKER_COLOR,
+            });
+        }
+
+        badges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_conditions_count() {
+        // 14 SRD conditions; exhaustion is tracked as a level
+        assert_eq!(Condition::all().len(), 14);
+    }
+
+    #[test]
+    fn test_condition_abbreviations_unique() {
+        let mut seen = std::collections::HashSet::new();
+        for condition in Condition::all() {
+            assert_eq!(condition.abbreviation().chars().count(), 2);
+            assert!(
+                seen.insert(condition.abbreviation()),
+                "duplicate abbreviation for {:?}",
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_data_default_is_empty() {
+        let data = TokenData::default();
+        assert!(data.is_empty());
+        assert!(data.badges().is_empty());
+    }
+
+    #[test]
+    fn test_set_condition_keeps_canonical_order() {
+        let mut data = TokenData::default();
+        data.set_condition(Condition::Prone, true);
+        data.set_condition(Condition::Blinded, true);
+        data.set_condition(Condition::Prone, true); // No duplicate
+        assert_eq!(data.conditions, vec![Condition::Blinded, Condition::Prone]);
+
+        data.set_condition(Condition::Blinded, false);
+        assert_eq!(data.conditions, vec![Condition::Prone]);
+    }
+
+    #[test]
+    fn test_clearing_last_condition_is_empty() {
+        let mut data = TokenData::default();
+        data.set_condition(Condition::Stunned, true);
+        assert!(!data.is_empty());
+        data.set_condition(Condition::Stunned, false);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_exhaustion_is_clamped() {
+        let mut data = TokenData::default();
+        data.set_exhaustion(9);
+        assert_eq!(data.exhaustion, MAX_EXHAUSTION);
+    }
+
+    #[test]
+    fn test_custom_markers_reject_blank_and_duplicates() {
+        let mut data = TokenData::default();
+        assert!(data.add_custom_marker("  Hexed "));
+        assert!(!data.add_custom_marker("hexed"));
+        assert!(!data.add_custom_marker("   "));
+        assert_eq!(data.custom_markers, vec!["Hexed".to_string()]);
+
+        data.remove_custom_marker(5); // Out of range is a no-op
+        data.remove_custom_marker(0);
+        assert!(data.custom_markers.is_empty());
+    }
+
+    #[test]
+    fn test_badges_order_and_labels() {
+        let mut data = TokenData::default();
+        data.set_condition(Condition::Poisoned, true);
+        data.concentrating = true;
+        data.set_exhaustion(2);
+        data.add_custom_marker("Blessed");
+
+        let labels: Vec<String> = data.badges().into_iter().map(|b| b.label).collect();
+        assert_eq!(labels, vec!["Po", "C", "E2", "*B"]);
+
+        let names: Vec<String> = data.badges().into_iter().map(|b| b.name).collect();
+        assert_eq!(names, vec!["Poisoned", "Concentrating", "Exhaustion 2", "Blessed"]);
+    }
+
+    #[test]
+    fn test_custom_marker_badges_differ_from_conditions() {
+        let mut data = TokenData::default();
+        for condition in Condition::all() {
+            data.add_custom_marker(condition.abbreviation());
+        }
+        data.add_custom_marker("Blessed");
+        data.add_custom_marker("Concentration");
+        data.add_custom_marker("E2");
+
+        let badges = data.badges();
+        assert_eq!(badges.len(), Condition::all().len() + 3);
+        for badge in &badges {
+            assert_ne!(badge.label, "C");
+            assert_ne!(badge.label, "E2");
+            for condition in Condition::all() {
+                assert_ne!(badge.label, condition.abbreviation());
+                assert_ne!(badge.color, condition.color());
+            }
+        }
+    }
+
+    #[test]
+    fn test_clear_status_keeps_size() {
+        let mut data = TokenData {
+            size: Some(CreatureSize::Large),
+            ..Default::default()
+        };
+        assert!(!data.is_empty());
+        assert!(!data.has_status());
+
+        data.set_condition(Condition::Prone, true);
+        assert!(data.has_status());
+        data.clear_status();
+        assert!(!data.has_status());
+        assert_eq!(data.size, Some(CreatureSize::Large));
+    }
+
+    #[test]
+    fn test_clear_status_keeps_light_and_auras() {
+        let mut data = TokenData {
+            bright_light_ft: 20.0,
+            dim_light_ft: 20.0,
+            auras: vec![TokenAura::default()],
+            ..Default::default()
+        };
+        data.concentrating = true;
+        data.clear_status();
+        assert!(!data.has_status());
+        assert!(data.emits_light());
+        assert_eq!(data.auras.len(), 1);
+    }
+
+    #[test]
+    fn test_light_reach_adds_dim_to_bright() {
+        let torch = TokenData {
+            bright_light_ft: 20.0,
+            dim_light_ft: 20.0,
+            ..Default::default()
+        };
+        assert_eq!(torch.light_reach_ft(), 40.0);
+        assert!(!TokenData::default().emits_light());
+    }
+
+    #[test]
+    fn test_feet_to_world_uses_grid_scale() {
+        assert_eq!(feet_to_world(5.0, 70.0), 70.0);
+        assert_eq!(feet_to_world(30.0, 50.0), 300.0);
+    }
+
+    #[test]
+    fn test_token_data_without_light_fields_deserializes() {
+        let json = r#"{"conditions":["Prone"],"concentrating":false,"exhaustion":0,"custom_markers":[]}"#;
+        let data: TokenData = serde_json::from_str(json).unwrap();
+        assert!(!data.emits_light());
+        assert!(data.auras.is_empty());
+    }
+
+    #[test]
+    fn test_creature_size_footprints() {
+        let cells: Vec<f32> = CreatureSize::all()
+            .iter()
+            .map(|s| s.footprint_cells())
+            .collect();
+        assert_eq!(cells, vec![0.5, 1.0, 1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_fit_scale_uses_longest_side() {
+        // 140x70 image into a 2x2 footprint of 70px cells
+        let scale = CreatureSize::Large.fit_scale(Vec2::new(140.0, 70.0), 70.0);
+        assert_eq!(scale, Some(1.0));
+
+        let scale = CreatureSize::Medium.fit_scale(Vec2::new(280.0, 280.0), 70.0);
+        assert_eq!(scale, Some(0.25));
+
+        assert_eq!(CreatureSize::Huge.fit_scale(Vec2::ZERO, 70.0), None);
+    }
+
+    #[test]
+    fn test_token_data_serialization_roundtrip() {
+        let mut data = TokenData::default();
+        data.set_condition(Condition::Frightened, true);
+        data.set_exhaustion(1);
+        data.add_custom_marker("Marked");
+        data.size = Some(CreatureSize::Huge);
+        data.bright_light_ft = 20.0;
+        data.auras.push(TokenAura {
+            player_visible: true,
+            ..Default::default()
+        });
+
+        let json = serde_json::to_string(&data).unwrap();
+        let deserialized: TokenData = serde_json::from_str(&json).unwrap();
+        assert_eq!(data, deserialized);
+    }
+}
@@ -1,8 +1,10 @@
 mod keep_awake;
 mod player_window;
+mod roll_flash;
 pub mod state;
 mod viewport;
 
+pub use roll_flash::FlashRollRequest;
 pub use state::{LiveSessionState, MonitorInfo, MonitorSelectionDialog, ViewportDragMode, ViewportDragState};
 pub use viewport::get_handle_at_position;
 
@@ -18,6 +20,7 @@ impl Plugin for LiveSessionPlugin {
             .init_resource::<ViewportDragState>()
             .init_resource::<MonitorSelectionDialog>()
             .init_resource::<keep_awake::KeepAwakeGuard>()
+            .add_message::<FlashRollRequest>()
             .init_gizmo_group::<viewport::ViewportGizmoGroup>()
             .add_systems(Startup, viewport::configure_viewport_gizmos)
             .add_systems(
@@ -33,6 +36,8 @@ impl Plugin for LiveSessionPlugin {
                     player_window::handle_player_window_close,
                     player_window::handle_player_window_close_request,
                     player_window::handle_graceful_shutdown,
+                    roll_flash::show_roll_flash,
+                    roll_flash::update_roll_flash.after(roll_flash::show_roll_flash),
                 ),
             );
     }
@@ -0,0 +1,116 @@
+//! Dice results flashed on the player window.
+//!
+//! The flash is a world-space banner on the player-only render layer, kept at
+//! the viewport center and counter-rotated so it reads upright on the display.
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::*;
+
+use super::state::LiveSessionState;
+
+/// How long a flash stays on screen (seconds)
+const FLASH_DURATION: f32 = 4.0;
+
+/// Fade-out time at the end of the flash (seconds)
+const FLASH_FADE: f32 = 1.0;
+
+/// Above fog (300) and everything else on the map
+const FLASH_Z: f32 = 900.0;
+
+/// Font size as a fraction of the viewport's shorter side
+const FLASH_TEXT_FRACTION: f32 = 0.08;
+
+/// Message to show a roll result on the player window
+#[derive(Message)]
+pub struct FlashRollRequest {
+    pub text: String,
+    pub color: Color,
+}
+
+/// Backdrop of an active flash; the text is its child
+#[derive(Component)]
+pub struct RollFlash {
+    timer: Timer,
+}
+
+/// Spawn a flash for the latest request, replacing any current one
+pub fn show_roll_flash(
+    mut commands: Commands,
+    mut events: MessageReader<FlashRollRequest>,
+    session_state: Res<LiveSessionState>,
+    existing: Query<Entity, With<RollFlash>>,
+) {
+    let Some(request) = events.read().last() else {
+        return;
+    };
+    if !session_state.is_active {
+        return;
+    }
+
+    for entity in existing.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let font_size = session_state.viewport_size.min_element() * FLASH_TEXT_FRACTION;
+    // Rough text extent; the backdrop only needs to frame it
+    let width = request.text.chars().count() as f32 * font_size * 0.6 + font_size;
+    let height = font_size * 1.6;
+
+    commands.spawn((
+        RollFlash {
+            timer: Timer::from_seconds(FLASH_DURATION, TimerMode::Once),
+        },
+        Sprite::from_color(Color::srgba(0.0, 0.0, 0.0, 0.75), Vec2::new(width, height)),
+        flash_transform(&session_state),
+        RenderLayers::layer(2),
+        children![(
+            Text2d::new(request.text.clone()),
+            TextFont {
+                font_size,
+                ..default()
+            },
+            TextColor(request.color),
+            Transform::from_xyz(0.0, 0.0, 0.1),
+            RenderLayers::layer(2),
+        )],
+    ));
+}
+
+/// Keep flashes centered on the viewport, fade them out, then despawn
+pub fn update_roll_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    session_state: Res<LiveSessionState>,
+    mut flashes: Query<(
+        Entity,
+        &mut RollFlash,
+        &mut Transform,
+        &mut Sprite,
+        &Children,
+    )>,
+    mut text_colors: Query<&mut TextColor>,
+) {
+    for (entity, mut flash, mut transform, mut sprite, children) in flashes.iter_mut() {
+        flash.timer.tick(time.delta());
+        if flash.timer.is_finished() || !session_state.is_active {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        *transform = flash_transform(&session_state);
+
+        let alpha = (flash.timer.remaining_secs() / FLASH_FADE).min(1.0);
+        sprite.color.set_alpha(0.75 * alpha);
+        for child in children.iter() {
+            if let Ok(mut color) = text_colors.get_mut(child) {
+                color.0.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+fn flash_transform(session_state: &LiveSessionState) -> Transform {
+    // The player camera is rotated by -rotation; matching it keeps the text upright
+    Transform::from_translation(session_state.viewport_center.extend(FLASH_Z))
+        .with_rotation(Quat::from_rotation_z(-session_state.rotation_radians()))
+}
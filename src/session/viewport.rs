@@ -304,8 +304,8 @@ pub fn handle_viewport_interaction(
                 session_state.viewport_size.x = new_width;
                 session_state.viewport_size.y = new_width / aspect_ratio;
             }
-            ViewportDragMode::Rotate | ViewportDragMode::None => {
-                // Rotate is not used for viewport, only for selection
+            ViewportDragMode::Rotate | ViewportDragMode::Vertex | ViewportDragMode::None => {
+                // Rotate and Vertex are not used for viewport, only for selection
             }
         }
     }
@@ -89,6 +89,36 @@ pub const FOG_BRUSH_CIRCLE: Color = Color::srgba(1.0, 1.0, 0.0, 0.8);
 /// Opaque black fog for player view
 pub const FOG_PLAYER: Color = Color::BLACK;
 
+// ============================================================================
+// Token Light Colors
+// ============================================================================
+
+/// Ring marking the edge of a token's bright light
+pub const LIGHT_BRIGHT: Color = Color::srgba(1.0, 0.9, 0.5, 0.8);
+
+/// Ring marking the outer edge of a token's dim light
+pub const LIGHT_DIM: Color = Color::srgba(0.8, 0.65, 0.3, 0.45);
+
+// ============================================================================
+// Movement Colors
+// ============================================================================
+
+/// Measurement line while dragging a token
+pub const MOVE_MEASURE: Color = Color::srgba(1.0, 1.0, 1.0, 0.9);
+
+/// Measurement line once a drag is longer than the token's speed
+pub const MOVE_OVER_SPEED: Color = Color::srgba(1.0, 0.3, 0.2, 0.95);
+
+/// Fading trail left by a token move
+pub const MOVE_TRAIL: Color = Color::srgba(0.4, 0.8, 1.0, 0.8);
+
+// ============================================================================
+// Initiative Colors
+// ============================================================================
+
+/// Gold ring around the token whose turn it is
+pub const INITIATIVE_ACTIVE: Color = Color::srgb(1.0, 0.84, 0.0);
+
 // ============================================================================
 // Asset Validation Colors
 // ============================================================================
@@ -140,6 +170,30 @@ pub mod ui {
     /// Dark background for asset browser panels
     pub const ASSET_BROWSER_BACKGROUND: egui::Color32 = egui::Color32::from_rgb(60, 60, 60);
 
+    /// Amber GM note pin
+    pub const NOTE_PIN: egui::Color32 = egui::Color32::from_rgb(240, 180, 60);
+
+    /// Outline of the note open in the notes panel
+    pub const NOTE_PIN_OPEN: egui::Color32 = egui::Color32::WHITE;
+
+    /// Minimap background
+    pub const MINIMAP_BACKGROUND: egui::Color32 = egui::Color32::from_rgb(25, 25, 28);
+
+    /// Minimap blocks for background and terrain items
+    pub const MINIMAP_TERRAIN: egui::Color32 = egui::Color32::from_rgb(90, 110, 90);
+
+    /// Minimap blocks for doodads
+    pub const MINIMAP_DOODAD: egui::Color32 = egui::Color32::from_rgb(150, 120, 80);
+
+    /// Minimap blocks for tokens
+    pub const MINIMAP_TOKEN: egui::Color32 = egui::Color32::from_rgb(80, 160, 230);
+
+    /// Minimap blocks for GM-only items
+    pub const MINIMAP_GM: egui::Color32 = egui::Color32::from_rgb(190, 90, 190);
+
+    /// Frame showing the editor camera's view on the minimap
+    pub const MINIMAP_CAMERA: egui::Color32 = egui::Color32::WHITE;
+
     /// File extension badge colors
     pub mod file_ext {
         use bevy_egui::egui;
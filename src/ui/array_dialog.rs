@@ -0,0 +1,204 @@
+//! Dialog for making an array of copies of the selection.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::editor::{ArrayDuplicateRequest, ArrayLayout};
+
+/// State for the Array dialog
+#[derive(Resource)]
+pub struct ArrayDialogState {
+    /// Whether the dialog is open
+    pub is_open: bool,
+    /// Radial rather than grid layout
+    radial: bool,
+    columns: u32,
+    rows: u32,
+    /// Grid spacing in cells
+    spacing: Vec2,
+    /// Total copies in a radial array, including the selection
+    count: u32,
+    /// Radial pivot in cells from the selection's center
+    pivot: Vec2,
+    sweep_degrees: f32,
+    rotate_copies: bool,
+}
+
+impl Default for ArrayDialogState {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            radial: false,
+            columns: 3,
+            rows: 1,
+            spacing: Vec2::ONE,
+            count: 6,
+            pivot: Vec2::new(0.0, -3.0),
+            sweep_degrees: 360.0,
+            rotate_copies: true,
+        }
+    }
+}
+
+impl ArrayDialogState {
+    fn layout(&self) -> ArrayLayout {
+        if self.radial {
+            ArrayLayout::Radial {
+                count: self.count,
+                pivot: self.pivot,
+                sweep_degrees: self.sweep_degrees,
+                rotate_copies: self.rotate_copies,
+            }
+        } else {
+            ArrayLayout::Grid {
+                columns: self.columns,
+                rows: self.rows,
+                spacing: self.spacing,
+            }
+        }
+    }
+
+    /// Number of copies the current settings make, not counting the selection
+    fn copy_count(&self) -> u32 {
+        if self.radial {
+            self.count.saturating_sub(1)
+        } else {
+            (self.columns * self.rows).saturating_sub(1)
+        }
+    }
+}
+
+/// Renders the Array dialog
+pub fn array_dialog_ui(
+    mut contexts: EguiContexts,
+    mut state: ResMut<ArrayDialogState>,
+    mut array_writer: MessageWriter<ArrayDuplicateRequest>,
+) -> Result {
+    if !state.is_open {
+        return Ok(());
+    }
+
+    let mut should_close = false;
+    let mut should_create = false;
+
+    egui::Window::new("Array")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut state.radial, false, "Grid");
+                ui.selectable_value(&mut state.radial, true, "Radial");
+            });
+            ui.add_space(4.0);
+
+            egui::Grid::new("array_settings_grid")
+                .num_columns(2)
+                .spacing([12.0, 4.0])
+                .show(ui, |ui| {
+                    if state.radial {
+                        render_radial_settings(ui, &mut state);
+                    } else {
+                        render_grid_settings(ui, &mut state);
+                    }
+                });
+
+            ui.add_space(4.0);
+            let copies = state.copy_count();
+            ui.label(
+                egui::RichText::new(format!(
+                    "Makes {} {}",
+                    copies,
+                    if copies == 1 { "copy" } else { "copies" }
+                ))
+                .weak(),
+            );
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(copies > 0, egui::Button::new("Create"))
+                    .clicked()
+                {
+                    should_create = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    should_close = true;
+                }
+            });
+        });
+
+    if should_create {
+        array_writer.write(ArrayDuplicateRequest {
+            layout: state.layout(),
+        });
+        should_close = true;
+    }
+    if should_close {
+        state.is_open = false;
+    }
+
+    Ok(())
+}
+
+fn render_grid_settings(ui: &mut egui::Ui, state: &mut ArrayDialogState) {
+    ui.label("Columns:");
+    ui.add(egui::DragValue::new(&mut state.columns).range(1..=50));
+    ui.end_row();
+
+    ui.label("Rows:");
+    ui.add(egui::DragValue::new(&mut state.rows).range(1..=50));
+    ui.end_row();
+
+    ui.label("Spacing (cells):");
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut state.spacing.x)
+                .speed(0.1)
+                .prefix("x: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut state.spacing.y)
+                .speed(0.1)
+                .prefix("y: "),
+        );
+    });
+    ui.end_row();
+}
+
+fn render_radial_settings(ui: &mut egui::Ui, state: &mut ArrayDialogState) {
+    ui.label("Count:");
+    ui.add(egui::DragValue::new(&mut state.count).range(2..=72))
+        .on_hover_text("Total items around the pivot, including the selection");
+    ui.end_row();
+
+    ui.label("Pivot (cells):");
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut state.pivot.x)
+                .speed(0.1)
+                .prefix("x: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut state.pivot.y)
+                .speed(0.1)
+                .prefix("y: "),
+        );
+    })
+    .response
+    .on_hover_text("Offset of the pivot from the selection's center");
+    ui.end_row();
+
+    ui.label("Sweep:");
+    ui.add(
+        egui::DragValue::new(&mut state.sweep_degrees)
+            .range(-360.0..=360.0)
+            .speed(1.0)
+            .suffix("°"),
+    );
+    ui.end_row();
+
+    ui.label("");
+    ui.checkbox(&mut state.rotate_copies, "Rotate copies");
+    ui.end_row();
+}
@@ -85,7 +85,7 @@ pub fn rename_asset(
     Ok((new_path, old_relative, new_relative))
 }
 
-/// Update asset_path references in all map files.
+/// Update asset_path references in all map files and their saved histories.
 pub fn update_asset_paths_in_maps(
     maps_dir: &Path,
     old_path: &str,
@@ -96,7 +96,10 @@ pub fn update_asset_paths_in_maps(
 
     for entry in entries.flatten() {
         let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+        if matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("json" | "history")
+        ) {
             // Read the map file
             let content = std::fs::read_to_string(&path)
                 .map_err(|e| format!("Failed to read map file {:?}: {}", path, e))?;
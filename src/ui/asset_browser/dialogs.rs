@@ -4,10 +4,11 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
 use crate::assets::{
-    AssetLibrary, RenameAssetRequest, SelectedAsset, ThumbnailCache,
-    UpdateLibraryMetadataRequest,
+    save_library_metadata, AssetLibrary, LibraryAsset, RenameAssetRequest, SelectedAsset,
+    ThumbnailCache, UpdateLibraryMetadataRequest,
 };
 use crate::config::SetDefaultLibraryRequest;
+use crate::keybindings::{Action, ActionInput};
 
 use super::asset_ops::{move_asset, rename_asset};
 use super::helpers::discover_folders;
@@ -180,6 +181,11 @@ pub fn render_rename_asset_dialog(
                     sel.name = browser_state.rename_new_name.trim().to_string();
                 }
 
+                // Keep per-asset metadata (creature size) attached to the file
+                if let Some(ref sel) = selected_asset.asset {
+                    rekey_asset_metadata(library, asset, sel);
+                }
+
                 // Clear thumbnail cache for this asset (path changed)
                 thumbnail_cache.thumbnails.remove(&asset.full_path);
                 thumbnail_cache.texture_ids.remove(&asset.full_path);
@@ -420,6 +426,11 @@ pub fn render_move_asset_dialog(
                     sel.folder_path = folder.clone();
                 }
 
+                // Keep per-asset metadata (creature size) attached to the file
+                if let Some(ref sel) = selected_asset.asset {
+                    rekey_asset_metadata(library, asset, sel);
+                }
+
                 // Update the browser to show the new folder
                 browser_state.selected_folder = folder.clone();
                 // Refresh discovered folders
@@ -461,14 +472,15 @@ pub fn render_move_asset_dialog(
 /// Handle keyboard shortcuts for opening rename dialogs.
 pub fn handle_rename_shortcuts(
     contexts: &mut EguiContexts,
+    keys: &ActionInput,
     browser_state: &mut AssetBrowserState,
     selected_asset: &SelectedAsset,
     map_res: &MapResources,
     library: &AssetLibrary,
 ) {
     if let Ok(ctx) = contexts.ctx_mut() {
-        // Handle F2 key to open rename dialog for selected asset
-        if ctx.input(|i| i.key_pressed(egui::Key::F2))
+        // Open rename dialog for selected asset (F2 by default)
+        if keys.just_pressed(Action::RenameAsset)
             && !browser_state.rename_dialog_open
             && let Some(ref asset) = selected_asset.asset
         {
@@ -477,16 +489,14 @@ pub fn handle_rename_shortcuts(
             browser_state.rename_dialog_open = true;
         }
 
-        // Handle F3 key to open rename map dialog
-        if ctx.input(|i| i.key_pressed(egui::Key::F3)) && !browser_state.rename_map_dialog_open {
+        // Open rename map dialog (F3 by default)
+        if keys.just_pressed(Action::RenameMap) && !browser_state.rename_map_dialog_open {
             browser_state.rename_map_new_name = map_res.map_data.name.clone();
             browser_state.rename_map_dialog_open = true;
         }
 
-        // Handle F4 key to open rename library dialog
-        if ctx.input(|i| i.key_pressed(egui::Key::F4))
-            && !browser_state.rename_library_dialog_open
-        {
+        // Open rename library dialog (F4 by default)
+        if keys.just_pressed(Action::RenameLibrary) && !browser_state.rename_library_dialog_open {
             browser_state.rename_library_new_name = library.metadata.name.clone();
             browser_state.rename_library_dialog_open = true;
         }
@@ -507,3 +517,12 @@ pub fn handle_rename_shortcuts(
         }
     }
 }
+
+/// Move an asset's library metadata to its new path and save it.
+fn rekey_asset_metadata(library: &mut AssetLibrary, old: &LibraryAsset, new: &LibraryAsset) {
+    if library.rekey_asset_metadata(&old.library_relative_path(), &new.library_relative_path())
+        && let Err(e) = save_library_metadata(&library.library_path, &library.metadata)
+    {
+        warn!("Failed to save library metadata: {}", e);
+    }
+}
@@ -7,21 +7,24 @@ use futures_lite::future;
 
 use crate::assets::{
     create_and_open_library, get_image_dimensions, open_library_directory, AssetLibrary,
-    LibraryAsset, RefreshAssetLibrary, RenameAssetRequest, SelectedAsset, ThumbnailCache,
-    UpdateLibraryMetadataRequest, THUMBNAIL_SIZE,
+    LibraryAsset, RefreshAssetLibrary, RenameAssetRequest, SelectedAsset,
+    SetAssetCreatureSizeRequest, ThumbnailCache, UpdateLibraryMetadataRequest, THUMBNAIL_SIZE,
 };
 use crate::config::{AppConfig, SetDefaultLibraryRequest};
 use crate::editor::{CurrentTool, EditorTool};
-use crate::map::SwitchMapRequest;
+use crate::keybindings::{Action, ActionInput, Keybindings};
+use crate::map::{CreatureSize, SwitchMapRequest};
 
 use super::dialogs::{
     handle_rename_shortcuts, render_import_error_dialog, render_move_asset_dialog,
     render_rename_asset_dialog, render_rename_library_dialog, render_rename_map_dialog,
     render_set_default_dialog, render_success_dialog,
 };
-use super::helpers::{discover_folders, extension_color, sanitize_map_name, scan_maps_directory};
+use super::helpers::{discover_folders, extension_color, scan_maps_directory};
 use super::library_ops::{export_library_to_zip, import_library_from_zip};
 use super::state::{AssetBrowserState, DialogStates, MapResources};
+use super::super::file_menu::save_active_map;
+use super::super::replace_assets_dialog::ReplaceAssetsDialogState;
 
 /// Main asset browser UI system.
 #[allow(clippy::too_many_arguments)]
@@ -36,9 +39,11 @@ pub fn asset_browser_ui(
     mut set_default_events: MessageWriter<SetDefaultLibraryRequest>,
     mut rename_events: MessageWriter<RenameAssetRequest>,
     mut library_metadata_events: MessageWriter<UpdateLibraryMetadataRequest>,
+    mut creature_size_events: MessageWriter<SetAssetCreatureSizeRequest>,
     mut refresh_events: MessageWriter<RefreshAssetLibrary>,
     mut map_res: MapResources,
     mut dialogs: DialogStates,
+    keys: ActionInput,
 ) -> Result {
     // Clear thumbnail cache and update folders if library path changed
     let current_path = library.library_path.clone();
@@ -49,13 +54,17 @@ pub fn asset_browser_ui(
     }
 
     // Handle keyboard shortcuts for rename dialogs
-    handle_rename_shortcuts(
-        &mut contexts,
-        &mut browser_state,
-        &selected_asset,
-        &map_res,
-        &library,
-    );
+    // The settings dialog reads keys while recording a new shortcut
+    if !dialogs.settings_state.is_open {
+        handle_rename_shortcuts(
+            &mut contexts,
+            &keys,
+            &mut browser_state,
+            &selected_asset,
+            &map_res,
+            &library,
+        );
+    }
 
     egui::SidePanel::left("asset_browser")
         .default_width(220.0)
@@ -67,6 +76,7 @@ pub fn asset_browser_ui(
                 &mut dialogs,
                 &mut map_res,
                 &mut thumbnail_cache,
+                keys.bindings(),
             );
 
             ui.separator();
@@ -89,7 +99,15 @@ pub fn asset_browser_ui(
             ui.separator();
             ui.add_space(4.0);
 
-            render_selected_asset_info(ui, &selected_asset, &mut browser_state);
+            render_selected_asset_info(
+                ui,
+                &library,
+                &selected_asset,
+                &mut browser_state,
+                &mut creature_size_events,
+                &mut dialogs.replace_assets,
+                keys.bindings(),
+            );
 
             // Settings button at bottom
             ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
@@ -252,6 +270,7 @@ fn render_library_section(
     dialogs: &mut DialogStates,
     map_res: &mut MapResources,
     _thumbnail_cache: &mut ThumbnailCache,
+    keybindings: &Keybindings,
 ) {
     // Poll pending async file dialog tasks before rendering buttons
     poll_file_dialog_tasks(library, browser_state);
@@ -288,10 +307,10 @@ fn render_library_section(
     // Library management and subsections (shown when expanded)
     if browser_state.library_expanded {
         ui.add_space(6.0);
-        render_library_buttons(ui, library, browser_state);
+        render_library_buttons(ui, library, browser_state, keybindings);
         render_export_import_buttons(ui, library, browser_state);
         ui.add_space(10.0);
-        render_maps_section(ui, library, browser_state, dialogs, map_res);
+        render_maps_section(ui, library, browser_state, dialogs, map_res, keybindings);
         ui.add_space(10.0);
         render_assets_buttons(ui, library, dialogs);
         ui.add_space(6.0);
@@ -303,6 +322,7 @@ fn render_library_buttons(
     ui: &mut egui::Ui,
     library: &mut AssetLibrary,
     browser_state: &mut AssetBrowserState,
+    keybindings: &Keybindings,
 ) {
     ui.horizontal(|ui| {
         if ui
@@ -347,7 +367,10 @@ fn render_library_buttons(
 
         if ui
             .add_sized([65.0, 24.0], egui::Button::new("Rename"))
-            .on_hover_text("Rename library (F4)")
+            .on_hover_text(format!(
+                "Rename library ({})",
+                keybindings.label(Action::RenameLibrary)
+            ))
             .clicked()
         {
             browser_state.rename_library_new_name = library.metadata.name.clone();
@@ -403,6 +426,7 @@ fn render_maps_section(
     browser_state: &mut AssetBrowserState,
     dialogs: &mut DialogStates,
     map_res: &mut MapResources,
+    keybindings: &Keybindings,
 ) {
     ui.label(egui::RichText::new("Maps").size(13.0).strong());
     ui.separator();
@@ -514,37 +538,32 @@ fn render_maps_section(
             .add_sized([45.0, 24.0], egui::Button::new("Save"))
             .clicked()
         {
-            // Only prompt for name if map is untitled; otherwise save directly
-            let active_map = map_res.open_maps.maps.get(&map_res.open_maps.active_map_id.unwrap_or(0));
-            if let Some(active) = active_map {
-                if active.name == "Untitled Map" {
-                    dialogs.menu_state.save_filename = active.name.clone();
-                    dialogs.menu_state.show_save_name_dialog = true;
-                } else if let Some(ref existing_path) = active.path {
-                    // Map has been saved before, save to same path
-                    map_res
-                        .save_events
-                        .write(crate::map::SaveMapRequest { path: existing_path.clone() });
-                } else {
-                    // Map has a name but no path yet - save to maps directory
-                    let maps_dir = library.library_path.join("maps");
-                    let filename = sanitize_map_name(&active.name);
-                    let path = maps_dir.join(format!("{}.json", filename));
-                    map_res
-                        .save_events
-                        .write(crate::map::SaveMapRequest { path });
-                }
-            }
+            save_active_map(
+                &mut dialogs.menu_state,
+                &map_res.open_maps,
+                library,
+                &mut map_res.save_events,
+            );
         }
         if ui
             .add_sized([55.0, 24.0], egui::Button::new("Rename"))
-            .on_hover_text("Rename map (F3)")
+            .on_hover_text(format!(
+                "Rename map ({})",
+                keybindings.label(Action::RenameMap)
+            ))
             .clicked()
         {
             browser_state.rename_map_new_name = map_res.map_data.name.clone();
             browser_state.rename_map_dialog_open = true;
         }
     });
+    if ui
+        .add_sized([155.0, 24.0], egui::Button::new("Generate Dungeon..."))
+        .on_hover_text("Lay out rooms and corridors from library folders as a new map")
+        .clicked()
+    {
+        dialogs.dungeon.is_open = true;
+    }
 
     // Scan and show available maps
     let maps_dir = library.library_path.join("maps");
@@ -810,15 +829,22 @@ fn render_asset_row(
 /// Render the selected asset information panel.
 fn render_selected_asset_info(
     ui: &mut egui::Ui,
+    library: &AssetLibrary,
     selected_asset: &SelectedAsset,
     browser_state: &mut AssetBrowserState,
+    creature_size_events: &mut MessageWriter<SetAssetCreatureSizeRequest>,
+    replace_dialog: &mut ReplaceAssetsDialogState,
+    keybindings: &Keybindings,
 ) {
     if let Some(ref asset) = selected_asset.asset {
         ui.horizontal(|ui| {
             ui.label(egui::RichText::new("Selected Asset").size(14.0).strong());
             if ui
                 .small_button("Rename")
-                .on_hover_text("Rename asset (F2)")
+                .on_hover_text(format!(
+                    "Rename asset ({})",
+                    keybindings.label(Action::RenameAsset)
+                ))
                 .clicked()
             {
                 browser_state.rename_new_name = asset.name.clone();
@@ -833,6 +859,13 @@ fn render_selected_asset_info(
                 browser_state.move_error = None;
                 browser_state.move_dialog_open = true;
             }
+            if ui
+                .small_button("Replace")
+                .on_hover_text("Replace placed instances of this asset on maps")
+                .clicked()
+            {
+                replace_dialog.open_for(asset);
+            }
         });
         ui.add_space(6.0);
 
@@ -871,6 +904,29 @@ fn render_selected_asset_info(
                 ui.label(egui::RichText::new("Unknown").size(13.0).weak());
             }
         });
+
+        // Creature size for tokens (auto-fits placement to the footprint)
+        let current_size = library.creature_size(asset);
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Creature:").size(13.0).weak());
+            egui::ComboBox::from_id_salt("asset_creature_size")
+                .selected_text(current_size.map_or("None", |s| s.display_name()))
+                .show_ui(ui, |ui| {
+                    let mut choice = current_size;
+                    ui.selectable_value(&mut choice, None, "None");
+                    for size in CreatureSize::all() {
+                        ui.selectable_value(&mut choice, Some(*size), size.display_name());
+                    }
+                    if choice != current_size {
+                        creature_size_events.write(SetAssetCreatureSizeRequest {
+                            asset_path: asset.library_relative_path(),
+                            size: choice,
+                        });
+                    }
+                })
+                .response
+                .on_hover_text("Placed tokens are scaled to this size's footprint");
+        });
     } else {
         ui.label(egui::RichText::new("No asset selected").size(13.0).weak());
     }
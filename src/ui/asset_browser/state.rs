@@ -12,6 +12,8 @@ use crate::map::{
 
 use super::super::file_menu::FileMenuState;
 use super::super::asset_import::AssetImportDialog;
+use super::super::dungeon_dialog::DungeonDialogState;
+use super::super::replace_assets_dialog::ReplaceAssetsDialogState;
 use super::super::settings_dialog::SettingsDialogState;
 
 /// Bundle of map-related resources and event writers.
@@ -32,6 +34,8 @@ pub struct DialogStates<'w> {
     pub menu_state: ResMut<'w, FileMenuState>,
     pub import_dialog: ResMut<'w, AssetImportDialog>,
     pub settings_state: ResMut<'w, SettingsDialogState>,
+    pub replace_assets: ResMut<'w, ReplaceAssetsDialogState>,
+    pub dungeon: ResMut<'w, DungeonDialogState>,
 }
 
 /// State resource for the asset browser panel.
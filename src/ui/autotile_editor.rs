@@ -0,0 +1,293 @@
+//! Window for building the library's auto-tile sets.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::assets::{
+    AssetLibrary, AutoTileRules, AutoTileSet, SelectedAsset, SetAutoTileSetsRequest, NEIGHBORS,
+};
+
+/// Size of the neighbor diagram drawn for each pattern
+const DIAGRAM_SIZE: f32 = 30.0;
+
+/// State for the auto-tile set editor
+#[derive(Resource, Default)]
+pub struct AutoTileEditorState {
+    /// Whether the window is open
+    pub is_open: bool,
+    /// Sets being edited, saved to the library on Save
+    draft: Vec<AutoTileSet>,
+    /// Index of the set shown on the right
+    selected: Option<usize>,
+    /// Name typed for a new set
+    new_name: String,
+    /// Rules for a new set
+    new_rules: AutoTileRules,
+}
+
+impl AutoTileEditorState {
+    /// Open the editor on the library's current sets
+    pub fn open(&mut self, library: &AssetLibrary) {
+        self.is_open = true;
+        self.draft = library.metadata.autotile_sets.clone();
+        self.selected = (!self.draft.is_empty()).then_some(0);
+        self.new_name.clear();
+    }
+
+    /// Why the draft can't be saved, if anything
+    fn problem(&self) -> Option<&'static str> {
+        if self.draft.iter().any(|set| set.name.trim().is_empty()) {
+            return Some("Every set needs a name");
+        }
+        let duplicate = self
+            .draft
+            .iter()
+            .enumerate()
+            .any(|(i, set)| self.draft[..i].iter().any(|other| other.name == set.name));
+        duplicate.then_some("Set names must be unique")
+    }
+}
+
+/// Renders the auto-tile set editor
+pub fn autotile_editor_ui(
+    mut contexts: EguiContexts,
+    mut state: ResMut<AutoTileEditorState>,
+    library: Res<AssetLibrary>,
+    selected_asset: Res<SelectedAsset>,
+    mut save_events: MessageWriter<SetAutoTileSetsRequest>,
+) -> Result {
+    if !state.is_open {
+        return Ok(());
+    }
+
+    let mut should_close = false;
+    let mut should_save = false;
+
+    egui::Window::new("Auto-tile Sets")
+        .collapsible(false)
+        .resizable(true)
+        .min_width(520.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal_top(|ui| {
+                ui.vertical(|ui| {
+                    ui.set_width(180.0);
+                    render_set_list(ui, &mut state);
+                });
+                ui.separator();
+                ui.vertical(|ui| {
+                    render_selected_set(ui, &mut state, &library, &selected_asset);
+                });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let problem = state.problem();
+                let changed = state.draft != library.metadata.autotile_sets;
+                if ui
+                    .add_enabled(changed && problem.is_none(), egui::Button::new("Save"))
+                    .clicked()
+                {
+                    should_save = true;
+                }
+                if ui.button("Close").clicked() {
+                    should_close = true;
+                }
+                if let Some(problem) = problem {
+                    ui.label(egui::RichText::new(problem).color(egui::Color32::YELLOW));
+                } else if changed {
+                    ui.label(egui::RichText::new("Unsaved changes").weak());
+                }
+            });
+        });
+
+    if should_save {
+        save_events.write(SetAutoTileSetsRequest {
+            sets: state.draft.clone(),
+        });
+    }
+    if should_close {
+        state.is_open = false;
+    }
+
+    Ok(())
+}
+
+/// List of sets and the form for adding one
+fn render_set_list(ui: &mut egui::Ui, state: &mut AutoTileEditorState) {
+    ui.label(egui::RichText::new("Sets").strong());
+    egui::ScrollArea::vertical()
+        .id_salt("autotile_set_list")
+        .max_height(240.0)
+        .show(ui, |ui| {
+            if state.draft.is_empty() {
+                ui.label(egui::RichText::new("No sets yet").weak().italics());
+            }
+            for (index, set) in state.draft.iter().enumerate() {
+                let label = format!("{} ({})", set.name, set.pieces.len());
+                if ui
+                    .selectable_label(state.selected == Some(index), label)
+                    .clicked()
+                {
+                    state.selected = Some(index);
+                }
+            }
+        });
+
+    ui.add_space(8.0);
+    ui.label(egui::RichText::new("New set").strong());
+    ui.add(
+        egui::TextEdit::singleline(&mut state.new_name)
+            .hint_text("Name")
+            .desired_width(f32::INFINITY),
+    );
+    egui::ComboBox::from_id_salt("autotile_new_rules")
+        .selected_text(state.new_rules.display_name())
+        .show_ui(ui, |ui| {
+            for rules in AutoTileRules::all() {
+                ui.selectable_value(&mut state.new_rules, *rules, rules.display_name());
+            }
+        });
+    let name = state.new_name.trim().to_string();
+    let can_add = !name.is_empty() && !state.draft.iter().any(|set| set.name == name);
+    if ui.add_enabled(can_add, egui::Button::new("Add")).clicked() {
+        state.draft.push(AutoTileSet::new(name, state.new_rules));
+        state.selected = Some(state.draft.len() - 1);
+        state.new_name.clear();
+    }
+}
+
+/// Name, rules and piece assignments of the selected set
+fn render_selected_set(
+    ui: &mut egui::Ui,
+    state: &mut AutoTileEditorState,
+    library: &AssetLibrary,
+    selected_asset: &SelectedAsset,
+) {
+    let Some(index) = state.selected.filter(|index| *index < state.draft.len()) else {
+        ui.label(egui::RichText::new("Select or add a set").weak());
+        return;
+    };
+
+    let mut delete = false;
+    let set = &mut state.draft[index];
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut set.name);
+        if ui.button("Delete").clicked() {
+            delete = true;
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Rules:");
+        let previous = set.rules;
+        egui::ComboBox::from_id_salt("autotile_set_rules")
+            .selected_text(set.rules.display_name())
+            .show_ui(ui, |ui| {
+                for rules in AutoTileRules::all() {
+                    ui.selectable_value(&mut set.rules, *rules, rules.display_name());
+                }
+            });
+        if set.rules != previous {
+            // Keep the pieces whose patterns the new rules still use
+            let masks = set.rules.canonical_masks();
+            set.pieces.retain(|mask, _| masks.contains(mask));
+        }
+    });
+
+    let selected_path = selected_asset
+        .asset
+        .as_ref()
+        .map(|asset| asset.library_relative_path());
+    ui.label(
+        egui::RichText::new(
+            "Each piece is also used turned, for the other rotations of its pattern",
+        )
+        .weak()
+        .size(11.0),
+    );
+    ui.add_space(4.0);
+
+    egui::ScrollArea::vertical()
+        .id_salt("autotile_pieces")
+        .max_height(360.0)
+        .show(ui, |ui| {
+            egui::Grid::new("autotile_pieces_grid")
+                .num_columns(4)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    for mask in set.rules.canonical_masks() {
+                        paint_mask_diagram(ui, mask);
+
+                        let piece = set.pieces.get(&mask).cloned();
+                        match &piece {
+                            Some(path) => {
+                                let missing = !library
+                                    .assets
+                                    .iter()
+                                    .any(|asset| asset.library_relative_path() == *path);
+                                let name = path.rsplit('/').next().unwrap_or(path);
+                                let text = if missing {
+                                    egui::RichText::new(name).color(egui::Color32::YELLOW)
+                                } else {
+                                    egui::RichText::new(name)
+                                };
+                                ui.label(text).on_hover_text(path);
+                            }
+                            None => {
+                                ui.label(egui::RichText::new("(none)").weak());
+                            }
+                        }
+
+                        if ui
+                            .add_enabled(selected_path.is_some(), egui::Button::new("Use selected"))
+                            .on_hover_text("Use the asset selected in the browser")
+                            .clicked()
+                            && let Some(path) = &selected_path
+                        {
+                            set.pieces.insert(mask, path.clone());
+                        }
+                        if ui
+                            .add_enabled(piece.is_some(), egui::Button::new("✕").small())
+                            .clicked()
+                        {
+                            set.pieces.remove(&mask);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+    if delete {
+        state.draft.remove(index);
+        state.selected = (!state.draft.is_empty()).then(|| index.min(state.draft.len() - 1));
+    }
+}
+
+/// A 3x3 diagram of a pattern: the cell in the middle, filled neighbors
+/// around it
+fn paint_mask_diagram(ui: &mut egui::Ui, mask: u8) {
+    let (rect, _) = ui.allocate_exact_size(egui::Vec2::splat(DIAGRAM_SIZE), egui::Sense::hover());
+    let painter = ui.painter();
+    let cell = DIAGRAM_SIZE / 3.0;
+    let cell_rect = |x: i32, y: i32| {
+        egui::Rect::from_min_size(
+            rect.min + egui::vec2(x as f32 * cell, y as f32 * cell),
+            egui::Vec2::splat(cell),
+        )
+        .shrink(0.5)
+    };
+
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(40));
+    painter.rect_filled(cell_rect(1, 1), 0.0, egui::Color32::from_rgb(90, 170, 110));
+    for (bit, offset) in NEIGHBORS {
+        if mask & bit != 0 {
+            // Grid y points up, screen y points down
+            painter.rect_filled(
+                cell_rect(1 + offset.x, 1 - offset.y),
+                0.0,
+                egui::Color32::from_rgb(60, 120, 80),
+            );
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! Dice roller window with roll log and saved macros.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::config::{AppConfig, SaveConfigRequest};
+use crate::dice::{roll_notation, DiceMacro, RollResult};
+use crate::session::{FlashRollRequest, LiveSessionState};
+
+/// Rolls kept in the log before the oldest are dropped
+const MAX_ROLL_HISTORY: usize = 200;
+
+/// Quick-roll buttons
+const QUICK_DICE: [u32; 7] = [4, 6, 8, 10, 12, 20, 100];
+
+/// A roll in the log
+pub struct RollLogEntry {
+    /// Macro name, if the roll came from one
+    pub label: Option<String>,
+    pub result: RollResult,
+    /// Local time of the roll (HH:MM:SS)
+    pub time: String,
+}
+
+/// State for the dice roller window
+#[derive(Resource)]
+pub struct DicePanelState {
+    /// Whether the window is open
+    pub is_open: bool,
+    /// Notation typed into the roll field
+    pub expression: String,
+    /// Parse error from the last roll attempt
+    pub error: Option<String>,
+    /// Name typed for saving the current expression as a macro
+    pub new_macro_name: String,
+    /// Whether results are flashed on the player window during a session
+    pub flash_to_players: bool,
+    /// Rolls this session, newest first
+    pub history: VecDeque<RollLogEntry>,
+    /// RNG used for rolls
+    pub rng: fastrand::Rng,
+}
+
+impl Default for DicePanelState {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            expression: String::new(),
+            error: None,
+            new_macro_name: String::new(),
+            flash_to_players: false,
+            history: VecDeque::new(),
+            rng: fastrand::Rng::new(),
+        }
+    }
+}
+
+impl DicePanelState {
+    /// Roll the notation and log the result. Returns the new entry.
+    fn roll(&mut self, notation: &str, label: Option<String>) -> Option<&RollLogEntry> {
+        match roll_notation(notation, &mut self.rng) {
+            Ok(result) => {
+                self.error = None;
+                self.history.push_front(RollLogEntry {
+                    label,
+                    result,
+                    time: chrono::Local::now().format("%H:%M:%S").to_string(),
+                });
+                self.history.truncate(MAX_ROLL_HISTORY);
+                self.history.front()
+            }
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+/// Highlight color for natural 20s and 1s
+fn natural_color(result: &RollResult) -> Option<egui::Color32> {
+    match result.natural_d20() {
+        Some(20) => Some(egui::Color32::from_rgb(100, 220, 100)),
+        Some(1) => Some(egui::Color32::from_rgb(230, 90, 90)),
+        _ => None,
+    }
+}
+
+/// Renders the dice roller window
+pub fn dice_panel_ui(
+    mut contexts: EguiContexts,
+    mut panel_state: ResMut<DicePanelState>,
+    mut config: ResMut<AppConfig>,
+    mut save_events: MessageWriter<SaveConfigRequest>,
+    mut flash_events: MessageWriter<FlashRollRequest>,
+    session_state: Res<LiveSessionState>,
+) -> Result {
+    if !panel_state.is_open {
+        return Ok(());
+    }
+
+    let ctx = contexts.ctx_mut()?;
+    let mut is_open = panel_state.is_open;
+    // (notation, macro name) to roll after the UI pass
+    let mut pending_roll: Option<(String, Option<String>)> = None;
+    let mut macros_changed = false;
+
+    egui::Window::new("Dice")
+        .open(&mut is_open)
+        .resizable(true)
+        .default_width(300.0)
+        .default_pos([600.0, 120.0])
+        .show(ctx, |ui| {
+            // Notation entry
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut panel_state.expression)
+                        .hint_text("e.g. 4d6kh3, 1d20+5 adv")
+                        .desired_width(180.0),
+                );
+                let submitted =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button("Roll").clicked() || submitted {
+                    pending_roll = Some((panel_state.expression.clone(), None));
+                    response.request_focus();
+                }
+            });
+
+            if let Some(ref error) = panel_state.error {
+                ui.colored_label(egui::Color32::from_rgb(230, 90, 90), error);
+            }
+
+            // Quick rolls
+            ui.horizontal_wrapped(|ui| {
+                for sides in QUICK_DICE {
+                    if ui.small_button(format!("d{}", sides)).clicked() {
+                        pending_roll = Some((format!("1d{}", sides), None));
+                    }
+                }
+                if ui
+                    .small_button("Adv")
+                    .on_hover_text("d20 with advantage")
+                    .clicked()
+                {
+                    pending_roll = Some(("adv".to_string(), None));
+                }
+                if ui
+                    .small_button("Dis")
+                    .on_hover_text("d20 with disadvantage")
+                    .clicked()
+                {
+                    pending_roll = Some(("dis".to_string(), None));
+                }
+            });
+
+            ui.add_enabled_ui(session_state.is_active, |ui| {
+                ui.checkbox(&mut panel_state.flash_to_players, "Show on player window")
+                    .on_disabled_hover_text("Start a session to show rolls to players");
+            });
+
+            ui.separator();
+
+            // Macros
+            ui.label(egui::RichText::new("Macros").weak());
+            let mut remove_index = None;
+            ui.horizontal_wrapped(|ui| {
+                for (index, dice_macro) in config.data.dice_macros.iter().enumerate() {
+                    let response = ui.button(&dice_macro.name).on_hover_text(format!(
+                        "{} (right-click to remove)",
+                        dice_macro.expression
+                    ));
+                    if response.clicked() {
+                        pending_roll =
+                            Some((dice_macro.expression.clone(), Some(dice_macro.name.clone())));
+                    }
+                    if response.secondary_clicked() {
+                        remove_index = Some(index);
+                    }
+                }
+            });
+            if let Some(index) = remove_index {
+                config.data.dice_macros.remove(index);
+                macros_changed = true;
+            }
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut panel_state.new_macro_name)
+                        .hint_text("Macro name")
+                        .desired_width(120.0),
+                );
+                let name = panel_state.new_macro_name.trim().to_string();
+                let expression = panel_state.expression.trim().to_string();
+                let valid = !name.is_empty() && crate::dice::parse(&expression).is_ok();
+                if ui
+                    .add_enabled(valid, egui::Button::new("Save"))
+                    .on_hover_text("Save the current roll as a macro")
+                    .on_disabled_hover_text("Enter a name and a valid roll")
+                    .clicked()
+                {
+                    // Saving over an existing name replaces it
+                    config.data.dice_macros.retain(|m| m.name != name);
+                    config.data.dice_macros.push(DiceMacro { name, expression });
+                    panel_state.new_macro_name.clear();
+                    macros_changed = true;
+                }
+            });
+
+            ui.separator();
+
+            // Roll log
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Roll Log").weak());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add_enabled(!panel_state.history.is_empty(), egui::Button::new("Clear"))
+                        .clicked()
+                    {
+                        panel_state.history.clear();
+                    }
+                });
+            });
+
+            if panel_state.history.is_empty() {
+                ui.label(egui::RichText::new("No rolls yet").weak().italics());
+                return;
+            }
+
+            egui::ScrollArea::vertical()
+                .max_height(320.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for entry in panel_state.history.iter() {
+                        ui.horizontal(|ui| {
+                            let mut total = egui::RichText::new(entry.result.total.to_string())
+                                .size(16.0)
+                                .strong();
+                            if let Some(color) = natural_color(&entry.result) {
+                                total = total.color(color);
+                            }
+                            ui.label(total);
+                            let title = match entry.label {
+                                Some(ref label) => {
+                                    format!("{} ({})", label, entry.result.expression)
+                                }
+                                None => entry.result.expression.clone(),
+                            };
+                            ui.label(title);
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.label(egui::RichText::new(&entry.time).weak().small());
+                                },
+                            );
+                        });
+                        ui.label(
+                            egui::RichText::new(entry.result.breakdown())
+                                .weak()
+                                .monospace(),
+                        )
+                        .on_hover_text("Dropped dice are in parentheses");
+                        ui.add_space(2.0);
+                    }
+                });
+        });
+
+    panel_state.is_open = is_open;
+
+    if let Some((notation, label)) = pending_roll {
+        let flash = panel_state.flash_to_players && session_state.is_active;
+        if let Some(entry) = panel_state.roll(&notation, label)
+            && flash
+        {
+            let text = match entry.label {
+                Some(ref label) => format!("{}: {}", label, entry.result.total),
+                None => format!("{}: {}", entry.result.expression, entry.result.total),
+            };
+            let color = match natural_color(&entry.result) {
+                Some(c) => Color::srgb_u8(c.r(), c.g(), c.b()),
+                None => Color::WHITE,
+            };
+            flash_events.write(FlashRollRequest { text, color });
+        }
+    }
+
+    if macros_changed {
+        config.dirty = true;
+        save_events.write(SaveConfigRequest);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! Dialog for generating a dungeon as a new map.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::map::{DungeonParams, GenerateDungeonRequest, DUNGEON_SIZE_RANGE, MAX_DUNGEON_ROOMS};
+
+use super::asset_browser::AssetBrowserState;
+
+/// State for the Generate Dungeon dialog
+#[derive(Resource, Default)]
+pub struct DungeonDialogState {
+    /// Whether the dialog is open
+    pub is_open: bool,
+    params: DungeonParams,
+    /// Library folders for each kind of tile (empty = none)
+    floor_folder: String,
+    wall_folder: String,
+    door_folder: String,
+}
+
+/// Renders the Generate Dungeon dialog
+pub fn dungeon_dialog_ui(
+    mut contexts: EguiContexts,
+    mut state: ResMut<DungeonDialogState>,
+    browser_state: Res<AssetBrowserState>,
+    mut generate_writer: MessageWriter<GenerateDungeonRequest>,
+) -> Result {
+    if !state.is_open {
+        return Ok(());
+    }
+
+    let mut should_close = false;
+    let mut should_generate = false;
+    let folders = &browser_state.discovered_folders;
+
+    egui::Window::new("Generate Dungeon")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut()?, |ui| {
+            egui::Grid::new("dungeon_settings_grid")
+                .num_columns(2)
+                .spacing([12.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Size (cells):");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut state.params.width)
+                                .range(DUNGEON_SIZE_RANGE)
+                                .prefix("w: "),
+                        );
+                        ui.add(
+                            egui::DragValue::new(&mut state.params.height)
+                                .range(DUNGEON_SIZE_RANGE)
+                                .prefix("h: "),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label("Rooms:");
+                    ui.add(
+                        egui::DragValue::new(&mut state.params.room_count)
+                            .range(1..=MAX_DUNGEON_ROOMS),
+                    )
+                    .on_hover_text("Small maps may fit fewer rooms");
+                    ui.end_row();
+
+                    ui.label("Seed:");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut state.params.seed).speed(1.0))
+                            .on_hover_text("The same seed and settings give the same dungeon");
+                        if ui.small_button("🎲").on_hover_text("New seed").clicked() {
+                            state.params.seed = fastrand::u32(..).into();
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Floors:");
+                    folder_combo(ui, "dungeon_floor_folder", &mut state.floor_folder, folders);
+                    ui.end_row();
+
+                    ui.label("Walls:");
+                    folder_combo(ui, "dungeon_wall_folder", &mut state.wall_folder, folders);
+                    ui.end_row();
+
+                    ui.label("Doors:");
+                    folder_combo(ui, "dungeon_door_folder", &mut state.door_folder, folders);
+                    ui.end_row();
+                });
+
+            ui.add_space(4.0);
+            ui.label(
+                egui::RichText::new(
+                    "Each cell uses a random asset from its folder. Opens in a new map tab.",
+                )
+                .weak(),
+            );
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        !state.floor_folder.is_empty(),
+                        egui::Button::new("Generate"),
+                    )
+                    .on_disabled_hover_text("Choose a floor folder first")
+                    .clicked()
+                {
+                    should_generate = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    should_close = true;
+                }
+            });
+        });
+
+    if should_generate {
+        generate_writer.write(GenerateDungeonRequest {
+            params: state.params.clone(),
+            floor_folder: state.floor_folder.clone(),
+            wall_folder: state.wall_folder.clone(),
+            door_folder: state.door_folder.clone(),
+        });
+        should_close = true;
+    }
+    if should_close {
+        state.is_open = false;
+    }
+
+    Ok(())
+}
+
+/// Pick a library folder, or none
+fn folder_combo(ui: &mut egui::Ui, id: &str, selected: &mut String, folders: &[String]) {
+    let text = if selected.is_empty() {
+        "(none)"
+    } else {
+        selected.as_str()
+    };
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(text)
+        .width(180.0)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, String::new(), "(none)");
+            for folder in folders {
+                ui.selectable_value(selected, folder.clone(), folder);
+            }
+        });
+}
@@ -4,6 +4,7 @@ use bevy_egui::{egui, EguiContexts};
 
 use crate::assets::AssetLibrary;
 use crate::config::{AppConfig, ConfigResetNotification, MissingMapWarning, SaveConfigRequest};
+use crate::keybindings::{Action, ActionInput};
 use crate::map::{
     AsyncMapOperation, LoadValidationWarning, MapLoadError, MapSaveError, NewMapRequest, OpenMaps,
     SaveMapRequest, SaveValidationWarning, UnsavedChangesDialog,
@@ -147,6 +148,63 @@ pub fn file_menu_ui(
     Ok(())
 }
 
+/// Save the active map: untitled maps ask for a name, saved maps go back to
+/// their file and named but unsaved maps go to the library's maps directory
+pub fn save_active_map(
+    menu_state: &mut FileMenuState,
+    open_maps: &OpenMaps,
+    library: &AssetLibrary,
+    save_events: &mut MessageWriter<SaveMapRequest>,
+) {
+    let Some(active) = open_maps.maps.get(&open_maps.active_map_id.unwrap_or(0)) else {
+        return;
+    };
+    if active.name == "Untitled Map" {
+        menu_state.save_filename = active.name.clone();
+        menu_state.show_save_name_dialog = true;
+    } else if let Some(ref existing_path) = active.path {
+        save_events.write(SaveMapRequest {
+            path: existing_path.clone(),
+        });
+    } else {
+        let maps_dir = library.library_path.join("maps");
+        let filename = sanitize_filename(&active.name);
+        let path = maps_dir.join(format!("{}.json", filename));
+        save_events.write(SaveMapRequest { path });
+    }
+}
+
+/// Handles the New, Save and Save As keyboard shortcuts
+pub fn handle_file_shortcuts(
+    keys: ActionInput,
+    mut contexts: EguiContexts,
+    mut menu_state: ResMut<FileMenuState>,
+    open_maps: Res<OpenMaps>,
+    library: Res<AssetLibrary>,
+    async_op: Res<AsyncMapOperation>,
+    mut save_events: MessageWriter<SaveMapRequest>,
+) {
+    // Don't trigger if typing in UI
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_keyboard_input()
+    {
+        return;
+    }
+
+    if keys.just_pressed(Action::NewMap) {
+        menu_state.show_new_confirmation = true;
+    } else if async_op.is_saving {
+        // One save at a time
+    } else if keys.just_pressed(Action::SaveMap) {
+        save_active_map(&mut menu_state, &open_maps, &library, &mut save_events);
+    } else if keys.just_pressed(Action::SaveMapAs)
+        && let Some(active) = open_maps.maps.get(&open_maps.active_map_id.unwrap_or(0))
+    {
+        menu_state.save_filename = active.name.clone();
+        menu_state.show_save_name_dialog = true;
+    }
+}
+
 fn sanitize_filename(name: &str) -> String {
     name.chars()
         .map(|c| {
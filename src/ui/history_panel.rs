@@ -0,0 +1,114 @@
+//! History panel: the map's undo history as a list of steps.
+//!
+//! Steps that have been undone are dimmed below the current one. Undone work
+//! set aside by a later edit is listed as a branch after the step it split
+//! off from. Clicking any entry undoes or redoes to just after it.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::config::{AppConfig, SaveConfigRequest};
+use crate::editor::history::{CommandHistory, HistoryPoint, JumpToHistoryRequest};
+
+/// State for the history panel
+#[derive(Resource, Default)]
+pub struct HistoryPanelState {
+    /// Whether the window is open
+    pub is_open: bool,
+}
+
+/// One clickable line of the history list
+fn history_entry(ui: &mut egui::Ui, label: &str, current: bool, undone: bool) -> bool {
+    let text = if undone {
+        egui::RichText::new(label).weak()
+    } else {
+        egui::RichText::new(label)
+    };
+    ui.selectable_label(current, text).clicked()
+}
+
+/// Renders the history panel and sends a jump when an entry is clicked
+pub fn history_panel_ui(
+    mut contexts: EguiContexts,
+    mut panel_state: ResMut<HistoryPanelState>,
+    history: Res<CommandHistory>,
+    mut config: ResMut<AppConfig>,
+    mut save_events: MessageWriter<SaveConfigRequest>,
+    mut jump_events: MessageWriter<JumpToHistoryRequest>,
+) -> Result {
+    if !panel_state.is_open {
+        return Ok(());
+    }
+
+    let depth = history.undo_count();
+    let mut is_open = panel_state.is_open;
+    let mut jump_to = None;
+
+    egui::Window::new("History")
+        .open(&mut is_open)
+        .resizable(true)
+        .default_width(240.0)
+        .default_pos([600.0, 420.0])
+        .show(contexts.ctx_mut()?, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(360.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    if history_entry(ui, "Start", depth == 0, false) {
+                        jump_to = Some(HistoryPoint::Main(0));
+                    }
+
+                    let done = history.undo_commands().map(|c| c.describe(false));
+                    let undone = history.redo_commands().map(|c| c.describe(true));
+                    for (index, label) in done.chain(undone).enumerate() {
+                        let step = index + 1;
+                        let label = format!("{}. {}", step, label);
+                        if history_entry(ui, &label, step == depth, step > depth) {
+                            jump_to = Some(HistoryPoint::Main(step));
+                        }
+                    }
+
+                    for (branch_index, branch) in history.branches().iter().enumerate() {
+                        let title = if branch.fork == 0 {
+                            "Branch from the start".to_string()
+                        } else {
+                            format!("Branch after step {}", branch.fork)
+                        };
+                        egui::CollapsingHeader::new(title)
+                            .id_salt(("history_branch", branch_index))
+                            .show(ui, |ui| {
+                                for (index, command) in branch.commands.iter().rev().enumerate() {
+                                    let label = format!(
+                                        "{}. {}",
+                                        branch.fork + index + 1,
+                                        command.describe(true)
+                                    );
+                                    if history_entry(ui, &label, false, true) {
+                                        jump_to = Some(HistoryPoint::Branch {
+                                            branch: branch_index,
+                                            steps: index + 1,
+                                        });
+                                    }
+                                }
+                            });
+                    }
+                });
+
+            ui.separator();
+            if ui
+                .checkbox(&mut config.data.save_history, "Save history with maps")
+                .on_hover_text("Keep each map's history in a .history file next to it")
+                .changed()
+            {
+                config.dirty = true;
+                save_events.write(SaveConfigRequest);
+            }
+        });
+    panel_state.is_open = is_open;
+
+    if let Some(target) = jump_to {
+        jump_events.write(JumpToHistoryRequest { target });
+    }
+
+    Ok(())
+}
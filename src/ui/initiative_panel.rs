@@ -0,0 +1,295 @@
+//! Initiative tracker window.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::editor::history::{EditorCommand, RecordEditorCommand};
+use crate::keybindings::{Action, ActionInput};
+use crate::map::{
+    roll_d20, token_display_name, InitiativeTracker, Layer, MapDirtyState, PlacedItem, Selected,
+};
+
+/// State for the initiative tracker window
+#[derive(Resource)]
+pub struct InitiativePanelState {
+    /// Whether the window is open
+    pub is_open: bool,
+    /// Name typed for a new manual entry (lair actions, off-map creatures, etc.)
+    pub new_entry_name: String,
+    /// RNG used for initiative rolls
+    pub rng: fastrand::Rng,
+}
+
+impl Default for InitiativePanelState {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            new_entry_name: String::new(),
+            rng: fastrand::Rng::new(),
+        }
+    }
+}
+
+/// Renders the initiative tracker window
+pub fn initiative_panel_ui(
+    mut contexts: EguiContexts,
+    mut panel_state: ResMut<InitiativePanelState>,
+    mut tracker: ResMut<InitiativeTracker>,
+    mut dirty_state: ResMut<MapDirtyState>,
+    tokens: Query<(Entity, &PlacedItem, Has<Selected>)>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) -> Result {
+    if !panel_state.is_open {
+        return Ok(());
+    }
+
+    let ctx = contexts.ctx_mut()?;
+    let mut is_open = panel_state.is_open;
+    let old_tracker = tracker.clone();
+    let mut changed = false;
+
+    egui::Window::new("Initiative")
+        .open(&mut is_open)
+        .resizable(true)
+        .default_width(320.0)
+        .default_pos([260.0, 120.0])
+        .show(ctx, |ui| {
+            // Round / turn status
+            ui.horizontal(|ui| {
+                if tracker.in_combat() {
+                    ui.label(
+                        egui::RichText::new(format!("Round {}", tracker.round))
+                            .size(14.0)
+                            .strong(),
+                    );
+                    if let Some(active) = tracker.active_entry() {
+                        ui.label(egui::RichText::new(format!("- {}'s turn", active.name)).weak());
+                    }
+                } else {
+                    ui.label(egui::RichText::new("Not in combat").weak());
+                }
+            });
+
+            ui.add_space(4.0);
+
+            // Turn controls
+            ui.horizontal(|ui| {
+                if tracker.in_combat() {
+                    if ui.button("◀ Prev").clicked() {
+                        tracker.previous_turn();
+                        changed = true;
+                    }
+                    if ui.button("Next ▶").clicked() {
+                        tracker.next_turn();
+                        changed = true;
+                    }
+                    if ui.button("End Combat").clicked() {
+                        tracker.end_combat();
+                        changed = true;
+                    }
+                } else if ui
+                    .add_enabled(
+                        !tracker.entries.is_empty(),
+                        egui::Button::new("Start Combat"),
+                    )
+                    .on_hover_text("Sort by initiative and begin round 1")
+                    .clicked()
+                {
+                    tracker.start_combat();
+                    changed = true;
+                }
+            });
+
+            ui.separator();
+
+            // Building the order
+            ui.horizontal(|ui| {
+                let selected_count = tokens
+                    .iter()
+                    .filter(|(_, item, selected)| *selected && item.layer == Layer::Token)
+                    .count();
+                if ui
+                    .add_enabled(selected_count > 0, egui::Button::new("Add Selected"))
+                    .on_hover_text("Add selected Token-layer items")
+                    .clicked()
+                {
+                    for (entity, item, selected) in tokens.iter() {
+                        if selected && item.layer == Layer::Token {
+                            changed |=
+                                tracker.add_token(entity, token_display_name(&item.asset_path));
+                        }
+                    }
+                }
+                if ui
+                    .button("Add All Tokens")
+                    .on_hover_text("Add every item on the Token layer")
+                    .clicked()
+                {
+                    for (entity, item, _) in tokens.iter() {
+                        if item.layer == Layer::Token {
+                            changed |=
+                                tracker.add_token(entity, token_display_name(&item.asset_path));
+                        }
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut panel_state.new_entry_name)
+                        .hint_text("Other combatant")
+                        .desired_width(160.0),
+                );
+                let name = panel_state.new_entry_name.trim().to_string();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new("Add"))
+                    .clicked()
+                {
+                    tracker.add_manual(name);
+                    panel_state.new_entry_name.clear();
+                    changed = true;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                let has_entries = !tracker.entries.is_empty();
+                if ui
+                    .add_enabled(has_entries, egui::Button::new("Roll All"))
+                    .on_hover_text("Roll d20 + modifier for every combatant")
+                    .clicked()
+                {
+                    tracker.roll_all(&mut panel_state.rng);
+                    changed = true;
+                }
+                if ui
+                    .add_enabled(has_entries, egui::Button::new("Sort"))
+                    .clicked()
+                {
+                    tracker.sort();
+                    changed = true;
+                }
+                if ui
+                    .add_enabled(has_entries, egui::Button::new("Clear"))
+                    .on_hover_text("Remove all combatants")
+                    .clicked()
+                {
+                    tracker.clear();
+                    changed = true;
+                }
+            });
+
+            ui.separator();
+
+            if tracker.entries.is_empty() {
+                ui.label(
+                    egui::RichText::new("Add tokens to build the turn order")
+                        .weak()
+                        .italics(),
+                );
+                return;
+            }
+
+            // Turn order
+            let active_index = tracker.in_combat().then_some(tracker.current);
+            let mut remove_index = None;
+
+            egui::ScrollArea::vertical()
+                .max_height(360.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("initiative_grid")
+                        .num_columns(6)
+                        .spacing([6.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("");
+                            ui.label(egui::RichText::new("Name").weak());
+                            ui.label(egui::RichText::new("Init").weak());
+                            ui.label(egui::RichText::new("Mod").weak());
+                            ui.label("");
+                            ui.label("");
+                            ui.end_row();
+
+                            for (index, entry) in tracker.entries.iter_mut().enumerate() {
+                                if active_index == Some(index) {
+                                    ui.label(
+                                        egui::RichText::new("▶")
+                                            .color(egui::Color32::from_rgb(255, 215, 0)),
+                                    );
+                                } else {
+                                    ui.label("");
+                                }
+
+                                let name_edit = ui.add(
+                                    egui::TextEdit::singleline(&mut entry.name)
+                                        .desired_width(110.0),
+                                );
+                                changed |= name_edit.changed();
+                                if entry.token.is_none() {
+                                    name_edit.on_hover_text("Not linked to a token");
+                                }
+
+                                changed |= ui
+                                    .add(
+                                        egui::DragValue::new(&mut entry.initiative).range(-10..=50),
+                                    )
+                                    .changed();
+                                changed |= ui
+                                    .add(egui::DragValue::new(&mut entry.modifier).range(-10..=20))
+                                    .changed();
+
+                                if ui
+                                    .small_button("🎲")
+                                    .on_hover_text("Roll d20 + modifier")
+                                    .clicked()
+                                {
+                                    entry.initiative =
+                                        roll_d20(&mut panel_state.rng) + entry.modifier;
+                                    changed = true;
+                                }
+                                if ui.small_button("✕").on_hover_text("Remove").clicked() {
+                                    remove_index = Some(index);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            if let Some(index) = remove_index {
+                tracker.remove(index);
+                changed = true;
+            }
+        });
+
+    panel_state.is_open = is_open;
+
+    // Tracker state is saved with the map
+    if changed {
+        dirty_state.is_dirty = true;
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditInitiative {
+                old: old_tracker,
+                new: tracker.clone(),
+            },
+        });
+    }
+
+    Ok(())
+}
+
+/// Handles the keyboard shortcut (I by default) to toggle the initiative tracker.
+pub fn handle_initiative_shortcut(
+    keys: ActionInput,
+    mut panel_state: ResMut<InitiativePanelState>,
+    mut contexts: EguiContexts,
+) {
+    // Don't toggle if typing in a text field
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_keyboard_input()
+    {
+        return;
+    }
+
+    if keys.just_pressed(Action::ToggleInitiative) {
+        panel_state.is_open = !panel_state.is_open;
+    }
+}
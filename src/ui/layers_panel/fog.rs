@@ -1,7 +1,9 @@
 //! Fog of War controls UI.
 
+use bevy::prelude::*;
 use bevy_egui::egui;
 
+use crate::editor::history::{EditorCommand, RecordEditorCommand};
 use crate::map::{FogOfWarData, Layer, MapData, MapDirtyState};
 
 /// Renders the Fog of War controls section.
@@ -10,6 +12,7 @@ pub fn render_fog_controls(
     map_data: &mut MapData,
     fog_data: &mut FogOfWarData,
     dirty_state: &mut MapDirtyState,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
 ) {
     ui.add_space(8.0);
     ui.horizontal(|ui| {
@@ -37,6 +40,7 @@ pub fn render_fog_controls(
         .on_hover_text("Toggle fog visibility for players")
         .changed()
     {
+        let old_map_data = map_data.clone();
         if let Some(layer_data) = map_data
             .layers
             .iter_mut()
@@ -45,11 +49,17 @@ pub fn render_fog_controls(
             layer_data.visible = fog_enabled;
         }
         dirty_state.is_dirty = true;
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditMapData {
+                old: old_map_data,
+                new: map_data.clone(),
+            },
+        });
     }
 
     ui.add_space(4.0);
 
-    // Reset Fog button - simply clears all revealed cells
+    // Reset Fog button - clears all revealed cells (undo reveals them again)
     let reset_enabled = fog_data.has_revealed_cells();
     if ui
         .add_enabled(
@@ -59,6 +69,11 @@ pub fn render_fog_controls(
         .on_hover_text("Hide all revealed areas (cover everything with fog)")
         .clicked()
     {
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::HideFog {
+                cells: fog_data.revealed_cells.iter().copied().collect(),
+            },
+        });
         fog_data.reset();
         dirty_state.is_dirty = true;
     }
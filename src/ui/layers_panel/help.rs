@@ -1,8 +1,12 @@
 //! Help popup window and keyboard shortcut handling.
+//!
+//! The keyboard rows are generated from the live [`Keybindings`], so the
+//! popup always shows what a key does right now.
 
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
+use crate::keybindings::{Action, ActionCategory, ActionInput, Keybindings};
 use crate::update::UpdateState;
 
 use super::HelpWindowState;
@@ -12,6 +16,7 @@ pub fn help_popup_ui(
     mut contexts: EguiContexts,
     mut help_state: ResMut<HelpWindowState>,
     update_state: Res<UpdateState>,
+    keybindings: Res<Keybindings>,
 ) -> Result {
     if !help_state.is_open {
         return Ok(());
@@ -46,19 +51,25 @@ pub fn help_popup_ui(
                 ui.separator();
 
                 // Tools Section
-                render_tools_section(ui);
+                render_tools_section(ui, &keybindings);
 
                 ui.add_space(10.0);
                 ui.separator();
 
                 // Selection Shortcuts
-                render_selection_section(ui);
+                render_selection_section(ui, &keybindings);
+
+                ui.add_space(10.0);
+                ui.separator();
+
+                // Clipboard and History
+                render_edit_section(ui, &keybindings);
 
                 ui.add_space(10.0);
                 ui.separator();
 
                 // Camera Controls
-                render_camera_section(ui);
+                render_camera_section(ui, &keybindings);
 
                 ui.add_space(10.0);
                 ui.separator();
@@ -69,14 +80,20 @@ pub fn help_popup_ui(
                 ui.add_space(10.0);
                 ui.separator();
 
+                // Combat
+                render_combat_section(ui, &keybindings);
+
+                ui.add_space(10.0);
+                ui.separator();
+
                 // Asset Management
-                render_assets_section(ui);
+                render_assets_section(ui, &keybindings);
 
                 ui.add_space(10.0);
                 ui.separator();
 
                 // File Operations
-                render_file_section(ui);
+                render_file_section(ui, &keybindings);
 
                 ui.add_space(10.0);
                 ui.separator();
@@ -104,43 +121,26 @@ pub fn help_popup_ui(
     Ok(())
 }
 
-fn render_tools_section(ui: &mut egui::Ui) {
+/// One row per action in `category`, showing its current bindings
+fn action_rows(ui: &mut egui::Ui, keybindings: &Keybindings, category: ActionCategory) {
+    for action in Action::all().iter().filter(|a| a.category() == category) {
+        ui.strong(keybindings.label(*action));
+        ui.label(action.display_name());
+        ui.end_row();
+    }
+}
+
+fn render_tools_section(ui: &mut egui::Ui, keybindings: &Keybindings) {
     ui.heading("Tools");
     egui::Grid::new("tools_grid")
         .num_columns(2)
         .spacing([20.0, 4.0])
         .show(ui, |ui| {
-            ui.strong("V / S");
-            ui.label("Select - Click to select items, drag to move");
-            ui.end_row();
-
-            ui.strong("P");
-            ui.label("Place - Single-click to place selected asset");
-            ui.end_row();
-
-            ui.strong("B");
-            ui.label("Brush - Drag to continuously place assets");
-            ui.end_row();
-
-            ui.strong("D");
-            ui.label("Draw - Freehand annotation paths");
-            ui.end_row();
-
-            ui.strong("L");
-            ui.label("Line - Straight line annotations");
-            ui.end_row();
-
-            ui.strong("F");
-            ui.label("Fog - Reveal/hide fog of war areas");
-            ui.end_row();
-
-            ui.strong("C / Shift+C");
-            ui.label("Cycle layer (Place/Brush tools)");
-            ui.end_row();
+            action_rows(ui, keybindings, ActionCategory::Tools);
         });
 }
 
-fn render_selection_section(ui: &mut egui::Ui) {
+fn render_selection_section(ui: &mut egui::Ui, keybindings: &Keybindings) {
     ui.heading("Selection");
     egui::Grid::new("selection_grid")
         .num_columns(2)
@@ -155,44 +155,32 @@ fn render_selection_section(ui: &mut egui::Ui) {
             ui.end_row();
 
             ui.strong("Drag (empty)");
-            ui.label("Box selection");
-            ui.end_row();
-
-            ui.strong("Escape");
-            ui.label("Clear selection");
+            ui.label("Box or lasso selection (Filter limits layers)");
             ui.end_row();
 
-            ui.strong("Delete / Backspace");
-            ui.label("Delete selected items");
+            ui.strong("Double-click text");
+            ui.label("Edit text (Ctrl+Enter to finish)");
             ui.end_row();
 
-            ui.strong("G");
-            ui.label("Fit selected to grid cell");
+            ui.strong("Drag vertex handle");
+            ui.label("Reshape a single selected annotation");
             ui.end_row();
 
-            ui.strong("Shift+G");
-            ui.label("Center selected to grid");
-            ui.end_row();
-
-            ui.strong("A");
-            ui.label("Restore aspect ratio");
-            ui.end_row();
-
-            ui.strong("R / Shift+R");
-            ui.label("Rotate 90° CW / CCW");
-            ui.end_row();
-
-            ui.strong("Ctrl+C / Ctrl+X");
-            ui.label("Copy / Cut selected items");
-            ui.end_row();
+            action_rows(ui, keybindings, ActionCategory::Selection);
+        });
+}
 
-            ui.strong("Ctrl+V");
-            ui.label("Paste items");
-            ui.end_row();
+fn render_edit_section(ui: &mut egui::Ui, keybindings: &Keybindings) {
+    ui.heading("Edit");
+    egui::Grid::new("edit_grid")
+        .num_columns(2)
+        .spacing([20.0, 4.0])
+        .show(ui, |ui| {
+            action_rows(ui, keybindings, ActionCategory::Edit);
         });
 }
 
-fn render_camera_section(ui: &mut egui::Ui) {
+fn render_camera_section(ui: &mut egui::Ui, keybindings: &Keybindings) {
     ui.heading("Camera");
     egui::Grid::new("camera_grid")
         .num_columns(2)
@@ -205,6 +193,16 @@ fn render_camera_section(ui: &mut egui::Ui) {
             ui.strong("Scroll Wheel");
             ui.label("Zoom in/out");
             ui.end_row();
+
+            ui.strong("Click/drag minimap");
+            ui.label("Jump there (Shift moves the player viewport)");
+            ui.end_row();
+
+            ui.strong("Bookmarks menu");
+            ui.label("Save named views; the first nine get number keys");
+            ui.end_row();
+
+            action_rows(ui, keybindings, ActionCategory::Camera);
         });
 }
 
@@ -225,60 +223,63 @@ fn render_placement_section(ui: &mut egui::Ui) {
             ui.strong("Shift+Drag");
             ui.label("Snap selected to grid while dragging");
             ui.end_row();
+
+            ui.strong("Shift+Rotate");
+            ui.label("Snap rotation to 15°/45°/90° steps");
+            ui.end_row();
+
+            ui.strong("Toolbar Snap menu");
+            ui.label("Snap targets, rotation steps, smart guides");
+            ui.end_row();
         });
 }
 
-fn render_assets_section(ui: &mut egui::Ui) {
-    ui.heading("Assets");
-    egui::Grid::new("asset_grid")
+fn render_combat_section(ui: &mut egui::Ui, keybindings: &Keybindings) {
+    ui.heading("Combat");
+    egui::Grid::new("combat_grid")
         .num_columns(2)
         .spacing([20.0, 4.0])
         .show(ui, |ui| {
-            ui.strong("F2");
-            ui.label("Rename selected asset");
-            ui.end_row();
+            action_rows(ui, keybindings, ActionCategory::Combat);
 
-            ui.strong("F3");
-            ui.label("Rename current map");
+            ui.strong("Right-click token");
+            ui.label("Edit conditions, light and auras");
             ui.end_row();
 
-            ui.strong("F4");
-            ui.label("Rename library");
+            ui.strong("Drag token");
+            ui.label("Measure movement in feet and squares");
             ui.end_row();
         });
 }
 
-fn render_file_section(ui: &mut egui::Ui) {
+fn render_assets_section(ui: &mut egui::Ui, keybindings: &Keybindings) {
+    ui.heading("Assets");
+    egui::Grid::new("asset_grid")
+        .num_columns(2)
+        .spacing([20.0, 4.0])
+        .show(ui, |ui| {
+            action_rows(ui, keybindings, ActionCategory::Assets);
+        });
+}
+
+fn render_file_section(ui: &mut egui::Ui, keybindings: &Keybindings) {
     ui.heading("File");
     egui::Grid::new("file_grid")
         .num_columns(2)
         .spacing([20.0, 4.0])
         .show(ui, |ui| {
-            ui.strong("Ctrl+S");
-            ui.label("Save map");
-            ui.end_row();
-
-            ui.strong("Ctrl+Shift+S");
-            ui.label("Save as...");
-            ui.end_row();
-
-            ui.strong("Ctrl+O");
-            ui.label("Open map");
-            ui.end_row();
-
-            ui.strong("Ctrl+N");
-            ui.label("New map");
-            ui.end_row();
-
-            ui.strong("H");
-            ui.label("Toggle this help window");
-            ui.end_row();
+            action_rows(ui, keybindings, ActionCategory::File);
         });
+    ui.label(
+        egui::RichText::new("Shortcuts can be changed in Settings.")
+            .small()
+            .weak(),
+    );
 }
 
-/// Handles the H keyboard shortcut to toggle help window.
+/// Handles the keyboard shortcut (H by default) to toggle the help window.
 pub fn handle_help_shortcut(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    keys: ActionInput,
     mut help_state: ResMut<HelpWindowState>,
     mut contexts: EguiContexts,
 ) {
@@ -289,7 +290,7 @@ pub fn handle_help_shortcut(
         return;
     }
 
-    if keyboard.just_pressed(KeyCode::KeyH) {
+    if keys.just_pressed(Action::ToggleHelp) {
         help_state.is_open = !help_state.is_open;
     }
 }
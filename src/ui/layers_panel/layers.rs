@@ -1,11 +1,21 @@
 //! Layer visibility and lock controls UI.
 
+use bevy::prelude::*;
 use bevy_egui::egui;
 
+use crate::editor::history::{EditorCommand, RecordEditorCommand};
 use crate::map::{Layer, MapData, MapDirtyState};
 
 /// Renders the layers section with visibility checkboxes and lock buttons.
-pub fn render_layers(ui: &mut egui::Ui, map_data: &mut MapData, dirty_state: &mut MapDirtyState) {
+pub fn render_layers(
+    ui: &mut egui::Ui,
+    map_data: &mut MapData,
+    dirty_state: &mut MapDirtyState,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    let old_map_data = map_data.clone();
+    let mut changed = false;
+
     ui.add_space(4.0);
     ui.label(egui::RichText::new("Layers").heading().size(18.0));
     ui.add_space(4.0);
@@ -25,9 +35,7 @@ pub fn render_layers(ui: &mut egui::Ui, map_data: &mut MapData, dirty_state: &mu
 
                     ui.add_enabled_ui(is_available, |ui| {
                         ui.horizontal(|ui| {
-                            if ui.checkbox(&mut layer_data.visible, "").changed() {
-                                dirty_state.is_dirty = true;
-                            }
+                            changed |= ui.checkbox(&mut layer_data.visible, "").changed();
                             ui.label(egui::RichText::new(layer.display_name()).size(14.0));
 
                             ui.with_layout(
@@ -48,7 +56,7 @@ pub fn render_layers(ui: &mut egui::Ui, map_data: &mut MapData, dirty_state: &mu
                                             .clicked()
                                         {
                                             layer_data.locked = !layer_data.locked;
-                                            dirty_state.is_dirty = true;
+                                            changed = true;
                                         }
                                     }
                                 },
@@ -58,4 +66,14 @@ pub fn render_layers(ui: &mut egui::Ui, map_data: &mut MapData, dirty_state: &mu
                 });
         }
     }
+
+    if changed {
+        dirty_state.is_dirty = true;
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditMapData {
+                old: old_map_data,
+                new: map_data.clone(),
+            },
+        });
+    }
 }
@@ -1,40 +1,80 @@
 //! Main layers panel UI orchestration.
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
+use crate::editor::history::RecordEditorCommand;
+use crate::editor::{AlignSelectionRequest, DuplicateSelectionRequest};
+use crate::keybindings::{Action, Keybindings};
 use crate::map::{FogOfWarData, MapData, MapDirtyState};
 use crate::session::LiveSessionState;
+use crate::ui::array_dialog::ArrayDialogState;
 
 use super::fog::render_fog_controls;
 use super::layers::render_layers;
-use super::properties::{render_properties, SelectedQuery};
+use super::properties::{
+    render_properties, SelectedAnnotationStyleQuery, SelectedQuery, SelectedTokenQuery,
+};
 use super::session::render_session_controls;
 use super::HelpWindowState;
 
+/// Messages sent from the layers panel
+#[derive(SystemParam)]
+pub struct PanelWriters<'w> {
+    align: MessageWriter<'w, AlignSelectionRequest>,
+    duplicate: MessageWriter<'w, DuplicateSelectionRequest>,
+    history: MessageWriter<'w, RecordEditorCommand>,
+}
+
 /// Main layers panel UI system.
 #[allow(clippy::too_many_arguments)]
 pub fn layers_panel_ui(
+    mut commands: Commands,
     mut contexts: EguiContexts,
     mut map_data: ResMut<MapData>,
     mut fog_data: ResMut<FogOfWarData>,
     mut dirty_state: ResMut<MapDirtyState>,
     mut selected_query: SelectedQuery,
+    mut selected_tokens: SelectedTokenQuery,
+    mut selected_annotations: SelectedAnnotationStyleQuery,
     images: Res<Assets<Image>>,
     mut session_state: ResMut<LiveSessionState>,
     mut help_state: ResMut<HelpWindowState>,
+    mut writers: PanelWriters,
+    mut array_dialog: ResMut<ArrayDialogState>,
+    keybindings: Res<Keybindings>,
 ) -> Result {
     egui::SidePanel::right("layers_panel")
         .default_width(200.0)
         .show(contexts.ctx_mut()?, |ui| {
             // Layers section
-            render_layers(ui, &mut map_data, &mut dirty_state);
+            render_layers(ui, &mut map_data, &mut dirty_state, &mut writers.history);
 
             // Fog of War controls
-            render_fog_controls(ui, &mut map_data, &mut fog_data, &mut dirty_state);
+            render_fog_controls(
+                ui,
+                &mut map_data,
+                &mut fog_data,
+                &mut dirty_state,
+                &mut writers.history,
+            );
 
             // Properties section
-            render_properties(ui, &map_data, &mut selected_query, &images);
+            render_properties(
+                ui,
+                &mut commands,
+                &map_data,
+                &mut selected_query,
+                &mut selected_tokens,
+                &mut selected_annotations,
+                &images,
+                &mut writers.align,
+                &mut writers.duplicate,
+                &mut writers.history,
+                &mut array_dialog,
+                &keybindings,
+            );
 
             // Live Session controls (when active)
             render_session_controls(ui, &mut session_state);
@@ -44,8 +84,12 @@ pub fn layers_panel_ui(
                 ui.add_space(8.0);
                 ui.weak(format!("v{}", crate::update::CURRENT_VERSION));
                 ui.add_space(4.0);
+                let help_label = match keybindings.get(Action::ToggleHelp).first() {
+                    Some(binding) => format!("Help ({})", binding),
+                    None => "Help".to_string(),
+                };
                 if ui
-                    .add_sized([120.0, 28.0], egui::Button::new("Help (H)"))
+                    .add_sized([120.0, 28.0], egui::Button::new(help_label))
                     .clicked()
                 {
                     help_state.is_open = true;
@@ -4,7 +4,19 @@ use bevy::camera::visibility::RenderLayers;
 use bevy::prelude::*;
 use bevy_egui::egui;
 
-use crate::map::{Layer, MapData, PlacedItem, Selected};
+use crate::editor::history::{
+    item_render_layers, AnnotationData, EditorCommand, ItemStateData, RecordEditorCommand,
+    TransformData,
+};
+use crate::editor::{
+    snap_to_footprint, AlignOp, AlignSelectionRequest, AnnotationMarker, DrawnLine, DrawnPath,
+    DrawnShape, DuplicateSelectionRequest, ShowToPlayers, TextAnnotation,
+};
+use crate::keybindings::{Action, Keybindings};
+use crate::map::{CreatureSize, Layer, MapData, PlacedItem, Selected, TokenData};
+use crate::theme;
+use crate::ui::array_dialog::ArrayDialogState;
+use crate::ui::token_status::{render_token_light_editor, render_token_status_editor};
 
 /// Selected item query type for the properties panel.
 pub type SelectedQuery<'w, 's> = Query<
@@ -20,12 +32,42 @@ pub type SelectedQuery<'w, 's> = Query<
     With<Selected>,
 >;
 
+/// Token status of the selected items, kept separate from [`SelectedQuery`]
+/// since most items never carry it.
+pub type SelectedTokenQuery<'w, 's> =
+    Query<'w, 's, Option<&'static mut TokenData>, With<Selected>>;
+
+/// Selected annotations, with the stroke style of paths, lines and shapes.
+/// Texts are included so visibility changes can be recorded for undo.
+pub type SelectedAnnotationStyleQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Has<ShowToPlayers>,
+        Option<&'static mut DrawnPath>,
+        Option<&'static mut DrawnLine>,
+        Option<&'static mut DrawnShape>,
+        Option<(&'static Transform, &'static TextAnnotation)>,
+    ),
+    (With<Selected>, With<AnnotationMarker>, Without<PlacedItem>),
+>;
+
 /// Renders the properties section for selected items.
+#[allow(clippy::too_many_arguments)]
 pub fn render_properties(
     ui: &mut egui::Ui,
+    commands: &mut Commands,
     map_data: &MapData,
     selected_query: &mut SelectedQuery,
+    selected_tokens: &mut SelectedTokenQuery,
+    selected_annotations: &mut SelectedAnnotationStyleQuery,
     images: &Assets<Image>,
+    align_writer: &mut MessageWriter<AlignSelectionRequest>,
+    duplicate_writer: &mut MessageWriter<DuplicateSelectionRequest>,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+    array_dialog: &mut ArrayDialogState,
+    keybindings: &Keybindings,
 ) {
     ui.add_space(4.0);
     ui.label(egui::RichText::new("Properties").heading().size(18.0));
@@ -34,13 +76,227 @@ pub fn render_properties(
     ui.add_space(4.0);
 
     let selected_count = selected_query.iter().count();
+    let annotation_count = selected_annotations.iter().count();
+
+    if annotation_count > 0 {
+        let label = if annotation_count == 1 {
+            "1 annotation selected".to_string()
+        } else {
+            format!("{} annotations selected", annotation_count)
+        };
+        ui.label(egui::RichText::new(label).size(14.0));
+        ui.add_space(4.0);
+
+        if let Some((color, stroke_width)) = annotation_stroke_style(selected_annotations) {
+            render_annotation_style(
+                ui,
+                selected_annotations,
+                color,
+                stroke_width,
+                history_writer,
+            );
+        }
+        render_annotation_visibility(ui, commands, selected_annotations, history_writer);
+
+        if selected_count > 0 {
+            ui.add_space(8.0);
+            ui.separator();
+            ui.add_space(4.0);
+        }
+    }
 
     if selected_count == 0 {
-        ui.label(egui::RichText::new("No item selected").size(14.0).weak());
+        if annotation_count == 0 {
+            ui.label(egui::RichText::new("No item selected").size(14.0).weak());
+        }
     } else if selected_count > 1 {
-        render_multi_selection(ui, map_data, selected_query, images);
+        render_multi_selection(
+            ui,
+            map_data,
+            selected_query,
+            images,
+            history_writer,
+            keybindings,
+        );
+        render_align_controls(ui, selected_count, align_writer, keybindings);
     } else {
-        render_single_selection(ui, map_data, selected_query, images);
+        render_single_selection(
+            ui,
+            commands,
+            map_data,
+            selected_query,
+            selected_tokens,
+            images,
+            history_writer,
+            keybindings,
+        );
+    }
+
+    if selected_count + annotation_count > 0 {
+        render_duplicate_controls(ui, duplicate_writer, array_dialog, keybindings);
+    }
+}
+
+/// Renders the Duplicate and Array buttons.
+fn render_duplicate_controls(
+    ui: &mut egui::Ui,
+    duplicate_writer: &mut MessageWriter<DuplicateSelectionRequest>,
+    array_dialog: &mut ArrayDialogState,
+    keybindings: &Keybindings,
+) {
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        if ui
+            .add(egui::Button::new("Duplicate").min_size(egui::vec2(68.0, 22.0)))
+            .on_hover_text(format!(
+                "Copy by the last paste offset ({})",
+                keybindings.label(Action::Duplicate)
+            ))
+            .clicked()
+        {
+            duplicate_writer.write(DuplicateSelectionRequest);
+        }
+        if ui
+            .add(egui::Button::new("Array...").min_size(egui::vec2(68.0, 22.0)))
+            .on_hover_text("Copy into a grid or around a pivot")
+            .clicked()
+        {
+            array_dialog.is_open = true;
+        }
+    });
+}
+
+/// Stroke color and width of the first selected path, line or shape.
+fn annotation_stroke_style(
+    selected_annotations: &SelectedAnnotationStyleQuery,
+) -> Option<(Color, f32)> {
+    selected_annotations
+        .iter()
+        .find_map(|(_, _, path, line, shape, _)| {
+            path.map(|p| (p.color, p.stroke_width))
+                .or(line.map(|l| (l.color, l.stroke_width)))
+                .or(shape.map(|s| (s.color, s.stroke_width)))
+        })
+}
+
+/// Renders color and width controls applied to all selected paths, lines
+/// and shapes.
+fn render_annotation_style(
+    ui: &mut egui::Ui,
+    selected_annotations: &mut SelectedAnnotationStyleQuery,
+    color: Color,
+    stroke_width: f32,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    let mut new_color = None;
+    let mut new_width = None;
+    egui::Grid::new("annotation_style_grid")
+        .num_columns(2)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("Color:");
+            let mut egui_color = theme::bevy_to_egui(color);
+            if ui.color_edit_button_srgba(&mut egui_color).changed() {
+                new_color = Some(theme::egui_to_bevy(egui_color));
+            }
+            ui.end_row();
+
+            ui.label("Width:");
+            let mut width = stroke_width;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut width)
+                        .range(1.0..=20.0)
+                        .speed(0.5)
+                        .suffix(" px"),
+                )
+                .changed()
+            {
+                new_width = Some(width);
+            }
+            ui.end_row();
+        });
+
+    if new_color.is_none() && new_width.is_none() {
+        return;
+    }
+    let mut annotations = Vec::new();
+    for (entity, shown, mut path, mut line, mut shape, text) in selected_annotations.iter_mut() {
+        let old_data = AnnotationData::capture(
+            shown,
+            path.as_deref(),
+            line.as_deref(),
+            shape.as_deref(),
+            text,
+        );
+        if let Some(path) = &mut path {
+            path.color = new_color.unwrap_or(path.color);
+            path.stroke_width = new_width.unwrap_or(path.stroke_width);
+        }
+        if let Some(line) = &mut line {
+            line.color = new_color.unwrap_or(line.color);
+            line.stroke_width = new_width.unwrap_or(line.stroke_width);
+        }
+        if let Some(shape) = &mut shape {
+            shape.color = new_color.unwrap_or(shape.color);
+            shape.stroke_width = new_width.unwrap_or(shape.stroke_width);
+        }
+        let new_data = AnnotationData::capture(
+            shown,
+            path.as_deref(),
+            line.as_deref(),
+            shape.as_deref(),
+            text,
+        );
+        if let (Some(old_data), Some(new_data)) = (old_data, new_data)
+            && old_data != new_data
+        {
+            annotations.push((entity, old_data, new_data));
+        }
+    }
+
+    if !annotations.is_empty() {
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditAnnotations { annotations },
+        });
+    }
+}
+
+/// Renders the "Show to players" toggle for all selected annotations.
+fn render_annotation_visibility(
+    ui: &mut egui::Ui,
+    commands: &mut Commands,
+    selected_annotations: &SelectedAnnotationStyleQuery,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    let mut show_to_players = selected_annotations.iter().all(|(_, shown, ..)| shown);
+    ui.add_space(4.0);
+    if !ui
+        .checkbox(&mut show_to_players, "Show to players")
+        .on_hover_text("Also draw in the player view during a live session")
+        .changed()
+    {
+        return;
+    }
+    let mut annotations = Vec::new();
+    for (entity, shown, path, line, shape, text) in selected_annotations.iter() {
+        if show_to_players {
+            commands.entity(entity).insert(ShowToPlayers);
+        } else {
+            commands.entity(entity).remove::<ShowToPlayers>();
+        }
+        if shown != show_to_players
+            && let Some(old_data) = AnnotationData::capture(shown, path, line, shape, text)
+        {
+            let new_data = old_data.clone().with_show_to_players(show_to_players);
+            annotations.push((entity, old_data, new_data));
+        }
+    }
+
+    if !annotations.is_empty() {
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditAnnotations { annotations },
+        });
     }
 }
 
@@ -50,14 +306,21 @@ fn render_multi_selection(
     map_data: &MapData,
     selected_query: &mut SelectedQuery,
     images: &Assets<Image>,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+    keybindings: &Keybindings,
 ) {
-    let selected_count = selected_query.iter().count();
+    let old_transforms: Vec<(Entity, TransformData)> = selected_query
+        .iter()
+        .map(|(entity, _, transform, ..)| (entity, TransformData::from(transform)))
+        .collect();
+    let selected_count = old_transforms.len();
     ui.label(egui::RichText::new(format!("{} items selected", selected_count)).size(14.0));
     ui.add_space(8.0);
 
     // Multi-selection: show fit-to-grid and center-to-grid buttons
     if ui
-        .add_sized([140.0, 26.0], egui::Button::new("Fit to Grid (G)"))
+        .add_sized([140.0, 26.0], egui::Button::new("Fit to Grid"))
+        .on_hover_text(format!("Shortcut: {}", keybindings.label(Action::FitToGrid)))
         .clicked()
     {
         for (_entity, _item, mut transform, sprite, _render_layers) in selected_query.iter_mut() {
@@ -81,7 +344,8 @@ fn render_multi_selection(
 
     ui.add_space(4.0);
     if ui
-        .add_sized([140.0, 26.0], egui::Button::new("Center to Grid (Shift+G)"))
+        .add_sized([140.0, 26.0], egui::Button::new("Center to Grid"))
+        .on_hover_text(format!("Shortcut: {}", keybindings.label(Action::CenterToGrid)))
         .clicked()
     {
         let grid_size = map_data.grid_size;
@@ -99,7 +363,8 @@ fn render_multi_selection(
 
     ui.add_space(4.0);
     if ui
-        .add_sized([140.0, 26.0], egui::Button::new("Restore Aspect Ratio (A)"))
+        .add_sized([140.0, 26.0], egui::Button::new("Restore Aspect Ratio"))
+        .on_hover_text(format!("Shortcut: {}", keybindings.label(Action::RestoreAspectRatio)))
         .clicked()
     {
         for (_entity, _item, mut transform, _sprite, _render_layers) in selected_query.iter_mut() {
@@ -108,18 +373,85 @@ fn render_multi_selection(
             transform.scale.y = uniform_scale;
         }
     }
+
+    // Record the buttons' net effect as a single move
+    let transforms: Vec<_> = old_transforms
+        .into_iter()
+        .filter_map(|(entity, old)| {
+            let (_, _, transform, ..) = selected_query.get(entity).ok()?;
+            let new = TransformData::from(transform);
+            (new != old).then_some((entity, old, new))
+        })
+        .collect();
+    if !transforms.is_empty() {
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::MoveItems { transforms },
+        });
+    }
+}
+
+/// Renders align and distribute buttons for a multi-selection.
+fn render_align_controls(
+    ui: &mut egui::Ui,
+    selected_count: usize,
+    align_writer: &mut MessageWriter<AlignSelectionRequest>,
+    keybindings: &Keybindings,
+) {
+    ui.add_space(8.0);
+    ui.label(egui::RichText::new("Align").size(14.0));
+    ui.add_space(4.0);
+
+    let rows: [&[AlignOp]; 4] = [
+        &[AlignOp::Left, AlignOp::Center, AlignOp::Right],
+        &[AlignOp::Top, AlignOp::Middle, AlignOp::Bottom],
+        &[AlignOp::DistributeHorizontally],
+        &[AlignOp::DistributeVertically],
+    ];
+    for row in rows {
+        ui.horizontal(|ui| {
+            let width = if row.len() == 1 { 140.0 } else { 44.0 };
+            for op in row {
+                let shortcut = keybindings.label(op.action());
+                if ui
+                    .add_enabled(
+                        selected_count >= op.min_items(),
+                        egui::Button::new(op.display_name()).min_size(egui::vec2(width, 22.0)),
+                    )
+                    .on_hover_text(&shortcut)
+                    .on_disabled_hover_text(format!(
+                        "{} ({}); select at least {} items",
+                        op.display_name(),
+                        shortcut,
+                        op.min_items()
+                    ))
+                    .clicked()
+                {
+                    align_writer.write(AlignSelectionRequest { op: *op });
+                }
+            }
+        });
+    }
 }
 
 /// Renders UI for a single selected item with full property controls.
+#[allow(clippy::too_many_arguments)]
 fn render_single_selection(
     ui: &mut egui::Ui,
+    commands: &mut Commands,
     map_data: &MapData,
     selected_query: &mut SelectedQuery,
+    selected_tokens: &mut SelectedTokenQuery,
     images: &Assets<Image>,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+    keybindings: &Keybindings,
 ) {
-    if let Ok((_entity, mut item, mut transform, sprite, mut render_layers)) =
+    if let Ok((entity, mut item, mut transform, sprite, mut render_layers)) =
         selected_query.single_mut()
     {
+        let old_token = selected_tokens.get(entity).ok().flatten();
+        let old_state = ItemStateData::capture(&item, &transform, old_token);
+        let mut new_token = old_state.token.clone();
+
         // Asset path (truncated if too long)
         let asset_name = item
             .asset_path
@@ -150,11 +482,7 @@ fn render_single_selection(
                             // Update z position to match new layer
                             transform.translation.z = layer.z_base() + item.z_index as f32;
                             // Update render layer for player visibility
-                            *render_layers = if layer.is_player_visible() {
-                                RenderLayers::layer(0)
-                            } else {
-                                RenderLayers::layer(1)
-                            };
+                            *render_layers = item_render_layers(*layer);
                         }
                     }
                 });
@@ -235,7 +563,8 @@ fn render_single_selection(
         ui.add_space(8.0);
 
         if ui
-            .add_sized([140.0, 26.0], egui::Button::new("Fit to Grid (G)"))
+            .add_sized([140.0, 26.0], egui::Button::new("Fit to Grid"))
+            .on_hover_text(format!("Shortcut: {}", keybindings.label(Action::FitToGrid)))
             .clicked()
         {
             let original_size = if let Some(custom_size) = sprite.custom_size {
@@ -257,7 +586,8 @@ fn render_single_selection(
 
         ui.add_space(4.0);
         if ui
-            .add_sized([140.0, 26.0], egui::Button::new("Center to Grid (Shift+G)"))
+            .add_sized([140.0, 26.0], egui::Button::new("Center to Grid"))
+            .on_hover_text(format!("Shortcut: {}", keybindings.label(Action::CenterToGrid)))
             .clicked()
         {
             let grid_size = map_data.grid_size;
@@ -273,12 +603,114 @@ fn render_single_selection(
 
         ui.add_space(4.0);
         if ui
-            .add_sized([140.0, 26.0], egui::Button::new("Restore Aspect Ratio (A)"))
+            .add_sized([140.0, 26.0], egui::Button::new("Restore Aspect Ratio"))
+            .on_hover_text(format!(
+                "Shortcut: {}",
+                keybindings.label(Action::RestoreAspectRatio)
+            ))
             .clicked()
         {
             let uniform_scale = transform.scale.x.abs().max(transform.scale.y.abs());
             transform.scale.x = uniform_scale;
             transform.scale.y = uniform_scale;
         }
+
+        // Creature size, speed, conditions, light and auras (tokens only)
+        if item.layer == Layer::Token
+            && let Ok(token_data) = selected_tokens.get_mut(entity)
+        {
+            let mut data = token_data.as_deref().cloned().unwrap_or_default();
+            let mut changed = false;
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Creature:").size(14.0));
+                let previous_size = data.size;
+                egui::ComboBox::from_id_salt("item_creature_size")
+                    .selected_text(data.size.map_or("None", |s| s.display_name()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut data.size, None, "None");
+                        for size in CreatureSize::all() {
+                            ui.selectable_value(&mut data.size, Some(*size), size.display_name());
+                        }
+                    });
+
+                if data.size != previous_size {
+                    changed = true;
+                    // Resize to the new footprint and line it up with the grid
+                    if let Some(size) = data.size {
+                        let image_size = sprite
+                            .custom_size
+                            .or_else(|| images.get(&sprite.image).map(|i| i.size().as_vec2()));
+                        if let Some(scale) =
+                            image_size.and_then(|s| size.fit_scale(s, map_data.grid_size))
+                        {
+                            transform.scale = Vec3::new(scale, scale, 1.0);
+                        }
+                        let snapped = snap_to_footprint(
+                            transform.translation.truncate(),
+                            map_data.grid_size,
+                            size.footprint_cells(),
+                            true,
+                        );
+                        transform.translation.x = snapped.x;
+                        transform.translation.y = snapped.y;
+                    }
+                }
+            });
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Speed:").size(14.0));
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut data.speed_ft)
+                            .range(0.0..=300.0)
+                            .speed(5.0)
+                            .suffix(" ft"),
+                    )
+                    .on_hover_text("Drags longer than this are flagged (0 = no limit)")
+                    .changed();
+            });
+
+            ui.add_space(4.0);
+            egui::CollapsingHeader::new(egui::RichText::new("Conditions").size(14.0))
+                .default_open(true)
+                .show(ui, |ui| {
+                    changed |= render_token_status_editor(ui, &mut data);
+                });
+
+            egui::CollapsingHeader::new(egui::RichText::new("Light & Auras").size(14.0))
+                .default_open(data.emits_light() || !data.auras.is_empty())
+                .show(ui, |ui| {
+                    changed |= render_token_light_editor(ui, &mut data);
+                });
+
+            if changed {
+                new_token = Some(data.clone());
+                match token_data {
+                    Some(mut existing) => *existing = data,
+                    None => {
+                        commands.entity(entity).insert(data);
+                    }
+                }
+            }
+        }
+
+        // Record every edit above; rapid ones (e.g. dragging a value) merge
+        // into one undo step
+        let new_state = ItemStateData {
+            layer: item.layer,
+            z_index: item.z_index,
+            transform: TransformData::from(&*transform),
+            token: new_token,
+        };
+        if new_state != old_state {
+            history_writer.write(RecordEditorCommand {
+                command: EditorCommand::EditItems {
+                    items: vec![(entity, old_state, new_state)],
+                },
+            });
+        }
     }
 }
@@ -0,0 +1,237 @@
+//! Minimal Markdown rendering for GM notes.
+//!
+//! Covers what tends to get typed into room descriptions: `#` headings,
+//! `-`/`*` bullets, numbered items, `---` rules and inline `**bold**`,
+//! `*italic*` and `` `code` ``. Anything else is shown as plain text.
+
+use bevy_egui::egui;
+
+/// One line of Markdown
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block<'a> {
+    Heading(usize, &'a str),
+    Bullet(&'a str),
+    Numbered(&'a str, &'a str),
+    Rule,
+    Paragraph(&'a str),
+    Blank,
+}
+
+/// Classify a single line
+pub fn parse_block(line: &str) -> Block<'_> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return Block::Blank;
+    }
+    if trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-') {
+        return Block::Rule;
+    }
+
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&hashes)
+        && let Some(heading) = trimmed[hashes..].strip_prefix(' ')
+    {
+        return Block::Heading(hashes, heading.trim());
+    }
+
+    if let Some(item) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+    {
+        return Block::Bullet(item.trim());
+    }
+
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0
+        && let Some(item) = trimmed[digits..].strip_prefix(". ")
+    {
+        return Block::Numbered(&trimmed[..digits], item.trim());
+    }
+
+    Block::Paragraph(trimmed)
+}
+
+/// Inline formatting of a run of text
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SpanStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub code: bool,
+}
+
+/// Split a line into runs of text with the same inline formatting.
+///
+/// Markers that don't open or close a span (like the `*` in `2 * 3`) are
+/// kept as literal text.
+pub fn parse_inline(text: &str) -> Vec<(String, SpanStyle)> {
+    let mut spans: Vec<(String, SpanStyle)> = Vec::new();
+    let mut style = SpanStyle::default();
+    let mut current = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let Some(marker) = marker_at(rest, &style, current.chars().last()) else {
+            current.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+
+        if !current.is_empty() {
+            spans.push((std::mem::take(&mut current), style));
+        }
+        match marker {
+            "`" => style.code = !style.code,
+            "**" => style.bold = !style.bold,
+            _ => style.italic = !style.italic,
+        }
+        rest = &rest[marker.len()..];
+    }
+
+    if !current.is_empty() {
+        spans.push((current, style));
+    }
+    spans
+}
+
+/// The formatting marker at the start of `rest`, if it opens or closes a span
+fn marker_at(rest: &str, style: &SpanStyle, previous: Option<char>) -> Option<&'static str> {
+    let marker = ["`", "**", "*"].into_iter().find(|m| rest.starts_with(m))?;
+    // Nothing but the closing backtick is special inside code
+    if style.code {
+        return (marker == "`").then_some(marker);
+    }
+
+    let is_open = match marker {
+        "`" => false,
+        "**" => style.bold,
+        _ => style.italic,
+    };
+    if is_open {
+        // Closing markers hug the text they close
+        return previous
+            .is_some_and(|c| !c.is_whitespace())
+            .then_some(marker);
+    }
+
+    let after = &rest[marker.len()..];
+    let hugs_text = after.chars().next().is_some_and(|c| !c.is_whitespace());
+    (hugs_text && after.contains(marker)).then_some(marker)
+}
+
+/// Render Markdown text into the UI
+pub fn render_markdown(ui: &mut egui::Ui, text: &str) {
+    for line in text.lines() {
+        match parse_block(line) {
+            Block::Heading(level, heading) => {
+                let size = match level {
+                    1 => 20.0,
+                    2 => 17.0,
+                    _ => 15.0,
+                };
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new(heading).size(size).strong());
+            }
+            Block::Bullet(item) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("•");
+                    inline_label(ui, item);
+                });
+            }
+            Block::Numbered(number, item) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(format!("{}.", number));
+                    inline_label(ui, item);
+                });
+            }
+            Block::Rule => {
+                ui.separator();
+            }
+            Block::Paragraph(paragraph) => inline_label(ui, paragraph),
+            Block::Blank => ui.add_space(6.0),
+        }
+    }
+}
+
+fn inline_label(ui: &mut egui::Ui, text: &str) {
+    let mut job = egui::text::LayoutJob::default();
+    for (span, style) in parse_inline(text) {
+        let mut rich = egui::RichText::new(span);
+        if style.bold {
+            rich = rich.strong();
+        }
+        if style.italic {
+            rich = rich.italics();
+        }
+        if style.code {
+            rich = rich.code();
+        }
+        rich.append_to(
+            &mut job,
+            ui.style(),
+            egui::FontSelection::Default,
+            egui::Align::Center,
+        );
+    }
+    job.wrap.max_width = ui.available_width();
+    ui.label(job);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_blocks() {
+        assert_eq!(
+            parse_block("## Guard Room"),
+            Block::Heading(2, "Guard Room")
+        );
+        assert_eq!(
+            parse_block("- two hobgoblins"),
+            Block::Bullet("two hobgoblins")
+        );
+        assert_eq!(parse_block("* a chest"), Block::Bullet("a chest"));
+        assert_eq!(parse_block("3. Trap"), Block::Numbered("3", "Trap"));
+        assert_eq!(parse_block("---"), Block::Rule);
+        assert_eq!(parse_block("   "), Block::Blank);
+        // Not quite Markdown syntax
+        assert_eq!(parse_block("#hashtag"), Block::Paragraph("#hashtag"));
+        assert_eq!(parse_block("3.5 ft"), Block::Paragraph("3.5 ft"));
+    }
+
+    #[test]
+    fn test_parse_inline_styles() {
+        let spans = parse_inline("A **locked** door, *DC 15* to `pick`");
+        let bold = SpanStyle {
+            bold: true,
+            ..SpanStyle::default()
+        };
+        let italic = SpanStyle {
+            italic: true,
+            ..SpanStyle::default()
+        };
+        let code = SpanStyle {
+            code: true,
+            ..SpanStyle::default()
+        };
+        assert_eq!(
+            spans,
+            vec![
+                ("A ".to_string(), SpanStyle::default()),
+                ("locked".to_string(), bold),
+                (" door, ".to_string(), SpanStyle::default()),
+                ("DC 15".to_string(), italic),
+                (" to ".to_string(), SpanStyle::default()),
+                ("pick".to_string(), code),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_stray_markers_are_literal() {
+        let spans = parse_inline("2 * 3 = 6, `x * y`");
+        assert_eq!(spans[0], ("2 * 3 = 6, ".to_string(), SpanStyle::default()));
+        assert_eq!(spans[1].0, "x * y");
+        assert!(spans[1].1.code && !spans[1].1.italic);
+    }
+}
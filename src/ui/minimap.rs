@@ -0,0 +1,258 @@
+//! Minimap window: an overview of the map with the editor and player views.
+//!
+//! Placed items are drawn as blocks colored by layer, scaled to fit the
+//! window. The white frame is what the editor camera sees and the orange one
+//! is the player viewport during a live session. Clicking or dragging moves
+//! the editor camera there; with Shift held it moves the player viewport.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::editor::{rotated_item_bounds, EditorCamera};
+use crate::map::{Layer, MapData, PlacedItem};
+use crate::session::LiveSessionState;
+use crate::theme;
+
+/// Size of the map overview
+const MINIMAP_SIZE: egui::Vec2 = egui::vec2(240.0, 180.0);
+
+/// Grid cells of empty space kept around the map contents
+const PADDING_CELLS: f32 = 2.0;
+
+/// Grid cells shown across an empty map
+const EMPTY_MAP_CELLS: f32 = 20.0;
+
+/// State for the minimap window
+#[derive(Resource, Default)]
+pub struct MinimapState {
+    /// Whether the window is open
+    pub is_open: bool,
+}
+
+/// Maps world positions into the minimap's screen rect at one scale, so the
+/// map keeps its proportions
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MinimapView {
+    /// World position at the center of the rect
+    center: Vec2,
+    /// Screen points per world unit
+    scale: f32,
+    rect: egui::Rect,
+}
+
+impl MinimapView {
+    /// The view that shows all of `min..max` centered in `rect`
+    fn fit(min: Vec2, max: Vec2, rect: egui::Rect) -> Self {
+        let size = (max - min).max(Vec2::ONE);
+        Self {
+            center: (min + max) / 2.0,
+            scale: (rect.width() / size.x).min(rect.height() / size.y),
+            rect,
+        }
+    }
+
+    fn to_screen(self, world: Vec2) -> egui::Pos2 {
+        let offset = (world - self.center) * self.scale;
+        // World y points up, screen y points down
+        self.rect.center() + egui::vec2(offset.x, -offset.y)
+    }
+
+    fn to_world(self, pos: egui::Pos2) -> Vec2 {
+        let offset = pos - self.rect.center();
+        self.center + Vec2::new(offset.x, -offset.y) / self.scale
+    }
+
+    /// Screen rect of the world area `min..max`
+    fn screen_rect(self, min: Vec2, max: Vec2) -> egui::Rect {
+        egui::Rect::from_two_pos(self.to_screen(min), self.to_screen(max))
+    }
+}
+
+/// The area the minimap shows: the map contents with some padding, or a
+/// square around the origin when the map is empty. The camera and viewport
+/// are left out so the overview doesn't shift while dragging them.
+fn content_bounds(bounds: impl IntoIterator<Item = (Vec2, Vec2)>, grid_size: f32) -> (Vec2, Vec2) {
+    let (min, max) = bounds.into_iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), (item_min, item_max)| (min.min(item_min), max.max(item_max)),
+    );
+    if min.x > max.x {
+        let half = Vec2::splat(EMPTY_MAP_CELLS * grid_size / 2.0);
+        return (-half, half);
+    }
+    let padding = Vec2::splat(PADDING_CELLS * grid_size);
+    (min - padding, max + padding)
+}
+
+fn layer_color(layer: Layer) -> egui::Color32 {
+    match layer {
+        Layer::Background | Layer::Terrain => theme::ui::MINIMAP_TERRAIN,
+        Layer::Doodad => theme::ui::MINIMAP_DOODAD,
+        Layer::Token => theme::ui::MINIMAP_TOKEN,
+        _ => theme::ui::MINIMAP_GM,
+    }
+}
+
+/// Renders the minimap window and moves the camera or player viewport when
+/// it is clicked
+pub fn minimap_ui(
+    mut contexts: EguiContexts,
+    mut minimap: ResMut<MinimapState>,
+    mut camera_query: Query<(&mut Transform, &Projection), With<EditorCamera>>,
+    items_query: Query<(&Transform, &Sprite, &PlacedItem), Without<EditorCamera>>,
+    images: Res<Assets<Image>>,
+    map_data: Res<MapData>,
+    mut session_state: ResMut<LiveSessionState>,
+) -> Result {
+    if !minimap.is_open {
+        return Ok(());
+    }
+
+    let Ok((mut camera_transform, projection)) = camera_query.single_mut() else {
+        return Ok(());
+    };
+
+    let layer_visible = |layer: Layer| {
+        map_data
+            .layers
+            .iter()
+            .find(|ld| ld.layer_type == layer)
+            .is_none_or(|ld| ld.visible)
+    };
+    // Drawn back to front, like the map itself
+    let mut items: Vec<(f32, Layer, (Vec2, Vec2))> = items_query
+        .iter()
+        .filter(|(_, _, item)| layer_visible(item.layer))
+        .map(|(transform, sprite, item)| {
+            let bounds = rotated_item_bounds(transform, sprite, &images);
+            (transform.translation.z, item.layer, bounds)
+        })
+        .collect();
+    items.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (min, max) = content_bounds(items.iter().map(|(_, _, b)| *b), map_data.grid_size);
+
+    let camera_pos = camera_transform.translation.truncate();
+    let camera_area = match projection {
+        Projection::Orthographic(ortho) => Some(ortho.area),
+        _ => None,
+    };
+
+    let mut is_open = minimap.is_open;
+    let mut jump_to = None;
+    egui::Window::new("Minimap")
+        .open(&mut is_open)
+        .resizable(false)
+        .default_pos([260.0, 520.0])
+        .show(contexts.ctx_mut()?, |ui| {
+            let (response, painter) =
+                ui.allocate_painter(MINIMAP_SIZE, egui::Sense::click_and_drag());
+            let view = MinimapView::fit(min, max, response.rect);
+            let painter = painter.with_clip_rect(response.rect);
+
+            painter.rect_filled(response.rect, 2.0, theme::ui::MINIMAP_BACKGROUND);
+            for (_, layer, (item_min, item_max)) in &items {
+                painter.rect_filled(
+                    view.screen_rect(*item_min, *item_max),
+                    0.0,
+                    layer_color(*layer),
+                );
+            }
+
+            if session_state.is_active {
+                let half = session_state.viewport_size / 2.0;
+                let center = session_state.viewport_center;
+                painter.rect_stroke(
+                    view.screen_rect(center - half, center + half),
+                    0.0,
+                    egui::Stroke::new(1.5, theme::bevy_to_egui_opaque(theme::VIEWPORT_OUTLINE)),
+                    egui::StrokeKind::Middle,
+                );
+            }
+            if let Some(area) = camera_area {
+                painter.rect_stroke(
+                    view.screen_rect(camera_pos + area.min, camera_pos + area.max),
+                    0.0,
+                    egui::Stroke::new(1.5, theme::ui::MINIMAP_CAMERA),
+                    egui::StrokeKind::Middle,
+                );
+            }
+
+            let response = if session_state.is_active {
+                response.on_hover_text(
+                    "Click or drag to move the view\nShift: move the player viewport",
+                )
+            } else {
+                response.on_hover_text("Click or drag to move the view")
+            };
+            if (response.clicked() || response.dragged())
+                && let Some(pos) = response.interact_pointer_pos()
+            {
+                let move_players = session_state.is_active && ui.input(|i| i.modifiers.shift);
+                jump_to = Some((view.to_world(pos), move_players));
+            }
+        });
+    minimap.is_open = is_open;
+
+    match jump_to {
+        Some((target, true)) => session_state.viewport_center = target,
+        Some((target, false)) => {
+            camera_transform.translation.x = target.x;
+            camera_transform.translation.y = target.y;
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_round_trips_and_flips_y() {
+        let rect = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(200.0, 100.0));
+        let view = MinimapView::fit(Vec2::new(-100.0, -100.0), Vec2::new(300.0, 100.0), rect);
+
+        // 400 x 200 world units fill the 200 x 100 rect
+        assert_eq!(view.scale, 0.5);
+        assert_eq!(view.to_screen(Vec2::new(-100.0, 100.0)), rect.left_top());
+        assert_eq!(
+            view.to_screen(Vec2::new(300.0, -100.0)),
+            rect.right_bottom()
+        );
+
+        let world = Vec2::new(42.0, -17.0);
+        assert!((view.to_world(view.to_screen(world)) - world).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_view_keeps_proportions() {
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(200.0, 100.0));
+        // A tall map is limited by the rect's height and centered across it
+        let view = MinimapView::fit(Vec2::ZERO, Vec2::new(100.0, 200.0), rect);
+        assert_eq!(view.scale, 0.5);
+        assert_eq!(
+            view.screen_rect(Vec2::ZERO, Vec2::new(100.0, 200.0)),
+            egui::Rect::from_min_max(egui::pos2(75.0, 0.0), egui::pos2(125.0, 100.0))
+        );
+    }
+
+    #[test]
+    fn test_content_bounds() {
+        let bounds = content_bounds(
+            [
+                (Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0)),
+                (Vec2::new(-30.0, 5.0), Vec2::new(-20.0, 40.0)),
+            ],
+            10.0,
+        );
+        assert_eq!(bounds, (Vec2::new(-50.0, -20.0), Vec2::new(30.0, 60.0)));
+
+        // An empty map shows an area around the origin
+        assert_eq!(
+            content_bounds([], 10.0),
+            (Vec2::splat(-100.0), Vec2::splat(100.0))
+        );
+    }
+}
@@ -1,16 +1,30 @@
+mod array_dialog;
 mod asset_browser;
 pub mod asset_import;
+mod autotile_editor;
+mod dice_panel;
+mod dungeon_dialog;
 pub mod file_menu;
+mod history_panel;
+mod initiative_panel;
 mod layers_panel;
+mod markdown;
+mod minimap;
+mod move_measurement;
+mod notes_panel;
+mod replace_assets_dialog;
 mod session_controls;
 mod settings_dialog;
 mod toolbar;
+mod token_status;
 
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_egui::EguiPrimaryContextPass;
 
 use crate::config::{ConfigResetNotification, MissingMapWarning};
+use crate::editor::no_dialog_open;
 use crate::map::{
     AsyncMapOperation, LoadValidationWarning, MapLoadError, MapSaveError, SaveValidationWarning,
     UnsavedChangesDialog,
@@ -26,6 +40,32 @@ pub struct DialogState {
     pub any_modal_open: bool,
 }
 
+/// Map file and config warnings that block editor input while shown
+#[derive(SystemParam)]
+struct MapFileDialogs<'w> {
+    missing_map: Res<'w, MissingMapWarning>,
+    config_reset: Res<'w, ConfigResetNotification>,
+    unsaved_changes: Res<'w, UnsavedChangesDialog>,
+    save_validation: Res<'w, SaveValidationWarning>,
+    load_validation: Res<'w, LoadValidationWarning>,
+    save_error: Res<'w, MapSaveError>,
+    load_error: Res<'w, MapLoadError>,
+    async_op: Res<'w, AsyncMapOperation>,
+}
+
+impl MapFileDialogs<'_> {
+    fn any_open(&self) -> bool {
+        self.missing_map.show
+            || self.config_reset.show
+            || self.unsaved_changes.show_close_confirmation
+            || self.save_validation.show
+            || self.load_validation.show
+            || self.save_error.message.is_some()
+            || self.load_error.message.is_some()
+            || self.async_op.is_busy()
+    }
+}
+
 /// System to aggregate all dialog open states into a single resource.
 /// Runs in First schedule before input handlers.
 #[allow(clippy::too_many_arguments)]
@@ -33,17 +73,14 @@ fn update_dialog_state(
     file_menu: Res<file_menu::FileMenuState>,
     asset_browser: Res<asset_browser::AssetBrowserState>,
     asset_import: Res<asset_import::AssetImportDialog>,
+    autotile_editor: Res<autotile_editor::AutoTileEditorState>,
+    array_dialog: Res<array_dialog::ArrayDialogState>,
+    dungeon_dialog: Res<dungeon_dialog::DungeonDialogState>,
+    replace_assets: Res<replace_assets_dialog::ReplaceAssetsDialogState>,
     settings: Res<settings_dialog::SettingsDialogState>,
     help: Res<layers_panel::HelpWindowState>,
     monitor_dialog: Res<MonitorSelectionDialog>,
-    missing_map: Res<MissingMapWarning>,
-    config_reset: Res<ConfigResetNotification>,
-    unsaved_changes: Res<UnsavedChangesDialog>,
-    save_validation: Res<SaveValidationWarning>,
-    load_validation: Res<LoadValidationWarning>,
-    save_error: Res<MapSaveError>,
-    load_error: Res<MapLoadError>,
-    async_op: Res<AsyncMapOperation>,
+    map_file_dialogs: MapFileDialogs,
     mut dialog_state: ResMut<DialogState>,
 ) {
     dialog_state.any_modal_open = file_menu.show_new_confirmation
@@ -54,31 +91,42 @@ fn update_dialog_state(
         || asset_browser.move_dialog_open
         || asset_browser.show_set_default_dialog
         || asset_import.is_open
+        || autotile_editor.is_open
+        || array_dialog.is_open
+        || dungeon_dialog.is_open
+        || replace_assets.is_open
         || settings.is_open
         || help.is_open
         || monitor_dialog.is_open
-        || missing_map.show
-        || config_reset.show
-        || unsaved_changes.show_close_confirmation
-        || save_validation.show
-        || load_validation.show
-        || save_error.message.is_some()
-        || load_error.message.is_some()
-        || async_op.is_busy()
+        || map_file_dialogs.any_open()
         || asset_browser.any_file_dialog_pending()
         || asset_import.pending_browse.is_some()
         || settings.pending_browse.is_some();
 }
 
+/// Run condition: the settings dialog is closed, so keys aren't being
+/// recorded as a new shortcut
+fn settings_closed(settings: Res<settings_dialog::SettingsDialogState>) -> bool {
+    !settings.is_open
+}
+
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DialogState>()
+            .init_resource::<array_dialog::ArrayDialogState>()
             .init_resource::<asset_browser::AssetBrowserState>()
             .init_resource::<asset_import::AssetImportDialog>()
+            .init_resource::<autotile_editor::AutoTileEditorState>()
             .init_resource::<file_menu::FileMenuState>()
             .init_resource::<layers_panel::HelpWindowState>()
+            .init_resource::<history_panel::HistoryPanelState>()
+            .init_resource::<initiative_panel::InitiativePanelState>()
+            .init_resource::<dice_panel::DicePanelState>()
+            .init_resource::<dungeon_dialog::DungeonDialogState>()
+            .init_resource::<minimap::MinimapState>()
+            .init_resource::<replace_assets_dialog::ReplaceAssetsDialogState>()
             .init_resource::<settings_dialog::SettingsDialogState>()
             // Load thumbnails before egui pass
             .add_systems(Update, asset_browser::load_and_register_thumbnails)
@@ -90,6 +138,7 @@ impl Plugin for UiPlugin {
                     // First: side panels
                     asset_browser::asset_browser_ui,
                     layers_panel::layers_panel_ui,
+                    notes_panel::notes_panel_ui,
                 )
                     .chain(),
             )
@@ -102,7 +151,8 @@ impl Plugin for UiPlugin {
                 )
                     .chain()
                     .after(asset_browser::asset_browser_ui)
-                    .after(layers_panel::layers_panel_ui),
+                    .after(layers_panel::layers_panel_ui)
+                    .after(notes_panel::notes_panel_ui),
             )
             .add_systems(
                 EguiPrimaryContextPass,
@@ -117,8 +167,25 @@ impl Plugin for UiPlugin {
                     file_menu::load_validation_warning_ui,
                     file_menu::config_reset_notification_ui,
                     asset_import::asset_import_ui,
+                    autotile_editor::autotile_editor_ui,
+                    array_dialog::array_dialog_ui,
+                    replace_assets_dialog::replace_assets_dialog_ui,
                     layers_panel::help_popup_ui,
                     settings_dialog::settings_dialog_ui,
+                    initiative_panel::initiative_panel_ui,
+                    dice_panel::dice_panel_ui,
+                    minimap::minimap_ui,
+                    token_status::token_context_menu_ui,
+                    move_measurement::move_measurement_ui,
+                    notes_panel::note_pins_ui,
+                )
+                    .after(toolbar::toolbar_ui),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                (
+                    history_panel::history_panel_ui,
+                    dungeon_dialog::dungeon_dialog_ui,
                 )
                     .after(toolbar::toolbar_ui),
             )
@@ -128,7 +195,15 @@ impl Plugin for UiPlugin {
                 session_controls::monitor_selection_dialog,
             )
             .add_systems(Update, session_controls::enumerate_monitors)
-            .add_systems(Update, layers_panel::handle_help_shortcut)
+            .add_systems(
+                Update,
+                (
+                    layers_panel::handle_help_shortcut,
+                    initiative_panel::handle_initiative_shortcut,
+                )
+                    .run_if(settings_closed),
+            )
+            .add_systems(Update, file_menu::handle_file_shortcuts.run_if(no_dialog_open))
             // Update dialog state at the start of each frame
             .add_systems(First, update_dialog_state);
     }
@@ -0,0 +1,46 @@
+//! Distance label shown next to the cursor while dragging tokens.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::editor::movement::TokenMoveMeasurement;
+use crate::theme;
+
+/// Renders the distance of the token move in progress
+pub fn move_measurement_ui(
+    mut contexts: EguiContexts,
+    measurement: Res<TokenMoveMeasurement>,
+) -> Result {
+    let Some(active) = &measurement.active else {
+        return Ok(());
+    };
+
+    let ctx = contexts.ctx_mut()?;
+    let Some(pointer) = ctx.pointer_latest_pos() else {
+        return Ok(());
+    };
+
+    let (text, color) = match active.speed_ft {
+        Some(speed) => (
+            format!("{} / {} ft", active.distance.label(), speed),
+            if active.exceeds_speed() {
+                theme::bevy_to_egui_opaque(theme::MOVE_OVER_SPEED)
+            } else {
+                egui::Color32::WHITE
+            },
+        ),
+        None => (active.distance.label(), egui::Color32::WHITE),
+    };
+
+    egui::Area::new(egui::Id::new("move_measurement"))
+        .fixed_pos(pointer + egui::vec2(16.0, 16.0))
+        .order(egui::Order::Tooltip)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(egui::RichText::new(text).color(color).strong());
+            });
+        });
+
+    Ok(())
+}
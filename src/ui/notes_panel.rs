@@ -0,0 +1,365 @@
+//! GM notes side panel, note pins and hover previews.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::editor::history::{EditorCommand, RecordEditorCommand};
+use crate::editor::notes::{linked_note_at, NotesState, NOTE_PIN_RADIUS};
+use crate::editor::{AnnotationMarker, EditorCamera, TextAnnotation};
+use crate::map::{MapDirtyState, MapNote, MapNotes};
+use crate::theme;
+
+use super::markdown::render_markdown;
+
+/// Lines of a note's body shown in the hover preview
+const PREVIEW_LINES: usize = 8;
+
+/// Renders the notes panel: a searchable list and the open note
+pub fn notes_panel_ui(
+    mut contexts: EguiContexts,
+    mut notes_state: ResMut<NotesState>,
+    mut notes: ResMut<MapNotes>,
+    mut dirty_state: ResMut<MapDirtyState>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) -> Result {
+    if !notes_state.panel_open {
+        return Ok(());
+    }
+
+    // The open note may have gone with a map switch
+    if let Some(id) = notes_state.open_note
+        && notes.get(id).is_none()
+    {
+        notes_state.open_note = None;
+    }
+
+    let mut text_changed = false;
+    let mut text_focused = false;
+    let mut delete = None;
+    egui::SidePanel::right("notes_panel")
+        .default_width(260.0)
+        .resizable(true)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Notes").heading().size(18.0));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("✕").on_hover_text("Close").clicked() {
+                        notes_state.panel_open = false;
+                    }
+                });
+            });
+            ui.add_space(4.0);
+
+            ui.add(
+                egui::TextEdit::singleline(&mut notes_state.search)
+                    .hint_text("Search notes")
+                    .desired_width(f32::INFINITY),
+            );
+            ui.add_space(4.0);
+
+            render_notes_list(ui, &mut notes_state, &notes);
+
+            ui.separator();
+
+            let Some(note) = notes_state.open_note.and_then(|id| notes.get_mut(id)) else {
+                ui.label(egui::RichText::new("Select a note to read it").weak());
+                return;
+            };
+            let open = render_open_note(ui, &mut notes_state, note);
+            text_changed = open.changed;
+            text_focused = open.focused;
+            if open.delete {
+                delete = Some(note.id);
+            }
+        });
+
+    // Notes are saved with the map. Typing only clones the notes on frames
+    // that change them: the old state is the current one with the open
+    // note's text put back to how it was before.
+    if text_changed
+        && let Some(id) = notes_state.open_note
+        && let Some(note) = notes.get(id).cloned()
+    {
+        dirty_state.is_dirty = true;
+        if let Some(before) = notes_state.text_before.replace(note).filter(|n| n.id == id) {
+            let mut old = notes.clone();
+            if let Some(old_note) = old.get_mut(id) {
+                old_note.key = before.key;
+                old_note.title = before.title;
+                old_note.body = before.body;
+            }
+            history_writer.write(RecordEditorCommand {
+                command: EditorCommand::EditNotes {
+                    old,
+                    new: notes.clone(),
+                },
+            });
+        }
+    }
+    if let Some(id) = delete {
+        let old = notes.clone();
+        notes.remove(id);
+        notes_state.open_note = None;
+        dirty_state.is_dirty = true;
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditNotes {
+                old,
+                new: notes.clone(),
+            },
+        });
+    }
+
+    // Remember the open note when typing in it starts
+    if !text_focused || notes_state.open_note.is_none() {
+        notes_state.text_before = None;
+    } else if notes_state.text_before.as_ref().map(|n| n.id) != notes_state.open_note {
+        notes_state.text_before = notes_state
+            .open_note
+            .and_then(|id| notes.get(id))
+            .cloned();
+    }
+
+    Ok(())
+}
+
+/// Notes matching the search, newest last; clicking one opens it and centers
+/// the view on its pin
+fn render_notes_list(ui: &mut egui::Ui, notes_state: &mut NotesState, notes: &MapNotes) {
+    if notes.notes.is_empty() {
+        ui.label(
+            egui::RichText::new("No notes yet. Use the Note tool (N) to pin one.")
+                .weak()
+                .italics(),
+        );
+        return;
+    }
+
+    let mut picked = None;
+    egui::ScrollArea::vertical()
+        .id_salt("notes_list")
+        .max_height(180.0)
+        .show(ui, |ui| {
+            let mut any = false;
+            for note in notes
+                .notes
+                .iter()
+                .filter(|n| n.matches(&notes_state.search))
+            {
+                any = true;
+                let open = notes_state.open_note == Some(note.id);
+                if ui.selectable_label(open, note.label()).clicked() {
+                    picked = Some((note.id, note.position));
+                }
+            }
+            if !any {
+                ui.label(egui::RichText::new("No matching notes").weak());
+            }
+        });
+
+    if let Some((id, position)) = picked {
+        notes_state.open(id);
+        notes_state.focus_request = Some(position);
+    }
+}
+
+/// What happened to the open note this frame
+struct OpenNoteResponse {
+    /// Its key, title or body was edited
+    changed: bool,
+    /// One of its text fields has keyboard focus
+    focused: bool,
+    /// Delete was clicked
+    delete: bool,
+}
+
+/// Key, title and body of the open note
+fn render_open_note(
+    ui: &mut egui::Ui,
+    notes_state: &mut NotesState,
+    note: &mut MapNote,
+) -> OpenNoteResponse {
+    let mut changed = false;
+    let mut focused = false;
+    let mut delete = false;
+    egui::Grid::new("open_note_grid")
+        .num_columns(2)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("Key:");
+            let key = ui
+                .add(
+                    egui::TextEdit::singleline(&mut note.key)
+                        .hint_text("A3")
+                        .desired_width(60.0),
+                )
+                .on_hover_text("Text annotations reading this key link to the note");
+            changed |= key.changed();
+            focused |= key.has_focus();
+            ui.end_row();
+
+            ui.label("Title:");
+            let title = ui.add(
+                egui::TextEdit::singleline(&mut note.title)
+                    .hint_text("Room name")
+                    .desired_width(f32::INFINITY),
+            );
+            if std::mem::take(&mut notes_state.focus_title) {
+                title.request_focus();
+            }
+            changed |= title.changed();
+            focused |= title.has_focus();
+            ui.end_row();
+        });
+
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut notes_state.editing, false, "Read");
+        ui.selectable_value(&mut notes_state.editing, true, "Edit");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("Delete").clicked() {
+                delete = true;
+            }
+            if ui
+                .button("Go to")
+                .on_hover_text("Center the view on the pin")
+                .clicked()
+            {
+                notes_state.focus_request = Some(note.position);
+            }
+        });
+    });
+    ui.add_space(4.0);
+
+    egui::ScrollArea::vertical()
+        .id_salt("open_note_body")
+        .show(ui, |ui| {
+            if notes_state.editing {
+                let body = ui.add(
+                    egui::TextEdit::multiline(&mut note.body)
+                        .hint_text("# Heading\n- bullet\n**bold**, *italic*")
+                        .desired_rows(16)
+                        .desired_width(f32::INFINITY),
+                );
+                changed |= body.changed();
+                focused |= body.has_focus();
+            } else if note.body.trim().is_empty() {
+                ui.label(egui::RichText::new("Empty note").weak().italics());
+            } else {
+                render_markdown(ui, &note.body);
+            }
+        });
+
+    OpenNoteResponse {
+        changed,
+        focused,
+        delete,
+    }
+}
+
+/// Paints note pins over the map and previews the note under the cursor
+pub fn note_pins_ui(
+    mut contexts: EguiContexts,
+    notes: Res<MapNotes>,
+    notes_state: Res<NotesState>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    camera: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
+    texts: Query<(&Transform, &TextAnnotation), With<AnnotationMarker>>,
+) -> Result {
+    let Ok((camera, camera_transform)) = camera.single() else {
+        return Ok(());
+    };
+    let ctx = contexts.ctx_mut()?;
+
+    // Stay within the map view, under the side and top panels
+    let painter = ctx
+        .layer_painter(egui::LayerId::background())
+        .with_clip_rect(ctx.available_rect());
+    let mut pins = Vec::with_capacity(notes.notes.len());
+    for note in &notes.notes {
+        let Ok(screen) = camera.world_to_viewport(camera_transform, note.position.extend(0.0))
+        else {
+            continue;
+        };
+        let center = egui::pos2(screen.x, screen.y);
+        let open = notes_state.open_note == Some(note.id);
+        paint_pin(&painter, center, note.key.trim(), open);
+        pins.push((note.id, center));
+    }
+
+    // Preview on hover, but not over the UI or while dragging a pin
+    let Some(pointer) = ctx.pointer_hover_pos() else {
+        return Ok(());
+    };
+    if ctx.is_pointer_over_area() || mouse_button.pressed(MouseButton::Left) {
+        return Ok(());
+    }
+    let hovered = pins
+        .iter()
+        .rev()
+        .find(|(_, center)| center.distance(pointer) <= NOTE_PIN_RADIUS)
+        .map(|(id, _)| *id)
+        .or_else(|| {
+            let world = camera
+                .viewport_to_world_2d(camera_transform, Vec2::new(pointer.x, pointer.y))
+                .ok()?;
+            linked_note_at(&notes, texts.iter(), world)
+        });
+    let Some(note) = hovered.and_then(|id| notes.get(id)) else {
+        return Ok(());
+    };
+
+    egui::Area::new(egui::Id::new("note_preview"))
+        .fixed_pos(pointer + egui::vec2(16.0, 16.0))
+        .order(egui::Order::Tooltip)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_max_width(300.0);
+                ui.label(egui::RichText::new(note.label()).strong());
+                let mut lines = note.body.lines().filter(|line| !line.trim().is_empty());
+                let preview: Vec<&str> = lines.by_ref().take(PREVIEW_LINES).collect();
+                if !preview.is_empty() {
+                    ui.separator();
+                    render_markdown(ui, &preview.join("\n"));
+                }
+                if lines.next().is_some() {
+                    ui.label(egui::RichText::new("…").weak());
+                }
+            });
+        });
+
+    Ok(())
+}
+
+/// A round pin with the note's room key beside it
+fn paint_pin(painter: &egui::Painter, center: egui::Pos2, key: &str, open: bool) {
+    let outline = if open {
+        theme::ui::NOTE_PIN_OPEN
+    } else {
+        egui::Color32::BLACK
+    };
+    painter.circle(
+        center,
+        NOTE_PIN_RADIUS,
+        theme::ui::NOTE_PIN,
+        egui::Stroke::new(2.0, outline),
+    );
+    painter.circle_filled(center, NOTE_PIN_RADIUS * 0.3, egui::Color32::BLACK);
+
+    if key.is_empty() {
+        return;
+    }
+    let galley = painter.layout_no_wrap(
+        key.to_string(),
+        egui::FontId::proportional(13.0),
+        egui::Color32::WHITE,
+    );
+    let pos = center + egui::vec2(NOTE_PIN_RADIUS + 4.0, -galley.size().y / 2.0);
+    painter.rect_filled(
+        egui::Rect::from_min_size(pos, galley.size()).expand(2.0),
+        3.0,
+        egui::Color32::from_black_alpha(180),
+    );
+    painter.galley(pos, galley, egui::Color32::WHITE);
+}
@@ -0,0 +1,284 @@
+//! Dialog for replacing every placed instance of one asset with another.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::assets::{get_image_dimensions, AssetLibrary, LibraryAsset, SelectedAsset};
+use crate::editor::replace_assets::{
+    count_in_map_files, footprint_rescale, replace_in_map_files, MapFilesReplaced,
+    ReplaceAssetsRequest,
+};
+use crate::map::{CurrentMapFile, PlacedItem};
+
+/// State for the Replace Asset dialog
+#[derive(Resource, Default)]
+pub struct ReplaceAssetsDialogState {
+    /// Whether the dialog is open
+    pub is_open: bool,
+    /// Library-relative path of the asset to find
+    from: Option<String>,
+    /// Library-relative path of the asset to replace it with
+    to: Option<String>,
+    /// Scale the new asset to fit the old one's footprint
+    rescale: bool,
+    /// Let the rescale change the new asset's aspect ratio
+    stretch: bool,
+    /// Also rewrite the other maps in the library's maps folder
+    all_maps: bool,
+    /// Uses of `from` in other map files, cached per asset
+    map_counts: Option<(String, Vec<(String, usize)>)>,
+    /// Outcome of the last replace
+    result: Option<Result<String, String>>,
+}
+
+impl ReplaceAssetsDialogState {
+    /// Open the dialog to replace `asset`
+    pub fn open_for(&mut self, asset: &LibraryAsset) {
+        self.is_open = true;
+        self.from = Some(asset.library_relative_path());
+        self.to = None;
+        self.map_counts = None;
+        self.result = None;
+    }
+}
+
+/// File name of a library-relative path
+fn asset_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Result line for a replace across the map files, naming the maps updated
+/// and, as an error, any that couldn't be
+fn map_files_summary(message: String, outcome: &MapFilesReplaced) -> Result<String, String> {
+    let mut message = format!(
+        "{} and {} item(s) in {} other map(s)",
+        message,
+        outcome.items,
+        outcome.updated.len()
+    );
+    if !outcome.updated.is_empty() {
+        message.push_str(&format!("\nUpdated: {}", outcome.updated.join(", ")));
+    }
+    if outcome.failed.is_empty() {
+        return Ok(message);
+    }
+    for (name, error) in &outcome.failed {
+        message.push_str(&format!("\nNot updated {}: {}", name, error));
+    }
+    Err(message)
+}
+
+/// Renders the Replace Asset dialog
+pub fn replace_assets_dialog_ui(
+    mut contexts: EguiContexts,
+    mut state: ResMut<ReplaceAssetsDialogState>,
+    library: Res<AssetLibrary>,
+    selected_asset: Res<SelectedAsset>,
+    items: Query<&PlacedItem>,
+    current_map_file: Res<CurrentMapFile>,
+    mut replace_writer: MessageWriter<ReplaceAssetsRequest>,
+) -> Result {
+    if !state.is_open {
+        return Ok(());
+    }
+
+    let bevy_to_relative = library.build_bevy_to_relative_map();
+    let relative_to_bevy = library.build_relative_to_bevy_map();
+    let to_bevy = |path: &str| {
+        relative_to_bevy
+            .get(path)
+            .map_or_else(|| path.to_string(), |bevy| bevy.to_string())
+    };
+
+    // Assets on this map, by library-relative path, with their counts
+    let mut on_map: BTreeMap<String, usize> = BTreeMap::new();
+    for item in items.iter() {
+        let path = bevy_to_relative
+            .get(item.asset_path.as_str())
+            .cloned()
+            .unwrap_or_else(|| item.asset_path.clone());
+        *on_map.entry(path).or_default() += 1;
+    }
+
+    let maps_dir = library.library_path.join("maps");
+    let skip = current_map_file.path.as_deref();
+    if state.all_maps
+        && let Some(from) = state.from.clone()
+        && state.map_counts.as_ref().map(|(asset, _)| asset) != Some(&from)
+    {
+        let counts = count_in_map_files(&maps_dir, &from, skip);
+        state.map_counts = Some((from, counts));
+    }
+
+    let selected_path = selected_asset
+        .asset
+        .as_ref()
+        .map(|asset| asset.library_relative_path());
+    let this_map_count = state
+        .from
+        .as_ref()
+        .and_then(|from| on_map.get(from))
+        .copied()
+        .unwrap_or(0);
+    let (other_items, other_maps) = match (&state.map_counts, state.all_maps) {
+        (Some((_, counts)), true) => (counts.iter().map(|(_, n)| n).sum(), counts.len()),
+        _ => (0, 0),
+    };
+
+    let mut should_close = false;
+    let mut should_replace = false;
+
+    egui::Window::new("Replace Asset")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut()?, |ui| {
+            egui::Grid::new("replace_assets_grid")
+                .num_columns(3)
+                .spacing([8.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label("Find:");
+                    let from_text = state.from.as_deref().map_or("(choose)", asset_name);
+                    egui::ComboBox::from_id_salt("replace_from")
+                        .selected_text(from_text)
+                        .width(180.0)
+                        .show_ui(ui, |ui| {
+                            for (path, count) in &on_map {
+                                let label = format!("{} ({})", asset_name(path), count);
+                                if ui
+                                    .selectable_label(state.from.as_ref() == Some(path), label)
+                                    .on_hover_text(path)
+                                    .clicked()
+                                {
+                                    state.from = Some(path.clone());
+                                    state.result = None;
+                                }
+                            }
+                        });
+                    if ui
+                        .add_enabled(selected_path.is_some(), egui::Button::new("Use selected"))
+                        .on_hover_text("Use the asset selected in the browser")
+                        .clicked()
+                    {
+                        state.from = selected_path.clone();
+                        state.result = None;
+                    }
+                    ui.end_row();
+
+                    ui.label("Replace with:");
+                    let to_text = state.to.as_deref().map_or("(choose)", asset_name);
+                    ui.label(egui::RichText::new(to_text).strong())
+                        .on_hover_text(state.to.as_deref().unwrap_or(""));
+                    if ui
+                        .add_enabled(selected_path.is_some(), egui::Button::new("Use selected"))
+                        .on_hover_text("Use the asset selected in the browser")
+                        .clicked()
+                    {
+                        state.to = selected_path.clone();
+                        state.result = None;
+                    }
+                    ui.end_row();
+                });
+
+            ui.add_space(4.0);
+            ui.checkbox(&mut state.rescale, "Rescale to match the old footprint");
+            ui.add_enabled_ui(state.rescale, |ui| {
+                ui.indent("replace_stretch", |ui| {
+                    ui.checkbox(&mut state.stretch, "Stretch to fill it exactly")
+                        .on_hover_text("Otherwise the new asset keeps its aspect ratio");
+                });
+            });
+            ui.checkbox(&mut state.all_maps, "Also replace in every map in maps/")
+                .on_hover_text("Other maps are rewritten on disk and can't be undone");
+
+            ui.add_space(4.0);
+            ui.label(format!("This map: {} item(s)", this_map_count));
+            if state.all_maps {
+                ui.label(format!(
+                    "Other maps: {} item(s) in {} map(s)",
+                    other_items, other_maps
+                ));
+                if let Some((_, counts)) = &state.map_counts
+                    && !counts.is_empty()
+                {
+                    egui::CollapsingHeader::new("Maps")
+                        .id_salt("replace_map_counts")
+                        .show(ui, |ui| {
+                            for (name, count) in counts {
+                                ui.label(format!("{}: {}", name, count));
+                            }
+                        });
+                }
+            }
+
+            if let Some(result) = &state.result {
+                ui.add_space(4.0);
+                match result {
+                    Ok(message) => {
+                        ui.label(egui::RichText::new(message).color(egui::Color32::LIGHT_GREEN));
+                    }
+                    Err(error) => {
+                        ui.label(egui::RichText::new(error).color(egui::Color32::YELLOW));
+                    }
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let ready = state.from.is_some()
+                    && state.to.is_some()
+                    && state.from != state.to
+                    && this_map_count + other_items > 0;
+                if ui
+                    .add_enabled(ready, egui::Button::new("Replace"))
+                    .clicked()
+                {
+                    should_replace = true;
+                }
+                if ui.button("Close").clicked() {
+                    should_close = true;
+                }
+            });
+        });
+
+    if should_replace && let (Some(from), Some(to)) = (state.from.clone(), state.to.clone()) {
+        let dimensions = |path: &str| {
+            library
+                .assets
+                .iter()
+                .find(|asset| asset.library_relative_path() == path)
+                .and_then(|asset| get_image_dimensions(&asset.full_path))
+        };
+        let rescale = if state.rescale {
+            footprint_rescale(dimensions(&from), dimensions(&to), state.stretch)
+        } else {
+            None
+        };
+
+        replace_writer.write(ReplaceAssetsRequest {
+            from: to_bevy(&from),
+            to: to_bevy(&to),
+            rescale,
+            all_maps: state.all_maps,
+        });
+
+        let mut message = format!("Replaced {} item(s) on this map", this_map_count);
+        if state.rescale && rescale.is_none() {
+            message.push_str(" (sizes unknown, scale kept)");
+        }
+        state.result = Some(if state.all_maps {
+            let outcome = replace_in_map_files(&maps_dir, &from, &to, rescale, skip);
+            map_files_summary(message, &outcome)
+        } else {
+            Ok(message)
+        });
+        state.map_counts = None;
+    }
+    if should_close {
+        state.is_open = false;
+    }
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ use std::path::PathBuf;
 
 use crate::assets::{AssetLibrary, UpdateLibraryMetadataRequest};
 use crate::config::{AppConfig, SaveConfigRequest, SetDefaultLibraryRequest};
+use crate::keybindings::{Action, ActionCategory, KeyBinding, Keybindings};
 
 /// State for the settings dialog
 #[derive(Resource, Default)]
@@ -22,6 +23,10 @@ pub struct SettingsDialogState {
     pub library_name_changed: bool,
     /// Pending async file dialog for browsing folders
     pub pending_browse: Option<Task<Option<PathBuf>>>,
+    /// Edited keyboard shortcuts, applied on Save
+    pub keybindings: Keybindings,
+    /// Action waiting for a key press to add as a new shortcut
+    pub capturing: Option<Action>,
 }
 
 impl SettingsDialogState {
@@ -33,6 +38,8 @@ impl SettingsDialogState {
             .as_ref()
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_default();
+        self.keybindings = Keybindings::from_config(&config.data.keybindings);
+        self.capturing = None;
         self.has_changes = false;
         self.library_name_changed = false;
     }
@@ -45,10 +52,13 @@ impl SettingsDialogState {
 }
 
 /// Renders the settings dialog
+#[allow(clippy::too_many_arguments)]
 pub fn settings_dialog_ui(
     mut contexts: EguiContexts,
     mut dialog_state: ResMut<SettingsDialogState>,
     mut config: ResMut<AppConfig>,
+    mut keybindings: ResMut<Keybindings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
     library: Res<AssetLibrary>,
     mut set_default_events: MessageWriter<SetDefaultLibraryRequest>,
     mut save_events: MessageWriter<SaveConfigRequest>,
@@ -69,6 +79,17 @@ pub fn settings_dialog_ui(
         return Ok(());
     }
 
+    // Record the next key combination pressed as a new shortcut
+    if let Some(action) = dialog_state.capturing
+        && let Some(binding) = KeyBinding::capture(&keyboard)
+    {
+        dialog_state.capturing = None;
+        if binding != KeyBinding::key(KeyCode::Escape) {
+            dialog_state.keybindings.add(action, binding);
+            dialog_state.has_changes = true;
+        }
+    }
+
     let mut should_close = false;
     let mut should_save = false;
     let mut should_browse = false;
@@ -182,6 +203,13 @@ pub fn settings_dialog_ui(
                 }
             });
 
+            ui.add_space(12.0);
+
+            // Keyboard Shortcuts section
+            ui.group(|ui| {
+                render_shortcuts_section(ui, &mut dialog_state);
+            });
+
             ui.add_space(16.0);
 
             // Action buttons
@@ -235,6 +263,8 @@ pub fn settings_dialog_ui(
 
         // Update config directly
         config.data.default_library_path = new_path.clone();
+        config.data.keybindings = dialog_state.keybindings.to_config();
+        *keybindings = dialog_state.keybindings.clone();
         config.dirty = true;
         save_events.write(SaveConfigRequest);
 
@@ -258,3 +288,97 @@ pub fn settings_dialog_ui(
 
     Ok(())
 }
+
+/// Lists every action with its shortcuts. Clicking a shortcut removes it,
+/// "+" records a new one and shortcuts shared with another action are red.
+fn render_shortcuts_section(ui: &mut egui::Ui, dialog_state: &mut SettingsDialogState) {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Keyboard Shortcuts").strong());
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.small_button("Reset All").clicked() {
+                dialog_state.keybindings = Keybindings::default();
+                dialog_state.capturing = None;
+                dialog_state.has_changes = true;
+            }
+        });
+    });
+
+    if dialog_state.keybindings.has_conflicts() {
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 165, 0),
+            "Some keys are bound to more than one action; they are marked in red.",
+        );
+    }
+    ui.add_space(4.0);
+
+    egui::ScrollArea::vertical()
+        .id_salt("shortcuts_scroll")
+        .max_height(300.0)
+        .show(ui, |ui| {
+            for category in ActionCategory::all() {
+                egui::CollapsingHeader::new(category.display_name())
+                    .id_salt(("shortcuts", category.display_name()))
+                    .show(ui, |ui| {
+                        egui::Grid::new(("shortcuts_grid", category.display_name()))
+                            .num_columns(2)
+                            .spacing([20.0, 4.0])
+                            .show(ui, |ui| {
+                                for action in
+                                    Action::all().iter().filter(|a| a.category() == *category)
+                                {
+                                    ui.label(action.display_name());
+                                    ui.horizontal(|ui| {
+                                        render_action_bindings(ui, dialog_state, *action);
+                                    });
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+        });
+}
+
+fn render_action_bindings(
+    ui: &mut egui::Ui,
+    dialog_state: &mut SettingsDialogState,
+    action: Action,
+) {
+    for binding in dialog_state.keybindings.get(action).to_vec() {
+        let conflicts = dialog_state.keybindings.conflicts(action, binding);
+        let mut text = egui::RichText::new(binding.to_string());
+        let hover = if conflicts.is_empty() {
+            "Click to remove".to_string()
+        } else {
+            text = text.color(egui::Color32::RED);
+            let names: Vec<&str> = conflicts.iter().map(|a| a.display_name()).collect();
+            format!("Also bound to: {}\nClick to remove", names.join(", "))
+        };
+        if ui.small_button(text).on_hover_text(hover).clicked() {
+            dialog_state.keybindings.remove(action, binding);
+            dialog_state.has_changes = true;
+        }
+    }
+
+    if dialog_state.capturing == Some(action) {
+        ui.label(egui::RichText::new("Press a key (Escape cancels)").italics());
+    } else if ui
+        .small_button("+")
+        .on_hover_text("Add a shortcut")
+        .clicked()
+    {
+        dialog_state.capturing = Some(action);
+    }
+
+    if !dialog_state.keybindings.is_default(action)
+        && ui
+            .small_button("Reset")
+            .on_hover_text(format!(
+                "Restore the default: {}",
+                Keybindings::default().label(action)
+            ))
+            .clicked()
+    {
+        dialog_state.keybindings.reset(action);
+        dialog_state.has_changes = true;
+    }
+}
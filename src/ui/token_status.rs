@@ -0,0 +1,254 @@
+//! Token condition and light/aura editors, shared by the properties panel
+//! and the token right-click menu.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::editor::history::{EditorCommand, ItemStateData, RecordEditorCommand};
+use crate::editor::TokenContextMenu;
+use crate::map::{
+    token_display_name, Condition, PlacedItem, TokenAura, TokenData, MAX_EXHAUSTION,
+};
+
+/// Renders checkboxes and fields for a token's conditions and markers.
+/// Returns true if anything changed.
+pub fn render_token_status_editor(ui: &mut egui::Ui, data: &mut TokenData) -> bool {
+    let mut changed = false;
+
+    egui::Grid::new(ui.id().with("conditions"))
+        .num_columns(2)
+        .spacing([8.0, 2.0])
+        .show(ui, |ui| {
+            for (i, condition) in Condition::all().iter().enumerate() {
+                let mut active = data.has_condition(*condition);
+                if ui
+                    .checkbox(&mut active, condition.display_name())
+                    .on_hover_text(format!("Badge: {}", condition.abbreviation()))
+                    .changed()
+                {
+                    data.set_condition(*condition, active);
+                    changed = true;
+                }
+                if i % 2 == 1 {
+                    ui.end_row();
+                }
+            }
+        });
+
+    ui.add_space(4.0);
+    changed |= ui
+        .checkbox(&mut data.concentrating, "Concentrating")
+        .changed();
+
+    ui.horizontal(|ui| {
+        ui.label("Exhaustion:");
+        let mut level = data.exhaustion;
+        if ui
+            .add(egui::DragValue::new(&mut level).range(0..=MAX_EXHAUSTION))
+            .changed()
+        {
+            data.set_exhaustion(level);
+            changed = true;
+        }
+    });
+
+    ui.add_space(4.0);
+    ui.label(egui::RichText::new("Markers").weak());
+
+    let mut remove_index = None;
+    for (index, marker) in data.custom_markers.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(marker);
+            if ui.small_button("✕").on_hover_text("Remove").clicked() {
+                remove_index = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove_index {
+        data.remove_custom_marker(index);
+        changed = true;
+    }
+
+    // Draft marker text lives in egui memory so each editor keeps its own
+    let draft_id = ui.id().with("new_marker");
+    let mut draft: String = ui.data_mut(|d| d.get_temp(draft_id).unwrap_or_default());
+    ui.horizontal(|ui| {
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut draft)
+                .hint_text("Custom marker")
+                .desired_width(110.0),
+        );
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if (ui.button("Add").clicked() || submitted) && data.add_custom_marker(&draft) {
+            draft.clear();
+            changed = true;
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(draft_id, draft));
+
+    ui.add_space(4.0);
+    if ui
+        .add_enabled(data.has_status(), egui::Button::new("Clear All"))
+        .clicked()
+    {
+        data.clear_status();
+        changed = true;
+    }
+
+    changed
+}
+
+/// Renders fields for a token's light radii and auras.
+/// Returns true if anything changed.
+pub fn render_token_light_editor(ui: &mut egui::Ui, data: &mut TokenData) -> bool {
+    let mut changed = false;
+
+    egui::Grid::new(ui.id().with("light"))
+        .num_columns(2)
+        .spacing([8.0, 2.0])
+        .show(ui, |ui| {
+            ui.label("Bright light:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut data.bright_light_ft)
+                        .range(0.0..=500.0)
+                        .speed(5.0)
+                        .suffix(" ft"),
+                )
+                .changed();
+            ui.end_row();
+
+            ui.label("Dim light:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut data.dim_light_ft)
+                        .range(0.0..=500.0)
+                        .speed(5.0)
+                        .prefix("+")
+                        .suffix(" ft"),
+                )
+                .on_hover_text("Dim light beyond the bright radius")
+                .changed();
+            ui.end_row();
+        });
+
+    ui.add_space(4.0);
+    ui.label(egui::RichText::new("Auras").weak());
+
+    let mut remove_index = None;
+    for (index, aura) in data.auras.iter_mut().enumerate() {
+        ui.push_id(index, |ui| {
+            ui.horizontal(|ui| {
+                changed |= ui.color_edit_button_rgba_unmultiplied(&mut aura.color).changed();
+                changed |= ui
+                    .add(egui::TextEdit::singleline(&mut aura.name).desired_width(90.0))
+                    .changed();
+                if ui.small_button("✕").on_hover_text("Remove").clicked() {
+                    remove_index = Some(index);
+                }
+            });
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut aura.radius_ft)
+                            .range(0.0..=500.0)
+                            .speed(5.0)
+                            .suffix(" ft"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut aura.player_visible, "Show players")
+                    .changed();
+            });
+        });
+    }
+    if let Some(index) = remove_index {
+        data.auras.remove(index);
+        changed = true;
+    }
+
+    if ui.button("Add Aura").clicked() {
+        data.auras.push(TokenAura::default());
+        changed = true;
+    }
+
+    changed
+}
+
+/// Renders the right-click menu for a token
+pub fn token_context_menu_ui(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    mut menu: ResMut<TokenContextMenu>,
+    mut tokens: Query<(&PlacedItem, &Transform, Option<&mut TokenData>)>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
+) -> Result {
+    let Some(target) = menu.target else {
+        return Ok(());
+    };
+    let Ok((item, transform, token_data)) = tokens.get_mut(target) else {
+        // Token was deleted while the menu was open
+        menu.close();
+        return Ok(());
+    };
+
+    let ctx = contexts.ctx_mut()?;
+    let mut data = token_data.as_deref().cloned().unwrap_or_default();
+    let mut changed = false;
+    let mut close = false;
+
+    // Open slightly up-left of the cursor so releasing the right button
+    // lands inside the menu
+    let pos = egui::pos2(menu.screen_pos.x - 2.0, menu.screen_pos.y - 2.0);
+    let response = egui::Area::new(egui::Id::new("token_context_menu"))
+        .fixed_pos(pos)
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_max_width(260.0);
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(token_display_name(&item.asset_path)).strong());
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✕").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+                ui.separator();
+                changed = render_token_status_editor(ui, &mut data);
+                egui::CollapsingHeader::new("Light & Auras")
+                    .default_open(data.emits_light() || !data.auras.is_empty())
+                    .show(ui, |ui| {
+                        changed |= render_token_light_editor(ui, &mut data);
+                    });
+            });
+        })
+        .response;
+
+    if changed {
+        let old_state = ItemStateData::capture(item, transform, token_data.as_deref());
+        let new_state = ItemStateData {
+            token: Some(data.clone()),
+            ..old_state.clone()
+        };
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditItems {
+                items: vec![(target, old_state, new_state)],
+            },
+        });
+
+        // Only touch the component on edits so opening the menu doesn't dirty the map
+        match token_data {
+            Some(mut existing) => *existing = data,
+            None => {
+                commands.entity(target).insert(data);
+            }
+        }
+    }
+
+    if close || response.clicked_elsewhere() || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+        menu.close();
+    }
+
+    Ok(())
+}
@@ -1,12 +1,243 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
+use crate::assets::{AssetLibrary, SelectedAsset};
+use crate::editor::annotations::TEXT_FONT_SIZE_RANGE;
+use crate::editor::autotile::AutoTileBrush;
+use crate::editor::bookmarks::{BookmarkSettings, RecallBookmarkRequest};
+use crate::editor::brush::{BrushMode, ScatterSettings};
+use crate::editor::fill::{FillShape, FillState};
 use crate::editor::fog::FogState;
-use crate::editor::{AnnotationSettings, CurrentTool, EditorTool, SelectedLayer};
-use crate::map::{Layer, MapData, MapDirtyState};
+use crate::editor::history::{EditorCommand, RecordEditorCommand};
+use crate::editor::movement::{DiagonalRule, MovementSettings};
+use crate::editor::notes::NotesState;
+use crate::editor::{
+    AnnotationSettings, BoxSelectSettings, CurrentTool, EditorCamera, EditorTool, GridSettings,
+    RotationSnap, SelectCommand, SelectRequest, SelectedLayer, ShapeKind, SnapMode,
+};
+use crate::keybindings::{Action, Keybindings};
+use crate::map::{BookmarkViewport, CameraBookmarks, Layer, MapData, MapDirtyState};
 use crate::session::{LiveSessionState, MonitorSelectionDialog};
+use crate::theme;
+
+use super::autotile_editor::AutoTileEditorState;
+use super::dice_panel::DicePanelState;
+use super::history_panel::HistoryPanelState;
+use super::initiative_panel::InitiativePanelState;
+use super::minimap::MinimapState;
+
+/// Contents of the toolbar's Snap menu
+fn snap_menu_ui(ui: &mut egui::Ui, settings: &mut GridSettings) {
+    ui.checkbox(&mut settings.snap_enabled, "Snap to grid")
+        .on_hover_text("Place tool snaps unless Shift is held");
+    ui.add_enabled_ui(settings.snap_enabled, |ui| {
+        for mode in SnapMode::all() {
+            ui.radio_value(&mut settings.snap_mode, *mode, mode.display_name());
+        }
+    });
+    ui.separator();
+    ui.checkbox(&mut settings.smart_guides, "Smart guides")
+        .on_hover_text("Line items up with the edges and centers of nearby items");
+    ui.separator();
+    ui.label("Rotation snap (Shift):");
+    ui.horizontal(|ui| {
+        for step in RotationSnap::all() {
+            ui.selectable_value(&mut settings.rotation_snap, *step, step.display_name());
+        }
+    });
+}
+
+/// Contents of the Select tool's Filter menu
+fn selection_filter_ui(ui: &mut egui::Ui, settings: &mut BoxSelectSettings) {
+    ui.label("Box and lasso pick from:");
+    for layer in Layer::all() {
+        let mut allowed = settings.filter.allows(*layer);
+        if ui.checkbox(&mut allowed, layer.display_name()).changed() {
+            settings.filter.set_allowed(*layer, allowed);
+        }
+    }
+    ui.separator();
+    ui.horizontal(|ui| {
+        if ui.button("All layers").clicked() {
+            settings.filter.allow_all();
+        }
+        if ui.button("Annotations only").clicked() {
+            settings.filter.annotations_only();
+        }
+    });
+}
+
+/// Selection settings and commands shown for the Select tool
+#[derive(SystemParam)]
+pub struct SelectToolParams<'w> {
+    box_select: ResMut<'w, BoxSelectSettings>,
+    select_writer: MessageWriter<'w, SelectRequest>,
+    keybindings: Res<'w, Keybindings>,
+}
+
+/// Contents of the Select tool's Select menu
+fn select_menu_ui(ui: &mut egui::Ui, select: &mut SelectToolParams) {
+    let mut command_button = |ui: &mut egui::Ui, command: SelectCommand| {
+        let mut button = egui::Button::new(command.display_name());
+        if let Some(action) = command.action() {
+            button = button.shortcut_text(select.keybindings.label(action));
+        }
+        if ui.add(button).clicked() {
+            select.select_writer.write(SelectRequest { command });
+            ui.close();
+        }
+    };
+
+    command_button(ui, SelectCommand::All);
+    command_button(ui, SelectCommand::Invert);
+    ui.separator();
+    command_button(ui, SelectCommand::SameAsset);
+    command_button(ui, SelectCommand::SameLayer);
+    ui.menu_button("Select Layer", |ui| {
+        for layer in Layer::all() {
+            command_button(ui, SelectCommand::Layer(*layer));
+        }
+    });
+}
+
+/// Camera bookmarks and what's needed to save new ones
+#[derive(SystemParam)]
+pub struct BookmarkParams<'w, 's> {
+    bookmarks: ResMut<'w, CameraBookmarks>,
+    settings: ResMut<'w, BookmarkSettings>,
+    recall_writer: MessageWriter<'w, RecallBookmarkRequest>,
+    camera_query: Query<'w, 's, (&'static Transform, &'static Projection), With<EditorCamera>>,
+    /// Name typed for the next bookmark
+    new_name: Local<'s, String>,
+}
+
+/// Contents of the toolbar's Bookmarks menu
+fn bookmarks_menu_ui(
+    ui: &mut egui::Ui,
+    params: &mut BookmarkParams,
+    session_state: &LiveSessionState,
+    keybindings: &Keybindings,
+    dirty_state: &mut MapDirtyState,
+    history_writer: &mut MessageWriter<RecordEditorCommand>,
+) {
+    let old_bookmarks = params.bookmarks.bookmarks.clone();
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut *params.new_name)
+                .hint_text("Name, e.g. Tavern")
+                .desired_width(140.0),
+        );
+        let hover = if session_state.is_active {
+            "Save the camera position, zoom and player viewport"
+        } else {
+            "Save the camera position and zoom"
+        };
+        if ui.button("Save View").on_hover_text(hover).clicked()
+            && let Ok((transform, projection)) = params.camera_query.single()
+        {
+            let zoom = match projection {
+                Projection::Orthographic(ortho) => ortho.scale,
+                _ => 1.0,
+            };
+            let viewport = session_state.is_active.then_some(BookmarkViewport {
+                center: session_state.viewport_center,
+                width: session_state.viewport_size.x,
+                rotation_degrees: session_state.rotation_degrees,
+            });
+            params.bookmarks.add(
+                &params.new_name,
+                transform.translation.truncate(),
+                zoom,
+                viewport,
+            );
+            params.new_name.clear();
+            changed = true;
+        }
+    });
+    ui.separator();
+
+    if params.bookmarks.bookmarks.is_empty() {
+        ui.weak("No bookmarks on this map");
+    }
+    let mut raise = None;
+    let mut remove = None;
+    for (index, bookmark) in params.bookmarks.bookmarks.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let mut button = egui::Button::new(&bookmark.name);
+            if let Some(action) = Action::recall_bookmark(index)
+                && !keybindings.get(action).is_empty()
+            {
+                button = button.shortcut_text(keybindings.label(action));
+            }
+            if ui
+                .add(button)
+                .on_hover_text("Move the camera here")
+                .clicked()
+            {
+                params.recall_writer.write(RecallBookmarkRequest {
+                    index,
+                    camera: true,
+                    players: params.settings.move_players,
+                });
+                ui.close();
+            }
+            if session_state.is_active
+                && ui
+                    .small_button("Players")
+                    .on_hover_text("Move the player viewport here")
+                    .clicked()
+            {
+                params.recall_writer.write(RecallBookmarkRequest {
+                    index,
+                    camera: false,
+                    players: true,
+                });
+            }
+            if ui
+                .add_enabled(index > 0, egui::Button::new("⏶").small())
+                .on_hover_text("Move up")
+                .clicked()
+            {
+                raise = Some(index);
+            }
+            if ui.small_button("✕").on_hover_text("Remove").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = raise {
+        params.bookmarks.move_up(index);
+        changed = true;
+    }
+    if let Some(index) = remove {
+        params.bookmarks.remove(index);
+        changed = true;
+    }
+    if changed {
+        dirty_state.is_dirty = true;
+        history_writer.write(RecordEditorCommand {
+            command: EditorCommand::EditBookmarks {
+                old: old_bookmarks,
+                new: params.bookmarks.bookmarks.clone(),
+            },
+        });
+    }
+
+    ui.separator();
+    ui.checkbox(&mut params.settings.smooth_pan, "Smooth pan");
+    if session_state.is_active {
+        ui.checkbox(
+            &mut params.settings.move_players,
+            "Also move the player viewport",
+        )
+        .on_hover_text("When recalling from this menu or with the number keys");
+    }
+}
 
 /// Main toolbar showing tools and session controls
+#[allow(clippy::too_many_arguments)]
 pub fn toolbar_ui(
     mut contexts: EguiContexts,
     mut current_tool: ResMut<CurrentTool>,
@@ -14,6 +245,15 @@ pub fn toolbar_ui(
     mut dirty_state: ResMut<MapDirtyState>,
     session_state: Res<LiveSessionState>,
     mut dialog: ResMut<MonitorSelectionDialog>,
+    mut initiative_panel: ResMut<InitiativePanelState>,
+    mut dice_panel: ResMut<DicePanelState>,
+    mut notes_state: ResMut<NotesState>,
+    mut minimap: ResMut<MinimapState>,
+    mut history_panel: ResMut<HistoryPanelState>,
+    mut bookmarks: BookmarkParams,
+    mut grid_settings: ResMut<GridSettings>,
+    keybindings: Res<Keybindings>,
+    mut history_writer: MessageWriter<RecordEditorCommand>,
 ) -> Result {
     egui::TopBottomPanel::top("main_toolbar")
         .frame(
@@ -27,7 +267,7 @@ pub fn toolbar_ui(
                 // Tool buttons with keyboard shortcuts
                 for tool in EditorTool::all() {
                     let selected = current_tool.tool == *tool;
-                    let button_text = tool_button_label(tool);
+                    let button_text = tool_button_label(tool, &keybindings);
 
                     let button = egui::Button::new(
                         egui::RichText::new(button_text).size(14.0).strong(),
@@ -39,7 +279,11 @@ pub fn toolbar_ui(
                     if response.clicked() {
                         current_tool.tool = *tool;
                     }
-                    response.on_hover_text(tool.display_name());
+                    response.on_hover_text(format!(
+                        "{}\nShortcut: {}",
+                        tool.action().display_name(),
+                        keybindings.label(tool.action())
+                    ));
                 }
 
                 ui.add_space(8.0);
@@ -47,10 +291,104 @@ pub fn toolbar_ui(
                 ui.add_space(8.0);
 
                 // Grid toggle
+                let old_map_data = map_data.clone();
                 if ui.checkbox(&mut map_data.grid_visible, "Grid").changed() {
                     dirty_state.is_dirty = true;
+                    history_writer.write(RecordEditorCommand {
+                        command: EditorCommand::EditMapData {
+                            old: old_map_data,
+                            new: map_data.clone(),
+                        },
+                    });
+                }
+
+                ui.menu_button("Snap", |ui| snap_menu_ui(ui, &mut grid_settings))
+                    .response
+                    .on_hover_text("Snap targets, rotation steps and smart guides");
+
+                ui.add_space(8.0);
+
+                // Initiative tracker toggle
+                if ui
+                    .add(
+                        egui::Button::new("Initiative")
+                            .min_size(egui::vec2(0.0, 24.0))
+                            .selected(initiative_panel.is_open),
+                    )
+                    .on_hover_text(format!(
+                        "Initiative tracker ({})",
+                        keybindings.label(Action::ToggleInitiative)
+                    ))
+                    .clicked()
+                {
+                    initiative_panel.is_open = !initiative_panel.is_open;
+                }
+
+                // Dice roller toggle
+                if ui
+                    .add(
+                        egui::Button::new("Dice")
+                            .min_size(egui::vec2(0.0, 24.0))
+                            .selected(dice_panel.is_open),
+                    )
+                    .on_hover_text("Dice roller")
+                    .clicked()
+                {
+                    dice_panel.is_open = !dice_panel.is_open;
+                }
+
+                // GM notes panel toggle
+                if ui
+                    .add(
+                        egui::Button::new("Notes")
+                            .min_size(egui::vec2(0.0, 24.0))
+                            .selected(notes_state.panel_open),
+                    )
+                    .on_hover_text("GM notes pinned to the map")
+                    .clicked()
+                {
+                    notes_state.panel_open = !notes_state.panel_open;
+                }
+
+                // Minimap toggle
+                if ui
+                    .add(
+                        egui::Button::new("Minimap")
+                            .min_size(egui::vec2(0.0, 24.0))
+                            .selected(minimap.is_open),
+                    )
+                    .on_hover_text("Map overview for jumping around large maps")
+                    .clicked()
+                {
+                    minimap.is_open = !minimap.is_open;
                 }
 
+                // History panel toggle
+                if ui
+                    .add(
+                        egui::Button::new("History")
+                            .min_size(egui::vec2(0.0, 24.0))
+                            .selected(history_panel.is_open),
+                    )
+                    .on_hover_text("Undo history; click a step to jump back or forward to it")
+                    .clicked()
+                {
+                    history_panel.is_open = !history_panel.is_open;
+                }
+
+                ui.menu_button("Bookmarks", |ui| {
+                    bookmarks_menu_ui(
+                        ui,
+                        &mut bookmarks,
+                        &session_state,
+                        &keybindings,
+                        &mut dirty_state,
+                        &mut history_writer,
+                    )
+                })
+                .response
+                .on_hover_text("Saved camera positions for this map");
+
                 // Right-aligned session controls
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if session_state.is_active {
@@ -71,18 +409,30 @@ pub fn toolbar_ui(
 }
 
 /// Secondary toolbar showing settings for the active tool
+#[allow(clippy::too_many_arguments)]
 pub fn tool_settings_ui(
     mut contexts: EguiContexts,
     current_tool: Res<CurrentTool>,
     mut annotation_settings: ResMut<AnnotationSettings>,
     mut selected_layer: ResMut<SelectedLayer>,
     mut fog_state: ResMut<FogState>,
+    mut movement_settings: ResMut<MovementSettings>,
+    mut brush_mode: ResMut<BrushMode>,
+    mut scatter: ResMut<ScatterSettings>,
+    mut autotile_brush: ResMut<AutoTileBrush>,
+    mut autotile_editor: ResMut<AutoTileEditorState>,
+    mut fill_state: ResMut<FillState>,
+    selected_asset: Res<SelectedAsset>,
+    library: Res<AssetLibrary>,
+    session_state: Res<LiveSessionState>,
+    mut select: SelectToolParams,
 ) -> Result {
     // Only show settings bar for tools that have settings
     let has_settings = current_tool.tool.is_annotation_tool()
-        || current_tool.tool == EditorTool::Place
-        || current_tool.tool == EditorTool::Brush
-        || current_tool.tool == EditorTool::Fog;
+        || current_tool.tool == EditorTool::Select
+        || current_tool.tool.places_assets()
+        || current_tool.tool == EditorTool::Fog
+        || current_tool.tool == EditorTool::Note;
     if !has_settings {
         return Ok(());
     }
@@ -98,12 +448,82 @@ pub fn tool_settings_ui(
                 ui.spacing_mut().item_spacing.x = 6.0;
 
                 match current_tool.tool {
-                    EditorTool::Place | EditorTool::Brush => {
-                        // Place/Brush tool settings
-                        let tool_name = if current_tool.tool == EditorTool::Place {
-                            "Place"
+                    EditorTool::Select => {
+                        ui.label(
+                            egui::RichText::new("Selection:").color(egui::Color32::LIGHT_GRAY),
+                        );
+
+                        ui.add_space(8.0);
+
+                        ui.selectable_value(&mut select.box_select.lasso, false, "Box");
+                        ui.selectable_value(&mut select.box_select.lasso, true, "Lasso");
+
+                        let filter_label = if select.box_select.filter.is_active() {
+                            "Filter*"
                         } else {
-                            "Brush"
+                            "Filter"
+                        };
+                        ui.menu_button(filter_label, |ui| {
+                            selection_filter_ui(ui, &mut select.box_select)
+                        })
+                        .response
+                        .on_hover_text("Layers that box and lasso selection pick from");
+
+                        ui.menu_button("Select", |ui| select_menu_ui(ui, &mut select));
+
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(12.0);
+
+                        // Token movement settings
+                        ui.label(
+                            egui::RichText::new("Movement:").color(egui::Color32::LIGHT_GRAY),
+                        );
+
+                        ui.add_space(8.0);
+
+                        ui.label("Diagonals:");
+                        egui::ComboBox::from_id_salt("diagonal_rule_select")
+                            .selected_text(movement_settings.diagonal_rule.display_name())
+                            .width(90.0)
+                            .show_ui(ui, |ui| {
+                                for rule in DiagonalRule::all() {
+                                    ui.selectable_value(
+                                        &mut movement_settings.diagonal_rule,
+                                        *rule,
+                                        rule.display_name(),
+                                    );
+                                }
+                            });
+
+                        ui.add_space(12.0);
+                        ui.separator();
+                        ui.add_space(12.0);
+
+                        ui.checkbox(&mut movement_settings.show_trails, "Trails");
+                        ui.add_enabled(
+                            movement_settings.show_trails,
+                            egui::DragValue::new(&mut movement_settings.trail_duration)
+                                .range(1.0..=60.0)
+                                .speed(0.5)
+                                .suffix(" s"),
+                        )
+                        .on_hover_text("How long movement trails stay visible");
+
+                        ui.add_space(8.0);
+
+                        ui.label(
+                            egui::RichText::new("Shift+Drag: Snap move/rotate")
+                                .color(egui::Color32::GRAY)
+                                .size(11.0),
+                        );
+                    }
+                    EditorTool::Place | EditorTool::Brush | EditorTool::Fill => {
+                        // Place/Brush/Fill tool settings
+                        let tool_name = match current_tool.tool {
+                            EditorTool::Place => "Place",
+                            EditorTool::Brush => "Brush",
+                            _ => "Fill",
                         };
                         ui.label(
                             egui::RichText::new(format!("{} Settings:", tool_name))
@@ -114,10 +534,10 @@ pub fn tool_settings_ui(
 
                         // Layer selector
                         ui.label("Layer:");
-                        let combo_id = if current_tool.tool == EditorTool::Place {
-                            "place_layer_select"
-                        } else {
-                            "brush_layer_select"
+                        let combo_id = match current_tool.tool {
+                            EditorTool::Place => "place_layer_select",
+                            EditorTool::Brush => "brush_layer_select",
+                            _ => "fill_layer_select",
                         };
                         egui::ComboBox::from_id_salt(combo_id)
                             .selected_text(selected_layer.layer.display_name())
@@ -140,12 +560,46 @@ pub fn tool_settings_ui(
                                 .color(egui::Color32::GRAY)
                                 .size(11.0),
                         );
+
+                        if current_tool.tool == EditorTool::Brush {
+                            ui.add_space(12.0);
+                            ui.separator();
+                            ui.add_space(12.0);
+
+                            ui.label("Mode:");
+                            for mode in BrushMode::all() {
+                                ui.selectable_value(&mut *brush_mode, *mode, mode.display_name());
+                            }
+
+                            match *brush_mode {
+                                BrushMode::Stamp => {}
+                                BrushMode::Scatter => {
+                                    ui.add_space(8.0);
+                                    scatter_settings_ui(ui, &mut scatter, &selected_asset);
+                                }
+                                BrushMode::AutoTile => {
+                                    ui.add_space(8.0);
+                                    autotile_settings_ui(
+                                        ui,
+                                        &mut autotile_brush,
+                                        &mut autotile_editor,
+                                        &library,
+                                    );
+                                }
+                            }
+                        } else if current_tool.tool == EditorTool::Fill {
+                            ui.add_space(12.0);
+                            ui.separator();
+                            ui.add_space(12.0);
+                            fill_settings_ui(ui, &mut fill_state);
+                        }
                     }
-                    EditorTool::Draw | EditorTool::Line | EditorTool::Text => {
+                    EditorTool::Draw | EditorTool::Line | EditorTool::Shape | EditorTool::Text => {
                         // Annotation tool settings
                         let tool_name = match current_tool.tool {
                             EditorTool::Draw => "Draw",
                             EditorTool::Line => "Line",
+                            EditorTool::Shape => "Shape",
                             EditorTool::Text => "Text",
                             _ => "",
                         };
@@ -223,19 +677,152 @@ pub fn tool_settings_ui(
                                         .speed(0.5)
                                         .suffix(" px"),
                                 );
+
+                                if current_tool.tool == EditorTool::Draw {
+                                    ui.add_space(8.0);
+                                    ui.checkbox(&mut annotation_settings.smooth_strokes, "Smooth")
+                                        .on_hover_text("Round off freehand strokes");
+
+                                    // Session sketches only make sense with players watching
+                                    if session_state.is_active {
+                                        ui.add_space(8.0);
+                                        ui.checkbox(
+                                            &mut annotation_settings.session_sketch,
+                                            "Sketch",
+                                        )
+                                        .on_hover_text(
+                                            "Strokes show to players, fade out and aren't saved",
+                                        );
+                                        ui.add_enabled(
+                                            annotation_settings.session_sketch,
+                                            egui::DragValue::new(
+                                                &mut annotation_settings.sketch_duration,
+                                            )
+                                            .range(1.0..=60.0)
+                                            .speed(0.5)
+                                            .suffix(" s"),
+                                        )
+                                        .on_hover_text("How long sketches stay visible");
+                                    }
+                                }
+                            }
+                            EditorTool::Shape => {
+                                egui::ComboBox::from_id_salt("shape_kind_select")
+                                    .selected_text(annotation_settings.shape_kind.display_name())
+                                    .width(90.0)
+                                    .show_ui(ui, |ui| {
+                                        for kind in ShapeKind::all() {
+                                            ui.selectable_value(
+                                                &mut annotation_settings.shape_kind,
+                                                *kind,
+                                                kind.display_name(),
+                                            );
+                                        }
+                                    });
+
+                                ui.add_space(8.0);
+
+                                ui.label("Width:");
+                                ui.add(
+                                    egui::DragValue::new(&mut annotation_settings.stroke_width)
+                                        .range(1.0..=20.0)
+                                        .speed(0.5)
+                                        .suffix(" px"),
+                                );
+
+                                ui.add_space(8.0);
+
+                                ui.checkbox(&mut annotation_settings.shape_dashed, "Dashed");
+
+                                if annotation_settings.shape_kind.is_closed() {
+                                    let mut has_fill = annotation_settings.shape_fill.is_some();
+                                    if ui.checkbox(&mut has_fill, "Fill").changed() {
+                                        annotation_settings.shape_fill = has_fill.then(|| {
+                                            annotation_settings.stroke_color.with_alpha(0.3)
+                                        });
+                                    }
+                                    if let Some(fill) = annotation_settings.shape_fill.as_mut() {
+                                        let mut color = theme::bevy_to_egui(*fill);
+                                        if ui.color_edit_button_srgba(&mut color).changed() {
+                                            *fill = theme::egui_to_bevy(color);
+                                        }
+                                    }
+                                }
+
+                                ui.add_space(8.0);
+
+                                let hint = if annotation_settings.shape_kind == ShapeKind::Polygon
+                                {
+                                    "Click to add points, Enter or click first point to close"
+                                } else {
+                                    "Drag to draw, Shift to constrain"
+                                };
+                                ui.label(
+                                    egui::RichText::new(hint)
+                                        .color(egui::Color32::GRAY)
+                                        .size(11.0),
+                                );
                             }
                             EditorTool::Text => {
                                 ui.label("Font Size:");
                                 ui.add(
                                     egui::DragValue::new(&mut annotation_settings.font_size)
-                                        .range(8.0..=72.0)
+                                        .range(TEXT_FONT_SIZE_RANGE)
                                         .speed(1.0)
                                         .suffix(" pt"),
                                 );
+
+                                ui.add_space(12.0);
+
+                                let mut has_background =
+                                    annotation_settings.text_background.is_some();
+                                if ui.checkbox(&mut has_background, "Background").changed() {
+                                    annotation_settings.text_background = has_background
+                                        .then_some(Color::srgba(0.0, 0.0, 0.0, 0.6));
+                                }
+                                if let Some(background) =
+                                    annotation_settings.text_background.as_mut()
+                                {
+                                    let mut color = theme::bevy_to_egui(*background);
+                                    if ui.color_edit_button_srgba(&mut color).changed() {
+                                        *background = theme::egui_to_bevy(color);
+                                    }
+                                }
+
+                                ui.add_space(8.0);
+
+                                ui.label(
+                                    egui::RichText::new("Double-click text to edit")
+                                        .color(egui::Color32::GRAY)
+                                        .size(11.0),
+                                );
                             }
                             _ => {}
                         }
                     }
+                    EditorTool::Eraser => {
+                        ui.label(
+                            egui::RichText::new("Eraser Settings:")
+                                .color(egui::Color32::LIGHT_GRAY),
+                        );
+
+                        ui.add_space(8.0);
+
+                        ui.label("Size:");
+                        ui.add(
+                            egui::DragValue::new(&mut annotation_settings.eraser_radius)
+                                .range(2.0..=100.0)
+                                .speed(0.5)
+                                .suffix(" px"),
+                        );
+
+                        ui.add_space(12.0);
+                        ui.label(
+                            egui::RichText::new("Drag over paths and lines to erase")
+                                .color(egui::Color32::GRAY)
+                                .size(11.0),
+                        );
+                    }
                     EditorTool::Fog => {
                         // Fog tool settings
                         ui.label(
@@ -273,22 +860,223 @@ pub fn tool_settings_ui(
                                 .size(11.0),
                         );
                     }
-                    _ => {}
+                    EditorTool::Note => {
+                        ui.label(
+                            egui::RichText::new("Note Settings:")
+                                .color(egui::Color32::LIGHT_GRAY),
+                        );
+
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new(
+                                "Click: Add or open a note · Drag pin: Move · \
+                                 Click a room key label: Open its note",
+                            )
+                            .color(egui::Color32::GRAY)
+                            .size(11.0),
+                        );
+                    }
                 }
             });
         });
     Ok(())
 }
 
-/// Get the button label for a tool (with keyboard shortcut)
-fn tool_button_label(tool: &EditorTool) -> &'static str {
-    match tool {
-        EditorTool::Select => "Select [V]",
-        EditorTool::Place => "Place [P]",
-        EditorTool::Brush => "Brush [B]",
-        EditorTool::Draw => "Draw [D]",
-        EditorTool::Line => "Line [L]",
-        EditorTool::Text => "Text [T]",
-        EditorTool::Fog => "Fog [F]",
+/// Area shape and tile options for the Fill tool
+fn fill_settings_ui(ui: &mut egui::Ui, fill_state: &mut FillState) {
+    ui.label("Area:");
+    for shape in FillShape::all() {
+        ui.selectable_value(&mut fill_state.shape, *shape, shape.display_name());
+    }
+
+    ui.add_space(8.0);
+    ui.checkbox(&mut fill_state.random_rotation, "Random rotation")
+        .on_hover_text("Turn each tile by a random multiple of 90°");
+    ui.checkbox(&mut fill_state.skip_covered, "Skip covered cells")
+        .on_hover_text("Leave cells that already hold an item on this layer");
+
+    ui.add_space(8.0);
+    let hint = match fill_state.shape {
+        FillShape::Rectangle => "Drag: Fill cells",
+        FillShape::Polygon => "Click: Add vertex · Enter/click first: Fill · Backspace: Undo",
+    };
+    ui.label(
+        egui::RichText::new(hint)
+            .color(egui::Color32::GRAY)
+            .size(11.0),
+    );
+}
+
+/// Auto-tile mode controls for the Brush tool
+fn autotile_settings_ui(
+    ui: &mut egui::Ui,
+    brush: &mut AutoTileBrush,
+    editor: &mut AutoTileEditorState,
+    library: &AssetLibrary,
+) {
+    // A set that was renamed or deleted can't be painted with
+    if let Some(name) = &brush.set_name
+        && library.autotile_set(name).is_none()
+    {
+        brush.set_name = None;
+    }
+
+    ui.label("Set:");
+    egui::ComboBox::from_id_salt("autotile_set_select")
+        .selected_text(brush.set_name.as_deref().unwrap_or("None"))
+        .width(120.0)
+        .show_ui(ui, |ui| {
+            for set in &library.metadata.autotile_sets {
+                ui.selectable_value(&mut brush.set_name, Some(set.name.clone()), &set.name);
+            }
+        });
+    if ui
+        .button("Edit sets…")
+        .on_hover_text("Create auto-tile sets and assign their pieces")
+        .clicked()
+    {
+        editor.open(library);
+    }
+
+    ui.add_space(8.0);
+    ui.label(
+        egui::RichText::new("Drag: Paint · Right-drag: Erase")
+            .color(egui::Color32::GRAY)
+            .size(11.0),
+    );
+}
+
+/// Scatter mode controls for the Brush tool
+fn scatter_settings_ui(
+    ui: &mut egui::Ui,
+    scatter: &mut ScatterSettings,
+    selected_asset: &SelectedAsset,
+) {
+    ui.label("Radius:");
+    ui.add(
+        egui::DragValue::new(&mut scatter.radius)
+            .range(0.5..=10.0)
+            .speed(0.1)
+            .suffix(" cells"),
+    );
+    ui.label("Density:");
+    ui.add(
+        egui::DragValue::new(&mut scatter.density)
+            .range(0.05..=4.0)
+            .speed(0.02)
+            .suffix("/cell"),
+    )
+    .on_hover_text("Items per grid cell inside the brush circle");
+    ui.label("Spacing:");
+    ui.add(
+        egui::DragValue::new(&mut scatter.spacing)
+            .range(0.0..=5.0)
+            .speed(0.05)
+            .suffix(" cells"),
+    )
+    .on_hover_text("Minimum distance between items in a stroke");
+
+    ui.add_space(8.0);
+    ui.menu_button("Variation", |ui| {
+        egui::Grid::new("scatter_variation_grid")
+            .num_columns(2)
+            .spacing([8.0, 4.0])
+            .show(ui, |ui| {
+                ui.label("Rotation:");
+                ui.add(
+                    egui::DragValue::new(&mut scatter.rotation_range)
+                        .range(0.0..=180.0)
+                        .speed(1.0)
+                        .prefix("± ")
+                        .suffix("°"),
+                );
+                ui.end_row();
+
+                ui.label("Scale:");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut scatter.scale_min)
+                            .range(0.1..=scatter.scale_max)
+                            .speed(0.01),
+                    );
+                    ui.label("to");
+                    ui.add(
+                        egui::DragValue::new(&mut scatter.scale_max)
+                            .range(scatter.scale_min..=5.0)
+                            .speed(0.01),
+                    );
+                });
+                ui.end_row();
+
+                ui.label("Flip:");
+                ui.checkbox(&mut scatter.random_flip, "Randomly mirror");
+                ui.end_row();
+            });
+    });
+
+    let assets_label = if scatter.assets.is_empty() {
+        "Assets: Selected".to_string()
+    } else {
+        format!("Assets: {}", scatter.assets.len())
+    };
+    ui.menu_button(assets_label, |ui| {
+        ui.set_min_width(220.0);
+        if scatter.assets.is_empty() {
+            ui.label(egui::RichText::new("Scattering the selected asset").weak());
+        }
+        let mut remove = None;
+        egui::Grid::new("scatter_assets_grid")
+            .num_columns(3)
+            .spacing([8.0, 4.0])
+            .show(ui, |ui| {
+                for (index, entry) in scatter.assets.iter_mut().enumerate() {
+                    ui.label(&entry.asset.name);
+                    ui.add(
+                        egui::DragValue::new(&mut entry.weight)
+                            .range(0.0..=100.0)
+                            .speed(0.1),
+                    )
+                    .on_hover_text("Relative weight");
+                    if ui.small_button("✕").on_hover_text("Remove").clicked() {
+                        remove = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(index) = remove {
+            scatter.assets.remove(index);
+        }
+
+        ui.separator();
+        let selected = selected_asset.asset.as_ref();
+        if ui
+            .add_enabled(selected.is_some(), egui::Button::new("Add selected asset"))
+            .on_disabled_hover_text("Select an asset in the asset browser first")
+            .clicked()
+            && let Some(asset) = selected
+        {
+            scatter.add_asset(asset);
+        }
+        if !scatter.assets.is_empty() && ui.button("Clear").clicked() {
+            scatter.assets.clear();
+        }
+    });
+
+    ui.add_space(8.0);
+    ui.label("Seed:");
+    ui.add(egui::DragValue::new(&mut scatter.seed).speed(1.0))
+        .on_hover_text("The same seed repeats a stroke's random choices");
+    if ui.small_button("🎲").on_hover_text("New seed").clicked() {
+        scatter.seed = fastrand::u32(..);
+    }
+    ui.checkbox(&mut scatter.lock_seed, "Lock")
+        .on_hover_text("Reuse this seed for every stroke");
+}
+
+/// Get the button label for a tool (with its first keyboard shortcut)
+fn tool_button_label(tool: &EditorTool, keybindings: &Keybindings) -> String {
+    match keybindings.get(tool.action()).first() {
+        Some(binding) => format!("{} [{}]", tool.display_name(), binding),
+        None => tool.display_name().to_string(),
     }
 }